@@ -31,10 +31,13 @@ fn App<G: Html>(cx: Scope) -> View<G> {
         ul {
             Indexed {
                 iterable: items,
-                view: |cx, Cat { id, name }| view! { cx,
-                    li {
-                        a(href=format!("https://www.youtube.com/watch?v={id}")) {
-                            (name)
+                view: |cx, cat| {
+                    let Cat { id, name } = *cat.get();
+                    view! { cx,
+                        li {
+                            a(href=format!("https://www.youtube.com/watch?v={id}")) {
+                                (name)
+                            }
                         }
                     }
                 }
@@ -0,0 +1,46 @@
+//! Server-side rendering on `wasm32-wasip1`, e.g. in an edge runtime.
+//!
+//! The path of the request is read from the first argument and the rendered page is written to
+//! stdout, which is how WASI request handlers usually talk to their host.
+
+use sycamore::prelude::*;
+
+#[derive(Prop)]
+struct PageProps {
+    path: String,
+}
+
+#[component]
+fn Page<G: Html>(cx: Scope, props: PageProps) -> View<G> {
+    let links = ["/", "/about", "/contact"]
+        .into_iter()
+        .map(|link| (link, link == props.path))
+        .collect();
+    let links = create_signal(cx, links);
+
+    view! { cx,
+        nav {
+            ul {
+                Keyed {
+                    iterable: links,
+                    view: |cx, (link, active)| view! { cx,
+                        li(class=if active { "active" } else { "" }) {
+                            a(href=link) { (link) }
+                        }
+                    },
+                    key: |(link, _)| *link,
+                }
+            }
+        }
+        main {
+            h1 { "Rendered at the edge" }
+            p { "You requested " code { (props.path) } "." }
+        }
+    }
+}
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| "/".to_string());
+    let body = sycamore::render_to_string(|cx| view! { cx, Page { path: path } });
+    println!("<!DOCTYPE html><html><head><meta charset=\"utf-8\"></head><body>{body}</body></html>");
+}
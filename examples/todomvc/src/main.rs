@@ -190,10 +190,7 @@ pub fn Header<G: Html>(cx: Scope) -> View<G> {
             if !task.is_empty() {
                 app_state.add_todo(task);
                 value.set("".to_string());
-                input_ref
-                    .get::<DomNode>()
-                    .unchecked_into::<HtmlInputElement>()
-                    .set_value("");
+                input_ref.get_as::<HtmlInputElement>().set_value("");
             }
         }
     };
@@ -239,11 +236,7 @@ pub fn Item<G: Html>(cx: Scope, todo: RcSignal<Todo>) -> View<G> {
 
     let handle_dblclick = move |_| {
         editing.set(true);
-        input_ref
-            .get::<DomNode>()
-            .unchecked_into::<HtmlInputElement>()
-            .focus()
-            .unwrap();
+        input_ref.get_as::<HtmlInputElement>().focus().unwrap();
         value.set(title());
     };
 
@@ -268,10 +261,7 @@ pub fn Item<G: Html>(cx: Scope, todo: RcSignal<Todo>) -> View<G> {
         match event.key().as_str() {
             "Enter" => handle_blur(),
             "Escape" => {
-                input_ref
-                    .get::<DomNode>()
-                    .unchecked_into::<HtmlInputElement>()
-                    .set_value(&title());
+                input_ref.get_as::<HtmlInputElement>().set_value(&title());
                 editing.set(false);
             }
             _ => {}
@@ -0,0 +1,259 @@
+//! Sets of attributes that can be spread onto an element.
+//!
+//! A struct deriving [`Attributes`](trait@Attributes) can be spread onto an element in the `view!`
+//! macro with `..`. Every field of the struct is set as an attribute of the element.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//!
+//! #[derive(Attributes)]
+//! struct Disclosure<'a> {
+//!     aria_expanded: MaybeDyn<'a, bool>,
+//!     aria_controls: &'static str,
+//!     #[attribute(name = "data-state")]
+//!     state: MaybeDyn<'a, String>,
+//! }
+//!
+//! #[component]
+//! fn Toggle<G: Html>(cx: Scope) -> View<G> {
+//!     let open = create_signal(cx, false);
+//!     let disclosure = Disclosure {
+//!         aria_expanded: open.into(),
+//!         aria_controls: "panel",
+//!         state: MaybeDyn::derived(|| if *open.get() { "open" } else { "closed" }.to_string()),
+//!     };
+//!     view! { cx,
+//!         button(..disclosure, on:click=|_| open.set(!*open.get())) { "Toggle" }
+//!     }
+//! }
+//! ```
+//!
+//! To forward arbitrary attributes and event handlers, e.g. from the props of a wrapper component
+//! to the element that it renders, use an [`AttributeMap`] instead.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use sycamore_reactive::*;
+
+use crate::generic_node::GenericNode;
+
+/// A set of attributes that can be spread onto an element with `..` in the `view!` macro.
+///
+/// This trait should usually be implemented with the `Attributes` derive macro, which sets every
+/// field of the struct as an attribute. Field names are converted to kebab-case, e.g.
+/// `aria_expanded` becomes `aria-expanded`. The name can be overridden with
+/// `#[attribute(name = "...")]`.
+pub trait Attributes<'a, G: GenericNode> {
+    /// Sets the attributes on `el`. Reactive attributes are updated for as long as `cx` is alive.
+    fn apply_attributes(self, cx: Scope<'a>, el: &G);
+}
+
+/// A value that can be set as an attribute of an element. Used by the `Attributes` derive macro.
+///
+/// Strings and numbers are set as is. Booleans are set as boolean attributes, i.e. the attribute
+/// is present with an empty value if `true` and absent if `false`. `None` removes the attribute.
+/// [`MaybeDyn`] and signals are reactive.
+pub trait AttributeValue<'a> {
+    /// Sets the attribute `name` of `el` to this value.
+    fn set_attribute<G: GenericNode>(self, cx: Scope<'a>, el: &G, name: &'static str);
+}
+
+macro_rules! impl_attribute_value_to_string {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a> AttributeValue<'a> for $ty {
+                fn set_attribute<G: GenericNode>(self, _: Scope<'a>, el: &G, name: &'static str) {
+                    el.set_attribute(name, &self.to_string());
+                }
+            }
+        )*
+    };
+}
+
+impl_attribute_value_to_string!(
+    String,
+    Cow<'_, str>,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64
+);
+
+impl<'a> AttributeValue<'a> for &str {
+    fn set_attribute<G: GenericNode>(self, _: Scope<'a>, el: &G, name: &'static str) {
+        el.set_attribute(name, self);
+    }
+}
+
+impl<'a> AttributeValue<'a> for bool {
+    fn set_attribute<G: GenericNode>(self, _: Scope<'a>, el: &G, name: &'static str) {
+        if self {
+            el.set_attribute(name, "");
+        } else {
+            el.remove_attribute(name);
+        }
+    }
+}
+
+impl<'a, T: AttributeValue<'a>> AttributeValue<'a> for Option<T> {
+    fn set_attribute<G: GenericNode>(self, cx: Scope<'a>, el: &G, name: &'static str) {
+        match self {
+            Some(value) => value.set_attribute(cx, el, name),
+            None => el.remove_attribute(name),
+        }
+    }
+}
+
+impl<'a, T: AttributeValue<'a> + Clone + 'a> AttributeValue<'a> for MaybeDyn<'a, T> {
+    fn set_attribute<G: GenericNode>(self, cx: Scope<'a>, el: &G, name: &'static str) {
+        match self {
+            MaybeDyn::Static(value) => value.set_attribute(cx, el, name),
+            value => {
+                let el = el.clone();
+                create_effect(cx, move || value.get().set_attribute(cx, &el, name));
+            }
+        }
+    }
+}
+
+impl<'a, T: AttributeValue<'a> + Clone + 'a> AttributeValue<'a> for &'a ReadSignal<T> {
+    fn set_attribute<G: GenericNode>(self, cx: Scope<'a>, el: &G, name: &'static str) {
+        MaybeDyn::Signal(self).set_attribute(cx, el, name);
+    }
+}
+
+impl<'a, T: AttributeValue<'a> + Clone + 'a> AttributeValue<'a> for &'a Signal<T> {
+    fn set_attribute<G: GenericNode>(self, cx: Scope<'a>, el: &G, name: &'static str) {
+        MaybeDyn::Signal(self).set_attribute(cx, el, name);
+    }
+}
+
+/// Sets an attribute of an [`AttributeMap`] on an element.
+type SetAttribute<'a, G> = Box<dyn FnOnce(Scope<'a>, &G) + 'a>;
+
+/// A collection of attributes, classes and event handlers that are not known in advance, which can
+/// be spread onto an element with `..` in the `view!` macro.
+///
+/// This is useful for wrapper components, e.g. buttons or inputs, which take an [`AttributeMap`]
+/// in their props and forward it to the element that they render. The attributes are set in the
+/// order in which they were added, after the attributes that come before the spread in the
+/// `view!` and before the ones that come after it. Classes are added to the classes of the element
+/// instead of replacing them.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[derive(Prop)]
+/// struct ButtonProps<'a, G: Html> {
+///     #[builder(default)]
+///     attributes: AttributeMap<'a, G>,
+///     children: Children<'a, G>,
+/// }
+///
+/// #[component]
+/// fn Button<'a, G: Html>(cx: Scope<'a>, props: ButtonProps<'a, G>) -> View<G> {
+///     let children = props.children.call(cx);
+///     view! { cx,
+///         button(class="btn", ..props.attributes) { (children) }
+///     }
+/// }
+///
+/// #[component]
+/// fn App<G: Html>(cx: Scope) -> View<G> {
+///     let attributes = AttributeMap::new()
+///         .attr("type", "submit")
+///         .attr("disabled", false)
+///         .class("btn-primary")
+///         .on("click", |_| {});
+///     view! { cx,
+///         Button { attributes: attributes, "Submit" }
+///     }
+/// }
+/// ```
+pub struct AttributeMap<'a, G: GenericNode> {
+    /// The names of the attributes and classes, for [`fmt::Debug`].
+    names: Vec<Cow<'static, str>>,
+    attributes: Vec<SetAttribute<'a, G>>,
+}
+
+impl<'a, G: GenericNode> AttributeMap<'a, G> {
+    /// Creates an empty [`AttributeMap`].
+    pub fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Adds the attribute `name`. `value` can be any [`AttributeValue`], e.g. a string, a boolean
+    /// for a boolean attribute, or a signal for a reactive attribute.
+    pub fn attr(mut self, name: &'static str, value: impl AttributeValue<'a> + 'a) -> Self {
+        self.names.push(Cow::Borrowed(name));
+        self.attributes
+            .push(Box::new(move |cx, el| value.set_attribute(cx, el, name)));
+        self
+    }
+
+    /// Adds the space separated classes in `class` to the classes of the element.
+    pub fn class(mut self, class: impl Into<Cow<'static, str>>) -> Self {
+        let class = class.into();
+        self.names.push(Cow::Owned(format!(".{class}")));
+        self.attributes
+            .push(Box::new(move |_, el| el.add_class(&class)));
+        self
+    }
+
+    /// Adds an event handler for the event `name`.
+    pub fn on(mut self, name: &'static str, handler: impl FnMut(G::EventType) + 'a) -> Self {
+        self.names.push(Cow::Owned(format!("on:{name}")));
+        self.attributes
+            .push(Box::new(move |cx, el| el.event(cx, name, handler)));
+        self
+    }
+
+    /// Adds all the attributes of `other` after the attributes of `self`.
+    pub fn extend(mut self, other: Self) -> Self {
+        self.names.extend(other.names);
+        self.attributes.extend(other.attributes);
+        self
+    }
+
+    /// Returns `true` if there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+impl<'a, G: GenericNode> Attributes<'a, G> for AttributeMap<'a, G> {
+    fn apply_attributes(self, cx: Scope<'a>, el: &G) {
+        for set in self.attributes {
+            set(cx, el);
+        }
+    }
+}
+
+impl<'a, G: GenericNode> Default for AttributeMap<'a, G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, G: GenericNode> fmt::Debug for AttributeMap<'a, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AttributeMap").field(&self.names).finish()
+    }
+}
@@ -1,5 +1,8 @@
 //! Utilities for components and component properties.
 
+use std::cell::Cell;
+use std::rc::Rc;
+
 use sycamore_reactive::*;
 
 use crate::generic_node::GenericNode;
@@ -21,6 +24,142 @@ pub fn component_scope<G: GenericNode>(f: impl FnOnce() -> View<G>) -> View<G> {
     }
 }
 
+thread_local! {
+    static COMPONENT_COMMENTS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Enables or disables comments marking the boundaries of components, e.g. `<!--<Counter>-->` and
+/// `<!--</Counter>-->` around the nodes rendered by `Counter`. This makes it easier to find which
+/// component rendered a node in the browser's devtools.
+///
+/// This is disabled by default and only has an effect in debug builds with the `diagnostics`
+/// feature. Nodes that are rendered on the server or hydrated never get boundary comments, as they
+/// would not match the server output.
+pub fn set_component_comments(enabled: bool) {
+    COMPONENT_COMMENTS.with(|comments| comments.set(enabled));
+}
+
+/// Enables or disables the `data-testid` attribute on the elements created by components, for
+/// end-to-end tests. The test id is made of the name of the component and the hydration key of the
+/// element, e.g. `Counter-1.2`. When rendering in the browser without hydration, elements do not
+/// have a hydration key and the id of the component instance on the current thread is used
+/// instead, along with the index of the element within the component.
+///
+/// This is disabled by default and only applies to the views rendered on the current thread.
+///
+/// _This API requires the following crate features to be activated: `test-ids`_
+#[cfg(feature = "test-ids")]
+pub fn set_test_ids(enabled: bool) {
+    TEST_IDS.with(|test_ids| test_ids.set(enabled));
+}
+
+/// Same as [`component_scope`] but surrounds the view with comments containing the component
+/// `name` if enabled with [`set_component_comments`].
+#[doc(hidden)]
+pub fn component_scope_with_name<G: GenericNode>(
+    name: &'static str,
+    f: impl FnOnce() -> View<G>,
+) -> View<G> {
+    #[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+    let _guard = ComponentGuard::enter(name);
+    let view = component_scope(f);
+    if cfg!(all(debug_assertions, feature = "diagnostics"))
+        && !G::USE_HYDRATION_CONTEXT
+        && COMPONENT_COMMENTS.with(Cell::get)
+    {
+        View::new_fragment(vec![
+            View::new_node(G::marker_with_text(&format!("<{name}>"))),
+            view,
+            View::new_node(G::marker_with_text(&format!("</{name}>"))),
+        ])
+    } else {
+        view
+    }
+}
+
+#[cfg(feature = "test-ids")]
+thread_local! {
+    static TEST_IDS: Cell<bool> = const { Cell::new(false) };
+    /// The id of the next component instance, used in the test ids of the elements that do not
+    /// have a hydration key.
+    static NEXT_COMPONENT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+thread_local! {
+    /// The components that are being created.
+    static COMPONENTS: std::cell::RefCell<Vec<ComponentFrame>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A component that is being created.
+#[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+struct ComponentFrame {
+    name: &'static str,
+    /// The id of the component instance.
+    #[cfg(feature = "test-ids")]
+    id: usize,
+    /// The number of elements without a hydration key that the component has created so far.
+    #[cfg(feature = "test-ids")]
+    nodes: usize,
+}
+
+/// Pushes a component onto [`COMPONENTS`] and pops it when dropped.
+#[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+struct ComponentGuard;
+
+#[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+impl ComponentGuard {
+    fn enter(name: &'static str) -> Self {
+        let frame = ComponentFrame {
+            name,
+            #[cfg(feature = "test-ids")]
+            id: NEXT_COMPONENT_ID.with(|id| id.replace(id.get() + 1)),
+            #[cfg(feature = "test-ids")]
+            nodes: 0,
+        };
+        COMPONENTS.with(|components| components.borrow_mut().push(frame));
+        Self
+    }
+}
+
+#[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+impl Drop for ComponentGuard {
+    fn drop(&mut self) {
+        COMPONENTS.with(|components| components.borrow_mut().pop());
+    }
+}
+
+/// Returns the name of the component that is being created, if any. Only tracked in debug builds
+/// with the `diagnostics` feature, or with the `test-ids` feature.
+#[doc(hidden)]
+pub fn current_component() -> Option<&'static str> {
+    #[cfg(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics")))]
+    return COMPONENTS.with(|components| components.borrow().last().map(|frame| frame.name));
+    #[cfg(not(any(feature = "test-ids", all(debug_assertions, feature = "diagnostics"))))]
+    None
+}
+
+/// Returns the test id of an element with the hydration key `key` that is created by the current
+/// component, if enabled with [`set_test_ids`]. Returns `None` outside of components, or when the
+/// component has already returned (e.g. when a dynamic view is re-rendered).
+#[cfg(feature = "test-ids")]
+#[doc(hidden)]
+pub fn test_id(key: Option<(usize, usize)>) -> Option<String> {
+    if !TEST_IDS.with(Cell::get) {
+        return None;
+    }
+    COMPONENTS.with(|components| {
+        let mut components = components.borrow_mut();
+        let frame = components.last_mut()?;
+        let (component, node) = key.unwrap_or_else(|| {
+            frame.nodes += 1;
+            (frame.id, frame.nodes - 1)
+        });
+        Some(format!("{}-{component}.{node}", frame.name))
+    })
+}
+
 /// A trait that is implemented automatically by the `Prop` derive macro.
 pub trait Prop {
     /// The type of the builder. This allows getting the builder type when the name is unknown (e.g.
@@ -74,3 +213,45 @@ impl<'a, G: GenericNode> Children<'a, G> {
         Self { f: Box::new(f) }
     }
 }
+
+/// Component children that take an argument, e.g. the item of a list that is rendered by the
+/// component. Unlike [`Children`], the children can be instantiated any number of times.
+///
+/// In the `view!` macro, the parameters are declared with closure syntax before the children:
+/// `DataTable { rows: rows, |row| td { (row) } }`. Several parameters are passed as a tuple.
+pub struct ChildrenWith<'a, T, G: GenericNode> {
+    #[allow(clippy::type_complexity)]
+    f: Rc<dyn Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a>,
+}
+impl<'a, T, G: GenericNode> std::fmt::Debug for ChildrenWith<'a, T, G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChildrenWith").finish()
+    }
+}
+
+impl<'a, T, G: GenericNode> Clone for ChildrenWith<'a, T, G> {
+    fn clone(&self) -> Self {
+        Self { f: self.f.clone() }
+    }
+}
+
+impl<'a, T, F, G: GenericNode> From<F> for ChildrenWith<'a, T, G>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+{
+    fn from(f: F) -> Self {
+        Self { f: Rc::new(f) }
+    }
+}
+
+impl<'a, T, G: GenericNode> ChildrenWith<'a, T, G> {
+    /// Instantiate the child [`View`] with the passed [`Scope`] and argument.
+    pub fn call(&self, cx: BoundedScope<'_, 'a>, arg: T) -> View<G> {
+        (self.f)(cx, arg)
+    }
+
+    /// Create a new [`ChildrenWith`] from a closure.
+    pub fn new(_cx: Scope<'a>, f: impl Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a) -> Self {
+        Self { f: Rc::new(f) }
+    }
+}
@@ -0,0 +1,171 @@
+//! Error boundaries.
+//!
+//! An error boundary catches the errors of the views rendered inside of it and displays a fallback
+//! view instead, so that a failing component does not take down the entire app. Three kinds of
+//! errors are caught:
+//! - panics while the children of the boundary are being rendered,
+//! - `Err` values returned by the view of a [`try_error_boundary`], or passed to [`view_or_throw`]
+//!   by a child component,
+//! - errors thrown with [`throw_error`], at any time.
+//!
+//! Panics can only be caught when they unwind. In particular, panics always abort on
+//! `wasm32-unknown-unknown`, so in the browser, only the errors thrown with [`throw_error`] are
+//! caught.
+
+use std::any::Any;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use sycamore_reactive::*;
+
+use crate::generic_node::GenericNode;
+use crate::view::View;
+
+/// An error caught by an error boundary.
+#[derive(Debug, Clone)]
+pub enum BoundaryError {
+    /// A child panicked while it was being rendered. Contains the panic message.
+    Panic(Rc<str>),
+    /// An error returned by a view or thrown with [`throw_error`].
+    Error(Rc<dyn Error>),
+}
+
+impl fmt::Display for BoundaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panic(message) => f.write_str(message),
+            Self::Error(err) => err.fmt(f),
+        }
+    }
+}
+
+/// Returns the message of a panic, if it is a string.
+fn panic_message(payload: Box<dyn Any + Send>) -> Rc<str> {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).into()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.as_str().into()
+    } else {
+        "panicked with a non-string payload".into()
+    }
+}
+
+/// The state of the nearest error boundary. Provided as a context to the children of the boundary.
+struct BoundaryState {
+    error: RcSignal<Option<BoundaryError>>,
+}
+
+/// Clears the error of an error boundary, rendering its children again. Passed to the fallback of
+/// the boundary.
+#[derive(Clone)]
+pub struct ErrorReset {
+    error: RcSignal<Option<BoundaryError>>,
+}
+
+impl ErrorReset {
+    /// Clears the error and renders the children of the boundary again.
+    pub fn reset(&self) {
+        self.error.set(None);
+    }
+}
+
+impl fmt::Debug for ErrorReset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorReset").finish_non_exhaustive()
+    }
+}
+
+/// Sends `error` to the nearest error boundary, which replaces its children with its fallback.
+///
+/// # Panics
+/// Panics if `cx` is not inside an error boundary.
+pub fn throw_error(cx: Scope, error: impl Into<Box<dyn Error>>) {
+    let error = BoundaryError::Error(error.into().into());
+    match try_use_context::<BoundaryState>(cx) {
+        Some(state) => state.error.set(Some(error)),
+        None => panic!("uncaught error: {error}"),
+    }
+}
+
+/// Returns the view in `result`. If it is an `Err`, sends the error to the nearest error boundary
+/// like [`throw_error`] and returns an empty view instead. Use this to propagate errors from child
+/// components, which cannot return a `Result`.
+///
+/// # Panics
+/// Panics if `result` is an `Err` and `cx` is not inside an error boundary.
+pub fn view_or_throw<G: GenericNode, E: Into<Box<dyn Error>>>(
+    cx: Scope,
+    result: Result<View<G>, E>,
+) -> View<G> {
+    result.unwrap_or_else(|err| {
+        throw_error(cx, err);
+        View::empty()
+    })
+}
+
+/// Renders `view` inside an error boundary. If an error is caught, `fallback` is rendered instead,
+/// with the error and an [`ErrorReset`] to render `view` again.
+///
+/// This is the underlying utility behind the `ErrorBoundary` component.
+pub fn error_boundary<'a, G: GenericNode>(
+    cx: Scope<'a>,
+    view: impl Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+    fallback: impl Fn(BoundedScope<'_, 'a>, BoundaryError, ErrorReset) -> View<G> + 'a,
+) -> View<G> {
+    try_error_boundary(cx, move |cx| Ok::<_, Infallible>(view(cx)), fallback)
+}
+
+/// Same as [`error_boundary`], but `view` returns a `Result`. An `Err` is caught like the other
+/// errors and `fallback` is rendered instead.
+pub fn try_error_boundary<'a, G: GenericNode, E: Into<Box<dyn Error>>>(
+    cx: Scope<'a>,
+    view: impl Fn(BoundedScope<'_, 'a>) -> Result<View<G>, E> + 'a,
+    fallback: impl Fn(BoundedScope<'_, 'a>, BoundaryError, ErrorReset) -> View<G> + 'a,
+) -> View<G> {
+    let error = create_rc_signal(None);
+    View::new_dyn_scoped(cx, move |cx| {
+        let reset = ErrorReset {
+            error: error.clone(),
+        };
+        if let Some(err) = error.get().as_ref().clone() {
+            return fallback(cx, err, reset);
+        }
+        provide_context(
+            cx,
+            BoundaryState {
+                error: error.clone(),
+            },
+        );
+
+        // Render the children in their own scope so that everything they created can be disposed
+        // if they fail.
+        let mut rendered = None;
+        let disposer = create_child_scope(cx, |child| {
+            // SAFETY: the child scope does not outlive `cx`, which does not outlive `'a`.
+            let child = unsafe { std::mem::transmute::<Scope, BoundedScope<'_, 'a>>(child) };
+            rendered = Some(catch_unwind(AssertUnwindSafe(|| view(child))));
+        });
+        match rendered.unwrap() {
+            // The error might have been thrown while rendering.
+            Ok(Ok(view)) if error.get_untracked().is_none() => view,
+            result => {
+                match result {
+                    Ok(Err(err)) => {
+                        error.set_silent(Some(BoundaryError::Error(err.into().into())));
+                    }
+                    Err(payload) => {
+                        error.set_silent(Some(BoundaryError::Panic(panic_message(payload))));
+                    }
+                    Ok(Ok(_)) => {}
+                }
+                // SAFETY: the child scope is not used anymore.
+                unsafe { disposer.dispose() };
+                let err = error.get_untracked().as_ref().clone().unwrap();
+                fallback(cx, err, reset)
+            }
+        }
+    })
+}
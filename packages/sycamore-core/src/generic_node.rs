@@ -13,6 +13,35 @@ pub trait SycamoreElement {
     const NAME_SPACE: Option<&'static str>;
 }
 
+/// Options for [`GenericNode::event_with_options`]. These correspond to the event modifiers of the
+/// `view!` macro, e.g. `on:submit.prevent`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EventOptions {
+    /// Calls `preventDefault()` on the event before calling the handler.
+    pub prevent_default: bool,
+    /// Calls `stopPropagation()` on the event before calling the handler.
+    pub stop_propagation: bool,
+    /// Registers the listener for the capture phase instead of the bubble phase.
+    pub capture: bool,
+    /// Removes the listener after it is called once.
+    pub once: bool,
+    /// Promises that the handler never calls `preventDefault()`, which lets the browser scroll
+    /// without waiting for the handler.
+    pub passive: bool,
+}
+
+/// An event handler of a node, as returned by [`GenericNode::event_listeners`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventListener {
+    /// Identifies the handler for [`GenericNode::remove_event_listener`]. Unique among the
+    /// handlers of all the nodes of a rendering backend.
+    pub id: usize,
+    /// The name of the event, e.g. `click`.
+    pub name: String,
+    /// The options that the handler was added with.
+    pub options: EventOptions,
+}
+
 /// Abstraction over a rendering backend.
 ///
 /// You would probably use this trait as a trait bound when you want to accept any rendering
@@ -35,6 +64,12 @@ pub trait SycamoreElement {
 ///
 /// [`GenericNode`]s should be cheaply cloneable (usually backed by a [`Rc`](std::rc::Rc) or other
 /// reference counted container) and preserve reference equality.
+///
+/// # Panics
+///
+/// Methods that can fail, e.g. [`set_attribute`](Self::set_attribute) with an invalid attribute
+/// name, are `#[track_caller]`. When called from the `view!` macro, the panic location therefore
+/// points at the element or attribute inside the `view!` block.
 pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     /// The type of the event that is passed to the event handler.
     type EventType;
@@ -47,11 +82,24 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     const CLIENT_SIDE_HYDRATION: bool = false;
 
     /// Create a new element node.
+    #[track_caller]
     fn element<T: SycamoreElement>() -> Self;
 
     /// Create a new element node from a tag string.
+    #[track_caller]
     fn element_from_tag(tag: &str) -> Self;
 
+    /// Create a new element node from a tag string in the namespace `ns`, e.g. an SVG `<a>`
+    /// element. If `ns` is `None`, this is the same as
+    /// [`element_from_tag`](Self::element_from_tag).
+    ///
+    /// The default implementation ignores the namespace.
+    #[track_caller]
+    fn element_from_tag_ns(tag: &str, ns: Option<&str>) -> Self {
+        let _ = ns;
+        Self::element_from_tag(tag)
+    }
+
     /// Create a new text node.
     fn text_node(text: &str) -> Self;
 
@@ -73,29 +121,36 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     fn marker_with_text(text: &str) -> Self;
 
     /// Sets an attribute on a node.
+    #[track_caller]
     fn set_attribute(&self, name: &str, value: &str);
 
     /// Removes an attribute on a node.
+    #[track_caller]
     fn remove_attribute(&self, name: &str);
 
     /// Sets the `class` attribute on a node.
     /// This should have the same outcome as calling `set_attribute("class", value)`.
     /// For `DomNode`, this sets the `className` property directly which is about 2x faster (on
     /// Chrome).
+    #[track_caller]
     fn set_class_name(&self, value: &str);
 
     /// Add a class to the element.
     /// If multiple classes are specified, delimited by spaces, all the classes should be added.
     /// Any classes that are already present should not be added a second time.
+    #[track_caller]
     fn add_class(&self, class: &str);
 
     /// Remove a class from the element.
+    #[track_caller]
     fn remove_class(&self, class: &str);
 
     /// Sets a property on a node.
+    #[track_caller]
     fn set_property(&self, name: &str, value: &Self::PropertyType);
 
     /// Removes a property on a node.
+    #[track_caller]
     fn remove_property(&self, name: &str);
 
     /// Appends a child to the node's children.
@@ -130,6 +185,51 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     /// Add a event handler to the event `name`.
     fn event<'a, F: FnMut(Self::EventType) + 'a>(&self, cx: Scope<'a>, name: &str, handler: F);
 
+    /// Add a event handler to the event `name` with the given [`EventOptions`].
+    ///
+    /// The default implementation ignores the options. Rendering backends that run in the browser
+    /// should override this method.
+    fn event_with_options<'a, F: FnMut(Self::EventType) + 'a>(
+        &self,
+        cx: Scope<'a>,
+        name: &str,
+        handler: F,
+        options: EventOptions,
+    ) {
+        let _ = options;
+        self.event(cx, name, handler);
+    }
+
+    /// Returns the event handlers that were added to the node with [`event`](Self::event) or
+    /// [`event_with_options`](Self::event_with_options), in the order in which they were added.
+    /// Handlers are no longer listed once they are removed, once the scope that they were added in
+    /// is disposed, or once a handler with [`EventOptions::once`] was called.
+    ///
+    /// The default implementation returns an empty list, which is correct for rendering backends
+    /// that never call event handlers, e.g. `SsrNode`.
+    fn event_listeners(&self) -> Vec<EventListener> {
+        Vec::new()
+    }
+
+    /// Removes the event handler `listener` from the node, e.g. to swap the behavior of a
+    /// long-lived node without recreating it. Returns `false` if the handler is not attached to
+    /// the node.
+    ///
+    /// The default implementation does nothing and returns `false`.
+    fn remove_event_listener(&self, listener: &EventListener) -> bool {
+        let _ = listener;
+        false
+    }
+
+    /// Removes all the event handlers of the event `name` from the node.
+    fn remove_event_listeners(&self, name: &str) {
+        for listener in self.event_listeners() {
+            if listener.name == name {
+                self.remove_event_listener(&listener);
+            }
+        }
+    }
+
     /// Update inner text of the node. If the node has elements, all the elements are replaced with
     /// a new text node.
     fn update_inner_text(&self, text: &str);
@@ -137,9 +237,20 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     /// Updates the inner html of the node.
     /// The html will not be parsed in non-browser environments. This means that accessing methods
     /// such as [`first_child`](GenericNode::first_child) will return `None`.
+    #[track_caller]
     fn dangerously_set_inner_html(&self, html: &str);
 
     /// Create a deep clone of the node.
     #[must_use = "clone_node returns a new node"]
     fn clone_node(&self) -> Self;
+
+    /// Called after a dynamic view is re-rendered with the new `nodes` of the view, and the name
+    /// of the component in which the dynamic view was created, if any. Only called in debug
+    /// builds with the `diagnostics` feature.
+    ///
+    /// This is used by development tools, e.g. to highlight the parts of the page that are
+    /// updated. The default implementation does nothing.
+    fn view_updated(nodes: &[Self], component: Option<&'static str>) {
+        let _ = (nodes, component);
+    }
 }
@@ -2,11 +2,20 @@
 //!
 //! This is backend-agnostic. If you are looking specifically for hydrating the DOM, see the
 //! `sycamore-web` crate.
+//!
+//! The [`HydrationRegistry`] of the page that is being rendered is kept in a thread-local because
+//! the nodes are created by [`GenericNode`](crate::generic_node::GenericNode) constructors, which
+//! do not have access to the reactive scope. Thread-locals are available on every `wasm32` target,
+//! including WASI and edge runtimes, where they are plain globals since there is a single thread.
+//! The context is only ever set for the duration of a synchronous call or of a single poll of a
+//! future (see [`with_hydration_context_async`]), so pages that are rendered concurrently on the
+//! same thread never share it.
 
 use std::cell::RefCell;
 
 thread_local! {
-    static HYDRATION_CONTEXT: RefCell<Option<HydrationRegistry>> = RefCell::new(None);
+    /// The hydration context of the render that is currently running on this thread.
+    static HYDRATION_CONTEXT: RefCell<Option<HydrationRegistry>> = const { RefCell::new(None) };
 }
 
 /// Run the closure inside a hydration context. If already inside a hydration context, creates a
@@ -29,21 +38,47 @@ where
 /// a nested context.
 ///
 /// Same as [`with_hydration_context`] but allows for async futures.
+///
+/// The hydration context is only set while the future is polled. This means that several futures
+/// can be run concurrently on the same thread, e.g. to render several pages at the same time in a
+/// single-threaded server or edge runtime, without sharing their hydration keys.
 pub async fn with_hydration_context_async<F, R>(f: F) -> R
 where
     F: std::future::Future<Output = R>,
 {
-    let mut prev = None;
+    let mut f = Box::pin(f);
+    let mut registry = HydrationRegistry::new();
+    std::future::poll_fn(move |cx| {
+        // Save previous context to restore after polling.
+        let prev = HYDRATION_CONTEXT.with(|context| context.replace(Some(registry)));
+        let poll = f.as_mut().poll(cx);
+        if let Some(current) = HYDRATION_CONTEXT.with(|context| context.replace(prev)) {
+            registry = current;
+        }
+        poll
+    })
+    .await
+}
+
+/// Run the closure inside a new hydration context in which the current component has the id
+/// `component_id`, e.g. to hydrate a single component of a page that was rendered inside of
+/// another hydration context.
+pub fn with_component_hydration_context<F, R>(component_id: usize, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
     HYDRATION_CONTEXT.with(|context| {
         // Save previous context to restore later.
-        prev = Some(*context.borrow());
-        *context.borrow_mut() = Some(HydrationRegistry::new());
-    });
-    let r = f.await;
-    HYDRATION_CONTEXT.with(|context| {
-        *context.borrow_mut() = prev.unwrap();
-    });
-    r
+        let prev = *context.borrow();
+        *context.borrow_mut() = Some(HydrationRegistry {
+            current_component_id: component_id,
+            next_component_id: component_id + 1,
+            ..HydrationRegistry::new()
+        });
+        let r = f();
+        *context.borrow_mut() = prev;
+        r
+    })
 }
 
 /// Run the closure without a hydration context. If called within an hydration context, the old
@@ -85,6 +120,21 @@ pub fn get_current_id() -> Option<(usize, usize)> {
     })
 }
 
+/// Returns a tuple of the current component id and the next unique id within the component.
+/// Increments the unique id.
+///
+/// Unlike [`get_next_id`], this does not affect the hydration keys of the nodes.
+///
+/// If hydration context does not exist, returns `None`.
+pub fn get_next_unique_id() -> Option<(usize, usize)> {
+    HYDRATION_CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        context
+            .as_mut()
+            .map(|reg| (reg.current_component_id, reg.get_next_unique_id()))
+    })
+}
+
 /// Returns `true` if hydration has completed.
 pub fn hydration_completed() -> bool {
     HYDRATION_CONTEXT.with(|context| context.borrow().is_none())
@@ -101,6 +151,7 @@ where
     HYDRATION_CONTEXT.with(|context| {
         if context.borrow().is_some() {
             let prev_id;
+            let prev_unique_id;
             let prev_component_id;
             {
                 let mut context = context.borrow_mut();
@@ -108,14 +159,17 @@ where
                 // Store previous state to restore after component.
                 prev_component_id = context.current_component_id;
                 prev_id = context.current_id;
+                prev_unique_id = context.current_unique_id;
 
                 context.current_component_id = context.next_component_id;
                 context.next_component_id += 1;
                 context.current_id = 0; // Reset current_id to 0.
+                context.current_unique_id = 0;
             }
             let r = f();
             context.borrow_mut().as_mut().unwrap().current_component_id = prev_component_id;
             context.borrow_mut().as_mut().unwrap().current_id = prev_id;
+            context.borrow_mut().as_mut().unwrap().current_unique_id = prev_unique_id;
             r
         } else {
             f()
@@ -128,6 +182,9 @@ where
 pub struct HydrationRegistry {
     /// The current node id. This is incremented every time a new element is created.
     pub current_id: usize,
+    /// The current unique id. This is incremented every time a unique id is generated and is
+    /// reset to `0` for every new component, like `current_id`.
+    pub current_unique_id: usize,
     /// The current component id. This is incremented every time a new component is created.
     /// Every time this is incremented, `current_id` is reset to `0`. This is to add more tolerance
     /// to hydration so that one component that doesn't hydrate correctly will not prevent other
@@ -144,6 +201,7 @@ impl HydrationRegistry {
     pub fn new() -> Self {
         Self {
             current_id: 0,
+            current_unique_id: 0,
             current_component_id: 0,
             next_component_id: 1,
         }
@@ -155,6 +213,13 @@ impl HydrationRegistry {
         self.current_id += 1;
         id
     }
+
+    /// Gets the next unique id.
+    pub fn get_next_unique_id(&mut self) -> usize {
+        let id = self.current_unique_id;
+        self.current_unique_id += 1;
+        id
+    }
 }
 
 impl Default for HydrationRegistry {
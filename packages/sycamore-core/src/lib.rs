@@ -5,14 +5,19 @@
 //!
 //! # Feature Flags
 //!
+//! - `diagnostics` (_default_) - Enables sanity checks and debugging aids in debug builds.
+//!
 //! - `hydrate` - Enables the hydration API.
 
 #![deny(missing_debug_implementations)]
 
+pub mod attributes;
 pub mod component;
+pub mod error_boundary;
 pub mod generic_node;
 #[cfg(feature = "hydrate")]
 pub mod hydrate;
 pub mod noderef;
 pub mod render;
+pub mod template;
 pub mod view;
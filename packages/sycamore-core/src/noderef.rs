@@ -1,13 +1,15 @@
-//! References to nodes in views.
+//! References to nodes in views and the removal of nodes.
 
 use std::any::Any;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::fmt;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
+use ahash::AHashSet;
 use sycamore_reactive::*;
 
 use crate::generic_node::GenericNode;
+use crate::view::View;
 
 /// A reference to a [`GenericNode`].
 /// This allows programmatically accessing the node and call imperative methods on it.
@@ -24,8 +26,17 @@ use crate::generic_node::GenericNode;
 ///     }
 /// }
 /// ```
-#[derive(Clone, PartialEq, Eq)]
-pub struct NodeRef<G: GenericNode>(Rc<RefCell<Option<G>>>);
+#[derive(Clone)]
+pub struct NodeRef<G: GenericNode>(Rc<NodeRefInner<G>>);
+
+/// A callback registered with [`NodeRef::on_set`], along with its id.
+type OnSet<G> = (usize, Box<dyn FnMut(G)>);
+
+struct NodeRefInner<G: GenericNode> {
+    node: RefCell<Option<G>>,
+    callbacks: RefCell<Vec<OnSet<G>>>,
+    next_id: Cell<usize>,
+}
 
 impl<G: GenericNode + Any> NodeRef<G> {
     /// Creates an empty [`NodeRef`].
@@ -33,7 +44,11 @@ impl<G: GenericNode + Any> NodeRef<G> {
     /// Generally, it is preferable to use [`create_node_ref`]
     /// instead.
     pub fn new() -> Self {
-        Self(Rc::new(RefCell::new(None)))
+        Self(Rc::new(NodeRefInner {
+            node: RefCell::new(None),
+            callbacks: RefCell::new(Vec::new()),
+            next_id: Cell::new(0),
+        }))
     }
 
     /// Gets the T stored inside the [`NodeRef`].
@@ -52,7 +67,7 @@ impl<G: GenericNode + Any> NodeRef<G> {
     ///
     /// For a panicking version, see [`NodeRef::get`].
     pub fn try_get<T: GenericNode>(&self) -> Option<T> {
-        let obj = self.0.borrow();
+        let obj = self.0.node.borrow();
         (obj.as_ref()? as &dyn Any).downcast_ref().cloned()
     }
 
@@ -72,7 +87,7 @@ impl<G: GenericNode + Any> NodeRef<G> {
     ///
     /// For a panicking version, see [`NodeRef::get`].
     pub fn try_get_raw(&self) -> Option<G> {
-        self.0.borrow().clone()
+        self.0.node.borrow().clone()
     }
 
     /// Sets the [`NodeRef`] with the specified [`GenericNode`].
@@ -80,10 +95,102 @@ impl<G: GenericNode + Any> NodeRef<G> {
     /// This method should be rarely used. Instead, use the `ref=` syntax in the `view!` macro to
     /// set the node.
     pub fn set(&self, node: G) {
-        *self.0.borrow_mut() = Some(node);
+        *self.0.node.borrow_mut() = Some(node.clone());
+        // Callbacks can register or remove other callbacks, so they are called without borrowing
+        // the list.
+        let mut callbacks = std::mem::take(&mut *self.0.callbacks.borrow_mut());
+        for (_, f) in &mut callbacks {
+            f(node.clone());
+        }
+        let mut current = self.0.callbacks.borrow_mut();
+        callbacks.append(&mut current);
+        *current = callbacks;
+    }
+
+    /// Calls `f` with the node every time the [`NodeRef`] is set, until `cx` is disposed. If the
+    /// [`NodeRef`] is already set, `f` is also called right away.
+    ///
+    /// The node is set as soon as it is created, which can be before it is inserted into the
+    /// document. To access the node once it is in the document, use `on_mount` instead, which is
+    /// called after all the nodes of the component are set.
+    ///
+    /// # Example
+    /// ```
+    /// use sycamore::prelude::*;
+    ///
+    /// #[component]
+    /// fn Component<G: Html>(cx: Scope) -> View<G> {
+    ///     let my_div = create_node_ref(cx);
+    ///     my_div.on_set(cx, |node: G| {
+    ///         // Initialize a third-party library with the node.
+    ///     });
+    ///     view! { cx,
+    ///         div(ref=my_div)
+    ///     }
+    /// }
+    /// ```
+    pub fn on_set<'a>(&self, cx: Scope<'a>, mut f: impl FnMut(G) + 'a) {
+        if let Some(node) = self.try_get_raw() {
+            f(node);
+        }
+        let id = self.0.next_id.get();
+        self.0.next_id.set(id + 1);
+        let f: Box<dyn FnMut(G) + 'a> = Box::new(f);
+        // SAFETY: `f` is removed from the callbacks when `cx` is disposed, so it is never called
+        // after `'a` ends.
+        let f: Box<dyn FnMut(G) + 'static> = unsafe { std::mem::transmute(f) };
+        self.0.callbacks.borrow_mut().push((id, f));
+        let inner = Rc::clone(&self.0);
+        on_cleanup(cx, move || {
+            inner
+                .callbacks
+                .borrow_mut()
+                .retain(|(other, _)| *other != id);
+        });
+    }
+
+    /// Calls `f` with the node when `cx` is disposed. When the node is removed from the document by
+    /// a change in the view, this happens right before the node is removed, so it is still attached
+    /// when `f` is called.
+    ///
+    /// The node is removed once the [`Removal`] passed to `f` is dropped. Keeping it, e.g. until an
+    /// exit animation is done, defers the removal. This only works for the nodes that are removed
+    /// directly, such as the root node of an item in a list: a node that is removed along with its
+    /// parent is removed right away.
+    ///
+    /// # Example
+    /// ```
+    /// use sycamore::prelude::*;
+    ///
+    /// #[component]
+    /// fn Component<G: Html>(cx: Scope) -> View<G> {
+    ///     let my_div = create_node_ref(cx);
+    ///     my_div.on_destroy(cx, |node: G, removal| {
+    ///         // Start an exit animation and drop `removal` when it is done.
+    ///     });
+    ///     view! { cx,
+    ///         div(ref=my_div)
+    ///     }
+    /// }
+    /// ```
+    pub fn on_destroy<'a>(&self, cx: Scope<'a>, f: impl FnOnce(G, Removal<G>) + 'a) {
+        let node_ref = self.clone();
+        on_cleanup(cx, move || {
+            if let Some(node) = node_ref.try_get_raw() {
+                f(node.clone(), Removal::new(node));
+            }
+        });
     }
 }
 
+impl<G: GenericNode> PartialEq for NodeRef<G> {
+    fn eq(&self, other: &Self) -> bool {
+        *self.0.node.borrow() == *other.0.node.borrow()
+    }
+}
+
+impl<G: GenericNode> Eq for NodeRef<G> {}
+
 impl<G: GenericNode> Default for NodeRef<G> {
     fn default() -> Self {
         Self::new()
@@ -92,7 +199,204 @@ impl<G: GenericNode> Default for NodeRef<G> {
 
 impl<G: GenericNode> fmt::Debug for NodeRef<G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_tuple("NodeRef").field(&self.0.borrow()).finish()
+        f.debug_tuple("NodeRef")
+            .field(&self.0.node.borrow())
+            .finish()
+    }
+}
+
+/// A value that can be passed to `ref=` on an element in the `view!` macro: a [`NodeRef`], which is
+/// set to the element, or a callback, which is called with the element.
+///
+/// An element can have several `ref=`, which are bound in order as soon as the element is created,
+/// before its children are created.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[component]
+/// fn Component<G: Html>(cx: Scope) -> View<G> {
+///     let my_div = create_node_ref(cx);
+///     view! { cx,
+///         div(ref=my_div, ref=|node| {
+///             // Initialize a third-party library with the node.
+///             let _: G = node;
+///         })
+///     }
+/// }
+/// ```
+pub trait BindRef<G: GenericNode> {
+    /// Binds `node` to the ref.
+    fn bind_ref(&self, node: G);
+}
+
+impl<G: GenericNode> BindRef<G> for NodeRef<G> {
+    fn bind_ref(&self, node: G) {
+        self.set(node);
+    }
+}
+
+impl<G: GenericNode, F: Fn(G)> BindRef<G> for F {
+    fn bind_ref(&self, node: G) {
+        self(node);
+    }
+}
+
+/// Calls a closure passed to `ref=` in the `view!` macro. Unlike [`BindRef`], this lets the type of
+/// the parameter of the closure be inferred.
+#[doc(hidden)]
+pub fn bind_ref_callback<G: GenericNode>(f: impl FnOnce(G), node: G) {
+    f(node);
+}
+
+thread_local! {
+    /// The pending [`Removal`]s, as `Weak<RemovalInner<G>>`s of any node type.
+    static REMOVALS: RefCell<Vec<Box<dyn Any>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A handle to the removal of a node that is passed to the callbacks of [`NodeRef::on_destroy`].
+/// The node is removed from the document when the handle is dropped.
+pub struct Removal<G: GenericNode>(Rc<RemovalInner<G>>);
+
+struct RemovalInner<G: GenericNode> {
+    node: G,
+    /// The parent to remove the node from, set when the node would have been removed.
+    parent: RefCell<Option<G>>,
+}
+
+impl<G: GenericNode> Removal<G> {
+    pub(crate) fn new(node: G) -> Self {
+        let inner = Rc::new(RemovalInner {
+            node,
+            parent: RefCell::new(None),
+        });
+        REMOVALS.with(|removals| removals.borrow_mut().push(Box::new(Rc::downgrade(&inner))));
+        Self(inner)
+    }
+
+    /// Removes the node now. This is the same as dropping the handle.
+    pub fn remove(self) {}
+}
+
+impl<G: GenericNode> fmt::Debug for Removal<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Removal").field(&self.0.node).finish()
+    }
+}
+
+impl<G: GenericNode> Drop for RemovalInner<G> {
+    fn drop(&mut self) {
+        if let Some(parent) = self.parent.take() {
+            if self.node.parent_node().as_ref() == Some(&parent) {
+                parent.remove_child(&self.node);
+            }
+        }
+    }
+}
+
+/// Returns `true` if a [`Removal`] of `node` is still held, in which case `node` is removed from
+/// `parent` when it is dropped instead of now.
+pub(crate) fn defer_removal<G: GenericNode>(parent: &G, node: &G) -> bool {
+    let deferred = REMOVALS.with(|removals| {
+        let mut removals = removals.borrow_mut();
+        let mut deferred = None;
+        removals.retain(
+            |removal| match removal.downcast_ref::<Weak<RemovalInner<G>>>() {
+                Some(removal) => match removal.upgrade() {
+                    Some(removal) if deferred.is_none() && removal.node == *node => {
+                        deferred = Some(removal);
+                        false
+                    }
+                    Some(_) => true,
+                    None => false,
+                },
+                None => true,
+            },
+        );
+        deferred
+    });
+    match deferred {
+        Some(removal) => {
+            *removal.parent.borrow_mut() = Some(parent.clone());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns `true` if any [`Removal`] may still be held.
+pub(crate) fn has_removals() -> bool {
+    REMOVALS.with(|removals| !removals.borrow().is_empty())
+}
+
+thread_local! {
+    /// The number of exit handlers that are provided, to skip looking for one when there are none.
+    static EXIT_HANDLERS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// An exit handler provided with [`provide_exit_handler`].
+struct ExitHandler<G: GenericNode>(Box<dyn Fn(G, Removal<G>)>);
+
+/// Provides a handler for the nodes that are removed by the dynamic views in the current [`Scope`]
+/// and its children, e.g. when the branch of an `if` changes. The handler is called for every
+/// removed node before it is removed, while it is still attached.
+///
+/// Like with [`NodeRef::on_destroy`], the node is removed once the [`Removal`] passed to the
+/// handler is dropped. Keeping it, e.g. until an exit animation is done, defers the removal.
+///
+/// Providing another handler in a nested scope replaces this one for the dynamic views of the
+/// nested scope.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[component]
+/// fn Component<G: Html>(cx: Scope) -> View<G> {
+///     let show = create_signal(cx, true);
+///     provide_exit_handler(cx, |node: G, removal| {
+///         // Start an exit animation and drop `removal` when it is done.
+///     });
+///     view! { cx,
+///         div {
+///             (if *show.get() { view! { cx, p { "Shown" } } } else { view! { cx, } })
+///         }
+///     }
+/// }
+/// ```
+pub fn provide_exit_handler<'a, G: GenericNode>(cx: Scope<'a>, f: impl Fn(G, Removal<G>) + 'a) {
+    let f: Box<dyn Fn(G, Removal<G>) + 'a> = Box::new(f);
+    // SAFETY: The handler is only called by the dynamic views of `cx` and its children, which are
+    // disposed before `cx`.
+    let f: Box<dyn Fn(G, Removal<G>) + 'static> = unsafe { std::mem::transmute(f) };
+    replace_context(cx, ExitHandler(f));
+    EXIT_HANDLERS.with(|handlers| handlers.set(handlers.get() + 1));
+    on_cleanup(cx, || {
+        EXIT_HANDLERS.with(|handlers| handlers.set(handlers.get() - 1))
+    });
+}
+
+/// Calls the exit handler of `cx`, if any, with the nodes of `current` under `parent` that are not
+/// in `new`.
+pub(crate) fn handle_exits<G: GenericNode>(
+    cx: Scope<'_>,
+    parent: &G,
+    current: &View<G>,
+    new: &View<G>,
+) {
+    if EXIT_HANDLERS.with(Cell::get) == 0 {
+        return;
+    }
+    if let Some(handler) = try_use_context::<ExitHandler<G>>(cx) {
+        untrack(|| {
+            let new: AHashSet<G> = new.clone().flatten().into_iter().collect();
+            for node in current.clone().flatten() {
+                if !new.contains(&node) && node.parent_node().as_ref() == Some(parent) {
+                    (handler.0)(node.clone(), Removal::new(node));
+                }
+            }
+        });
     }
 }
 
@@ -102,3 +406,41 @@ impl<G: GenericNode> fmt::Debug for NodeRef<G> {
 pub fn create_node_ref<G: GenericNode>(cx: Scope<'_>) -> &NodeRef<G> {
     create_ref(cx, NodeRef::new())
 }
+
+/// Creates a new [`NodeRef`] on the current [`Scope`] that sets all the `refs` when it is set. This
+/// puts several refs on the same node, e.g. the ref forwarded to a component with `ref=` and a ref
+/// of the component itself.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[derive(Prop)]
+/// struct MyButtonProps<G: GenericNode> {
+///     /// Set by `MyButton(ref=node_ref)` in `view!`.
+///     #[builder(default)]
+///     node_ref: NodeRef<G>,
+/// }
+///
+/// #[component]
+/// fn MyButton<G: Html>(cx: Scope, props: MyButtonProps<G>) -> View<G> {
+///     let button = create_node_ref(cx);
+///     let node_ref = merge_node_refs(cx, [props.node_ref, button.clone()]);
+///     view! { cx,
+///         button(ref=node_ref) { "Click" }
+///     }
+/// }
+/// ```
+pub fn merge_node_refs<G: GenericNode>(
+    cx: Scope<'_>,
+    refs: impl IntoIterator<Item = NodeRef<G>>,
+) -> &NodeRef<G> {
+    let merged = create_node_ref(cx);
+    let refs: Vec<_> = refs.into_iter().collect();
+    merged.on_set(cx, move |node: G| {
+        for node_ref in &refs {
+            node_ref.set(node.clone());
+        }
+    });
+    merged
+}
@@ -6,6 +6,7 @@ use ahash::AHashMap;
 use sycamore_reactive::*;
 
 use crate::generic_node::GenericNode;
+use crate::noderef::{defer_removal, handle_exits, has_removals};
 use crate::view::{View, ViewType};
 
 /// Insert a [`GenericNode`] under `parent` at the specified `marker`. If `initial` is `Some(_)`,
@@ -69,11 +70,16 @@ fn insert_expression<G: GenericNode>(
             let parent = parent.clone();
             let marker = marker.cloned();
             let f = f.clone();
+            let component = crate::component::current_component();
+            let mut initial = true;
             create_effect_scoped(cx, move |cx| {
                 let mut value = f.get();
                 while let ViewType::Dyn(f) = &value.inner {
                     value = f.get();
                 }
+                if let Some(current) = current.as_ref().filter(|_| !initial) {
+                    handle_exits(cx, &parent, current, &value);
+                }
                 insert_expression(
                     cx,
                     &parent,
@@ -83,6 +89,10 @@ fn insert_expression<G: GenericNode>(
                     false,
                     multi,
                 );
+                if cfg!(all(debug_assertions, feature = "diagnostics")) && !initial {
+                    G::view_updated(&value.as_ref().clone().flatten(), component);
+                }
+                initial = false;
                 current = Some(value.as_ref().clone());
             });
         }
@@ -162,7 +172,16 @@ pub fn clean_children<G: GenericNode>(
     multi: bool,
 ) {
     if !multi {
-        parent.update_inner_text("");
+        if has_removals() {
+            // Remove the nodes one by one to keep the ones whose removal is deferred.
+            let mut child = parent.first_child();
+            while let Some(node) = child {
+                child = node.next_sibling();
+                remove_child(parent, &node);
+            }
+        } else {
+            parent.update_inner_text("");
+        }
         if let Some(replacement) = replacement {
             parent.append_child(replacement);
         }
@@ -172,14 +191,32 @@ pub fn clean_children<G: GenericNode>(
     for node in current {
         if node.parent_node().as_ref() == Some(parent) {
             if let Some(replacement) = replacement {
-                parent.replace_child(&node, replacement);
+                replace_child(parent, &node, replacement);
             } else {
-                parent.remove_child(&node);
+                remove_child(parent, &node);
             }
         }
     }
 }
 
+/// Removes `node` from `parent`, unless its removal is deferred with
+/// [`NodeRef::on_destroy`](crate::noderef::NodeRef::on_destroy).
+fn remove_child<G: GenericNode>(parent: &G, node: &G) {
+    if !defer_removal(parent, node) {
+        parent.remove_child(node);
+    }
+}
+
+/// Replaces `old` with `new` in `parent`. If the removal of `old` is deferred, `new` is inserted
+/// before it instead.
+fn replace_child<G: GenericNode>(parent: &G, old: &G, new: &G) {
+    if defer_removal(parent, old) {
+        parent.insert_child_before(new, Some(old));
+    } else {
+        parent.replace_child(old, new);
+    }
+}
+
 /// Appends all the nodes in `fragment` to `parent` behind `marker`.
 pub fn append_nodes<G: GenericNode>(parent: &G, fragment: Vec<G>, marker: Option<&G>) {
     for node in fragment {
@@ -248,7 +285,7 @@ pub fn reconcile_fragments<G: GenericNode>(parent: &G, a: &mut [G], b: &[G]) {
     debug_assert!(!a.is_empty(), "a cannot be empty");
 
     // Sanity check: make sure all nodes in a are children of parent.
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "diagnostics"))]
     {
         for (i, node) in a.iter().enumerate() {
             if node.parent_node().as_ref() != Some(parent) {
@@ -291,7 +328,7 @@ pub fn reconcile_fragments<G: GenericNode>(parent: &G, a: &mut [G], b: &[G]) {
             // Remove.
             for node in &a[a_start..a_end] {
                 if map.is_none() || !map.as_ref().unwrap().contains_key(node) {
-                    parent.remove_child(node);
+                    remove_child(parent, node);
                 }
             }
             a_start = a_end;
@@ -347,7 +384,7 @@ pub fn reconcile_fragments<G: GenericNode>(parent: &G, a: &mut [G], b: &[G]) {
                             b_start += 1;
                         }
                     } else {
-                        parent.replace_child(&a[a_start], &b[b_start]);
+                        replace_child(parent, &a[a_start], &b[b_start]);
                         a_start += 1;
                         b_start += 1;
                     }
@@ -355,14 +392,14 @@ pub fn reconcile_fragments<G: GenericNode>(parent: &G, a: &mut [G], b: &[G]) {
                     a_start += 1;
                 }
             } else {
-                parent.remove_child(&a[a_start]);
+                remove_child(parent, &a[a_start]);
                 a_start += 1;
             }
         }
     }
 
     // Sanity check: make sure all nodes in b are children of parent after reconciliation.
-    #[cfg(debug_assertions)]
+    #[cfg(all(debug_assertions, feature = "diagnostics"))]
     {
         for (i, node) in b.iter().enumerate() {
             if node.parent_node().as_ref() != Some(parent) {
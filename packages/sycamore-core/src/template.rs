@@ -0,0 +1,175 @@
+//! Views that are built once and instantiated many times.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use sycamore_reactive::*;
+
+use crate::generic_node::GenericNode;
+use crate::render::insert;
+use crate::view::View;
+
+/// A view that is built once and then instantiated many times by cloning its nodes, which is
+/// cheaper than creating the view again, e.g. for the rows of a large table. The dynamic parts of
+/// the view are named holes that are filled with a different view for every instance.
+///
+/// Only the nodes of the template are cloned: the event handlers, node refs and reactive parts of
+/// the view that the template is built from are not copied to the instances. These belong in the
+/// holes instead. Instances are not hydrated.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::template::Template;
+///
+/// #[component]
+/// fn Rows<G: Html>(cx: Scope) -> View<G> {
+///     let row = Template::new(|cx, holes| view! { cx,
+///         tr(class="row") {
+///             td(class="name") { (holes.hole("name")) }
+///             td(class="count") { (holes.hole("count")) }
+///         }
+///     });
+///     let rows = (0..1000)
+///         .map(|i| {
+///             row.instantiate(cx, |hole| match hole {
+///                 "name" => view! { cx, "Row " (i) },
+///                 _ => view! { cx, (i * 2) },
+///             })
+///         })
+///         .collect();
+///     View::new_fragment(rows)
+/// }
+/// ```
+pub struct Template<G: GenericNode> {
+    nodes: Vec<G>,
+    holes: Vec<Hole>,
+}
+
+/// A hole in a [`Template`].
+#[derive(Debug)]
+struct Hole {
+    name: String,
+    /// The index of the root node of the template, followed by the indices of the children to go
+    /// through to reach the marker of the hole.
+    path: Vec<usize>,
+}
+
+/// Creates the holes of a [`Template`] while it is built.
+pub struct Holes<G: GenericNode>(RefCell<Vec<(String, G)>>);
+
+impl<G: GenericNode> Holes<G> {
+    /// Returns a hole named `name` to put in the view of the template.
+    pub fn hole(&self, name: &str) -> View<G> {
+        let marker = G::marker();
+        self.0.borrow_mut().push((name.to_string(), marker.clone()));
+        View::new_node(marker)
+    }
+}
+
+impl<G: GenericNode> fmt::Debug for Holes<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.borrow().iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+impl<G: GenericNode> Template<G> {
+    /// Builds a template from the view returned by `f`, with the holes created with
+    /// [`Holes::hole`].
+    ///
+    /// # Panics
+    /// Panics if a hole is not part of the view.
+    pub fn new(f: impl for<'a> FnOnce(Scope<'a>, &'a Holes<G>) -> View<G>) -> Self {
+        let holes = Rc::new(Holes(RefCell::new(Vec::new())));
+        let mut nodes = Vec::new();
+        create_scope_immediate(|cx| {
+            let holes = create_ref(cx, Rc::clone(&holes));
+            nodes = f(cx, holes).flatten();
+        });
+        let holes = holes
+            .0
+            .take()
+            .into_iter()
+            .map(|(name, marker)| Hole {
+                path: find_path(&nodes, &marker)
+                    .unwrap_or_else(|| panic!("hole `{name}` is not part of the template")),
+                name,
+            })
+            .collect();
+        Self { nodes, holes }
+    }
+
+    /// Returns the names of the holes of the template, in the order in which they were created.
+    pub fn holes(&self) -> impl Iterator<Item = &str> {
+        self.holes.iter().map(|hole| hole.name.as_str())
+    }
+
+    /// Creates a new instance of the template, filling every hole with the view returned by `fill`
+    /// for the name of the hole.
+    pub fn instantiate(&self, cx: Scope<'_>, mut fill: impl FnMut(&str) -> View<G>) -> View<G> {
+        let nodes: Vec<G> = self.nodes.iter().map(G::clone_node).collect();
+        // Find all the markers before filling the holes, which changes the indices of the nodes.
+        let markers: Vec<G> = self
+            .holes
+            .iter()
+            .map(|hole| resolve_path(&nodes, &hole.path))
+            .collect();
+        let mut views: Vec<View<G>> = nodes.into_iter().map(View::new_node).collect();
+        for (hole, marker) in self.holes.iter().zip(markers) {
+            let view = fill(&hole.name);
+            match marker.parent_node() {
+                Some(parent) => insert(cx, &parent, view, None, Some(&marker), true),
+                // The hole is a root node of the template.
+                None => views[hole.path[0]] = view,
+            }
+        }
+        View::new_fragment(views)
+    }
+}
+
+impl<G: GenericNode> fmt::Debug for Template<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Template")
+            .field("nodes", &self.nodes)
+            .field("holes", &self.holes)
+            .finish()
+    }
+}
+
+/// Returns the path of `marker` in `nodes`, as stored in [`Hole::path`].
+fn find_path<G: GenericNode>(nodes: &[G], marker: &G) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    let mut node = marker.clone();
+    while let Some(parent) = node.parent_node() {
+        let mut index = 0;
+        let mut child = parent.first_child();
+        while let Some(current) = child {
+            if current == node {
+                break;
+            }
+            index += 1;
+            child = current.next_sibling();
+        }
+        path.push(index);
+        node = parent;
+    }
+    path.push(nodes.iter().position(|root| *root == node)?);
+    path.reverse();
+    Some(path)
+}
+
+/// Returns the node at `path` in `nodes`.
+fn resolve_path<G: GenericNode>(nodes: &[G], path: &[usize]) -> G {
+    let mut node = nodes[path[0]].clone();
+    for &index in &path[1..] {
+        let mut child = node.first_child().unwrap();
+        for _ in 0..index {
+            child = child.next_sibling().unwrap();
+        }
+        node = child;
+    }
+    node
+}
@@ -160,6 +160,12 @@ impl<G: GenericNode> Default for View<G> {
     }
 }
 
+impl<G: GenericNode> FromIterator<View<G>> for View<G> {
+    fn from_iter<I: IntoIterator<Item = View<G>>>(iter: I) -> Self {
+        Self::new_fragment(iter.into_iter().collect())
+    }
+}
+
 impl<G: GenericNode> fmt::Debug for View<G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.inner {
@@ -242,3 +248,70 @@ impl<T: fmt::Display + 'static, G: GenericNode> IntoView<G> for T {
         View::new_node(G::text_node(&t))
     }
 }
+
+/// Interpolation of values in the `view!` macro. Not intended for use by end-users.
+///
+/// Besides [`IntoView`] values, the `view!` macro accepts collections of [`View`]s (e.g.
+/// `Vec<View<G>>`) and cloneable iterators of [`View`]s (e.g. `.iter().map(...)` chains), which
+/// are rendered as static fragments. The right implementation is selected with autoref-based
+/// specialization: the receiver is `&&&Interpolate<T>` and every level of references is tried in
+/// turn.
+#[doc(hidden)]
+pub mod interpolate {
+    use std::borrow::Borrow;
+
+    use super::*;
+
+    /// A value interpolated in the `view!` macro.
+    pub struct Interpolate<'a, T: ?Sized>(pub &'a T);
+
+    impl<'a, T: ?Sized> fmt::Debug for Interpolate<'a, T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Interpolate").finish()
+        }
+    }
+
+    /// Interpolates a value implementing [`IntoView`].
+    pub trait InterpolateView<G: GenericNode> {
+        fn interpolate(&self) -> View<G>;
+    }
+
+    impl<'a, G: GenericNode, T: IntoView<G> + ?Sized> InterpolateView<G> for &&Interpolate<'a, T> {
+        fn interpolate(&self) -> View<G> {
+            self.0.create()
+        }
+    }
+
+    /// Interpolates a collection of [`View`]s as a fragment.
+    pub trait InterpolateCollection<G: GenericNode> {
+        fn interpolate(&self) -> View<G>;
+    }
+
+    impl<'a, G: GenericNode, T: ?Sized> InterpolateCollection<G> for &Interpolate<'a, T>
+    where
+        &'a T: IntoIterator,
+        <&'a T as IntoIterator>::Item: Borrow<View<G>>,
+    {
+        fn interpolate(&self) -> View<G> {
+            self.0
+                .into_iter()
+                .map(|view| view.borrow().clone())
+                .collect()
+        }
+    }
+
+    /// Interpolates a cloneable iterator of [`View`]s as a fragment.
+    pub trait InterpolateIter<G: GenericNode> {
+        fn interpolate(&self) -> View<G>;
+    }
+
+    impl<'a, G: GenericNode, T> InterpolateIter<G> for Interpolate<'a, T>
+    where
+        T: Iterator + Clone,
+        T::Item: Borrow<View<G>>,
+    {
+        fn interpolate(&self) -> View<G> {
+            self.0.clone().map(|view| view.borrow().clone()).collect()
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Cleanup callbacks that need to await, e.g. to flush a buffer or to close a connection
+//! gracefully.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::mem;
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use futures::future::{join_all, select};
+use sycamore_reactive::{on_cleanup, Scope};
+
+use crate::{sleep, spawn_local};
+
+thread_local! {
+    /// Resolved once the async cleanups that were started are done or have timed out.
+    static PENDING: RefCell<Vec<oneshot::Receiver<()>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds an async callback that is started when the scope is destroyed, like
+/// [`on_cleanup`](sycamore_reactive::on_cleanup).
+///
+/// The future returned by `f` is spawned on the current thread and driven to completion after the
+/// scope is disposed, so it cannot borrow from the scope. If it takes longer than `timeout`, it is
+/// dropped. Use [`wait_for_async_cleanups`] to wait for the cleanups that were started, e.g. before
+/// shutting down a server.
+///
+/// Except on `wasm32` targets with a JavaScript host, the timeout uses the timer of `tokio`, so
+/// the `tokio` runtime must have the time driver enabled, e.g. with
+/// `tokio::runtime::Builder::enable_time`. Otherwise, starting the cleanup panics.
+///
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// # use sycamore_futures::*;
+/// # use sycamore_reactive::*;
+/// # async fn close(_: &str) {}
+/// # async fn example() {
+/// # provide_executor_scope(async {
+/// let disposer = create_scope(|cx| {
+///     let connection = "ws://example.com";
+///     on_cleanup_async(cx, Duration::from_secs(1), move || async move {
+///         close(connection).await;
+///     });
+/// });
+/// unsafe { disposer.dispose() };
+/// wait_for_async_cleanups().await;
+/// # }).await;
+/// # }
+/// ```
+pub fn on_cleanup_async<'a, F>(cx: Scope<'a>, timeout: Duration, f: impl FnOnce() -> F + 'a)
+where
+    F: Future<Output = ()> + 'static,
+{
+    on_cleanup(cx, move || {
+        let future = f();
+        let (done, receiver) = oneshot::channel();
+        PENDING.with(|pending| pending.borrow_mut().push(receiver));
+        spawn_local(async move {
+            let _ = select(Box::pin(future), Box::pin(sleep(timeout))).await;
+            let _ = done.send(());
+        });
+    });
+}
+
+/// Resolves once all the async cleanups added with [`on_cleanup_async`] that were started on the
+/// current thread are done or have timed out, including the cleanups that are started in the
+/// meantime.
+pub async fn wait_for_async_cleanups() {
+    loop {
+        let pending = PENDING.with(|pending| mem::take(&mut *pending.borrow_mut()));
+        if pending.is_empty() {
+            return;
+        }
+        join_all(pending).await;
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use sycamore_reactive::*;
+
+    use super::*;
+    use crate::deterministic::enable_deterministic_scheduler;
+    use crate::spawn_local_scoped;
+
+    #[test]
+    fn async_cleanup_runs_after_dispose() {
+        let scheduler = enable_deterministic_scheduler();
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let disposer = create_scope(|cx| {
+            let log = Rc::clone(&log);
+            on_cleanup_async(cx, Duration::from_secs(1), move || async move {
+                log.borrow_mut().push("started");
+                sleep(Duration::from_millis(10)).await;
+                log.borrow_mut().push("flushed");
+            });
+        });
+        unsafe { disposer.dispose() };
+
+        let waited = Rc::new(Cell::new(false));
+        create_scope_immediate(|cx| {
+            spawn_local_scoped(cx, {
+                let waited = Rc::clone(&waited);
+                async move {
+                    wait_for_async_cleanups().await;
+                    waited.set(true);
+                }
+            });
+            scheduler.tick();
+            assert_eq!(*log.borrow(), ["started"]);
+            assert!(!waited.get());
+            scheduler.advance(Duration::from_millis(10));
+            assert_eq!(*log.borrow(), ["started", "flushed"]);
+            assert!(waited.get());
+        });
+    }
+
+    #[test]
+    fn async_cleanup_times_out() {
+        let scheduler = enable_deterministic_scheduler();
+        let dropped = Rc::new(Cell::new(false));
+        let disposer = create_scope(|cx| {
+            let dropped = Rc::clone(&dropped);
+            on_cleanup_async(cx, Duration::from_millis(100), move || async move {
+                let _guard = DropGuard(dropped);
+                futures::future::pending::<()>().await;
+            });
+        });
+        unsafe { disposer.dispose() };
+        scheduler.tick();
+        assert!(!dropped.get());
+        scheduler.advance(Duration::from_millis(100));
+        assert!(dropped.get());
+    }
+
+    struct DropGuard(Rc<Cell<bool>>);
+
+    impl Drop for DropGuard {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! A deterministic scheduler for reproducible tests.
+//!
+//! While a [`DeterministicScheduler`] is alive, the deferred work created through the framework
+//! primitives is not run by the browser or by `tokio`. Instead, it is queued on the current thread
+//! and only run when the test calls [`DeterministicScheduler::tick`]. This includes:
+//! - futures spawned with [`spawn_local_scoped`](crate::spawn_local_scoped), including suspense
+//!   scopes and resources,
+//! - timers, e.g. the fallback delay of `Suspense`, which use a virtual clock that is only moved
+//!   forward by [`DeterministicScheduler::advance`],
+//! - the flushes of the read and write queues of `sycamore::web::scheduler`.
+//!
+//! Work is always run in the order in which it was queued. Signal updates and effects are still
+//! run synchronously, as usual.
+//!
+//! # Example
+//! ```
+//! # use std::time::Duration;
+//! # use sycamore_futures::deterministic::*;
+//! # use sycamore_futures::*;
+//! # use sycamore_reactive::*;
+//! let scheduler = enable_deterministic_scheduler();
+//! create_scope_immediate(|cx| {
+//!     let state = create_signal(cx, "idle");
+//!     spawn_local_scoped(cx, async move {
+//!         state.set("loading");
+//!         sleep(Duration::from_millis(100)).await;
+//!         state.set("done");
+//!     });
+//!     assert_eq!(*state.get(), "idle");
+//!     scheduler.tick();
+//!     assert_eq!(*state.get(), "loading");
+//!     scheduler.advance(Duration::from_millis(100));
+//!     assert_eq!(*state.get(), "done");
+//! });
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+type Task = Box<dyn FnOnce()>;
+
+/// A future spawned while the deterministic scheduler is enabled.
+struct Spawned {
+    future: RefCell<Option<Pin<Box<dyn Future<Output = ()>>>>>,
+    woken: Arc<Woken>,
+}
+
+/// Set when the waker of a [`Spawned`] future is woken. The waker can be sent to other threads,
+/// so the future is only marked as ready here and polled on the next tick.
+struct Woken(AtomicBool);
+
+impl Wake for Woken {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A timer created by [`sleep`].
+struct Timer {
+    deadline: Duration,
+    fired: Rc<Cell<bool>>,
+    waker: Rc<RefCell<Option<Waker>>>,
+}
+
+#[derive(Default)]
+struct State {
+    now: Duration,
+    tasks: VecDeque<Task>,
+    spawned: Vec<Rc<Spawned>>,
+    /// Sorted by deadline. Timers with the same deadline are kept in the order they were created.
+    timers: Vec<Timer>,
+}
+
+thread_local! {
+    static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+fn with_state<U>(f: impl FnOnce(&mut State) -> U) -> U {
+    STATE.with(|state| {
+        f(state
+            .borrow_mut()
+            .as_mut()
+            .expect("the deterministic scheduler is not enabled"))
+    })
+}
+
+/// Returns `true` if a [`DeterministicScheduler`] is alive on the current thread.
+pub fn is_deterministic() -> bool {
+    STATE.with(|state| state.borrow().is_some())
+}
+
+/// Handle to the deterministic scheduler of the current thread. Created with
+/// [`enable_deterministic_scheduler`]. The scheduler is disabled when the handle is dropped and
+/// all the work that has not been run yet is dropped with it.
+pub struct DeterministicScheduler {
+    // Not `Send` because the scheduler is per-thread.
+    _marker: std::marker::PhantomData<*const ()>,
+}
+
+/// Enables the deterministic scheduler on the current thread. See the [module
+/// docs](self) for more details.
+///
+/// # Panics
+/// Panics if the scheduler is already enabled on the current thread.
+pub fn enable_deterministic_scheduler() -> DeterministicScheduler {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        assert!(
+            state.is_none(),
+            "the deterministic scheduler is already enabled"
+        );
+        *state = Some(State::default());
+    });
+    DeterministicScheduler {
+        _marker: std::marker::PhantomData,
+    }
+}
+
+impl DeterministicScheduler {
+    /// Runs all the work that is ready, including the work that is queued while running, until
+    /// nothing is left. Timers that have not expired are not run. Returns `true` if anything was
+    /// run.
+    pub fn tick(&self) -> bool {
+        let mut ran = false;
+        loop {
+            let mut progress = false;
+            while let Some(task) = with_state(|state| state.tasks.pop_front()) {
+                task();
+                progress = true;
+            }
+            // Futures spawned while polling are polled in the same round.
+            let mut i = 0;
+            while let Some(spawned) = with_state(|state| state.spawned.get(i).cloned()) {
+                i += 1;
+                if spawned.woken.0.swap(false, Ordering::SeqCst) {
+                    progress = true;
+                    poll(&spawned);
+                }
+            }
+            with_state(|state| {
+                state
+                    .spawned
+                    .retain(|spawned| spawned.future.borrow().is_some());
+            });
+            if !progress {
+                return ran;
+            }
+            ran = true;
+        }
+    }
+
+    /// Moves the virtual clock forward by `duration`. The timers that expire are fired in order
+    /// of their deadline, and the work that becomes ready is run with [`tick`](Self::tick) after
+    /// each of them. The work that is already ready is run first.
+    pub fn advance(&self, duration: Duration) {
+        self.tick();
+        let target = with_state(|state| state.now) + duration;
+        loop {
+            let timer = with_state(|state| {
+                if state
+                    .timers
+                    .first()
+                    .map_or(false, |timer| timer.deadline <= target)
+                {
+                    let timer = state.timers.remove(0);
+                    state.now = timer.deadline;
+                    Some(timer)
+                } else {
+                    None
+                }
+            });
+            let Some(timer) = timer else { break };
+            timer.fired.set(true);
+            let waker = timer.waker.borrow_mut().take();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+            self.tick();
+        }
+        with_state(|state| state.now = target);
+    }
+
+    /// Returns the time elapsed on the virtual clock since the scheduler was enabled.
+    pub fn now(&self) -> Duration {
+        with_state(|state| state.now)
+    }
+
+    /// Returns `true` if there is work that is ready to run or timers that have not fired yet.
+    pub fn has_pending(&self) -> bool {
+        with_state(|state| {
+            !state.tasks.is_empty()
+                || !state.timers.is_empty()
+                || state
+                    .spawned
+                    .iter()
+                    .any(|spawned| spawned.woken.0.load(Ordering::SeqCst))
+        })
+    }
+}
+
+impl Drop for DeterministicScheduler {
+    fn drop(&mut self) {
+        // Drop the pending work outside of the borrow because dropping a future can run arbitrary
+        // code, e.g. cleanup callbacks.
+        let state = STATE.with(|state| state.borrow_mut().take());
+        if let Some(state) = state {
+            for spawned in &state.spawned {
+                drop(spawned.future.borrow_mut().take());
+            }
+            drop(state);
+        }
+    }
+}
+
+impl fmt::Debug for DeterministicScheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeterministicScheduler")
+            .field("now", &self.now())
+            .finish()
+    }
+}
+
+fn poll(spawned: &Spawned) {
+    let waker = Waker::from(Arc::clone(&spawned.woken));
+    let mut cx = Context::from_waker(&waker);
+    // Take the future out while polling in case it is woken and polled recursively.
+    let future = spawned.future.borrow_mut().take();
+    if let Some(mut future) = future {
+        if future.as_mut().poll(&mut cx).is_pending() {
+            *spawned.future.borrow_mut() = Some(future);
+        }
+    }
+}
+
+/// Queues `future` to be polled by the deterministic scheduler of the current thread.
+pub(crate) fn spawn(future: Pin<Box<dyn Future<Output = ()>>>) {
+    let spawned = Rc::new(Spawned {
+        future: RefCell::new(Some(future)),
+        woken: Arc::new(Woken(AtomicBool::new(true))),
+    });
+    with_state(|state| state.spawned.push(spawned));
+}
+
+/// Queues `f` to be run on the next [`DeterministicScheduler::tick`].
+///
+/// # Panics
+/// Panics if the deterministic scheduler is not enabled.
+#[doc(hidden)]
+pub fn queue_task(f: impl FnOnce() + 'static) {
+    with_state(|state| state.tasks.push_back(Box::new(f)));
+}
+
+/// Resolves after `duration` has elapsed on the virtual clock of the deterministic scheduler.
+///
+/// # Panics
+/// Panics if the deterministic scheduler is not enabled.
+pub fn sleep(duration: Duration) -> impl Future<Output = ()> {
+    let fired = Rc::new(Cell::new(false));
+    let waker = Rc::new(RefCell::new(None));
+    with_state(|state| {
+        let deadline = state.now + duration;
+        let index = state
+            .timers
+            .partition_point(|timer| timer.deadline <= deadline);
+        state.timers.insert(
+            index,
+            Timer {
+                deadline,
+                fired: fired.clone(),
+                waker: waker.clone(),
+            },
+        );
+    });
+    Sleep { fired, waker }
+}
+
+struct Sleep {
+    fired: Rc<Cell<bool>>,
+    waker: Rc<RefCell<Option<Waker>>>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.fired.get() {
+            Poll::Ready(())
+        } else {
+            *self.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sycamore_reactive::*;
+
+    use super::*;
+    use crate::spawn_local_scoped;
+
+    #[test]
+    fn futures_run_on_tick() {
+        let scheduler = enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let log = create_signal(cx, Vec::new());
+            spawn_local_scoped(cx, async move {
+                log.modify().push(1);
+                spawn_local_scoped(cx, async move { log.modify().push(3) });
+                log.modify().push(2);
+            });
+            assert!(log.get().is_empty());
+            assert!(scheduler.tick());
+            assert_eq!(*log.get(), [1, 2, 3]);
+            assert!(!scheduler.tick());
+        });
+    }
+
+    #[test]
+    fn timers_fire_in_order() {
+        let scheduler = enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let log = create_signal(cx, Vec::new());
+            for (ms, name) in [(30, "c"), (10, "a"), (20, "b"), (10, "a2")] {
+                spawn_local_scoped(cx, async move {
+                    sleep(Duration::from_millis(ms)).await;
+                    log.modify().push((name, scheduler_now()));
+                });
+            }
+            scheduler.advance(Duration::from_millis(15));
+            assert_eq!(*log.get(), [("a", 10), ("a2", 10)]);
+            assert_eq!(scheduler.now(), Duration::from_millis(15));
+            scheduler.advance(Duration::from_millis(100));
+            assert_eq!(*log.get(), [("a", 10), ("a2", 10), ("b", 20), ("c", 30)]);
+            assert!(!scheduler.has_pending());
+        });
+    }
+
+    fn scheduler_now() -> u128 {
+        with_state(|state| state.now.as_millis())
+    }
+
+    #[test]
+    fn disposed_scope_aborts_future() {
+        let scheduler = enable_deterministic_scheduler();
+        let done = Rc::new(Cell::new(false));
+        let disposer = create_scope({
+            let done = done.clone();
+            move |cx| {
+                spawn_local_scoped(cx, async move {
+                    sleep(Duration::from_millis(10)).await;
+                    done.set(true);
+                });
+            }
+        });
+        scheduler.tick();
+        unsafe { disposer.dispose() };
+        scheduler.advance(Duration::from_millis(10));
+        assert!(!done.get());
+    }
+}
@@ -2,23 +2,37 @@
 
 #![deny(missing_debug_implementations)]
 
+mod cleanup;
+pub mod deterministic;
+mod resource;
+#[cfg(feature = "threads")]
+mod threaded;
+
+use std::fmt;
 use std::pin::Pin;
+use std::time::Duration;
 
+use futures::channel::mpsc;
 use futures::future::abortable;
-use futures::Future;
-use sycamore_reactive::{on_cleanup, Scope};
+use futures::{Future, FutureExt, StreamExt};
+use sycamore_reactive::{on_cleanup, Scope, Signal};
+#[cfg(feature = "threads")]
+pub use threaded::*;
+
+pub use crate::cleanup::*;
+pub use crate::resource::*;
 
-/// If running on `wasm32` target, does nothing. Otherwise creates a new `tokio::task::LocalSet`
-/// scope.
+/// If running on `wasm32` target with a JavaScript host, does nothing. Otherwise, including on
+/// WASI, creates a new `tokio::task::LocalSet` scope.
 ///
 /// Normally, you do not need to call this as it is handled internally by Sycamore when creating
 /// your app.
 pub async fn provide_executor_scope<U>(f: impl Future<Output = U>) -> U {
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
     {
         f.await
     }
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"))))]
     {
         let local = tokio::task::LocalSet::new();
         local.run_until(f).await
@@ -28,6 +42,9 @@ pub async fn provide_executor_scope<U>(f: impl Future<Output = U>) -> U {
 /// Spawns a `!Send` future on the current scope. If the scope is destroyed before the future is
 /// completed, it is aborted immediately. This ensures that it is impossible to access any
 /// values referencing the scope after they are destroyed.
+///
+/// If the [deterministic scheduler](deterministic) is enabled, the future is only polled when the
+/// scheduler ticks.
 pub fn spawn_local_scoped<'a>(cx: Scope<'a>, f: impl Future<Output = ()> + 'a) {
     let boxed: Pin<Box<dyn Future<Output = ()> + 'a>> = Box::pin(f);
     // SAFETY: We are just transmuting the lifetime here so that we can spawn the future.
@@ -37,10 +54,189 @@ pub fn spawn_local_scoped<'a>(cx: Scope<'a>, f: impl Future<Output = ()> + 'a) {
         unsafe { std::mem::transmute(boxed) };
     let (abortable, handle) = abortable(extended);
     on_cleanup(cx, move || handle.abort());
-    #[cfg(not(target_arch = "wasm32"))]
-    tokio::task::spawn_local(abortable);
-    #[cfg(target_arch = "wasm32")]
-    wasm_bindgen_futures::spawn_local(async move {
+    spawn_local(async move {
         let _ = abortable.await;
     });
 }
+
+/// Spawns a `'static` future that is not tied to a scope.
+fn spawn_local(f: impl Future<Output = ()> + 'static) {
+    if deterministic::is_deterministic() {
+        deterministic::spawn(Box::pin(f));
+        return;
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"))))]
+    tokio::task::spawn_local(f);
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+    wasm_bindgen_futures::spawn_local(f);
+}
+
+/// Resolves after `duration`. Uses the virtual clock if the deterministic scheduler is enabled.
+///
+/// On `wasm32` targets with a JavaScript host, this uses the global `setTimeout`, which is also
+/// available in web workers and edge runtimes. Otherwise, this uses the timer of `tokio`, so the
+/// `tokio` runtime must have the time driver enabled.
+pub async fn sleep(duration: Duration) {
+    if deterministic::is_deterministic() {
+        return deterministic::sleep(duration).await;
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"))))]
+    tokio::time::sleep(duration).await;
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+    {
+        use wasm_bindgen::prelude::*;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_name = setTimeout)]
+            fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+        }
+
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            set_timeout(&resolve, duration.as_millis() as i32);
+        });
+        let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+    }
+}
+
+/// A handle for setting a [`Signal`] from any thread. Created with [`create_signal_sender`].
+///
+/// Unlike the signal itself, the handle is `'static` and is `Send` if `T` is `Send`, which means
+/// that it can be moved into a background thread or a worker. It can also be cloned to have
+/// multiple threads update the same signal.
+pub struct SignalSender<T> {
+    sender: mpsc::UnboundedSender<T>,
+}
+
+impl<T> SignalSender<T> {
+    /// Sends a new value for the signal. The signal is set on the thread that created the handle
+    /// the next time its executor polls the receiving task.
+    ///
+    /// If the scope that created the handle has been disposed, the value is returned as an error.
+    pub fn send(&self, value: T) -> Result<(), T> {
+        self.sender
+            .unbounded_send(value)
+            .map_err(|err| err.into_inner())
+    }
+
+    /// Returns `true` if the scope that created the handle has been disposed, in which case
+    /// [`SignalSender::send`] always fails.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+impl<T> Clone for SignalSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for SignalSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalSender")
+            .field("is_closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// Creates a [`SignalSender`] that sets `signal` from any thread, e.g. with data received from a
+/// background thread or a WebSocket worker.
+///
+/// The values are marshalled onto the current thread using a channel that is read by a task
+/// spawned with [`spawn_local_scoped`]. If multiple values are received at once, only the latest
+/// one is set to avoid needless updates. The task is aborted when the scope is disposed.
+///
+/// # Example
+/// ```
+/// # use sycamore_futures::*;
+/// # use sycamore_reactive::*;
+/// # async fn example() {
+/// # provide_executor_scope(async {
+/// # create_scope(|cx| {
+/// let progress = create_signal(cx, 0);
+/// let sender = create_signal_sender(cx, progress);
+/// std::thread::spawn(move || {
+///     for i in 1..=100 {
+///         // Stop working if the scope has been disposed.
+///         if sender.send(i).is_err() {
+///             break;
+///         }
+///     }
+/// });
+/// # });
+/// # }).await;
+/// # }
+/// ```
+pub fn create_signal_sender<'a, T: 'a>(cx: Scope<'a>, signal: &'a Signal<T>) -> SignalSender<T> {
+    let (sender, mut receiver) = mpsc::unbounded();
+    spawn_local_scoped(cx, async move {
+        while let Some(mut value) = receiver.next().await {
+            // Coalesce the values that are already waiting in the channel.
+            while let Some(Some(next)) = receiver.next().now_or_never() {
+                value = next;
+            }
+            signal.set(value);
+        }
+    });
+    SignalSender { sender }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use futures::channel::oneshot;
+    use sycamore_reactive::*;
+
+    use super::*;
+
+    pub(crate) fn block_on<U>(f: impl Future<Output = U>) -> U {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(provide_executor_scope(f))
+    }
+
+    #[test]
+    fn signal_sender_from_thread() {
+        block_on(async {
+            let (done, on_done) = oneshot::channel();
+            let mut done = Some(done);
+            let disposer = create_scope(|cx| {
+                let signal = create_signal(cx, 0);
+                let sender = create_signal_sender(cx, signal);
+                std::thread::spawn(move || {
+                    for i in 1..=10 {
+                        sender.send(i).unwrap();
+                    }
+                });
+                create_effect(cx, move || {
+                    if *signal.get() == 10 {
+                        done.take().unwrap().send(()).unwrap();
+                    }
+                });
+            });
+            on_done.await.unwrap();
+            unsafe { disposer.dispose() };
+        });
+    }
+
+    #[test]
+    fn signal_sender_closed_on_dispose() {
+        block_on(async {
+            let mut sender = None;
+            let disposer = create_scope(|cx| {
+                let signal = create_signal(cx, 0);
+                sender = Some(create_signal_sender(cx, signal));
+            });
+            let sender = sender.unwrap();
+            assert!(!sender.is_closed());
+            unsafe { disposer.dispose() };
+            // The receiving task is aborted and dropped once polled.
+            tokio::task::yield_now().await;
+            assert!(sender.is_closed());
+            assert_eq!(sender.send(1), Err(1));
+        });
+    }
+}
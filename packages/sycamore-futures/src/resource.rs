@@ -0,0 +1,239 @@
+//! Async resources that are refetched every time their source changes.
+
+use std::cell::Cell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use sycamore_reactive::*;
+
+use crate::spawn_local_scoped;
+
+/// An async resource that is refetched every time its source changes. Created with
+/// [`create_resource`].
+pub struct Resource<'a, U, E> {
+    value: &'a Signal<Option<U>>,
+    error: &'a Signal<Option<E>>,
+    loading: &'a Signal<bool>,
+    /// Triggered by [`Resource::refetch`].
+    refetch: &'a Signal<()>,
+}
+
+impl<'a, U, E> Resource<'a, U, E> {
+    /// The value of the latest successful fetch, or `None` if no fetch has succeeded yet. The
+    /// previous value is kept while the resource is refetched.
+    pub fn value(&self) -> &'a ReadSignal<Option<U>> {
+        self.value
+    }
+
+    /// The error of the latest fetch, or `None` if it succeeded.
+    pub fn error(&self) -> &'a ReadSignal<Option<E>> {
+        self.error
+    }
+
+    /// Whether a fetch is in progress.
+    pub fn loading(&self) -> &'a ReadSignal<bool> {
+        self.loading
+    }
+
+    /// Fetches the resource again with the current value of the source.
+    pub fn refetch(&self) {
+        self.refetch.trigger_subscribers();
+    }
+}
+
+impl<'a, U: fmt::Debug, E: fmt::Debug> fmt::Debug for Resource<'a, U, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resource")
+            .field("value", &*self.value.get_untracked())
+            .field("error", &*self.error.get_untracked())
+            .field("loading", &*self.loading.get_untracked())
+            .finish()
+    }
+}
+
+/// Spawns the fetches of the resources created with [`create_resource`], instead of
+/// [`spawn_local_scoped`]. Provided as a context, e.g. by the `Suspense` component of `sycamore`
+/// so that it awaits the fetches that are started while it is rendered.
+#[derive(Clone, Copy)]
+pub struct ResourceSpawner(pub for<'a> fn(Scope<'a>, Pin<Box<dyn Future<Output = ()> + 'a>>));
+
+impl fmt::Debug for ResourceSpawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceSpawner").finish()
+    }
+}
+
+/// Creates a [`Resource`] that calls `fetcher` with the value returned by `source`, and calls it
+/// again every time the signals tracked by `source` change.
+///
+/// Only the result of the latest fetch is kept: the results of fetches that were started before
+/// the source changed again are ignored.
+///
+/// The fetches are spawned with the [`ResourceSpawner`] provided in the context, or with
+/// [`spawn_local_scoped`] if there is none. With `sycamore`, the fetches started while a `Suspense`
+/// is being rendered, usually the first one, are awaited by that `Suspense`. Later fetches do not
+/// suspend the page, so the previous value keeps being displayed while [`Resource::loading`] is
+/// `true`.
+///
+/// # Example
+/// ```
+/// # use sycamore_futures::*;
+/// # use sycamore_reactive::*;
+/// async fn fetch_user(id: u32) -> Result<String, String> {
+///     // Fetch the user from the server...
+///     Ok(format!("User {id}"))
+/// }
+///
+/// # async fn example() {
+/// # provide_executor_scope(async {
+/// # create_scope(|cx| {
+/// let id = create_signal(cx, 1);
+/// let user = create_resource(cx, || *id.get(), fetch_user);
+/// create_effect(cx, || {
+///     if let Some(name) = user.value().get().as_ref() {
+///         println!("{name}");
+///     }
+/// });
+/// // Fetches the other user.
+/// id.set(2);
+/// # });
+/// # }).await;
+/// # }
+/// ```
+pub fn create_resource<'a, S, U, E, Src, F, Fut>(
+    cx: Scope<'a>,
+    source: Src,
+    fetcher: F,
+) -> &'a Resource<'a, U, E>
+where
+    S: 'a,
+    U: 'a,
+    E: 'a,
+    Src: Fn() -> S + 'a,
+    F: Fn(S) -> Fut + 'a,
+    Fut: Future<Output = Result<U, E>> + 'a,
+{
+    let resource = create_ref(
+        cx,
+        Resource {
+            value: create_signal(cx, None),
+            error: create_signal(cx, None),
+            loading: create_signal(cx, false),
+            refetch: create_signal(cx, ()),
+        },
+    );
+    let latest = create_ref(cx, Cell::new(0u32));
+    let fetcher = create_ref(cx, fetcher);
+    let spawn = try_use_context::<ResourceSpawner>(cx).map(|spawner| spawner.0);
+
+    create_effect(cx, move || {
+        resource.refetch.track();
+        let source = source();
+        untrack(|| {
+            let id = latest.get().wrapping_add(1);
+            latest.set(id);
+            resource.loading.set(true);
+            let fut = fetcher(source);
+            let fut = async move {
+                let result = fut.await;
+                if latest.get() != id {
+                    // The source changed while fetching.
+                    return;
+                }
+                match result {
+                    Ok(value) => {
+                        resource.value.set(Some(value));
+                        resource.error.set(None);
+                    }
+                    Err(err) => resource.error.set(Some(err)),
+                }
+                resource.loading.set(false);
+            };
+            match spawn {
+                Some(spawn) => spawn(cx, Box::pin(fut)),
+                None => spawn_local_scoped(cx, fut),
+            }
+        });
+    });
+
+    resource
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use futures::channel::oneshot;
+
+    use super::*;
+    use crate::tests::block_on;
+
+    #[test]
+    fn latest_fetch_wins() {
+        block_on(async {
+            let (sender, receiver) = oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let id = create_signal(cx, 1);
+                let (first, first_result) = oneshot::channel::<Result<u32, ()>>();
+                let results = create_ref(cx, std::cell::RefCell::new(vec![first_result]));
+                let resource = create_resource(
+                    cx,
+                    || *id.get(),
+                    |id| {
+                        let result = results.borrow_mut().pop();
+                        async move {
+                            match result {
+                                Some(result) => result.await.unwrap(),
+                                None => Ok(id * 10),
+                            }
+                        }
+                    },
+                );
+                // Changing the source starts a new fetch.
+                id.set(2);
+                spawn_local_scoped(cx, async move {
+                    while *resource.loading().get() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*resource.value().get(), Some(20));
+                    // The result of the first fetch is ignored.
+                    first.send(Ok(10)).unwrap();
+                    tokio::task::yield_now().await;
+                    assert_eq!(*resource.value().get(), Some(20));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        });
+    }
+
+    #[test]
+    fn spawner_from_context() {
+        thread_local! {
+            static SPAWNED: Cell<u32> = const { Cell::new(0) };
+        }
+        fn spawn<'a>(cx: Scope<'a>, f: Pin<Box<dyn Future<Output = ()> + 'a>>) {
+            SPAWNED.with(|spawned| spawned.set(spawned.get() + 1));
+            spawn_local_scoped(cx, f);
+        }
+
+        block_on(async {
+            let (sender, receiver) = oneshot::channel();
+            let disposer = create_scope(|cx| {
+                provide_context(cx, ResourceSpawner(spawn));
+                let resource = create_resource(cx, || (), |()| async { Ok::<_, ()>(1) });
+                resource.refetch();
+                spawn_local_scoped(cx, async move {
+                    while *resource.loading().get() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*resource.value().get(), Some(1));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        });
+        assert_eq!(SPAWNED.with(Cell::get), 2);
+    }
+}
@@ -0,0 +1,173 @@
+//! Derived values that are computed on a thread pool, keeping the current thread free during
+//! large computations.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use sycamore_reactive::{create_effect, create_signal, ReadSignal, Scope};
+
+use crate::spawn_local_scoped;
+
+/// Creates a memoized value that is computed on the [`rayon`] global thread pool. The returned
+/// signal is `initial` until the first computation finishes.
+///
+/// `input` runs on the current thread and is tracked like the function of a
+/// [`create_memo`](sycamore_reactive::create_memo): it should read the signals that the
+/// computation depends on and return the data that it needs. `compute` must be a pure function of
+/// that data. The data is moved to the thread pool, so it must be owned, e.g. cloned out of the
+/// signals. Large data can be stored in an [`Arc`] to avoid copying it. `compute` runs on the
+/// thread pool whenever the input changes and its result is set on the returned signal by a task
+/// spawned with [`spawn_local_scoped`].
+///
+/// If the input changes before a computation finishes, the result of that computation is
+/// discarded, so the signal never goes back to an outdated value. Computations that have not
+/// started yet are skipped.
+///
+/// On `wasm32`, the app must be built with support for threads (the `atomics` and `bulk-memory`
+/// target features) and the thread pool must be started before the first computation, e.g. with
+/// the `init_thread_pool` function of `wasm-bindgen-rayon`. Pages that use threads must be served
+/// with the headers that enable `SharedArrayBuffer`.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use sycamore_futures::*;
+/// # use sycamore_reactive::*;
+/// # fn example(cx: Scope) {
+/// let rows = create_signal(cx, Arc::new((0..100_000).collect::<Vec<u64>>()));
+/// let total = create_memo_threaded(
+///     cx,
+///     0,
+///     || Arc::clone(&rows.get()),
+///     |rows| rows.iter().map(|row| row * row).sum::<u64>(),
+/// );
+/// # }
+/// ```
+pub fn create_memo_threaded<'a, I, T>(
+    cx: Scope<'a>,
+    initial: T,
+    mut input: impl FnMut() -> I + 'a,
+    compute: impl Fn(I) -> T + Send + Sync + 'static,
+) -> &'a ReadSignal<T>
+where
+    I: Send + 'static,
+    T: Send + 'static,
+{
+    let signal = create_signal(cx, initial);
+    let (sender, mut receiver) = mpsc::unbounded::<(u64, T)>();
+    let compute = Arc::new(compute);
+    // The generation of the latest input. Results of older generations are discarded.
+    let latest = Arc::new(AtomicU64::new(0));
+
+    create_effect(cx, {
+        let latest = Arc::clone(&latest);
+        move || {
+            let input = input();
+            let generation = latest.fetch_add(1, Ordering::SeqCst) + 1;
+            let compute = Arc::clone(&compute);
+            let latest = Arc::clone(&latest);
+            let sender = sender.clone();
+            rayon::spawn(move || {
+                if latest.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                // The receiving task is aborted if the scope has been disposed.
+                let _ = sender.unbounded_send((generation, compute(input)));
+            });
+        }
+    });
+
+    spawn_local_scoped(cx, async move {
+        while let Some((generation, value)) = receiver.next().await {
+            if latest.load(Ordering::SeqCst) == generation {
+                signal.set(value);
+            }
+        }
+    });
+
+    signal
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::sync::{mpsc as std_mpsc, Mutex};
+
+    use futures::channel::oneshot;
+    use sycamore_reactive::*;
+
+    use super::*;
+    use crate::tests::block_on;
+
+    #[test]
+    fn computes_on_thread_pool() {
+        block_on(async {
+            let (done, on_done) = oneshot::channel();
+            let mut done = Some(done);
+            let disposer = create_scope(|cx| {
+                let n = create_signal(cx, 10u64);
+                let sum = create_memo_threaded(cx, 0, || *n.get(), |n| (1..=n).sum::<u64>());
+                assert_eq!(*sum.get(), 0);
+                create_effect(cx, move || {
+                    if *sum.get() == 55 {
+                        n.set(100);
+                    }
+                    if *sum.get() == 5050 {
+                        done.take().unwrap().send(()).unwrap();
+                    }
+                });
+            });
+            on_done.await.unwrap();
+            unsafe { disposer.dispose() };
+        });
+    }
+
+    #[test]
+    fn discards_outdated_results() {
+        block_on(async {
+            // The first computation waits for `release` once it has started.
+            let (started_sender, started) = std_mpsc::channel();
+            let (release, released) = std_mpsc::channel();
+            let (finished_sender, finished) = oneshot::channel();
+            let first = Mutex::new(Some((started_sender, released, finished_sender)));
+            let (updates_sender, mut updates) = mpsc::unbounded();
+            let n = create_rc_signal(1u64);
+            let disposer = create_scope({
+                let n = n.clone();
+                move |cx| {
+                    let value = create_memo_threaded(
+                        cx,
+                        0,
+                        move || *n.get(),
+                        move |n| {
+                            if let Some((started, released, finished)) =
+                                first.lock().unwrap().take()
+                            {
+                                started.send(()).unwrap();
+                                released.recv().unwrap();
+                                finished.send(()).unwrap();
+                            }
+                            n
+                        },
+                    );
+                    create_effect(cx, move || {
+                        updates_sender.unbounded_send(*value.get()).unwrap();
+                    });
+                }
+            });
+            assert_eq!(updates.next().await, Some(0));
+            started.recv().unwrap();
+            // The input changes while the first computation is running.
+            n.set(2);
+            release.send(()).unwrap();
+            assert_eq!(updates.next().await, Some(2));
+            finished.await.unwrap();
+            n.set(3);
+            // The result of the first computation was not applied.
+            assert_eq!(updates.next().await, Some(3));
+            unsafe { disposer.dispose() };
+            assert_eq!(updates.next().await, None);
+        });
+    }
+}
@@ -0,0 +1,100 @@
+//! The `Attributes` derive macro implementation.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Error, Lifetime, Lit, Meta, NestedMeta, Result};
+
+/// Returns the name of the attribute set by `field`, either from `#[attribute(name = "...")]` or
+/// from the field name converted to kebab-case.
+fn attribute_name(field: &syn::Field) -> Result<String> {
+    let mut name = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("attribute"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[attribute(...)]`")),
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("name") =>
+                {
+                    match name_value.lit {
+                        Lit::Str(lit) => name = Some(lit.value()),
+                        lit => return Err(Error::new(lit.span(), "expected a string literal")),
+                    }
+                }
+                nested => return Err(Error::new(nested.span(), "unknown attribute option")),
+            }
+        }
+    }
+    Ok(name.unwrap_or_else(|| {
+        let ident = field.ident.as_ref().unwrap().to_string();
+        ident.trim_start_matches("r#").replace('_', "-")
+    }))
+}
+
+pub fn impl_derive_attributes(ast: &DeriveInput) -> Result<TokenStream> {
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new(
+                ast.span(),
+                "Attributes is only supported for structs with named fields",
+            ))
+        }
+    };
+
+    // The lifetime of the scope. Reactive fields such as `MaybeDyn<'a, T>` must live as long as
+    // the scope, so the first lifetime of the struct is used if there is one.
+    let mut generics = ast.generics.clone();
+    let lifetime = match generics.lifetimes().next() {
+        Some(def) => def.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__cx", Span::call_site());
+            generics.params.insert(0, syn::parse_quote!(#lifetime));
+            lifetime
+        }
+    };
+    generics
+        .params
+        .push(syn::parse_quote!(__G: ::sycamore::generic_node::GenericNode));
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: ::sycamore::attributes::AttributeValue<#lifetime>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let set_attributes = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let name = attribute_name(field)?;
+            Ok(quote! {
+                ::sycamore::attributes::AttributeValue::set_attribute(self.#ident, cx, el, #name);
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let ident = &ast.ident;
+    Ok(quote! {
+        impl #impl_generics ::sycamore::attributes::Attributes<#lifetime, __G> for #ident #ty_generics
+            #where_clause
+        {
+            fn apply_attributes(self, cx: ::sycamore::reactive::Scope<#lifetime>, el: &__G) {
+                #( #set_attributes )*
+            }
+        }
+    })
+}
@@ -5,11 +5,58 @@ use quote::{format_ident, quote, ToTokens};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
 use syn::{
-    parse_quote, Expr, FnArg, Item, ItemFn, Pat, Result, ReturnType, Signature, Token, Type,
-    TypeTuple,
+    parse_quote, Expr, FnArg, GenericArgument, Item, ItemFn, Lifetime, Pat, PathArguments, Result,
+    ReturnType, Signature, Token, Type, TypeBareFn, TypeTuple,
 };
 
+/// The arguments of the `#[component]` attribute.
+#[derive(Default)]
+pub struct ComponentArgs {
+    /// The `HydrateOn` variant of the component if it is an island, e.g. `#[component(island)]`.
+    island: Option<syn::Ident>,
+}
+
+impl Parse for ComponentArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut args = Self::default();
+        if input.is_empty() {
+            return Ok(args);
+        }
+        let option: syn::Ident = input.parse()?;
+        if option != "island" {
+            return Err(syn::Error::new(
+                option.span(),
+                "unknown component option, expected `island`",
+            ));
+        }
+        let hydrate_on = if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            let hydrate_on: syn::Ident = content.parse()?;
+            match hydrate_on.to_string().as_str() {
+                "load" => "Load",
+                "visible" => "Visible",
+                "interaction" => "Interaction",
+                _ => {
+                    return Err(syn::Error::new(
+                        hydrate_on.span(),
+                        "expected `load`, `visible` or `interaction`",
+                    ))
+                }
+            }
+        } else {
+            "Load"
+        };
+        args.island = Some(format_ident!("{}", hydrate_on));
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after component options"));
+        }
+        Ok(args)
+    }
+}
+
 pub struct ComponentFunction {
     pub f: ItemFn,
 }
@@ -185,6 +232,75 @@ fn async_comp_inputs_from_sig_inputs(
     }
 }
 
+/// Replaces the elided lifetimes in the inputs of `sig` with the lifetime of the scope.
+///
+/// The props of an async component are moved into a future that must live as long as the scope,
+/// so they cannot borrow for an unrelated anonymous lifetime. Async functions also do not allow
+/// hidden lifetimes such as `cx: Scope`. If the scope lifetime is elided as well, a new lifetime
+/// `'__cx` is added to the generics.
+fn name_elided_lifetimes(sig: &mut Signature) {
+    struct NameElided(Lifetime);
+
+    impl VisitMut for NameElided {
+        fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+            if lifetime.ident == "_" {
+                *lifetime = self.0.clone();
+            }
+        }
+
+        fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+            if reference.lifetime.is_none() {
+                reference.lifetime = Some(self.0.clone());
+            }
+            visit_mut::visit_type_reference_mut(self, reference);
+        }
+
+        fn visit_path_segment_mut(&mut self, segment: &mut syn::PathSegment) {
+            if segment.ident == "Scope" && segment.arguments.is_empty() {
+                let lifetime = &self.0;
+                segment.arguments = PathArguments::AngleBracketed(parse_quote!(<#lifetime>));
+            }
+            visit_mut::visit_path_segment_mut(self, segment);
+        }
+
+        // Elided lifetimes in `fn(&T)` and `Fn(&T)` are higher-ranked and must be left alone.
+        fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+        fn visit_parenthesized_generic_arguments_mut(
+            &mut self,
+            _: &mut syn::ParenthesizedGenericArguments,
+        ) {
+        }
+    }
+
+    let scope_lifetime = match sig.inputs.first() {
+        Some(FnArg::Typed(t)) => match &*t.ty {
+            Type::Path(path) => match &path.path.segments.last().unwrap().arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(GenericArgument::Lifetime(lifetime)) if lifetime.ident != "_" => {
+                        Some(lifetime.clone())
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    };
+    let scope_lifetime = scope_lifetime.unwrap_or_else(|| {
+        let lifetime = Lifetime::new("'__cx", sig.paren_token.span);
+        sig.generics.params.insert(0, parse_quote!(#lifetime));
+        lifetime
+    });
+
+    let mut visitor = NameElided(scope_lifetime);
+    for input in &mut sig.inputs {
+        if let FnArg::Typed(t) = input {
+            visitor.visit_type_mut(&mut t.ty);
+        }
+    }
+}
+
 impl ToTokens for ComponentFunction {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ComponentFunction { f } = self;
@@ -208,6 +324,8 @@ impl ToTokens for ComponentFunction {
             //
             // Note: that the change to the signature is not semantically different to a would be
             // caller.
+            let mut sig = sig.clone();
+            name_elided_lifetimes(&mut sig);
             let inputs = &sig.inputs;
             let AsyncCompInputs {
                 cx,
@@ -252,6 +370,55 @@ impl ToTokens for ComponentFunction {
     }
 }
 
-pub fn component_impl(comp: ComponentFunction) -> Result<TokenStream> {
-    Ok(comp.to_token_stream())
+/// Wraps the body of an island component so that it is rendered inside of a `<sycamore-island>`
+/// element with its serialized props.
+fn island_impl(comp: ComponentFunction, hydrate_on: syn::Ident) -> Result<TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        mut sig,
+        block,
+    } = comp.f;
+    if sig.asyncness.is_some() {
+        return Err(syn::Error::new(
+            sig.asyncness.span(),
+            "island components can't be async",
+        ));
+    }
+    let name = sig.ident.to_string();
+    let cx = match &sig.inputs[0] {
+        FnArg::Typed(t) => match &*t.pat {
+            Pat::Ident(id) => id.ident.clone(),
+            _ => unreachable!("checked when parsing the component"),
+        },
+        FnArg::Receiver(_) => unreachable!("checked when parsing the component"),
+    };
+    let (props, props_pat) = match sig.inputs.iter_mut().nth(1) {
+        Some(FnArg::Typed(t)) => {
+            let pat = std::mem::replace(&mut *t.pat, parse_quote! { __props });
+            (quote! { __props }, quote! { #pat })
+        }
+        _ => (quote! { () }, quote! { () }),
+    };
+
+    Ok(quote! {
+        #[allow(non_snake_case)]
+        #(#attrs)*
+        #vis #sig {
+            ::sycamore::web::island::island(
+                #cx,
+                #name,
+                ::sycamore::web::island::HydrateOn::#hydrate_on,
+                #props,
+                move |#props_pat| #block,
+            )
+        }
+    })
+}
+
+pub fn component_impl(comp: ComponentFunction, args: ComponentArgs) -> Result<TokenStream> {
+    match args.island {
+        Some(hydrate_on) => island_impl(comp, hydrate_on),
+        None => Ok(comp.to_token_stream()),
+    }
 }
@@ -0,0 +1,214 @@
+//! The `css!` macro.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, LitStr, Result};
+
+/// At-rules whose body is a list of rules that should be scoped as well.
+const NESTED_AT_RULES: [&str; 4] = ["@media", "@supports", "@container", "@layer"];
+
+pub fn css_impl(input: LitStr) -> Result<TokenStream> {
+    let source = input.value();
+    let class = format!("css-{:08x}", fnv1a(&source));
+    let css = scope_stylesheet(&source, &class).map_err(|err| Error::new(input.span(), err))?;
+    Ok(quote! {
+        ::sycamore::web::style::ScopedStyle::new(#class, #css)
+    })
+}
+
+/// A stable hash, so that the class names are the same on the server and in the browser.
+fn fnv1a(s: &str) -> u32 {
+    s.bytes().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
+
+/// Scopes every rule of the stylesheet to the elements with the `class`. `&` refers to the
+/// elements with the class and other selectors match their descendants.
+fn scope_stylesheet(css: &str, class: &str) -> std::result::Result<String, String> {
+    scope_rules(&strip_comments(css)?, class)
+}
+
+fn strip_comments(css: &str) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    // `/*` inside of a string, e.g. `content: "/*"`, does not start a comment.
+    while let Some(slash) = find_outside_strings(rest, &['/']) {
+        if rest[slash + 1..].starts_with('*') {
+            out.push_str(&rest[..slash]);
+            let end = rest[slash + 2..].find("*/").ok_or("unterminated comment")?;
+            rest = &rest[slash + 2 + end + 2..];
+        } else {
+            out.push_str(&rest[..=slash]);
+            rest = &rest[slash + 1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn scope_rules(mut css: &str, class: &str) -> std::result::Result<String, String> {
+    let mut out = String::new();
+    loop {
+        css = css.trim_start();
+        if css.is_empty() {
+            return Ok(out);
+        }
+        let Some(end) = find_outside_strings(css, &['{', ';', '}']) else {
+            return Err(format!("expected `{{` after `{}`", css.trim()));
+        };
+        let prelude = css[..end].trim();
+        match css.as_bytes()[end] {
+            b';' => {
+                // An at-rule without a block, e.g. `@import`.
+                out.push_str(prelude);
+                out.push(';');
+                css = &css[end + 1..];
+            }
+            b'}' => return Err(format!("unexpected `}}` after `{prelude}`")),
+            _ => {
+                let body_end = end + 1 + matching_brace(&css[end + 1..])?;
+                let body = &css[end + 1..body_end];
+                if NESTED_AT_RULES.iter().any(|rule| prelude.starts_with(rule)) {
+                    out.push_str(prelude);
+                    out.push('{');
+                    out.push_str(&scope_rules(body, class)?);
+                    out.push('}');
+                } else if prelude.starts_with('@') {
+                    // Other at-rules, e.g. `@keyframes` and `@font-face`, are kept as is.
+                    out.push_str(prelude);
+                    out.push('{');
+                    out.push_str(body.trim());
+                    out.push('}');
+                } else {
+                    let selectors = split_outside_parens(prelude)
+                        .into_iter()
+                        .map(|selector| scope_selector(selector.trim(), class))
+                        .collect::<Vec<_>>();
+                    out.push_str(&selectors.join(","));
+                    out.push('{');
+                    out.push_str(body.trim());
+                    out.push('}');
+                }
+                css = &css[body_end + 1..];
+            }
+        }
+    }
+}
+
+fn scope_selector(selector: &str, class: &str) -> String {
+    if selector.contains('&') {
+        selector.replace('&', &format!(".{class}"))
+    } else {
+        format!(".{class} {selector}")
+    }
+}
+
+/// Returns the index of the first of `chars` that is not inside of a string.
+fn find_outside_strings(css: &str, chars: &[char]) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in css.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if chars.contains(&c) => return Some(i),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Returns the index of the `}` that closes a block, given the content after the `{`.
+fn matching_brace(css: &str) -> std::result::Result<usize, String> {
+    let mut depth = 0;
+    let mut offset = 0;
+    while let Some(i) = find_outside_strings(&css[offset..], &['{', '}']) {
+        let i = offset + i;
+        if css.as_bytes()[i] == b'{' {
+            depth += 1;
+        } else if depth == 0 {
+            return Ok(i);
+        } else {
+            depth -= 1;
+        }
+        offset = i + 1;
+    }
+    Err("unclosed `{`".to_string())
+}
+
+/// Splits a selector list on the commas that are not inside of parentheses, e.g. in `:is(a, b)`.
+fn split_outside_parens(selectors: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selectors.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&selectors[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&selectors[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_selectors() {
+        assert_eq!(
+            scope_stylesheet("& { padding: 4px; } p, &:hover span { color: red; }", "c").unwrap(),
+            ".c{padding: 4px;}.c p,.c:hover span{color: red;}"
+        );
+        assert_eq!(
+            scope_stylesheet(":is(a, b) > & { color: red }", "c").unwrap(),
+            ":is(a, b) > .c{color: red}"
+        );
+    }
+
+    #[test]
+    fn at_rules() {
+        assert_eq!(
+            scope_stylesheet(
+                "@import url(\"a;b.css\");
+                 @media (min-width: 600px) { & { display: flex } }
+                 @keyframes spin { from { rotate: 0deg } to { rotate: 360deg } }",
+                "c"
+            )
+            .unwrap(),
+            "@import url(\"a;b.css\");@media (min-width: 600px){.c{display: flex}}\
+             @keyframes spin{from { rotate: 0deg } to { rotate: 360deg }}"
+        );
+    }
+
+    #[test]
+    fn comments_and_strings() {
+        assert_eq!(
+            scope_stylesheet("/* { */ p::before { content: \"}\" }", "c").unwrap(),
+            ".c p::before{content: \"}\"}"
+        );
+        assert_eq!(
+            scope_stylesheet(
+                "p::before { content: \"/*\"; background: url('a/*b.png') } /* */ a { }",
+                "c"
+            )
+            .unwrap(),
+            ".c p::before{content: \"/*\"; background: url('a/*b.png')}.c a{}"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(scope_stylesheet("p { color: red", "c").is_err());
+        assert!(scope_stylesheet("p }", "c").is_err());
+        assert!(scope_stylesheet("p", "c").is_err());
+        assert!(scope_stylesheet("/* p", "c").is_err());
+    }
+}
@@ -6,8 +6,11 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
+mod attributes;
 mod component;
+mod css;
 mod prop;
+mod store;
 mod view;
 
 /// A macro for ergonomically creating complex UI structures.
@@ -21,6 +24,36 @@ pub fn view(view: TokenStream) -> TokenStream {
     view::view_impl(view_root).into()
 }
 
+/// An alternative to the [`view!`] macro with an HTML-like syntax. Both macros generate the same
+/// code.
+///
+/// Attribute values are either literals or Rust expressions in braces, and so are interpolated
+/// children. Components are written like elements, with their props as attributes.
+///
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[component]
+/// fn Counter<G: Html>(cx: Scope) -> View<G> {
+///     let count = create_signal(cx, 0);
+///     html! { cx,
+///         <div class="counter" data-count={count.get()}>
+///             <button on:click={|_| count.set(*count.get() + 1)}>"+"</button>
+///             <input type="number" disabled value={count.get()}>
+///         </div>
+///     }
+/// }
+/// ```
+///
+/// To learn more, see the chapter on [the `view!` macro](https://sycamore-rs.netlify.app/docs/basics/view)
+/// in the Sycamore Book.
+#[proc_macro]
+pub fn html(input: TokenStream) -> TokenStream {
+    let html_root = parse_macro_input!(input as view::WithcxArg<view::html::HtmlRoot>);
+
+    view::html_impl(html_root).into()
+}
+
 /// ```
 /// use sycamore::prelude::*;
 ///
@@ -40,20 +73,56 @@ pub fn node(input: TokenStream) -> TokenStream {
     view::node_impl(elem).into()
 }
 
+/// A macro for declaring a stylesheet that is scoped to a component.
+///
+/// The stylesheet is a string literal. `&` refers to the elements that the style is applied to,
+/// usually the root elements of a component, and every other selector matches their descendants.
+/// The selectors are rewritten at compile time to use a class name generated from the stylesheet.
+///
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::style::ScopedStyle;
+///
+/// const STYLE: ScopedStyle = css!(r#"
+///     & { padding: 8px; }
+///     p { color: gray; }
+/// "#);
+///
+/// #[component]
+/// fn Card<G: Html>(cx: Scope) -> View<G> {
+///     STYLE.scope(cx, view! { cx,
+///         div { p { "Hello" } }
+///     })
+/// }
+/// ```
+#[proc_macro]
+pub fn css(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::LitStr);
+
+    css::css_impl(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// A macro for creating components from functions.
 ///
 /// Add this attribute to a `fn` to create a component from that function.
 ///
+/// Use `#[component(island)]` to make the component an island, which is hydrated on its own when
+/// the rest of the page is not. The component can be hydrated later with `island(visible)` or
+/// `island(interaction)`. See the `sycamore::web::island` module for more details.
+///
 /// To learn more about components, see the chapter on
 /// [components](https://sycamore-rs.netlify.app/docs/basics/components) in the Sycamore Book.
 #[proc_macro_attribute]
-pub fn component(_attr: TokenStream, component: TokenStream) -> TokenStream {
+pub fn component(attr: TokenStream, component: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as component::ComponentArgs);
     let comp = {
         let component = component.clone();
         parse_macro_input!(component as component::ComponentFunction)
     };
 
-    component::component_impl(comp)
+    component::component_impl(comp, args)
         .unwrap_or_else(|err| {
             // If proc-macro errors, emit the original function for better IDE support.
             let error_tokens = err.into_compile_error();
@@ -66,7 +135,66 @@ pub fn component(_attr: TokenStream, component: TokenStream) -> TokenStream {
         .into()
 }
 
+/// A derive macro for implementing `Attributes` on a struct, so that it can be spread onto an
+/// element with `..` in the [`view!`] macro.
+///
+/// Every field is set as an attribute named after the field in kebab-case, e.g. `aria_expanded`
+/// becomes `aria-expanded`. Use `#[attribute(name = "...")]` to set another name.
+#[proc_macro_derive(Attributes, attributes(attribute))]
+pub fn derive_attributes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    attributes::impl_derive_attributes(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// A derive macro for turning a struct into a deeply reactive store.
+///
+/// This generates a `{Name}Store` struct with an accessor for every field, so that every field can
+/// be read and set individually: `store.name().get()` only tracks the `name` field. Fields are
+/// stored in an `RcSignal` and must implement `Clone`. Fields marked with `#[store(nested)]` are
+/// stores themselves, which is supported for structs that derive `Store` and for `Vec`s of such
+/// structs.
+///
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[derive(Store)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Store)]
+/// struct User {
+///     name: String,
+///     #[store(nested)]
+///     address: Address,
+/// }
+///
+/// create_scope_immediate(|cx| {
+///     let user = create_store(cx, User {
+///         name: "Ada".to_string(),
+///         address: Address { city: "London".to_string() },
+///     });
+///     user.address().city().set("Paris".to_string());
+///     assert_eq!(*user.address().city().get(), "Paris");
+///     assert_eq!(user.get().address.city, "Paris");
+/// });
+/// ```
+#[proc_macro_derive(Store, attributes(store))]
+pub fn derive_store(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    store::impl_derive_store(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// A derive macro for creating a builder-like API used in the [`view!`] macro.
+///
+/// Fields of type `MaybeDyn` accept anything that can be converted into a `MaybeDyn`, i.e. a plain
+/// value, a signal or a closure wrapped with `MaybeDyn::derived`.
 #[proc_macro_derive(Prop, attributes(builder))]
 pub fn derive_prop(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -793,8 +793,16 @@ mod field_info {
         pub fn new(
             ordinal: usize,
             field: &syn::Field,
-            field_defaults: FieldBuilderAttr,
+            mut field_defaults: FieldBuilderAttr,
         ) -> Result<FieldInfo, Error> {
+            // `MaybeDyn` props accept anything that can be converted into a `MaybeDyn`, unless
+            // opted out with `#[builder(setter(!into))]`.
+            if is_maybe_dyn(&field.ty) {
+                field_defaults
+                    .setter
+                    .auto_into
+                    .get_or_insert_with(|| field.ty.span());
+            }
             if let Some(ref name) = field.ident {
                 Ok(FieldInfo {
                     ordinal,
@@ -858,6 +866,18 @@ mod field_info {
         }
     }
 
+    /// Returns `true` if `ty` is a path to a type named `MaybeDyn`.
+    fn is_maybe_dyn(ty: &syn::Type) -> bool {
+        match ty {
+            syn::Type::Path(type_path) if type_path.qself.is_none() => type_path
+                .path
+                .segments
+                .last()
+                .map_or(false, |segment| segment.ident == "MaybeDyn"),
+            _ => false,
+        }
+    }
+
     #[derive(Debug, Default, Clone)]
     pub struct FieldBuilderAttr {
         pub default: Option<syn::Expr>,
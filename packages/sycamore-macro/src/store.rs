@@ -0,0 +1,137 @@
+//! The `Store` derive macro implementation.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Error, Meta, NestedMeta, Result};
+
+/// Returns `true` if `field` is marked with `#[store(nested)]`.
+fn is_nested(field: &syn::Field) -> Result<bool> {
+    let mut nested = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("store"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected `#[store(...)]`")),
+        };
+        for meta in list.nested {
+            match meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("nested") => nested = true,
+                meta => return Err(Error::new(meta.span(), "unknown store option")),
+            }
+        }
+    }
+    Ok(nested)
+}
+
+pub fn impl_derive_store(ast: &DeriveInput) -> Result<TokenStream> {
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new(
+                ast.span(),
+                "Store is only supported for structs with named fields",
+            ))
+        }
+    };
+
+    let vis = &ast.vis;
+    let ident = &ast.ident;
+    let store_ident = format_ident!("{}Store", ident);
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut store_fields = Vec::new();
+    let mut accessors = Vec::new();
+    let mut into_store = Vec::new();
+    let mut from_store = Vec::new();
+    let mut set_store = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let (store_ty, into, from, set) = if is_nested(field)? {
+            (
+                quote! { <#ty as ::sycamore::reactive::Store>::Store },
+                quote! { ::sycamore::reactive::Store::into_store(value.#name) },
+                quote! { <#ty as ::sycamore::reactive::Store>::from_store(&store.#name) },
+                quote! { <#ty as ::sycamore::reactive::Store>::set_store(&store.#name, value.#name) },
+            )
+        } else {
+            (
+                quote! { ::sycamore::reactive::RcSignal<#ty> },
+                quote! { ::sycamore::reactive::create_rc_signal(value.#name) },
+                quote! { ::std::clone::Clone::clone(&*store.#name.get()) },
+                quote! { store.#name.set(value.#name) },
+            )
+        };
+        let doc = format!("Returns the `{name}` field of the store.");
+        accessors.push(quote! {
+            #[doc = #doc]
+            #vis fn #name(&self) -> &#store_ty {
+                &self.#name
+            }
+        });
+        store_fields.push(quote! { #name: #store_ty });
+        into_store.push(quote! { #name: #into });
+        from_store.push(quote! { #name: #from });
+        set_store.push(set);
+    }
+    let field_names = fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
+
+    let store_doc =
+        format!("The reactive version of [`{ident}`], where every field is tracked individually.");
+    Ok(quote! {
+        #[doc = #store_doc]
+        #vis struct #store_ident #impl_generics #where_clause {
+            #( #store_fields, )*
+        }
+
+        impl #impl_generics ::std::clone::Clone for #store_ident #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self {
+                    #( #field_names: ::std::clone::Clone::clone(&self.#field_names), )*
+                }
+            }
+        }
+
+        impl #impl_generics #store_ident #ty_generics #where_clause {
+            #( #accessors )*
+
+            /// Returns the current value of the store. This tracks every field of the store.
+            #vis fn get(&self) -> #ident #ty_generics {
+                <#ident #ty_generics as ::sycamore::reactive::Store>::from_store(self)
+            }
+
+            /// Sets every field of the store to the fields of `value`.
+            #vis fn set(&self, value: #ident #ty_generics) {
+                <#ident #ty_generics as ::sycamore::reactive::Store>::set_store(self, value)
+            }
+        }
+
+        impl #impl_generics ::sycamore::reactive::Store for #ident #ty_generics #where_clause {
+            type Store = #store_ident #ty_generics;
+
+            fn into_store(self) -> Self::Store {
+                let value = self;
+                #store_ident {
+                    #( #into_store, )*
+                }
+            }
+
+            fn from_store(store: &Self::Store) -> Self {
+                Self {
+                    #( #from_store, )*
+                }
+            }
+
+            fn set_store(store: &Self::Store, value: Self) {
+                #( #set_store; )*
+            }
+        }
+    })
+}
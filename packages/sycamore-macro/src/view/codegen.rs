@@ -3,16 +3,65 @@
 //! Note: we are not using the `ToTokens` trait from `quote` because we need to keep track
 //! of some internal state during the entire codegen.
 
-use proc_macro2::TokenStream;
-use quote::quote;
+use std::cell::Cell;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::{Expr, ExprLit, Ident, Lit};
+use syn::{Expr, ExprLit, Ident, Lit, Pat, PatType, Path};
 
 use crate::view::ir::*;
 
 /// A struct for keeping track of the state when emitting Rust code.
 pub struct Codegen {
     pub cx: Ident,
+    /// The namespace of the elements that are being emitted.
+    pub namespace: Cell<Namespace>,
+}
+
+/// The namespace of an element, which depends on its ancestors in the `view!`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Namespace {
+    #[default]
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    /// The tags that are both HTML and SVG elements. They are defined as HTML elements in
+    /// `sycamore::web::html`, so they are created from their tag in the SVG namespace.
+    const AMBIGUOUS_SVG_TAGS: &'static [&'static str] = &["a", "script", "style", "title"];
+
+    /// Returns the namespace of an element with `tag` inside of an element of this namespace.
+    fn of_element(self, tag: &str) -> Self {
+        match tag {
+            "svg" => Self::Svg,
+            "math" => Self::MathMl,
+            _ => self,
+        }
+    }
+
+    /// Returns the namespace of the children of an element with `tag` of this namespace.
+    fn of_children(self, tag: &str) -> Self {
+        match tag {
+            // The content of a `<foreignObject>` is HTML.
+            "foreignObject" => Self::Html,
+            _ => self,
+        }
+    }
+
+    fn uri(self) -> TokenStream {
+        match self {
+            Self::Html => quote! { ::std::option::Option::None },
+            Self::Svg => {
+                quote! { ::std::option::Option::Some(::sycamore::web::html::SVG_NAMESPACE) }
+            }
+            Self::MathMl => {
+                quote! { ::std::option::Option::Some(::sycamore::web::html::MATHML_NAMESPACE) }
+            }
+        }
+    }
 }
 
 impl Codegen {
@@ -41,8 +90,34 @@ impl Codegen {
         }
     }
 
+    /// Converts the value of an interpolation into a `View`. See `sycamore::view::interpolate`.
+    fn interpolate(value: &Expr) -> TokenStream {
+        quote! {{
+            #[allow(unused_imports)]
+            use ::sycamore::view::interpolate::{
+                InterpolateCollection as _, InterpolateIter as _, InterpolateView as _,
+            };
+            (&&&::sycamore::view::interpolate::Interpolate(&(#value))).interpolate()
+        }}
+    }
+
+    /// Creates the comment node of a `comment(...)` node.
+    fn comment(Comment { value }: &Comment) -> TokenStream {
+        match value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(_), ..
+            }) => quote! {
+                ::sycamore::generic_node::GenericNode::marker_with_text(#value)
+            },
+            _ => quote! {
+                ::sycamore::generic_node::GenericNode::marker_with_text(
+                    &::std::string::ToString::to_string(&(#value))
+                )
+            },
+        }
+    }
+
     pub fn view_node(&self, view_node: &ViewNode) -> TokenStream {
-        let cx = &self.cx;
         match view_node {
             ViewNode::Element(elem) => {
                 let elem = self.element(elem);
@@ -54,40 +129,118 @@ impl Codegen {
             ViewNode::Text(Text { value }) => quote! {
                 ::sycamore::view::View::new_node(::sycamore::generic_node::GenericNode::text_node(#value))
             },
-            ViewNode::Dyn(d @ Dyn { value }) => {
-                let needs_cx = d.needs_cx(&cx.to_string());
-                match needs_cx {
-                    true => quote! {
-                        ::sycamore::view::View::new_dyn_scoped(#cx, move |#cx|
-                            ::sycamore::view::IntoView::create(&(#value))
-                        )
-                    },
-                    false => quote! {
-                        ::sycamore::view::View::new_dyn(#cx, move ||
-                            ::sycamore::view::IntoView::create(&(#value))
-                        )
-                    },
+            ViewNode::Comment(comment) => {
+                let comment = Self::comment(comment);
+                quote! {
+                    ::sycamore::view::View::new_node(#comment)
                 }
             }
+            ViewNode::Dyn(d) => self.dyn_view(d),
+            ViewNode::If(node) => self.if_view(node),
+            ViewNode::Keyed(keyed) => self.keyed_view(keyed),
         }
     }
 
+    /// Creates the dynamic view of an element or a component with a `key`. The node is created
+    /// again in a new scope every time the key changes, which resets its state.
+    fn keyed_view(&self, Keyed { key, node }: &Keyed) -> TokenStream {
+        let cx = &self.cx;
+        let node = self.view_node(node);
+        quote! {{
+            let __key = ::sycamore::reactive::create_selector(#cx, move || #key);
+            ::sycamore::view::View::new_dyn_scoped(#cx, move |#cx| {
+                __key.track();
+                // Only the key recreates the node.
+                ::sycamore::reactive::untrack(|| #node)
+            })
+        }}
+    }
+
+    /// Creates the dynamic view of a `(...)` interpolation.
+    fn dyn_view(&self, d: &Dyn) -> TokenStream {
+        let cx = &self.cx;
+        let interpolate = Self::interpolate(&d.value);
+        match d.needs_cx(&cx.to_string()) {
+            true => quote! {
+                ::sycamore::view::View::new_dyn_scoped(#cx, move |#cx|
+                    #interpolate
+                )
+            },
+            false => quote! {
+                ::sycamore::view::View::new_dyn(#cx, move ||
+                    #interpolate
+                )
+            },
+        }
+    }
+
+    /// Creates the dynamic view of an `if` block. The index of the branch to render is memoized
+    /// so that the nodes of a branch are only created again when another branch was rendered in
+    /// between, not every time the signals used by the conditions change.
+    fn if_view(
+        &self,
+        If {
+            branches,
+            else_branch,
+        }: &If,
+    ) -> TokenStream {
+        let cx = &self.cx;
+        let conds = branches.iter().map(|(cond, _)| cond);
+        let indices = (0..branches.len()).collect::<Vec<_>>();
+        let else_index = branches.len();
+        let views = branches.iter().map(|(_, body)| self.view_root(body));
+        let else_view = match else_branch {
+            Some(body) => self.view_root(body),
+            None => quote! { ::sycamore::view::View::empty() },
+        };
+        quote! {{
+            let __branch = ::sycamore::reactive::create_selector(#cx, move || {
+                #(if #conds { #indices } else)* { #else_index }
+            });
+            ::sycamore::view::View::new_dyn_scoped(#cx, move |#cx| match *__branch.get() {
+                #(#indices => #views,)*
+                _ => #else_view,
+            })
+        }}
+    }
+
     pub fn element(&self, elem: &Element) -> TokenStream {
         let cx = &self.cx;
         let Element {
             tag,
             attrs,
             children,
+            span,
         } = elem;
 
+        // Panics when creating the element, e.g. from an invalid tag, point at the tag. See
+        // `Codegen::attribute`.
+        let el = Ident::new("__el", Span::call_site());
+        let tag_s = match tag {
+            ElementTag::Builtin(id) => id.to_string(),
+            ElementTag::Custom(tag_s) => tag_s.clone(),
+        };
+        let namespace = self.namespace.get().of_element(&tag_s);
         let quote_tag = match tag {
-            ElementTag::Builtin(id) => quote! {
-                let __el = ::sycamore::generic_node::GenericNode::element::<::sycamore::web::html::#id>();
-            },
-            ElementTag::Custom(tag_s) => quote! {
-                let __el = ::sycamore::generic_node::GenericNode::element_from_tag(#tag_s);
+            ElementTag::Builtin(id)
+                if namespace != Namespace::Svg
+                    || !Namespace::AMBIGUOUS_SVG_TAGS.contains(&tag_s.as_str()) =>
+            {
+                quote_spanned! {*span=>
+                    let #el = ::sycamore::generic_node::GenericNode::element::<::sycamore::web::html::#id>();
+                }
+            }
+            _ if namespace != Namespace::Html => {
+                let uri = namespace.uri();
+                quote_spanned! {*span=>
+                    let #el = ::sycamore::generic_node::GenericNode::element_from_tag_ns(#tag_s, #uri);
+                }
+            }
+            _ => quote_spanned! {*span=>
+                let #el = ::sycamore::generic_node::GenericNode::element_from_tag(#tag_s);
             },
         };
+        let parent_namespace = self.namespace.replace(namespace.of_children(&tag_s));
 
         let quote_attrs: TokenStream = attrs.iter().map(|attr| self.attribute(attr)).collect();
 
@@ -117,6 +270,15 @@ impl Codegen {
                             ::sycamore::generic_node::GenericNode::append_child(&__el, &__marker);
                             let __marker = ::std::option::Option::Some(&__marker);
                         }
+                    } else if let Some(ViewNode::Comment(comment)) =
+                        children.next_if(|x| matches!(x, ViewNode::Comment(_)))
+                    {
+                        let comment = Self::comment(comment);
+                        quote! {
+                            let __marker = #comment;
+                            ::sycamore::generic_node::GenericNode::append_child(&__el, &__marker);
+                            let __marker = ::std::option::Option::Some(&__marker);
+                        }
                     } else if children.peek().is_none() {
                         marker_is_some = false;
                         quote! {
@@ -162,19 +324,12 @@ impl Codegen {
                                 #quoted
                             })
                         }
-                        ViewNode::Dyn(d @ Dyn { value}) => {
-                            let needs_cx = d.needs_cx(&self.cx.to_string());
-                            let view_quoted = match needs_cx {
-                                true => quote! {
-                                    ::sycamore::view::View::new_dyn_scoped(#cx, move |#cx|
-                                        ::sycamore::view::IntoView::create(&(#value))
-                                    )
-                                },
-                                false => quote! {
-                                    ::sycamore::view::View::new_dyn(#cx, move ||
-                                        ::sycamore::view::IntoView::create(&(#value))
-                                    )
-                                }
+                        ViewNode::Dyn(_) | ViewNode::If(_) | ViewNode::Keyed(_) => {
+                            let view_quoted = match child {
+                                ViewNode::Dyn(d) => self.dyn_view(d),
+                                ViewNode::If(node) => self.if_view(node),
+                                ViewNode::Keyed(keyed) => self.keyed_view(keyed),
+                                _ => unreachable!(),
                             };
                             let quoted = quote! {
                                 #marker
@@ -210,10 +365,12 @@ impl Codegen {
                         }
                     }),
                     ViewNode::Component(_) => unreachable!("component is always dynamic"),
+                    ViewNode::If(_) => unreachable!("if is always dynamic"),
+                    ViewNode::Keyed(_) => unreachable!("keyed node is always dynamic"),
                     ViewNode::Text(Text { value }) => {
                         let intern = quote! {
                             // Since this is static text, intern it as it will likely be constructed many times.
-                            #[cfg(target_arch = "wasm32")]
+                            #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
                             ::sycamore::rt::intern(#value);
                         };
                         quoted.extend(match multi {
@@ -231,16 +388,26 @@ impl Codegen {
                             },
                         });
                     }
-                    ViewNode::Dyn(Dyn { value }) => quoted.extend(quote! {
-                        ::sycamore::utils::render::insert(#cx, &__el,
-                            ::sycamore::view::IntoView::create(&(#value)),
-                            None, None, #multi
-                        );
-                    }),
+                    ViewNode::Comment(comment) => {
+                        let comment = Self::comment(comment);
+                        quoted.extend(quote! {
+                            ::sycamore::generic_node::GenericNode::append_child(&__el, &#comment);
+                        });
+                    }
+                    ViewNode::Dyn(Dyn { value }) => {
+                        let interpolate = Self::interpolate(value);
+                        quoted.extend(quote! {
+                            ::sycamore::utils::render::insert(#cx, &__el,
+                                #interpolate,
+                                None, None, #multi
+                            );
+                        });
+                    }
                 }
             }
             quoted
         };
+        self.namespace.set(parent_namespace);
 
         quote! {{
             #quote_tag
@@ -254,6 +421,11 @@ impl Codegen {
         let cx = &self.cx;
         let mut tokens = TokenStream::new();
         let expr = &attr.value;
+        // Use the span of the attribute so that panics in the generated code, e.g. from an invalid
+        // attribute name, point at the attribute. `__el` keeps the call site span so that it still
+        // resolves if the input comes from another macro.
+        let span = attr.span;
+        let el = Ident::new("__el", Span::call_site());
 
         let is_dynamic = !matches!(expr, Expr::Lit(ExprLit { .. }));
 
@@ -269,108 +441,128 @@ impl Codegen {
                 {
                     // Since this is static text, intern it as it will likely be constructed many
                     // times.
-                    quote! {
-                        if ::std::cfg!(target_arch = "wasm32") {
+                    quote_spanned! {span=>
+                        if ::std::cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
                             ::sycamore::rt::intern(#text)
                         } else {
                             #text
                         }
                     }
                 } else {
-                    quote! {
+                    quote_spanned! {span=>
                         &::std::string::ToString::to_string(&#expr)
                     }
                 };
                 let quoted_set_attribute = if is_class {
-                    quote! {
-                        ::sycamore::generic_node::GenericNode::set_class_name(&__el, #quoted_text);
+                    quote_spanned! {span=>
+                        ::sycamore::generic_node::GenericNode::set_class_name(&#el, #quoted_text);
                     }
                 } else {
-                    quote! {
-                        ::sycamore::generic_node::GenericNode::set_attribute(&__el, #name, #quoted_text);
+                    quote_spanned! {span=>
+                        ::sycamore::generic_node::GenericNode::set_attribute(&#el, #name, #quoted_text);
                     }
                 };
 
                 if is_dynamic {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         ::sycamore::reactive::create_effect(#cx, {
-                            let __el = ::std::clone::Clone::clone(&__el);
+                            let #el = ::std::clone::Clone::clone(&#el);
                             move || { #quoted_set_attribute }
                         });
                     });
                 } else {
-                    tokens.extend(quote! { #quoted_set_attribute });
+                    tokens.extend(quote_spanned! {span=> #quoted_set_attribute });
                 };
             }
             AttributeType::Bool { name } => {
                 let name = name.to_string();
-                let quoted_set_attribute = quote! {
+                let quoted_set_attribute = quote_spanned! {span=>
                     if #expr {
-                        ::sycamore::generic_node::GenericNode::set_attribute(&__el, #name, "");
+                        ::sycamore::generic_node::GenericNode::set_attribute(&#el, #name, "");
                     } else {
-                        ::sycamore::generic_node::GenericNode::remove_attribute(&__el, #name);
+                        ::sycamore::generic_node::GenericNode::remove_attribute(&#el, #name);
                     }
                 };
 
                 if is_dynamic {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         ::sycamore::reactive::create_effect(#cx, {
-                            let __el = ::std::clone::Clone::clone(&__el);
+                            let #el = ::std::clone::Clone::clone(&#el);
                             move || {
                                 #quoted_set_attribute
                             }
                         });
                     });
                 } else {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         #quoted_set_attribute
                     });
                 };
             }
             AttributeType::DangerouslySetInnerHtml => {
                 if is_dynamic {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         ::sycamore::reactive::create_effect(#cx, {
-                            let __el = ::std::clone::Clone::clone(&__el);
+                            let #el = ::std::clone::Clone::clone(&#el);
                             move || {
                                 ::sycamore::generic_node::GenericNode::dangerously_set_inner_html(
-                                    &__el,
+                                    &#el,
                                     #expr,
                                 );
                             }
                         });
                     });
                 } else {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         ::sycamore::generic_node::GenericNode::dangerously_set_inner_html(
-                            &__el,
+                            &#el,
                             #expr,
                         );
                     });
                 };
             }
-            AttributeType::Event { event } => {
-                tokens.extend(quote! {
+            AttributeType::Event { event, modifiers } if modifiers.is_empty() => {
+                tokens.extend(quote_spanned! {span=>
                     ::sycamore::generic_node::GenericNode::event(
-                        &__el,
+                        &#el,
                         #cx,
                         #event,
                         #expr,
                     );
                 });
             }
+            AttributeType::Event { event, modifiers } => {
+                let has = |modifier: &str| modifiers.iter().any(|m| m == modifier);
+                let (prevent_default, stop_propagation) = (has("prevent"), has("stop"));
+                let (capture, once, passive) = (has("capture"), has("once"), has("passive"));
+                tokens.extend(quote_spanned! {span=>
+                    ::sycamore::generic_node::GenericNode::event_with_options(
+                        &#el,
+                        #cx,
+                        #event,
+                        #expr,
+                        ::sycamore::generic_node::EventOptions {
+                            prevent_default: #prevent_default,
+                            stop_propagation: #stop_propagation,
+                            capture: #capture,
+                            once: #once,
+                            passive: #passive,
+                        },
+                    );
+                });
+            }
             AttributeType::Property { prop } => {
-                let set_property = quote! {
+                let set_property = quote_spanned! {span=>
                     ::sycamore::generic_node::GenericNode::set_property(
-                        &__el,
+                        &#el,
                         #prop,
                         &::std::convert::Into::<::sycamore::rt::JsValue>::into(#expr)
                     );
                 };
                 if is_dynamic {
-                    tokens.extend(quote! {
+                    tokens.extend(quote_spanned! {span=>
                         ::sycamore::reactive::create_effect(#cx, {
-                            let __el = ::std::clone::Clone::clone(&__el);
+                            let #el = ::std::clone::Clone::clone(&#el);
                             move || { #set_property }
                         });
                     });
@@ -382,11 +574,13 @@ impl Codegen {
                 #[derive(Clone, Copy)]
                 enum JsPropertyType {
                     Bool,
+                    Number,
                     String,
                 }
 
                 let (event_name, property_ty) = match prop.as_str() {
                     "value" => ("input", JsPropertyType::String),
+                    "valueAsNumber" => ("input", JsPropertyType::Number),
                     "checked" => ("change", JsPropertyType::Bool),
                     _ => {
                         tokens.extend(
@@ -402,10 +596,13 @@ impl Codegen {
 
                 let convert_into_jsvalue_fn = match property_ty {
                     JsPropertyType::Bool => {
-                        quote! { ::sycamore::rt::JsValue::from_bool(*#expr.get()) }
+                        quote_spanned! {span=> ::sycamore::rt::JsValue::from_bool(*#expr.get()) }
+                    }
+                    JsPropertyType::Number => {
+                        quote_spanned! {span=> ::sycamore::rt::JsValue::from_f64(*#expr.get()) }
                     }
                     JsPropertyType::String => {
-                        quote! {
+                        quote_spanned! {span=>
                             ::sycamore::rt::JsValue::from_str(
                                 &::std::string::ToString::to_string(&#expr.get())
                             )
@@ -413,7 +610,7 @@ impl Codegen {
                     }
                 };
 
-                let event_target_prop = quote! {
+                let event_target_prop = quote_spanned! {span=>
                     ::sycamore::rt::Reflect::get(
                         &event.target().unwrap(),
                         &::std::convert::Into::<::sycamore::rt::JsValue>::into(#prop)
@@ -421,25 +618,28 @@ impl Codegen {
                 };
 
                 let convert_from_jsvalue_fn = match property_ty {
-                    JsPropertyType::Bool => quote! {
+                    JsPropertyType::Bool => quote_spanned! {span=>
                         ::sycamore::rt::JsValue::as_bool(&#event_target_prop).unwrap()
                     },
-                    JsPropertyType::String => quote! {
+                    JsPropertyType::Number => quote_spanned! {span=>
+                        ::sycamore::rt::JsValue::as_f64(&#event_target_prop).unwrap()
+                    },
+                    JsPropertyType::String => quote_spanned! {span=>
                         ::sycamore::rt::JsValue::as_string(&#event_target_prop).unwrap()
                     },
                 };
 
-                tokens.extend(quote! {
-                    #[cfg(target_arch = "wasm32")]
+                tokens.extend(quote_spanned! {span=>
+                    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
                     ::sycamore::reactive::create_effect(#cx, {
-                        let __el = ::std::clone::Clone::clone(&__el);
+                        let #el = ::std::clone::Clone::clone(&#el);
                         move ||::sycamore::generic_node::GenericNode::set_property(
-                            &__el,
+                            &#el,
                             #prop,
                             &#convert_into_jsvalue_fn,
                         )
                     });
-                    ::sycamore::generic_node::GenericNode::event(&__el, #cx, #event_name,
+                    ::sycamore::generic_node::GenericNode::event(&#el, #cx, #event_name,
                         ::std::boxed::Box::new(|event: ::sycamore::rt::Event| {
                             #expr.set(#convert_from_jsvalue_fn);
                         }),
@@ -447,40 +647,103 @@ impl Codegen {
                 });
             }
             AttributeType::Ref => {
-                tokens.extend(quote! {{
-                    ::sycamore::noderef::NodeRef::set(&#expr, ::std::clone::Clone::clone(&__el));
-                }});
+                tokens.extend(if let Expr::Closure(_) = expr {
+                    quote_spanned! {span=>
+                        ::sycamore::noderef::bind_ref_callback(#expr, ::std::clone::Clone::clone(&#el));
+                    }
+                } else {
+                    quote_spanned! {span=>{
+                        use ::sycamore::noderef::BindRef as _;
+                        (#expr).bind_ref(::std::clone::Clone::clone(&#el));
+                    }}
+                });
+            }
+            AttributeType::Spread => {
+                tokens.extend(quote_spanned! {span=>
+                    ::sycamore::attributes::Attributes::apply_attributes(#expr, #cx, &#el);
+                });
             }
+            AttributeType::Key => unreachable!("key is not emitted as an attribute"),
         }
         tokens
     }
 
+    /// The name of the component shown in component boundary comments. See
+    /// `sycamore::component::set_component_comments`.
+    fn component_name(ident: &Path) -> String {
+        ident
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default()
+    }
+
     pub fn component(&self, comp: &Component) -> TokenStream {
         let cx = &self.cx;
         match comp {
             Component::FnLike(comp) => {
                 let FnLikeComponent { ident, args } = comp;
-                quote! { ::sycamore::component::component_scope(move || #ident(#cx, #args)) }
+                let name = Self::component_name(ident);
+                quote! {
+                    ::sycamore::component::component_scope_with_name(#name, move || #ident(#cx, #args))
+                }
             }
             Component::ElementLike(comp) => {
                 let ElementLikeComponent {
                     ident,
+                    node_ref,
                     props,
+                    children_params,
                     children,
                     ..
                 } = comp;
-                if props.is_empty() && children.is_none() {
+                let name = Self::component_name(ident);
+                if props.is_empty() && children.is_none() && node_ref.is_none() {
                     quote! {
-                       ::sycamore::component::component_scope(move || #ident(#cx))
+                       ::sycamore::component::component_scope_with_name(#name, move || #ident(#cx))
                     }
                 } else {
                     let mut props_quoted = quote! {
                         ::sycamore::component::element_like_component_builder(__component)
                     };
+                    if let Some(node_ref) = node_ref {
+                        props_quoted.extend(quote! {
+                            .node_ref(::sycamore::noderef::NodeRef::clone(&#node_ref))
+                        });
+                    }
                     for (field, expr) in props {
                         props_quoted.extend(quote! { .#field(#expr) });
                     }
-                    if let Some(children) = children {
+                    if let (Some(params), Some(children)) = (children_params, children) {
+                        let view_root = self.view_root(children);
+                        // Several parameters are passed as a tuple. Types cannot be written
+                        // inside a tuple pattern so they are moved to the type of the tuple.
+                        let params = if params.len() == 1 {
+                            quote! { #params }
+                        } else if params.iter().any(|param| matches!(param, Pat::Type(_))) {
+                            let (pats, tys): (Vec<_>, Vec<_>) = params
+                                .iter()
+                                .map(|param| match param {
+                                    Pat::Type(PatType { pat, ty, .. }) => {
+                                        (quote! { #pat }, quote! { #ty })
+                                    }
+                                    pat => (quote! { #pat }, quote! { _ }),
+                                })
+                                .unzip();
+                            quote! { (#(#pats,)*): (#(#tys,)*) }
+                        } else {
+                            quote! { (#params) }
+                        };
+                        props_quoted.extend(quote! {
+                            .children(
+                                ::sycamore::component::ChildrenWith::new(#cx, move |#cx, #params| {
+                                    #[allow(unused_variables)]
+                                    let #cx: ::sycamore::reactive::BoundedScope = #cx;
+                                    #view_root
+                                })
+                            )
+                        });
+                    } else if let Some(children) = children {
                         let view_root = self.view_root(children);
                         props_quoted.extend(quote! {
                             .children(
@@ -495,7 +758,10 @@ impl Codegen {
                     props_quoted.extend(quote! { .build() });
                     quote! {{
                         let __component = &#ident; // We do this to make sure the compiler can infer the value for `<G>`.
-                        ::sycamore::component::component_scope(move || __component(#cx, #props_quoted))
+                        ::sycamore::component::component_scope_with_name(
+                            #name,
+                            move || __component(#cx, #props_quoted),
+                        )
                     }}
                 }
             }
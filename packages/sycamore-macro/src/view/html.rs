@@ -0,0 +1,258 @@
+//! Parse the HTML-like syntax of the `html!` macro into the same IR as the `view!` macro.
+
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::token::Brace;
+use syn::{braced, parse_quote, Expr, Ident, Lit, LitStr, Path, Result, Token};
+
+use super::ir::*;
+
+/// The root of an `html!` macro invocation. Parses to a [`ViewRoot`].
+pub struct HtmlRoot(pub ViewRoot);
+
+impl Parse for HtmlRoot {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut children = Vec::new();
+        while !input.is_empty() {
+            children.push(parse_node(input)?);
+        }
+        Ok(Self(ViewRoot(children)))
+    }
+}
+
+/// Elements that never have children and therefore do not need a closing tag.
+/// See <https://html.spec.whatwg.org/multipage/syntax.html#void-elements>.
+fn is_void_element(tag: &str) -> bool {
+    matches!(
+        tag,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+fn parse_node(input: ParseStream) -> Result<ViewNode> {
+    if input.peek(LitStr) {
+        Ok(ViewNode::Text(input.parse()?))
+    } else if input.peek(Brace) {
+        let content;
+        braced!(content in input);
+        Ok(ViewNode::Dyn(Dyn {
+            value: content.parse()?,
+        }))
+    } else if input.peek(Token![<]) {
+        if input.peek2(Token![/]) {
+            return Err(input.error("unexpected closing tag"));
+        }
+        let _lt: Token![<] = input.parse()?;
+        if peek_component(input) {
+            parse_component(input)
+        } else {
+            parse_element(input)
+        }
+    } else {
+        Err(input.error("expected a string literal, `{` or `<`"))
+    }
+}
+
+/// Whether the next tag is a component, i.e. a path or an identifier starting with an uppercase
+/// letter, like in the `view!` macro.
+fn peek_component(input: ParseStream) -> bool {
+    if input.peek(Token![::]) {
+        return true;
+    }
+    let fork = input.fork();
+    match fork.call(Ident::parse_any) {
+        Ok(ident) => {
+            ident
+                .to_string()
+                .starts_with(|c: char| c.is_ascii_uppercase())
+                || fork.peek(Token![::])
+        }
+        Err(_) => false,
+    }
+}
+
+/// Parses the value of an attribute or prop, either a literal or an expression in braces.
+fn parse_value(input: ParseStream) -> Result<Expr> {
+    if input.peek(Brace) {
+        let content;
+        braced!(content in input);
+        content.parse()
+    } else {
+        let lit: Lit = input.parse().map_err(|err| {
+            syn::Error::new(err.span(), "expected a literal or an expression in braces")
+        })?;
+        Ok(parse_quote!(#lit))
+    }
+}
+
+/// Parses the end of an opening tag. Returns `true` if the tag is self-closing, i.e. `/>`.
+fn parse_tag_end(input: ParseStream) -> Result<bool> {
+    if input.peek(Token![/]) {
+        let _slash: Token![/] = input.parse()?;
+        let _gt: Token![>] = input.parse()?;
+        Ok(true)
+    } else {
+        let _gt: Token![>] = input.parse()?;
+        Ok(false)
+    }
+}
+
+/// Parses the children of an element and its closing tag, which must match `name`.
+fn parse_children(input: ParseStream, name: &str) -> Result<Vec<ViewNode>> {
+    let mut children = Vec::new();
+    while !(input.peek(Token![<]) && input.peek2(Token![/])) {
+        if input.is_empty() {
+            return Err(input.error(format!("expected closing tag `</{}>`", name)));
+        }
+        children.push(parse_node(input)?);
+    }
+    let _lt: Token![<] = input.parse()?;
+    let _slash: Token![/] = input.parse()?;
+    let span = input.span();
+    let closing = if input.peek(Token![>]) {
+        String::new()
+    } else if peek_component(input) {
+        path_to_string(&input.call(Path::parse_mod_style)?)
+    } else {
+        tag_to_string(&input.parse()?)
+    };
+    if closing != name {
+        return Err(syn::Error::new(
+            span,
+            format!("expected closing tag `</{}>`", name),
+        ));
+    }
+    let _gt: Token![>] = input.parse()?;
+    Ok(children)
+}
+
+fn tag_to_string(tag: &ElementTag) -> String {
+    match tag {
+        ElementTag::Builtin(ident) => ident.to_string(),
+        ElementTag::Custom(tag) => tag.clone(),
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_token_stream().to_string().replace(' ', "")
+}
+
+fn parse_element(input: ParseStream) -> Result<ViewNode> {
+    let span = input.span();
+    let tag: ElementTag = input.parse()?;
+    let name = tag_to_string(&tag);
+
+    let mut attrs = Vec::new();
+    while !input.peek(Token![>]) && !input.peek(Token![/]) {
+        let span = input.span();
+        if input.peek(Brace) {
+            let content;
+            braced!(content in input);
+            let _dot2: Token![..] = content.parse()?;
+            attrs.push(Attribute {
+                ty: AttributeType::Spread,
+                value: content.parse()?,
+                span,
+            });
+            continue;
+        }
+        let ty: AttributeType = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            let _eq: Token![=] = input.parse()?;
+            parse_value(input)?
+        } else {
+            // An attribute without a value, e.g. `<input disabled />`.
+            match &ty {
+                AttributeType::Bool { .. } => parse_quote!(true),
+                AttributeType::Str { .. } => parse_quote!(""),
+                _ => return Err(syn::Error::new(span, "expected `=`")),
+            }
+        };
+        attrs.push(Attribute { ty, value, span });
+    }
+
+    let self_closing = parse_tag_end(input)?;
+    let children = if self_closing || is_void_element(&name) {
+        Vec::new()
+    } else {
+        parse_children(input, &name)?
+    };
+
+    Element {
+        tag,
+        attrs,
+        children,
+        span,
+    }
+    .into_view_node()
+}
+
+fn parse_component(input: ParseStream) -> Result<ViewNode> {
+    let ident = input.call(Path::parse_mod_style)?;
+    let name = path_to_string(&ident);
+
+    let mut props = Vec::new();
+    let mut node_ref = None;
+    while !input.peek(Token![>]) && !input.peek(Token![/]) {
+        let prop = input.call(Ident::parse_any)?;
+        let _eq: Token![=] = input.parse()?;
+        let value = parse_value(input)?;
+        if prop == "ref" {
+            // Forwarded to the `node_ref` prop.
+            node_ref = Some(Box::new(value));
+        } else {
+            props.push((prop, value));
+        }
+    }
+
+    let self_closing = parse_tag_end(input)?;
+    let children = if self_closing {
+        Vec::new()
+    } else {
+        parse_children(input, &name)?
+    };
+
+    Ok(ViewNode::Component(Component::ElementLike(
+        ElementLikeComponent {
+            ident,
+            brace: Brace(Span::call_site()),
+            key: None,
+            node_ref,
+            props,
+            children_params: None,
+            children: (!children.is_empty()).then_some(ViewRoot(children)),
+        },
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closing_tags() {
+        let root: HtmlRoot =
+            syn::parse_str(r#"<div><custom-el></custom-el><br><Comp /></div> <Comp></Comp>"#)
+                .unwrap();
+        assert_eq!(root.0 .0.len(), 2);
+
+        let err = syn::parse_str::<HtmlRoot>("<div></span>").err().unwrap();
+        assert_eq!(err.to_string(), "expected closing tag `</div>`");
+        let err = syn::parse_str::<HtmlRoot>("<div><p></div>").err().unwrap();
+        assert_eq!(err.to_string(), "expected closing tag `</p>`");
+    }
+}
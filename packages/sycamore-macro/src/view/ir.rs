@@ -7,7 +7,7 @@ use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::ToTokens;
 use syn::punctuated::Punctuated;
 use syn::token::Brace;
-use syn::{Expr, Ident, LitStr, Path, Token};
+use syn::{Expr, Ident, LitStr, Pat, Path, Token};
 
 pub struct ViewRoot(pub Vec<ViewNode>);
 
@@ -15,7 +15,10 @@ pub enum ViewNode {
     Element(Element),
     Component(Component),
     Text(Text),
+    Comment(Comment),
     Dyn(Dyn),
+    If(If),
+    Keyed(Keyed),
 }
 
 impl ViewNode {
@@ -31,10 +34,13 @@ impl ViewNode {
             ViewNode::Element(_) => false,
             ViewNode::Component(_) => true,
             ViewNode::Text(_) => false,
+            ViewNode::Comment(_) => false,
             ViewNode::Dyn(Dyn {
                 value: Expr::Lit(_) | Expr::Path(_),
             }) => false,
             ViewNode::Dyn(_) => true,
+            ViewNode::If(_) => true,
+            ViewNode::Keyed(_) => true,
         }
     }
 }
@@ -43,12 +49,15 @@ pub enum NodeType {
     Element,
     Component,
     Text,
+    Comment,
     Dyn,
+    If,
 }
 pub struct Element {
     pub tag: ElementTag,
     pub attrs: Vec<Attribute>,
     pub children: Vec<ViewNode>,
+    pub span: Span,
 }
 
 pub enum ElementTag {
@@ -74,14 +83,22 @@ pub enum AttributeType {
     Bool { name: String },
     /// Syntax: `dangerously_set_inner_html`.
     DangerouslySetInnerHtml,
-    /// Syntax: `on:<event>`.
-    Event { event: String },
+    /// Syntax: `on:<event>`, optionally followed by modifiers, e.g. `on:submit.prevent.once`.
+    Event {
+        event: String,
+        modifiers: Vec<String>,
+    },
     /// Syntax: `bind:<prop>`.
     Bind { prop: String },
     /// Syntax: `prop:<prop>`.
     Property { prop: String },
     /// Syntax: `ref`.
     Ref,
+    /// Syntax: `key`. Not emitted as an attribute: the element is wrapped in a [`Keyed`] node
+    /// instead.
+    Key,
+    /// Syntax: `..<expr>`. The expression must implement `Attributes`.
+    Spread,
 }
 
 pub fn is_bool_attr(name: &str) -> bool {
@@ -134,7 +151,15 @@ pub struct FnLikeComponent {
 pub struct ElementLikeComponent {
     pub ident: Path,
     pub brace: Brace,
+    /// Syntax: `key=<expr>` before the props. The component is wrapped in a [`Keyed`] node.
+    pub key: Option<Box<Expr>>,
+    /// Syntax: `ref=<expr>` before the props, or `Comp(ref=<expr>)`. The `NodeRef` is passed to
+    /// the `node_ref` prop of the component.
+    pub node_ref: Option<Box<Expr>>,
     pub props: Vec<(Ident, Expr)>,
+    /// Syntax: `|<pat>, ...|` before the children. The children are passed as a
+    /// `ChildrenWith` that takes the parameters.
+    pub children_params: Option<Punctuated<Pat, Token![,]>>,
     pub children: Option<ViewRoot>,
 }
 
@@ -142,10 +167,29 @@ pub struct Text {
     pub value: LitStr,
 }
 
+pub struct Comment {
+    pub value: Expr,
+}
+
 pub struct Dyn {
     pub value: Expr,
 }
 
+/// Syntax: `if <cond> { <nodes> } else if <cond> { <nodes> } else { <nodes> }`.
+pub struct If {
+    /// The conditions and the nodes of the `if` and `else if` branches.
+    pub branches: Vec<(Expr, ViewRoot)>,
+    pub else_branch: Option<ViewRoot>,
+}
+
+/// An element or a component that is created again every time its key changes.
+///
+/// Syntax: `<tag>(key=<expr>, ...)` or `<Component> { key=<expr>, ... }`.
+pub struct Keyed {
+    pub key: Expr,
+    pub node: Box<ViewNode>,
+}
+
 fn needs_cx(ts: TokenStream, cx: &str) -> bool {
     for t in ts {
         match t {
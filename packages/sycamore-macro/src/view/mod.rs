@@ -3,6 +3,7 @@
 #![allow(clippy::eval_order_dependence)] // Needed when using `syn::parenthesized!`.
 
 pub mod codegen;
+pub mod html;
 pub mod ir;
 pub mod parse;
 
@@ -12,6 +13,7 @@ use syn::parse::{Parse, ParseStream};
 use syn::{parse_quote, Expr, Result, Token};
 
 use self::codegen::Codegen;
+use self::html::HtmlRoot;
 use self::ir::*;
 
 pub struct WithcxArg<T> {
@@ -32,6 +34,7 @@ pub fn view_impl(view_root: WithcxArg<ViewRoot>) -> TokenStream {
     let cx = view_root.cx;
     let codegen_state = Codegen {
         cx: parse_quote!(#cx),
+        namespace: Default::default(),
     };
     let quoted = codegen_state.view_root(&view_root.rest);
     quote! {{
@@ -41,10 +44,18 @@ pub fn view_impl(view_root: WithcxArg<ViewRoot>) -> TokenStream {
     }}
 }
 
+pub fn html_impl(html_root: WithcxArg<HtmlRoot>) -> TokenStream {
+    view_impl(WithcxArg {
+        cx: html_root.cx,
+        rest: html_root.rest.0,
+    })
+}
+
 pub fn node_impl(elem: WithcxArg<Element>) -> TokenStream {
     let cx = elem.cx;
     let codegen_state = Codegen {
         cx: parse_quote!(#cx),
+        namespace: Default::default(),
     };
     let quoted = codegen_state.element(&elem.rest);
     quote! {{
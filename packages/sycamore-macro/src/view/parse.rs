@@ -7,7 +7,9 @@ use syn::parse::discouraged::Speculative;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::token::{Brace, Paren};
-use syn::{braced, parenthesized, token, Expr, FieldValue, Ident, LitStr, Result, Token};
+use syn::{
+    braced, parenthesized, token, Expr, FieldValue, Ident, LitStr, Pat, PatType, Result, Token,
+};
 
 use super::ir::*;
 
@@ -29,6 +31,8 @@ impl ViewNode {
 
         if input.peek(LitStr) {
             Some(NodeType::Text)
+        } else if input.peek(Token![if]) {
+            Some(NodeType::If)
         } else if input.peek(Paren) {
             Some(NodeType::Dyn)
         } else if input.peek(Token![::]) {
@@ -39,6 +43,8 @@ impl ViewNode {
 
             if ident.chars().next().unwrap().is_ascii_uppercase() || input.peek(Token![::]) {
                 Some(NodeType::Component)
+            } else if ident == "comment" && input.peek(Paren) {
+                Some(NodeType::Comment)
             } else {
                 Some(NodeType::Element)
             }
@@ -56,16 +62,68 @@ impl Parse for ViewNode {
         };
 
         Ok(match ty {
-            NodeType::Element => Self::Element(input.parse()?),
-            NodeType::Component => Self::Component(input.parse()?),
+            NodeType::Element => input.parse::<Element>()?.into_view_node()?,
+            NodeType::Component => match input.parse()? {
+                Component::ElementLike(ElementLikeComponent {
+                    key: Some(key),
+                    ident,
+                    brace,
+                    node_ref,
+                    props,
+                    children_params,
+                    children,
+                }) => Self::Keyed(Keyed {
+                    key: *key,
+                    node: Box::new(Self::Component(Component::ElementLike(
+                        ElementLikeComponent {
+                            ident,
+                            brace,
+                            key: None,
+                            node_ref,
+                            props,
+                            children_params,
+                            children,
+                        },
+                    ))),
+                }),
+                comp => Self::Component(comp),
+            },
             NodeType::Text => Self::Text(input.parse()?),
+            NodeType::Comment => Self::Comment(input.parse()?),
             NodeType::Dyn => Self::Dyn(input.parse()?),
+            NodeType::If => Self::If(input.parse()?),
         })
     }
 }
 
+impl Element {
+    /// Wraps the element in a [`Keyed`] node if it has a `key` attribute.
+    pub fn into_view_node(mut self) -> Result<ViewNode> {
+        let mut keys = self
+            .attrs
+            .iter()
+            .filter(|attr| attr.ty == AttributeType::Key);
+        if let (Some(_), Some(duplicate)) = (keys.next(), keys.next()) {
+            return Err(syn::Error::new(duplicate.span, "duplicate `key` attribute"));
+        }
+        let key = match self
+            .attrs
+            .iter()
+            .position(|attr| attr.ty == AttributeType::Key)
+        {
+            Some(index) => self.attrs.remove(index).value,
+            None => return Ok(ViewNode::Element(self)),
+        };
+        Ok(ViewNode::Keyed(Keyed {
+            key,
+            node: Box::new(ViewNode::Element(self)),
+        }))
+    }
+}
+
 impl Parse for Element {
     fn parse(input: ParseStream) -> Result<Self> {
+        let span = input.span();
         let tag = input.parse()?;
         let attrs = if input.peek(token::Paren) {
             let content;
@@ -107,6 +165,7 @@ impl Parse for Element {
             tag,
             attrs,
             children,
+            span,
         })
     }
 }
@@ -137,6 +196,15 @@ impl Parse for ElementTag {
 impl Parse for Attribute {
     fn parse(input: ParseStream) -> Result<Self> {
         let span = input.span();
+        if input.peek(Token![..]) {
+            let _dot2: Token![..] = input.parse()?;
+            let value = input.parse()?;
+            return Ok(Self {
+                ty: AttributeType::Spread,
+                value,
+                span,
+            });
+        }
         let ty = input.parse()?;
         let _eqs: Token![=] = input.parse()?;
         let value = input.parse()?;
@@ -144,6 +212,9 @@ impl Parse for Attribute {
     }
 }
 
+/// The modifiers that can follow the name of an event in `on:<event>`.
+const EVENT_MODIFIERS: &[&str] = &["prevent", "stop", "capture", "once", "passive"];
+
 impl Parse for AttributeType {
     fn parse(input: ParseStream) -> Result<Self> {
         pub struct AttributeName {
@@ -181,16 +252,45 @@ impl Parse for AttributeType {
 
         if name == "ref" {
             Ok(Self::Ref)
+        } else if name == "key" {
+            Ok(Self::Key)
         } else if name == "dangerously_set_inner_html" {
             Ok(Self::DangerouslySetInnerHtml)
         } else if input.peek(Token![:]) {
             let _colon: Token![:] = input.parse()?;
             match name.as_str() {
                 "on" => {
-                    let event = input.call(Ident::parse_any)?;
-                    Ok(Self::Event {
-                        event: event.to_string(),
-                    })
+                    // Custom events, e.g. those of web components, often have dashed names.
+                    let mut event = input.call(Ident::parse_any)?.to_string();
+                    while input.peek(Token![-]) {
+                        let _dash: Token![-] = input.parse()?;
+                        event.push('-');
+                        event.push_str(&input.call(Ident::parse_any)?.to_string());
+                    }
+                    let mut modifiers = Vec::new();
+                    while input.peek(Token![.]) && !input.peek(Token![..]) {
+                        let _dot: Token![.] = input.parse()?;
+                        let modifier = input.call(Ident::parse_any)?;
+                        let name = modifier.to_string();
+                        if !EVENT_MODIFIERS.contains(&name.as_str()) {
+                            return Err(syn::Error::new_spanned(
+                                modifier,
+                                format!(
+                                    "unknown event modifier `{}`, expected one of: {}",
+                                    name,
+                                    EVENT_MODIFIERS.join(", ")
+                                ),
+                            ));
+                        }
+                        if modifiers.contains(&name) {
+                            return Err(syn::Error::new_spanned(
+                                modifier,
+                                format!("duplicate event modifier `{}`", name),
+                            ));
+                        }
+                        modifiers.push(name);
+                    }
+                    Ok(Self::Event { event, modifiers })
                 }
                 "prop" => {
                     let prop = input.call(Ident::parse_any)?;
@@ -217,18 +317,64 @@ impl Parse for AttributeType {
     }
 }
 
+/// Parses `ref=<expr>` on a component.
+fn parse_ref(input: ParseStream) -> Result<Box<Expr>> {
+    let _ref: Token![ref] = input.parse()?;
+    let _eq: Token![=] = input.parse()?;
+    Ok(Box::new(input.parse()?))
+}
+
 impl Parse for Component {
     fn parse(input: ParseStream) -> Result<Self> {
         let ident = input.parse()?;
         let content;
         if input.peek(Paren) {
+            let paren = parenthesized!(content in input);
+            if content.peek(Token![ref]) {
+                // Parse component with only a forwarded ref, e.g. `Comp(ref=node_ref)`.
+                let node_ref = parse_ref(&content)?;
+                if !content.is_empty() {
+                    return Err(content.error("expected only `ref=<expr>`"));
+                }
+                return Ok(Self::ElementLike(ElementLikeComponent {
+                    ident,
+                    brace: Brace(paren.span),
+                    key: None,
+                    node_ref: Some(node_ref),
+                    props: Vec::new(),
+                    children_params: None,
+                    children: None,
+                }));
+            }
             // Parse fn-like component.
-            parenthesized!(content in input);
             let args = content.parse_terminated(Expr::parse)?;
             Ok(Self::FnLike(FnLikeComponent { ident, args }))
         } else if input.peek(Brace) {
             // Parse element link component.
             let brace = braced!(content in input);
+            // `key=<expr>` and `ref=<expr>` are not valid props, so they cannot be confused with
+            // props.
+            let mut key = None;
+            let mut node_ref = None;
+            loop {
+                if key.is_none()
+                    && content.peek(Ident::peek_any)
+                    && content.peek2(Token![=])
+                    && !content.peek2(Token![==])
+                    && content.fork().call(Ident::parse_any)? == "key"
+                {
+                    let _key = content.call(Ident::parse_any)?;
+                    let _eq: Token![=] = content.parse()?;
+                    key = Some(Box::new(content.parse()?));
+                } else if node_ref.is_none() && content.peek(Token![ref]) {
+                    node_ref = Some(parse_ref(&content)?);
+                } else {
+                    break;
+                }
+                if !content.is_empty() {
+                    let _comma: Token![,] = content.parse()?;
+                }
+            }
             let mut props = Punctuated::<FieldValue, Token![,]>::new();
             while !content.is_empty() {
                 let fork = content.fork();
@@ -248,6 +394,40 @@ impl Parse for Component {
                     break;
                 }
             }
+            let children_params = if content.peek(Token![||]) {
+                let _or: Token![||] = content.parse()?;
+                Some(Punctuated::new())
+            } else if content.peek(Token![|]) {
+                let _or: Token![|] = content.parse()?;
+                let mut params = Punctuated::new();
+                while !content.peek(Token![|]) {
+                    let pat = content.parse()?;
+                    params.push_value(if content.peek(Token![:]) {
+                        Pat::Type(PatType {
+                            attrs: Vec::new(),
+                            pat: Box::new(pat),
+                            colon_token: content.parse()?,
+                            ty: content.parse()?,
+                        })
+                    } else {
+                        pat
+                    });
+                    if content.peek(Token![|]) {
+                        break;
+                    }
+                    params.push_punct(content.parse()?);
+                }
+                let _or: Token![|] = content.parse()?;
+                Some(params)
+            } else {
+                None
+            };
+            if children_params.is_some()
+                && !content.peek(Brace)
+                && ViewNode::peek_type(&content).is_none()
+            {
+                return Err(content.error("expected children after the children parameters"));
+            }
             let children = if content.peek(Brace) {
                 // Parse view fragment as children
                 let children;
@@ -261,6 +441,8 @@ impl Parse for Component {
             Ok(Self::ElementLike(ElementLikeComponent {
                 ident,
                 brace,
+                key,
+                node_ref,
                 props: props
                     .into_iter()
                     .map(|x| match x.member {
@@ -268,6 +450,7 @@ impl Parse for Component {
                         syn::Member::Unnamed(_) => todo!("implement error handling"),
                     })
                     .collect(),
+                children_params,
                 children,
             }))
         } else {
@@ -276,10 +459,65 @@ impl Parse for Component {
     }
 }
 
+/// Collapses every run of whitespace into a single space and trims the start and end, like HTML
+/// does for most elements.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Removes the first and last lines if they are blank and the indentation common to all the
+/// non-blank lines. The other whitespace, including newlines, is kept.
+fn dedent(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if lines.first().map_or(false, |line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    if lines.last().map_or(false, |line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl Parse for Text {
     fn parse(input: ParseStream) -> Result<Self> {
+        let lit: LitStr = input.parse()?;
+        let value = match lit.suffix() {
+            "" => return Ok(Self { value: lit }),
+            "trim" => collapse_whitespace(&lit.value()),
+            "dedent" => dedent(&lit.value()),
+            suffix => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "unknown text modifier `{}`, expected `trim` or `dedent`",
+                        suffix
+                    ),
+                ))
+            }
+        };
+        Ok(Self {
+            value: LitStr::new(&value, lit.span()),
+        })
+    }
+}
+
+impl Parse for Comment {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _comment: Ident = input.parse()?;
+        let content;
+        parenthesized!(content in input);
         Ok(Self {
-            value: input.parse()?,
+            value: content.parse()?,
         })
     }
 }
@@ -293,3 +531,57 @@ impl Parse for Dyn {
         })
     }
 }
+
+impl Parse for If {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut branches = Vec::new();
+        let mut else_branch = None;
+        loop {
+            let _if: Token![if] = input.parse()?;
+            let cond = Expr::parse_without_eager_brace(input)?;
+            if let Expr::Let(cond) = &cond {
+                return Err(syn::Error::new_spanned(
+                    cond.let_token,
+                    "`if let` is not supported in `view!`, use an interpolation with a nested \
+                     `view!` instead",
+                ));
+            }
+            let content;
+            braced!(content in input);
+            branches.push((cond, content.parse()?));
+
+            if !input.peek(Token![else]) {
+                break;
+            }
+            let _else: Token![else] = input.parse()?;
+            if !input.peek(Token![if]) {
+                let content;
+                braced!(content in input);
+                else_branch = Some(content.parse()?);
+                break;
+            }
+        }
+        Ok(Self {
+            branches,
+            else_branch,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_modifiers() {
+        assert_eq!(
+            collapse_whitespace("  Hello,\n    world!  "),
+            "Hello, world!"
+        );
+        assert_eq!(
+            dedent("\n        fn main() {\n            loop {}\n        }\n    "),
+            "fn main() {\n    loop {}\n}"
+        );
+        assert_eq!(dedent("  a\n\n    b"), "a\n\n  b");
+    }
+}
@@ -23,4 +23,34 @@ async fn AsyncCompWithProps<G: Html>(_cx: Scope<'_>, prop: ::std::primitive::i32
     ::std::todo!();
 }
 
+#[component]
+fn GenericComp<'a, T, G: Html>(_cx: Scope<'a>, prop: &'a [T]) -> View<G>
+where
+    T: ::std::fmt::Display + ::std::clone::Clone + 'static,
+{
+    let _ = prop;
+    ::std::todo!();
+}
+
+#[component]
+async fn AsyncCompElidedScope<G: Html>(_cx: Scope) -> View<G> {
+    ::std::todo!();
+}
+
+#[component]
+async fn AsyncCompElidedProps<T, G>(_cx: Scope<'_>, prop: &[T]) -> View<G>
+where
+    T: ::std::fmt::Display + 'static,
+    G: Html,
+{
+    let _ = prop;
+    ::std::todo!();
+}
+
+#[component]
+async fn AsyncCompNamedScope<'a, G: Html>(_cx: Scope<'a>, prop: &::std::primitive::str) -> View<G> {
+    let _ = prop;
+    ::std::todo!();
+}
+
 fn main() {}
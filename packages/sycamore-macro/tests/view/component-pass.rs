@@ -22,6 +22,19 @@ pub fn ComponentWithChildren<'a, G: Html>(cx: Scope<'a>, prop: PropWithChildren<
     prop.children.call(cx)
 }
 
+#[derive(Prop)]
+pub struct PropWithChildrenWith<'a, T, G: GenericNode> {
+    children: ChildrenWith<'a, T, G>,
+}
+
+#[component]
+pub fn ComponentWithChildrenWith<'a, T: Default, G: Html>(
+    cx: Scope<'a>,
+    prop: PropWithChildrenWith<'a, T, G>,
+) -> View<G> {
+    prop.children.call(cx, T::default())
+}
+
 #[component]
 pub async fn AsyncComponentWithPropDestructuring<'a, G: Html>(
     cx: Scope<'a>,
@@ -30,6 +43,52 @@ pub async fn AsyncComponentWithPropDestructuring<'a, G: Html>(
     children.call(cx)
 }
 
+#[derive(Prop)]
+pub struct MaybeDynProp<'a> {
+    value: MaybeDyn<'a, i32>,
+}
+
+#[component]
+pub fn MaybeDynComponent<'a, G: Html>(cx: Scope<'a>, prop: MaybeDynProp<'a>) -> View<G> {
+    view! { cx,
+        (prop.value.get())
+    }
+}
+
+#[derive(Prop)]
+pub struct GenericProp<'a, T>
+where
+    T: std::fmt::Display + 'static,
+{
+    items: &'a ReadSignal<Vec<T>>,
+}
+
+#[component]
+pub fn GenericComponent<'a, T, G: Html>(cx: Scope<'a>, prop: GenericProp<'a, T>) -> View<G>
+where
+    T: std::fmt::Display + Clone + 'static,
+{
+    let text = create_memo(cx, || prop.items.get().iter().map(T::to_string).collect::<String>());
+    view! { cx,
+        (text.get())
+    }
+}
+
+#[derive(Prop)]
+pub struct RefProp<G: GenericNode> {
+    #[builder(default)]
+    node_ref: NodeRef<G>,
+    #[builder(default)]
+    label: &'static str,
+}
+
+#[component]
+pub fn RefComponent<G: Html>(cx: Scope, prop: RefProp<G>) -> View<G> {
+    view! { cx,
+        button(ref=prop.node_ref) { (prop.label) }
+    }
+}
+
 #[component]
 pub fn Component<G: Html>(cx: Scope) -> View<G> {
     view! { cx,
@@ -45,6 +104,24 @@ fn compile_pass<G: Html>() {
         let prop = "prop";
         let _: View<G> = view! { cx, PropComponent { prop: prop } };
         let _: View<G> = view! { cx, PropComponent { prop } };
+        let _: View<G> = view! { cx, PropComponent { key=prop, prop: prop } };
+        let _: View<G> = view! { cx, Component { key=1 } };
+
+        let node_ref = create_node_ref(cx);
+        let _: View<G> = view! { cx, RefComponent(ref=node_ref) };
+        let _: View<G> = view! { cx, RefComponent { ref=node_ref, label: "Ok" } };
+        let _: View<G> = view! { cx, RefComponent { key=1, ref=node_ref } };
+
+        let signal = create_signal(cx, 0);
+        let _: View<G> = view! { cx, MaybeDynComponent { value: 0 } };
+        let _: View<G> = view! { cx, MaybeDynComponent { value: signal } };
+        let _: View<G> = view! { cx,
+            MaybeDynComponent { value: MaybeDyn::derived(|| *signal.get() + 1) }
+        };
+
+        let items = create_signal(cx, vec![1, 2]);
+        let _: View<G> = view! { cx, GenericComponent { items: items } };
+        let _: View<G> = view! { cx, GenericComponent::<i32, _> { items: items } };
 
         let _: View<G> = view! { cx,
             ComponentWithChildren {
@@ -58,6 +135,28 @@ fn compile_pass<G: Html>() {
             }
         };
 
+        let _: View<G> = view! { cx,
+            ComponentWithChildrenWith {
+                |i: i32| p { (i) }
+            }
+        };
+
+        let _: View<G> = view! { cx,
+            ComponentWithChildrenWith {
+                |(a, b): (i32, String), c: bool| {
+                    p { (b) }
+                    (a)
+                    (c)
+                }
+            }
+        };
+
+        let _: View<G> = view! { cx,
+            ComponentWithChildrenWith {
+                || Component {}
+            }
+        };
+
         let _: View<G> = view! { cx,
             AsyncComponentWithPropDestructuring {
                 Component {}
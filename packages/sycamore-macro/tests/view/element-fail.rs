@@ -6,10 +6,15 @@ fn compile_fail<G: GenericNode>() {
         let _: View<G> = view! { cx, button(on:click) };
         let _: View<G> = view! { cx, button(prop:disabled) };
         let _: View<G> = view! { cx, button(unknown:directive="123") };
+        let _: View<G> = view! { cx, button(on:click.unknown=|_| {}) };
+        let _: View<G> = view! { cx, button(on:click.once.once=|_| {}) };
         let _: View<G> = view! { cx, unknownelement };
 
         let _: View<G> = view! { cx, button(a.b.c="123") };
 
+        let value = Some(1);
+        let _: View<G> = view! { cx, p { if let Some(v) = value { (v) } } };
+
         let _: View<G> = view! { cx,
             p(dangerously_set_inner_html="<span>Test</span>") {
                 "Error"
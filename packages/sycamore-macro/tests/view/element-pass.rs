@@ -12,12 +12,44 @@ fn compile_pass<G: GenericNode>() {
         let _: View<G> = view! { cx, p(class="my-class", id="my-id") };
 
         let _: View<G> = view! { cx, button(class="my-btn", on:click=|_| {}) };
+        let _: View<G> = view! { cx, form(on:submit.prevent.stop=|_| {}) };
+        let _: View<G> = view! { cx, div(on:scroll.passive.capture.once=|_| {}) };
+        let _: View<G> = view! { cx, custom-element(on:value-changed.stop=|_| {}) };
         let _: View<G> = view! { cx, button(class="my-btn", aria-hidden="true") };
 
         let _: View<G> = view! { cx, p(dangerously_set_inner_html="<span>Test</span>") };
 
+        let _: View<G> = view! { cx, comment("comment") };
+        let _: View<G> = view! { cx, p { comment("comment") (1) comment(1 + 2) } };
+
         // view! should correctly parenthesize the (1 + 2) when borrowing.
         let _: View<G> = view! { cx, p { (1 + 2) } };
+
+        let show = create_signal(cx, true);
+        let _: View<G> = view! { cx, p { if *show.get() { "shown" } } };
+        let _: View<G> = view! { cx,
+            p {
+                if *show.get() {
+                    span { "a" }
+                } else if !*show.get() {
+                    "b"
+                } else {
+                    "c" (1)
+                }
+            }
+        };
+        let _: View<G> = view! { cx, if *show.get() { p } else { span } };
+        let _: View<G> = view! { cx, form(key=*show.get(), class="form") { input } };
+        let _: View<G> = view! { cx, div { p(key=*show.get()) } };
+
+        let _: View<G> = view! { cx,
+            svg {
+                a(href="#") { title { "Link" } text { "Link" } }
+                custom-shape
+                foreignObject { a(href="#") }
+            }
+        };
+        let _: View<G> = view! { cx, math { mrow { mi { "x" } mo { "=" } mn { "1" } } } };
     });
 }
 
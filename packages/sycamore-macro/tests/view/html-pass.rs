@@ -0,0 +1,72 @@
+use sycamore::prelude::*;
+
+#[component]
+fn Button<'a, G: Html>(cx: Scope<'a>, props: ButtonProps<'a, G>) -> View<G> {
+    let _ = props.kind;
+    props.children.call(cx)
+}
+
+#[derive(Prop)]
+struct ButtonProps<'a, G: GenericNode> {
+    kind: &'static str,
+    children: Children<'a, G>,
+}
+
+#[derive(Prop)]
+struct RefInputProps<G: GenericNode> {
+    node_ref: NodeRef<G>,
+}
+
+#[component]
+fn RefInput<G: Html>(cx: Scope, props: RefInputProps<G>) -> View<G> {
+    view! { cx, input(ref=props.node_ref) }
+}
+
+#[component]
+fn Empty<G: Html>(cx: Scope) -> View<G> {
+    view! { cx, }
+}
+
+fn compile_pass<G: Html>() {
+    create_scope_immediate(|cx| {
+        let _: View<G> = html! { cx, };
+        let _: View<G> = html! { cx, "text" };
+        let _: View<G> = html! { cx, <div></div> };
+        let _: View<G> = html! { cx, <div /> };
+        let _: View<G> = html! { cx, <custom-element></custom-element> };
+        let _: View<G> = html! { cx, <br> <hr /> };
+
+        let _: View<G> = html! { cx,
+            <div class="x" data-id="5" aria-hidden="true" tabindex=0>
+                <p>{1 + 2}</p>
+                <input type="checkbox" checked disabled={true}>
+            </div>
+        };
+
+        let value = create_signal(cx, String::new());
+        let node_ref = create_node_ref(cx);
+        let _: View<G> = html! { cx,
+            <input
+                ref={node_ref}
+                bind:value={value}
+                on:input={|_| {}}
+                prop:id={"input"}
+            />
+        };
+
+        let number = create_signal(cx, 0.0);
+        let _: View<G> = html! { cx, <input type="number" bind:valueAsNumber={number} /> };
+
+        let _: View<G> = html! { cx, <Empty /> };
+        let _: View<G> = html! { cx, <Empty></Empty> };
+        let input_ref = create_node_ref(cx);
+        let _: View<G> = html! { cx, <RefInput ref={input_ref} /> };
+        let _: View<G> = html! { cx,
+            <Button kind="primary">
+                <span>"Click"</span>
+            </Button>
+        };
+    });
+}
+
+fn main() {}
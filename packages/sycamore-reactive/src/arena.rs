@@ -1,29 +1,105 @@
 //! Arena allocator for [`Scope`](crate::Scope).
 
-use std::cell::UnsafeCell;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{RefCell, UnsafeCell};
+use core::mem::{self, MaybeUninit};
+use core::ptr;
 
 use bumpalo::Bump;
 use smallvec::SmallVec;
 
+use crate::ScopeRaw;
+
 /// The size of the [`SmallVec`] inline data.
 const SCOPE_ARENA_STACK_SIZE: usize = 4;
+/// The maximum number of allocators and of scope slots kept in a [`ScopePool`].
+const SCOPE_POOL_SIZE: usize = 256;
+/// Allocators that kept a chunk larger than this are not pooled, so that a single large scope
+/// does not hold on to its memory until the root scope is disposed.
+const SCOPE_POOL_MAX_BYTES: usize = 4096;
+
+/// The memory of the scopes of a tree of scopes that were disposed, reused by the scopes that are
+/// created afterwards in the same tree. This avoids allocating and freeing memory for every child
+/// scope, e.g. for every row of a list.
+///
+/// Created by the root scope and shared with all its descendants. The memory is freed once the
+/// root scope is disposed.
+#[derive(Default)]
+pub(crate) struct ScopePool {
+    /// The allocators of the [`ScopeArena`]s that were dropped, reset and ready to be reused.
+    bumps: RefCell<Vec<Bump>>,
+    /// The slots of the child scopes that were disposed. The lifetime of the scopes does not
+    /// matter since the slots are uninitialized.
+    #[allow(clippy::vec_box)] // The boxes are the slots, whose addresses are handed out.
+    slots: RefCell<Vec<Box<MaybeUninit<ScopeRaw<'static>>>>>,
+}
+
+impl ScopePool {
+    /// Moves `scope` into a free slot, or into a new one if there is none.
+    pub(crate) fn alloc_scope<'a>(&self, scope: ScopeRaw<'a>) -> *mut ScopeRaw<'a> {
+        let slot = self.slots.borrow_mut().pop();
+        let slot = slot.unwrap_or_else(|| Box::new(MaybeUninit::uninit()));
+        let ptr = Box::into_raw(slot).cast::<ScopeRaw<'a>>();
+        // SAFETY: the slot is uninitialized and has the layout of a `ScopeRaw`, whatever its
+        // lifetime.
+        unsafe { ptr.write(scope) };
+        ptr
+    }
+
+    /// Drops the scope and keeps its slot in the pool of the scope.
+    ///
+    /// # Safety
+    /// `scope` must have been returned by [`alloc_scope`](Self::alloc_scope) and must not be used
+    /// afterwards.
+    pub(crate) unsafe fn free_scope(scope: *mut ScopeRaw<'_>) {
+        let pool = Rc::clone(&(*scope).arena.pool);
+        ptr::drop_in_place(scope);
+        let slot = Box::from_raw(scope.cast::<MaybeUninit<ScopeRaw<'static>>>());
+        let mut slots = pool.slots.borrow_mut();
+        if slots.len() < SCOPE_POOL_SIZE {
+            slots.push(slot);
+        }
+    }
+}
 
 /// A trait that is implemented for everything.
 pub(crate) trait ReallyAny {}
 impl<T> ReallyAny for T {}
 
-#[derive(Default)]
 pub(crate) struct ScopeArena<'a> {
     bump: Bump,
     // We need to store the raw pointers because otherwise the values won't be dropped.
     inner: UnsafeCell<SmallVec<[*mut (dyn ReallyAny + 'a); SCOPE_ARENA_STACK_SIZE]>>,
+    /// The pool of the tree of scopes, to which the allocator is returned when dropped.
+    pool: Rc<ScopePool>,
 }
 
 impl<'a> ScopeArena<'a> {
+    /// Creates a new arena, reusing an allocator from `pool` if possible.
+    pub fn new(pool: Rc<ScopePool>) -> Self {
+        let bump = pool.bumps.borrow_mut().pop();
+        Self {
+            bump: bump.unwrap_or_default(),
+            inner: Default::default(),
+            pool,
+        }
+    }
+
+    /// Returns the pool of the tree of scopes that this arena belongs to.
+    pub fn pool(&self) -> &Rc<ScopePool> {
+        &self.pool
+    }
+
     /// Allocate a value onto the arena. Returns a mutable reference that lasts as long as the arena
     /// itself.
     #[allow(clippy::mut_from_ref)] // We return a new reference each time so this is a false-positive.
     pub fn alloc<T: 'a>(&'a self, value: T) -> &'a mut T {
+        if !mem::needs_drop::<T>() {
+            // Nothing to do when the arena is disposed so there is no need to keep track of it.
+            return self.bump.alloc(value);
+        }
         let boxed = bumpalo::boxed::Box::new_in(value, &self.bump);
         let ptr = bumpalo::boxed::Box::into_raw(boxed);
         unsafe {
@@ -56,12 +132,59 @@ impl<'a> ScopeArena<'a> {
             drop(boxed);
         }
         // Clear the inner Vec to prevent dangling references.
-        drop(std::mem::take(&mut *self.inner.get()));
+        drop(mem::take(&mut *self.inner.get()));
     }
 }
 
 impl<'a> Drop for ScopeArena<'a> {
     fn drop(&mut self) {
         unsafe { self.dispose() }
+        // Nothing can reference the memory of the arena anymore so it can be reused.
+        let mut bump = mem::take(&mut self.bump);
+        bump.reset();
+        let mut bumps = self.pool.bumps.borrow_mut();
+        if bump.allocated_bytes() <= SCOPE_POOL_MAX_BYTES && bumps.len() < SCOPE_POOL_SIZE {
+            bumps.push(bump);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn reuse_disposed_scope_memory() {
+        create_scope_immediate(|cx| {
+            let mut addresses = Vec::new();
+            for _ in 0..2 {
+                let disposer = create_child_scope(cx, |cx| {
+                    let value = create_ref(cx, String::from("value"));
+                    addresses.push(value as *const String);
+                });
+                unsafe { disposer.dispose() };
+            }
+            // The second child scope reuses the memory of the first one.
+            assert_eq!(addresses[0], addresses[1]);
+        });
+    }
+
+    #[test]
+    fn do_not_share_memory_between_roots() {
+        let mut addresses = Vec::new();
+        let disposer = create_scope(|cx| {
+            let child = create_child_scope(cx, |cx| {
+                addresses.push(create_ref(cx, 0_u64) as *const u64);
+            });
+            unsafe { child.dispose() };
+        });
+        create_scope_immediate(|cx| {
+            let _ = create_child_scope(cx, |cx| {
+                addresses.push(create_ref(cx, 0_u64) as *const u64);
+            });
+        });
+        unsafe { disposer.dispose() };
+        // The memory released in the first root is only reused in the first root.
+        assert_ne!(addresses[0], addresses[1]);
     }
 }
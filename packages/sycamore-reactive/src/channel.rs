@@ -0,0 +1,205 @@
+//! Channels for feeding messages from imperative code into the reactive graph.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+
+use crate::*;
+
+/// State shared between a [`ChannelSender`] and its [`ChannelReceiver`].
+struct ChannelState<T> {
+    queue: RefCell<VecDeque<T>>,
+    /// Triggered every time a message is sent.
+    notify: RcSignal<()>,
+}
+
+/// The sending end of a channel created with [`create_channel`].
+///
+/// The sender is `'static` and can be cloned, which means that it can be moved into event
+/// listeners, callbacks from JS libraries or timers without being tied to the lifetime of the
+/// scope that created the channel.
+pub struct ChannelSender<T> {
+    state: Weak<ChannelState<T>>,
+}
+
+impl<T> ChannelSender<T> {
+    /// Sends a message to the receiver. Effects and memos that depend on the receiver are
+    /// re-executed immediately.
+    ///
+    /// If the scope that created the channel has been disposed, the message is returned as an
+    /// error.
+    pub fn send(&self, value: T) -> Result<(), T> {
+        match self.state.upgrade() {
+            Some(state) => {
+                state.queue.borrow_mut().push_back(value);
+                state.notify.set(());
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// Returns `true` if the scope that created the channel has been disposed, in which case
+    /// [`ChannelSender::send`] always fails.
+    pub fn is_closed(&self) -> bool {
+        self.state.strong_count() == 0
+    }
+}
+
+impl<T> Clone for ChannelSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ChannelSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelSender")
+            .field("is_closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// The receiving end of a channel created with [`create_channel`]. The receiver is owned by the
+/// scope that created the channel.
+///
+/// Messages are queued until they are received. Reading the queue with [`ChannelReceiver::drain`]
+/// or [`ChannelReceiver::len`] inside an effect or memo subscribes it to new messages.
+pub struct ChannelReceiver<T> {
+    state: Rc<ChannelState<T>>,
+}
+
+impl<T> ChannelReceiver<T> {
+    /// Removes all the queued messages and returns them in the order they were sent. Subscribes
+    /// the current effect or memo to new messages.
+    pub fn drain(&self) -> Vec<T> {
+        self.state.notify.track();
+        self.state.queue.borrow_mut().drain(..).collect()
+    }
+
+    /// Removes the oldest queued message and returns it, if any. Does not subscribe to new
+    /// messages.
+    pub fn try_recv(&self) -> Option<T> {
+        self.state.queue.borrow_mut().pop_front()
+    }
+
+    /// Returns the number of queued messages. Subscribes the current effect or memo to new
+    /// messages.
+    pub fn len(&self) -> usize {
+        self.state.notify.track();
+        self.state.queue.borrow().len()
+    }
+
+    /// Returns `true` if there are no queued messages. Subscribes the current effect or memo to
+    /// new messages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Subscribes the current effect or memo to new messages without receiving them.
+    pub fn track(&self) {
+        self.state.notify.track();
+    }
+}
+
+impl<T> fmt::Debug for ChannelReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChannelReceiver")
+            .field("queued", &self.state.queue.borrow().len())
+            .finish()
+    }
+}
+
+/// Creates a new channel. The receiver is owned by the scope while the sender is `'static` and
+/// can be cloned, giving a clean way for imperative code (event listeners, timers, callbacks from
+/// JS libraries, ...) to feed messages into the reactive graph.
+///
+/// Once the scope is disposed, the queued messages are dropped and sending fails.
+///
+/// To send messages from another thread, use `create_signal_sender` from `sycamore-futures`
+/// instead.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|cx| {
+/// let (sender, receiver) = create_channel(cx);
+/// let log = create_signal(cx, Vec::new());
+/// create_effect(cx, || {
+///     for msg in receiver.drain() {
+///         log.modify().push(msg);
+///     }
+/// });
+///
+/// sender.send("Hello").unwrap();
+/// sender.clone().send("World").unwrap();
+/// assert_eq!(*log.get(), ["Hello", "World"]);
+/// # });
+/// ```
+pub fn create_channel<T: 'static>(cx: Scope<'_>) -> (ChannelSender<T>, &ChannelReceiver<T>) {
+    let state = Rc::new(ChannelState {
+        queue: RefCell::new(VecDeque::new()),
+        notify: create_rc_signal(()),
+    });
+    let sender = ChannelSender {
+        state: Rc::downgrade(&state),
+    };
+    (sender, create_ref(cx, ChannelReceiver { state }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_queue() {
+        create_scope_immediate(|cx| {
+            let (sender, receiver) = create_channel(cx);
+            sender.send(1).unwrap();
+            sender.send(2).unwrap();
+            assert_eq!(receiver.len(), 2);
+            assert_eq!(receiver.try_recv(), Some(1));
+            assert_eq!(receiver.drain(), vec![2]);
+            assert!(receiver.is_empty());
+        });
+    }
+
+    #[test]
+    fn channel_effect() {
+        create_scope_immediate(|cx| {
+            let (sender, receiver) = create_channel(cx);
+            let counter = create_signal(cx, 0);
+            let received = create_signal(cx, Vec::new());
+            create_effect(cx, || {
+                counter.set(*counter.get_untracked() + 1);
+                received.modify().extend(receiver.drain());
+            });
+            assert_eq!(*counter.get(), 1);
+
+            sender.send(1).unwrap();
+            assert_eq!(*counter.get(), 2);
+            assert_eq!(*received.get(), vec![1]);
+
+            // Every message re-runs the effect, including the ones sent by a cloned sender.
+            let cloned = sender.clone();
+            cloned.send(2).unwrap();
+            cloned.send(3).unwrap();
+            assert_eq!(*counter.get(), 4);
+            assert_eq!(*received.get(), vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn channel_closed_on_dispose() {
+        let mut sender = None;
+        create_scope_immediate(|cx| {
+            let (tx, _) = create_channel(cx);
+            assert!(!tx.is_closed());
+            sender = Some(tx);
+        });
+        let sender = sender.unwrap();
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(1), Err(1));
+    }
+}
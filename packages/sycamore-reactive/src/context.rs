@@ -48,6 +48,35 @@ pub fn provide_context_ref<'a, T: 'static>(cx: Scope<'a>, value: &'a T) -> &'a T
     value
 }
 
+/// Provides a context in the current [`Scope`], like [`provide_context`]. If a context with the
+/// same type exists already in this scope, it is replaced instead of panicking.
+///
+/// Note that the values that were already obtained with [`use_context`] are not updated. Use
+/// [`provide_reactive_context`] for a context that can change.
+pub fn replace_context<T: 'static>(cx: Scope<'_>, value: T) -> &T {
+    let value = create_ref(cx, value);
+    cx.raw
+        .inner
+        .borrow_mut()
+        .contexts
+        .get_or_insert_with(Default::default)
+        .insert(TypeId::of::<T>(), value);
+    value
+}
+
+/// Returns the context of the given type that was provided in the current [`Scope`] itself,
+/// ignoring the parent scopes.
+pub(crate) fn use_own_context<T: 'static>(cx: Scope<'_>) -> Option<&T> {
+    let value = *cx
+        .raw
+        .inner
+        .borrow()
+        .contexts
+        .as_ref()?
+        .get(&TypeId::of::<T>())?;
+    Some(value.downcast_ref::<T>().unwrap())
+}
+
 /// Tries to get a context value of the given type. If no context with the right type found,
 /// returns `None`. For a panicking version, see [`use_context`].
 pub fn try_use_context<T: 'static>(cx: Scope) -> Option<&T> {
@@ -108,6 +137,59 @@ where
     try_use_context(cx).unwrap_or_else(|| provide_context_ref(cx, f()))
 }
 
+/// Gets a context value of the given type or its [`Default`] value.
+///
+/// Note that if no context exists, the default value will be created in the _current_ scope. This
+/// means that the new value will still be inaccessible in an outer scope.
+pub fn use_context_or_default<T: Default + 'static>(cx: Scope<'_>) -> &T {
+    use_context_or_else(cx, T::default)
+}
+
+/// Provides a context that can be replaced in the current [`Scope`]. The context is a [`Signal`]
+/// that can later be accessed by using [`use_reactive_context`] lower in the scope hierarchy.
+/// Setting the signal notifies all the consumers of the context.
+///
+/// If a reactive context with the same type exists already in this scope, its value is set to
+/// `value` instead of panicking. If one exists in a parent scope, the new context will shadow it.
+pub fn provide_reactive_context<T: 'static>(cx: Scope<'_>, value: T) -> &Signal<T> {
+    match use_own_context::<Signal<T>>(cx) {
+        Some(signal) => {
+            signal.set(value);
+            signal
+        }
+        None => provide_context_ref(cx, create_signal(cx, value)),
+    }
+}
+
+/// Tries to get a reactive context of the given type, provided with [`provide_reactive_context`].
+/// If no context with the right type found, returns `None`. For a panicking version, see
+/// [`use_reactive_context`].
+pub fn try_use_reactive_context<T: 'static>(cx: Scope<'_>) -> Option<&ReadSignal<T>> {
+    try_use_context::<Signal<T>>(cx).map(|signal| &**signal)
+}
+
+/// Gets a reactive context of the given type, provided with [`provide_reactive_context`]. Reading
+/// the signal subscribes to the changes of the context.
+///
+/// # Panics
+/// This method panics if the context cannot be found in the current scope hierarchy.
+/// For a non-panicking version, see [`try_use_reactive_context`].
+#[track_caller]
+pub fn use_reactive_context<T: 'static>(cx: Scope<'_>) -> &ReadSignal<T> {
+    try_use_reactive_context(cx).expect("context not found for type")
+}
+
+/// Gets a reactive context of the given type or provides one with its [`Default`] value.
+///
+/// Note that if no context exists, the new context will be created in the _current_ scope. This
+/// means that the new value will still be inaccessible in an outer scope.
+pub fn use_reactive_context_or_default<T: Default + 'static>(cx: Scope<'_>) -> &ReadSignal<T> {
+    match try_use_reactive_context(cx) {
+        Some(signal) => signal,
+        None => provide_reactive_context(cx, T::default()),
+    }
+}
+
 /// Returns the current depth of the scope. If the scope is the root scope, returns `0`.
 pub fn scope_depth(cx: Scope) -> u32 {
     let mut depth = 0;
@@ -185,6 +267,68 @@ mod tests {
         });
     }
 
+    #[test]
+    fn replace_context_in_same_scope() {
+        create_scope_immediate(|cx| {
+            provide_context(cx, 0i32);
+            let _ = create_child_scope(cx, |cx| {
+                replace_context(cx, 1i32);
+                replace_context(cx, 2i32);
+                assert_eq!(*use_context::<i32>(cx), 2);
+            });
+            assert_eq!(*use_context::<i32>(cx), 0);
+        });
+    }
+
+    #[test]
+    fn test_use_context_or_default() {
+        create_scope_immediate(|cx| {
+            assert_eq!(*use_context_or_default::<i32>(cx), 0);
+            provide_context(cx, String::from("provided"));
+            assert_eq!(use_context_or_default::<String>(cx), "provided");
+        });
+    }
+
+    #[test]
+    fn reactive_context() {
+        create_scope_immediate(|cx| {
+            provide_reactive_context(cx, 1i32);
+            let _ = create_child_scope(cx, |child| {
+                let value = use_reactive_context::<i32>(child);
+                let double = create_memo(child, || *value.get() * 2);
+                assert_eq!(*double.get(), 2);
+
+                // Providing the context again in the same scope replaces the value.
+                provide_reactive_context(cx, 2i32);
+                assert_eq!(*double.get(), 4);
+            });
+        });
+    }
+
+    #[test]
+    fn reactive_context_shadowing() {
+        create_scope_immediate(|cx| {
+            let outer = provide_reactive_context(cx, 1i32);
+            let _ = create_child_scope(cx, |cx| {
+                assert_eq!(*use_reactive_context::<i32>(cx).get(), 1);
+                provide_reactive_context(cx, 2i32);
+                assert_eq!(*use_reactive_context::<i32>(cx).get(), 2);
+                outer.set(3);
+                assert_eq!(*use_reactive_context::<i32>(cx).get(), 2);
+            });
+            assert_eq!(*use_reactive_context::<i32>(cx).get(), 3);
+        });
+    }
+
+    #[test]
+    fn test_use_reactive_context_or_default() {
+        create_scope_immediate(|cx| {
+            assert!(try_use_reactive_context::<i32>(cx).is_none());
+            assert_eq!(*use_reactive_context_or_default::<i32>(cx).get(), 0);
+            assert!(try_use_reactive_context::<i32>(cx).is_some());
+        });
+    }
+
     #[test]
     fn root_scope_is_zero_depth() {
         create_scope_immediate(|cx| {
@@ -0,0 +1,352 @@
+//! Utilities for debugging the reactive graph.
+
+// Signals are only tracked in debug builds with the `diagnostics` feature.
+#![cfg_attr(not(all(debug_assertions, feature = "diagnostics")), allow(dead_code))]
+
+use core::fmt::Write;
+
+use crate::effect::EFFECTS;
+use crate::*;
+
+/// Maximum number of times the same signal can be triggered recursively before it is considered
+/// to be part of an update cycle.
+const MAX_RECURSIVE_TRIGGERS: usize = 100;
+
+/// An item in the trigger stack.
+struct TriggerFrame {
+    /// The signal that is triggering its subscribers.
+    emitter: WeakSignalEmitter,
+    /// The name of the effect that was running when the signal was triggered, if any.
+    effect: Option<&'static str>,
+}
+
+type Logger = Box<dyn Fn(&str)>;
+
+thread_local! {
+    /// The signals that are currently triggering their subscribers. The last item is the signal
+    /// that triggered the effect that is currently running. Only tracked in debug builds with the
+    /// `diagnostics` feature.
+    static TRIGGER_STACK: RefCell<Vec<TriggerFrame>> = Default::default();
+    /// Names assigned to signals using [`ReadSignal::set_debug_name`].
+    static DEBUG_NAMES: RefCell<AHashMap<*const SignalEmitterInner, (WeakSignalEmitter, &'static str)>> =
+        Default::default();
+    /// The function used to log messages. If `None`, messages are printed to stderr.
+    static LOGGER: RefCell<Option<Logger>> = Default::default();
+}
+
+/// Pushes a signal onto the trigger stack and pops it when dropped.
+pub(crate) struct TriggerGuard(());
+
+impl TriggerGuard {
+    pub(crate) fn new(emitter: &SignalEmitter) -> Self {
+        let frame = TriggerFrame {
+            emitter: emitter.downgrade(),
+            effect: current_effect_name(),
+        };
+        TRIGGER_STACK.with(|stack| stack.borrow_mut().push(frame));
+        Self(())
+    }
+}
+
+impl Drop for TriggerGuard {
+    fn drop(&mut self) {
+        TRIGGER_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+pub(crate) fn set_debug_name(emitter: &SignalEmitter, name: &'static str) {
+    DEBUG_NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        // Remove names of signals that have been dropped.
+        names.retain(|_, (weak, _)| weak.upgrade().is_some());
+        names.insert(Rc::as_ptr(&emitter.0), (emitter.downgrade(), name));
+    });
+}
+
+pub(crate) fn debug_name(emitter: &SignalEmitter) -> Option<&'static str> {
+    DEBUG_NAMES.with(|names| {
+        names
+            .borrow()
+            .get(&Rc::as_ptr(&emitter.0))
+            .filter(|(weak, _)| weak.upgrade().is_some())
+            .map(|(_, name)| *name)
+    })
+}
+
+/// Returns a label that identifies the signal in debugging output.
+pub(crate) fn signal_label(emitter: &SignalEmitter) -> String {
+    let id = Rc::as_ptr(&emitter.0) as usize;
+    match debug_name(emitter) {
+        Some(name) => format!("`{name}` (signal {id:#x})"),
+        None => format!("signal {id:#x}"),
+    }
+}
+
+/// Returns a label for the signal that triggered the effect that is currently running, and an id
+/// that identifies the signal.
+fn current_trigger() -> Option<(usize, String)> {
+    let emitter = TRIGGER_STACK.with(|stack| {
+        stack
+            .borrow()
+            .last()
+            .and_then(|frame| frame.emitter.upgrade())
+    })?;
+    Some((Rc::as_ptr(&emitter.0) as usize, signal_label(&emitter)))
+}
+
+/// Returns the name of the effect that is currently running, if any.
+fn current_effect_name() -> Option<&'static str> {
+    EFFECTS.with(|effects| {
+        effects.borrow().last().and_then(|last| {
+            // SAFETY: See guarantee on EffectState within EFFECTS.
+            let last = unsafe { &**last };
+            last.debug_name()
+        })
+    })
+}
+
+/// Gives the effect that is currently running a name that is used to identify it in debugging
+/// output, such as update cycle reports. Does nothing if not called inside an effect.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|cx| {
+/// let state = create_signal(cx, 0);
+/// create_effect(cx, || {
+///     set_effect_debug_name("log_state");
+///     println!("{}", state.get());
+/// });
+/// # });
+/// ```
+pub fn set_effect_debug_name(name: &'static str) {
+    EFFECTS.with(|effects| {
+        if let Some(last) = effects.borrow().last() {
+            // SAFETY: See guarantee on EffectState within EFFECTS.
+            let last = unsafe { &mut **last };
+            last.set_debug_name(name);
+        }
+    });
+}
+
+/// Checks whether triggering `emitter` would continue an update cycle, i.e. `emitter` is already
+/// being triggered recursively too many times. If so, the cycle is reported and `true` is
+/// returned, in which case the subscribers should not be called.
+pub(crate) fn detect_cycle(emitter: &SignalEmitter) -> bool {
+    let report = TRIGGER_STACK.with(|stack| {
+        let stack = stack.borrow();
+        let is_emitter =
+            |frame: &TriggerFrame| Weak::as_ptr(&frame.emitter.0) == Rc::as_ptr(&emitter.0);
+        if stack.iter().filter(|frame| is_emitter(frame)).count() < MAX_RECURSIVE_TRIGGERS {
+            return None;
+        }
+
+        // Report the chain of updates starting at the last time the signal was triggered.
+        let start = stack.iter().rposition(is_emitter).unwrap();
+        let mut chain = String::new();
+        for frame in &stack[start..] {
+            if let Some(emitter) = frame.emitter.upgrade() {
+                write!(chain, "\n  {}", signal_label(&emitter)).unwrap();
+                if let Some(effect) = frame.effect {
+                    write!(chain, " was set by effect `{effect}`").unwrap();
+                }
+            }
+        }
+        write!(chain, "\n  {}", signal_label(emitter)).unwrap();
+        if let Some(effect) = current_effect_name() {
+            write!(chain, " was set by effect `{effect}`").unwrap();
+        }
+        Some(format!(
+            "[sycamore-reactive] update cycle detected: {} was triggered recursively \
+             {MAX_RECURSIVE_TRIGGERS} times. The cycle was broken by not notifying its \
+             subscribers. Chain of updates:{chain}",
+            signal_label(emitter)
+        ))
+    });
+    match report {
+        Some(report) => {
+            log(&report);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Sets the function used by [`why_did_you_update`] and by update cycle detection to log
+/// messages. By default, messages are printed to stderr, or discarded without the `std` feature.
+///
+/// On `wasm32-unknown-unknown`, stderr is not available. Use this function to log to the browser
+/// console instead.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// set_debug_logger(|msg| println!("{msg}"));
+/// ```
+pub fn set_debug_logger(f: impl Fn(&str) + 'static) {
+    LOGGER.with(|logger| *logger.borrow_mut() = Some(Box::new(f)));
+}
+
+pub(crate) fn log(msg: &str) {
+    LOGGER.with(|logger| match &*logger.borrow() {
+        Some(logger) => logger(msg),
+        #[cfg(feature = "std")]
+        None => eprintln!("{msg}"),
+        #[cfg(not(feature = "std"))]
+        None => {}
+    });
+}
+
+/// Same as [`create_memo`] but logs which dependency triggered each re-run, and how many times
+/// that dependency has triggered a re-run so far. This is useful for hunting down unnecessary
+/// invalidations in complex reactive graphs.
+///
+/// Signals can be given a name using [`ReadSignal::set_debug_name`] to make the logs easier to
+/// read. Otherwise, signals are identified by their address.
+///
+/// To debug an effect instead of a memo, simply return `()` from `f`.
+///
+/// The triggering signal is only tracked in debug builds with the `diagnostics` feature.
+/// Otherwise, it is reported as unknown.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|cx| {
+/// let state = create_signal(cx, 0);
+/// state.set_debug_name("state");
+/// let double = why_did_you_update(cx, "double", || *state.get() * 2);
+///
+/// state.set(1); // Logs "[why_did_you_update] `double` re-run #1 triggered by `state` ..."
+/// assert_eq!(*double.get(), 2);
+/// # });
+/// ```
+pub fn why_did_you_update<'a, U: 'a>(
+    cx: Scope<'a>,
+    name: &'static str,
+    mut f: impl FnMut() -> U + 'a,
+) -> &'a ReadSignal<U> {
+    let mut runs = 0;
+    let mut counts = AHashMap::<usize, usize>::default();
+    create_memo(cx, move || {
+        set_effect_debug_name(name);
+        if runs > 0 {
+            let mut msg = format!("[why_did_you_update] `{name}` re-run #{runs} triggered by ");
+            match current_trigger() {
+                Some((id, label)) => {
+                    let count = counts.entry(id).or_default();
+                    *count += 1;
+                    write!(msg, "{label} ({count} time(s) so far)").unwrap();
+                }
+                None => msg.push_str("an unknown dependency"),
+            }
+            log(&msg);
+        }
+        runs += 1;
+        f()
+    })
+}
+
+#[cfg(all(test, feature = "diagnostics"))]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+
+    fn capture_logs() -> Rc<RefCell<Vec<String>>> {
+        let logs = Rc::new(RefCell::new(Vec::new()));
+        set_debug_logger({
+            let logs = Rc::clone(&logs);
+            move |msg| logs.borrow_mut().push(msg.to_string())
+        });
+        logs
+    }
+
+    #[test]
+    fn logs_triggering_signal() {
+        let logs = capture_logs();
+        create_scope_immediate(|cx| {
+            let a = create_signal(cx, 0);
+            a.set_debug_name("a");
+            let b = create_signal(cx, 0);
+            b.set_debug_name("b");
+            let sum = why_did_you_update(cx, "sum", || *a.get() + *b.get());
+            assert!(logs.borrow().is_empty(), "initial run is not logged");
+
+            a.set(1);
+            b.set(1);
+            a.set(2);
+            assert_eq!(*sum.get(), 3);
+
+            let logs = logs.borrow();
+            assert_eq!(logs.len(), 3);
+            assert!(logs[0].contains("`sum` re-run #1 triggered by `a`"));
+            assert!(logs[0].ends_with("(1 time(s) so far)"));
+            assert!(logs[1].contains("re-run #2 triggered by `b`"));
+            assert!(logs[2].contains("re-run #3 triggered by `a`"));
+            assert!(logs[2].ends_with("(2 time(s) so far)"));
+        });
+    }
+
+    #[test]
+    fn logs_unnamed_signal() {
+        let logs = capture_logs();
+        create_scope_immediate(|cx| {
+            let a = create_signal(cx, 0);
+            why_did_you_update(cx, "effect", || {
+                a.track();
+            });
+            a.set(1);
+            assert!(logs.borrow()[0].contains("triggered by signal 0x"));
+        });
+    }
+
+    /// Creates an effect that, when re-executed, creates a new effect subscribed to `x` and then
+    /// sets `x`, which re-executes the new effect, and so on.
+    fn spawn_cycle<'a>(cx: Scope<'a>, x: &'a Signal<i32>, runs: &'a Cell<usize>) {
+        let mut initial = true;
+        create_effect(cx, move || {
+            set_effect_debug_name("spawner");
+            x.track();
+            if initial {
+                initial = false;
+                return;
+            }
+            runs.set(runs.get() + 1);
+            spawn_cycle(cx, x, runs);
+            x.set(*x.get_untracked() + 1);
+        });
+    }
+
+    #[test]
+    fn break_update_cycle() {
+        let logs = capture_logs();
+        create_scope_immediate(|cx| {
+            let x = create_signal(cx, 0);
+            x.set_debug_name("x");
+            let runs = create_ref(cx, Cell::new(0));
+            spawn_cycle(cx, x, runs);
+
+            x.set(1);
+            assert_eq!(runs.get(), MAX_RECURSIVE_TRIGGERS);
+            let logs = logs.borrow();
+            assert_eq!(logs.len(), 1);
+            assert!(logs[0].contains("update cycle detected: `x`"));
+            assert!(logs[0].contains("was set by effect `spawner`"));
+            TRIGGER_STACK.with(|stack| assert!(stack.borrow().is_empty()));
+        });
+    }
+
+    #[test]
+    fn trigger_stack_is_restored() {
+        create_scope_immediate(|cx| {
+            let a = create_signal(cx, 0);
+            let b = create_memo(cx, || *a.get());
+            create_effect(cx, || {
+                b.track();
+            });
+            a.set(1);
+            TRIGGER_STACK.with(|stack| assert!(stack.borrow().is_empty()));
+        });
+    }
+}
@@ -1,7 +1,5 @@
 //! Side effects.
 
-use ahash::AHashSet;
-
 use crate::*;
 
 thread_local! {
@@ -18,18 +16,22 @@ pub(crate) struct EffectState<'a> {
     cb: Rc<RefCell<dyn FnMut() + 'a>>,
     /// A list of dependencies that can trigger this effect.
     dependencies: AHashSet<EffectDependency>,
+    /// A name used to identify the effect in debugging output.
+    debug_name: Option<&'static str>,
+    #[cfg(feature = "leak-detection")]
+    _leak: crate::leak::LiveToken,
 }
 
 /// Implements reference equality for [`WeakSignalEmitter`]s.
 pub(crate) struct EffectDependency(WeakSignalEmitter);
-impl std::cmp::PartialEq for EffectDependency {
+impl core::cmp::PartialEq for EffectDependency {
     fn eq(&self, other: &Self) -> bool {
         Weak::as_ptr(&self.0 .0) == Weak::as_ptr(&other.0 .0)
     }
 }
-impl std::cmp::Eq for EffectDependency {}
-impl std::hash::Hash for EffectDependency {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::cmp::Eq for EffectDependency {}
+impl core::hash::Hash for EffectDependency {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         Weak::as_ptr(&self.0 .0).hash(state);
     }
 }
@@ -41,7 +43,7 @@ impl<'a> EffectState<'a> {
         for dependency in &self.dependencies {
             if let Some(dep) = dependency.0.upgrade() {
                 // SAFETY: We only access the pointer, not the pointed data.
-                dep.unsubscribe(unsafe { std::mem::transmute(Rc::as_ptr(&self.cb)) })
+                dep.unsubscribe(unsafe { core::mem::transmute(Rc::as_ptr(&self.cb)) })
             };
         }
         self.dependencies.clear();
@@ -51,10 +53,46 @@ impl<'a> EffectState<'a> {
     pub fn add_dependency(&mut self, signal: WeakSignalEmitter) {
         self.dependencies.insert(EffectDependency(signal));
     }
+
+    /// Set the name used to identify the effect in debugging output.
+    pub fn set_debug_name(&mut self, name: &'static str) {
+        self.debug_name = Some(name);
+    }
+
+    /// Get the name used to identify the effect in debugging output.
+    pub fn debug_name(&self) -> Option<&'static str> {
+        self.debug_name
+    }
+}
+
+/// The value returned by the closure of an effect. Either `()`, or a cleanup closure that is called
+/// before the effect is run again and when the scope is disposed. See [`create_effect`].
+pub trait EffectCleanup<'a> {
+    /// Returns the cleanup closure, if any.
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce() + 'a>>;
 }
 
+impl EffectCleanup<'_> for () {
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce()>> {
+        None
+    }
+}
+
+impl<'a, F: FnOnce() + 'a> EffectCleanup<'a> for F {
+    fn into_cleanup(self) -> Option<Box<dyn FnOnce() + 'a>> {
+        Some(Box::new(self))
+    }
+}
+
+/// A cleanup closure returned by an effect.
+type Cleanup<'a> = Box<dyn FnOnce() + 'a>;
+
 /// Creates an effect on signals used inside the effect closure.
 ///
+/// The closure can return a cleanup closure, which is called before the effect is run again and
+/// when the scope is disposed, e.g. to cancel a subscription made by the effect. The cleanup
+/// closure is called in an [untracked](untrack) scope.
+///
 /// # Example
 /// ```
 /// # use sycamore_reactive::*;
@@ -66,34 +104,94 @@ impl<'a> EffectState<'a> {
 /// }); // Prints "State changed. New state value = 0"
 ///
 /// state.set(1); // Prints "State changed. New state value = 1"
+///
+/// create_effect(cx, || {
+///     let value = *state.get();
+///     println!("Subscribed to {value}");
+///     move || println!("Unsubscribed from {value}")
+/// }); // Prints "Subscribed to 1"
+///
+/// state.set(2); // Prints "Unsubscribed from 1" and "Subscribed to 2"
 /// # });
 /// ```
-pub fn create_effect<'a>(cx: Scope<'a>, f: impl FnMut() + 'a) {
-    let f = cx.alloc(f);
+#[track_caller]
+pub fn create_effect<'a, R: EffectCleanup<'a>>(cx: Scope<'a>, mut f: impl FnMut() -> R + 'a) {
+    // Only allocated once the effect returns a cleanup closure for the first time.
+    let mut cleanup: Option<&'a RefCell<Option<Cleanup<'a>>>> = None;
+    let f = cx.alloc(move || {
+        if let Some(prev) = cleanup.and_then(|cleanup| cleanup.take()) {
+            untrack(prev);
+        }
+        if let Some(next) = f().into_cleanup() {
+            let cleanup = cleanup.get_or_insert_with(|| {
+                let cleanup = create_ref(cx, RefCell::new(None::<Cleanup<'a>>));
+                on_cleanup(cx, || {
+                    if let Some(cleanup) = cleanup.take() {
+                        cleanup();
+                    }
+                });
+                cleanup
+            });
+            *cleanup.borrow_mut() = Some(next);
+        }
+    });
     _create_effect(cx, f)
 }
 
+/// An effect that is running. Pops the effect from [`EFFECTS`] and puts its state back if the
+/// effect panics, so that a panic caught further up (e.g. by an error boundary) does not leave the
+/// runtime in an inconsistent state.
+struct RunningEffect<'e, 'a> {
+    effect: &'e RefCell<Option<EffectState<'a>>>,
+    state: Option<EffectState<'a>>,
+}
+
+impl<'a> RunningEffect<'_, 'a> {
+    /// Pops the effect from [`EFFECTS`] once it has finished running and returns its state.
+    fn finish(&mut self) -> EffectState<'a> {
+        EFFECTS.with(|effects| effects.borrow_mut().pop().unwrap());
+        self.state.take().unwrap()
+    }
+}
+
+impl Drop for RunningEffect<'_, '_> {
+    fn drop(&mut self) {
+        // The state is only still here if the effect panicked.
+        if let Some(state) = self.state.take() {
+            EFFECTS.with(|effects| effects.borrow_mut().pop());
+            *self.effect.borrow_mut() = Some(state);
+        }
+    }
+}
+
 /// Internal implementation for `create_effect`. Use dynamic dispatch to reduce code-bloat.
+#[track_caller]
 fn _create_effect<'a>(cx: Scope<'a>, f: &'a mut (dyn FnMut() + 'a)) {
     let effect = &*cx.alloc(RefCell::new(None::<EffectState<'a>>));
     let cb = Rc::new(RefCell::new({
         move || {
+            // Deferred signal updates are flushed once the outermost effect finishes.
+            let _depth = crate::runtime::EffectDepthGuard::new();
             EFFECTS.with(|effects| {
                 // Record initial effect stack length to verify that it is the same after.
                 let initial_effect_stack_len = effects.borrow().len();
 
                 // Take effect out.
-                let mut tmp_effect = effect.take().unwrap();
+                let mut running = RunningEffect {
+                    effect,
+                    state: effect.take(),
+                };
+                let tmp_effect = running.state.as_mut().unwrap();
                 tmp_effect.clear_dependencies();
 
                 // Push the effect onto the effect stack so that it is visible by signals.
                 effects
                     .borrow_mut()
-                    .push((&mut tmp_effect as *mut EffectState<'a>).cast::<EffectState<'static>>());
+                    .push((tmp_effect as *mut EffectState<'a>).cast::<EffectState<'static>>());
                 // Now we can call the user-provided function.
                 f();
                 // Pop the effect from the effect stack.
-                effects.borrow_mut().pop().unwrap();
+                let tmp_effect = running.finish();
                 // The raw pointer pushed onto `effects` is dead and can no longer be accessed.
                 // We can now access `effect` directly again.
 
@@ -106,7 +204,7 @@ fn _create_effect<'a>(cx: Scope<'a>, f: &'a mut (dyn FnMut() + 'a)) {
                         // SAFETY: When the effect is destroyed or when the emitter is dropped,
                         // this link will be destroyed to prevent dangling references.
                         emitter.subscribe(Rc::downgrade(unsafe {
-                            std::mem::transmute(&tmp_effect.cb)
+                            core::mem::transmute(&tmp_effect.cb)
                         }));
                     }
                 }
@@ -122,11 +220,72 @@ fn _create_effect<'a>(cx: Scope<'a>, f: &'a mut (dyn FnMut() + 'a)) {
     // Initialize initial effect state.
     *effect.borrow_mut() = Some(EffectState {
         cb: cb.clone(),
-        dependencies: AHashSet::new(),
+        dependencies: AHashSet::default(),
+        debug_name: None,
+        #[cfg(feature = "leak-detection")]
+        _leak: crate::leak::LiveToken::new(crate::leak::Kind::Effect),
     });
 
     // Initial callback call to get everything started.
     cb.borrow_mut()();
+    crate::runtime::flush_deferred_if_idle();
+
+    if is_strict_mode(cx) {
+        rerun_strict(
+            effect,
+            &mut *cb.borrow_mut(),
+            core::panic::Location::caller(),
+        );
+        crate::runtime::flush_deferred_if_idle();
+    }
+    crate::runtime::after_update_if_idle();
+}
+
+/// Runs a newly created effect a second time in strict mode and logs a message if it tracked
+/// different signals than during the first run. See [`enable_strict_mode`].
+fn rerun_strict<'a>(
+    effect: &RefCell<Option<EffectState<'a>>>,
+    cb: &mut dyn FnMut(),
+    location: &'static core::panic::Location<'static>,
+) {
+    let dependencies = || {
+        effect
+            .borrow()
+            .as_ref()
+            .map(|state| {
+                state
+                    .dependencies
+                    .iter()
+                    .filter_map(|dependency| dependency.0.upgrade())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let first = dependencies();
+    cb();
+    let second = dependencies();
+
+    let same_as = |a: &[SignalEmitter], b: &[SignalEmitter]| {
+        a.iter().all(|x| b.iter().any(|y| Rc::ptr_eq(&x.0, &y.0)))
+    };
+    if first.len() != second.len() || !same_as(&first, &second) {
+        let labels = |dependencies: &[SignalEmitter]| {
+            dependencies
+                .iter()
+                .map(crate::debug::signal_label)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let name = effect.borrow().as_ref().and_then(EffectState::debug_name);
+        let name = name.map(|name| format!("`{name}` ")).unwrap_or_default();
+        crate::debug::log(&format!(
+            "[strict mode] the effect {name}created at {location} tracked different signals when \
+             it was run a second time, which means that it is not idempotent.\n  first run: [{}]\n  \
+             second run: [{}]",
+            labels(&first),
+            labels(&second),
+        ));
+    }
 }
 
 /// Creates an effect on signals used inside the effect closure.
@@ -149,6 +308,7 @@ fn _create_effect<'a>(cx: Scope<'a>, f: &'a mut (dyn FnMut() + 'a)) {
 /// });
 /// # });
 /// ```
+#[track_caller]
 pub fn create_effect_scoped<'a, F>(cx: Scope<'a>, mut f: F)
 where
     F: for<'child_lifetime> FnMut(BoundedScope<'child_lifetime, 'a>) + 'a,
@@ -166,7 +326,7 @@ where
         let new_disposer: Option<ScopeDisposer<'a>> = Some(create_child_scope(cx, |cx| {
             // SAFETY: f takes the same parameter as the argument to
             // self.create_child_scope(_).
-            f(unsafe { std::mem::transmute(cx) });
+            f(unsafe { core::mem::transmute(cx) });
         }));
         disposer = new_disposer;
     });
@@ -192,16 +352,22 @@ where
 /// # });
 /// ```
 pub fn untrack<T>(f: impl FnOnce() -> T) -> T {
-    EFFECTS.with(|effects| {
-        let tmp = effects.take();
-        let ret = f();
-        *effects.borrow_mut() = tmp;
-        ret
-    })
+    /// Restores the effect stack when dropped, even if `f` panics.
+    struct Restore(Vec<*mut EffectState<'static>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            EFFECTS.with(|effects| *effects.borrow_mut() = mem::take(&mut self.0));
+        }
+    }
+
+    let _restore = Restore(EFFECTS.with(|effects| effects.take()));
+    f()
 }
 
 #[cfg(test)]
 mod tests {
+    use core::cell::Cell;
+
     use super::*;
 
     #[test]
@@ -424,4 +590,53 @@ mod tests {
             trigger.set(());
         });
     }
+
+    #[test]
+    fn caught_panic_does_not_break_runtime() {
+        create_scope_immediate(|cx| {
+            let state = create_signal(cx, 0);
+            let runs = create_ref(cx, Cell::new(0));
+            create_effect(cx, move || {
+                state.track();
+                runs.set(runs.get() + 1);
+                let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+                    create_effect(cx, || untrack::<()>(|| panic!("panic in effect")));
+                }));
+                assert!(result.is_err());
+            });
+            assert_eq!(runs.get(), 1);
+
+            state.set(1);
+            assert_eq!(runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn effect_cleanup() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let disposer = create_scope({
+            let log = Rc::clone(&log);
+            move |cx| {
+                let state = create_signal(cx, 0);
+                let log = create_ref(cx, log);
+                create_effect(cx, move || {
+                    let value = *state.get();
+                    log.borrow_mut().push(format!("run {value}"));
+                    move || {
+                        // Not tracked by the effect.
+                        state.track();
+                        log.borrow_mut().push(format!("cleanup {value}"));
+                    }
+                });
+                state.set(1);
+                state.set(2);
+            }
+        });
+        assert_eq!(
+            *log.borrow(),
+            ["run 0", "cleanup 0", "run 1", "cleanup 1", "run 2"]
+        );
+        unsafe { disposer.dispose() };
+        assert_eq!(log.borrow().last().unwrap(), "cleanup 2");
+    }
 }
@@ -1,10 +1,8 @@
 //! Reactive utilities for dealing with lists and iterables.
 
-use std::hash::Hash;
-use std::mem;
-use std::rc::Rc;
-
-use ahash::AHashMap;
+use alloc::rc::Rc;
+use core::hash::Hash;
+use core::mem;
 
 use crate::*;
 
@@ -113,7 +111,8 @@ where
 
             // 0) Prepare a map of indices in newItems. Scan backwards so we encounter them in
             // natural order.
-            let mut new_indices = AHashMap::with_capacity(new_end - start);
+            let mut new_indices =
+                AHashMap::with_capacity_and_hasher(new_end - start, Default::default());
 
             // Indexes for new_indices_next are shifted by start because values at 0..start are
             // always None.
@@ -200,22 +199,43 @@ where
 ///
 /// This function is the underlying utility behind `Indexed`.
 ///
+/// # Stable per-index scopes
+///
+/// Each index owns a child [`Scope`] and a [`ReadSignal`] holding the current value at that index.
+/// `map_fn` is only called once per index, when the index is first created. Afterwards:
+///
+/// * Changing the value at an index updates the signal for that index in place. `map_fn` is not
+///   called again and the scope is not recreated. Only the subscribers of that signal are
+///   re-executed.
+/// * Appending items to the end of the list only calls `map_fn` for the new indexes.
+/// * Removing items from the end of the list only disposes the scopes of the removed indexes.
+/// * Inserting or removing an item in the middle of the list shifts the values of all the following
+///   indexes, updating each of their signals in place.
+///
+/// The returned [`ReadSignal`] is only updated when the length of the list changes.
+///
 /// # Params
 /// * `list` - The list to be mapped. The list must be a [`ReadSignal`] (obtained from a [`Signal`])
 ///   and therefore reactive.
-/// * `map_fn` - A closure that maps from the input type to the output type.
+/// * `map_fn` - A closure that maps from a signal of the input type to the output type.
 pub fn map_indexed<'a, T, U>(
     cx: Scope<'a>,
     list: &'a ReadSignal<Vec<T>>,
-    map_fn: impl for<'child_lifetime> Fn(BoundedScope<'child_lifetime, 'a>, T) -> U + 'a,
+    map_fn: impl for<'child_lifetime> Fn(
+            BoundedScope<'child_lifetime, 'a>,
+            &'child_lifetime ReadSignal<T>,
+        ) -> U
+        + 'a,
 ) -> &'a ReadSignal<Vec<U>>
 where
-    T: PartialEq + Clone,
+    T: PartialEq + Clone + 'a,
     U: Clone + 'a,
 {
     // Previous state used for diffing.
-    let mut items = Rc::new(Vec::new());
     let mut mapped = Vec::new();
+    // The value signal for each index. The signals are allocated inside the child scope of each
+    // index and are only accessed while the corresponding disposer has not been called.
+    let mut values: Vec<&'a Signal<T>> = Vec::new();
     let mut disposers: Vec<ScopeDisposer<'a>> = Vec::new();
 
     let signal = create_signal(cx, Vec::new());
@@ -223,71 +243,151 @@ where
     // Diff and update signal each time list is updated.
     create_effect(cx, move || {
         let new_items = list.get();
+        let prev_len = mapped.len();
 
         if new_items.is_empty() {
             // Fast path for removing all items.
+            values.clear();
             for dis in mem::take(&mut disposers) {
                 unsafe {
                     dis.dispose();
                 }
             }
-            items = Rc::new(Vec::new());
             mapped = Vec::new();
         } else {
-            // Pre-allocate space needed
-            if new_items.len() > items.len() {
-                let new_count = new_items.len() - items.len();
-                mapped.reserve(new_count);
-                disposers.reserve(new_count);
-            }
-
-            for (i, new_item) in new_items.iter().enumerate() {
-                let new_item = new_item.clone();
-                let item = items.get(i);
-                // We lift the equality out of the else if branch to satisfy borrow checker.
-                let eqs = item != Some(&new_item);
-
-                let mut tmp = None;
-                if item.is_none() || eqs {
-                    let new_disposer = create_child_scope(cx, |cx| {
-                        // SAFETY: f takes the same parameter as the argument to
-                        // create_child_scope(cx, _).
-                        tmp = Some(map_fn(unsafe { mem::transmute(cx) }, new_item));
-                    });
-                    if item.is_none() {
-                        // SAFETY: tmp is written in create_child_scope.
-                        mapped.push(tmp.unwrap());
-                        disposers.push(new_disposer);
-                    } else if eqs {
-                        // SAFETY: tmp is written in create_child_scope.
-                        mapped[i] = tmp.unwrap();
-                        let prev = mem::replace(&mut disposers[i], new_disposer);
-                        unsafe {
-                            prev.dispose();
-                        }
-                    }
+            // Update the values of existing indexes in place.
+            for (value, new_item) in values.iter().zip(new_items.iter()) {
+                if *value.get_untracked() != *new_item {
+                    value.set(new_item.clone());
                 }
             }
 
-            if new_items.len() < items.len() {
-                for _i in new_items.len()..items.len() {
+            // Dispose of the scopes for the indexes that were removed.
+            if new_items.len() < mapped.len() {
+                values.truncate(new_items.len());
+                for dis in disposers.drain(new_items.len()..).rev() {
                     unsafe {
-                        disposers.pop().unwrap().dispose();
+                        dis.dispose();
                     }
                 }
+                mapped.truncate(new_items.len());
             }
 
-            // In case the new set is shorter than the old, set the length of the mapped array.
-            mapped.truncate(new_items.len());
+            // Create new scopes for the indexes that were added.
+            if new_items.len() > mapped.len() {
+                let new_count = new_items.len() - mapped.len();
+                mapped.reserve(new_count);
+                values.reserve(new_count);
+                disposers.reserve(new_count);
+            }
+            for new_item in new_items[mapped.len()..].iter().cloned() {
+                let mut tmp = None;
+                let mut value = None;
+                let new_disposer = create_child_scope(cx, |cx| {
+                    let signal = create_signal(cx, new_item);
+                    // SAFETY: the signal is only accessed while the scope is alive because it is
+                    // removed from `values` before the scope is disposed.
+                    value = Some(unsafe { mem::transmute::<&Signal<T>, &'a Signal<T>>(signal) });
+                    // SAFETY: f takes the same parameter as the argument to
+                    // create_child_scope(cx, _).
+                    tmp = Some(map_fn(unsafe { mem::transmute(cx) }, signal));
+                });
+                mapped.push(tmp.unwrap());
+                values.push(value.unwrap());
+                disposers.push(new_disposer);
+            }
 
-            // save a copy of the mapped items for the next update.
-            items = Rc::clone(&new_items);
-            debug_assert!([items.len(), mapped.len(), disposers.len()]
+            debug_assert!([values.len(), mapped.len(), disposers.len()]
                 .iter()
                 .all(|l| *l == new_items.len()));
         }
 
-        // Update signal to trigger updates.
+        // Update signal to trigger updates. Values that were updated in place do not need to
+        // trigger the signal.
+        if mapped.len() != prev_len {
+            signal.set(mapped.clone());
+        }
+    });
+
+    signal
+}
+
+/// Function that maps a [`ReactiveVec`] to a `Vec` via a map function. Unlike [`map_keyed`], the
+/// changes to the input are not found by diffing it against its previous value. Instead, the
+/// [`VecDiff`]s recorded by the [`ReactiveVec`] are applied directly, so updating the mapped `Vec`
+/// only costs as much as the changes themselves.
+///
+/// Each value is mapped in its own child [`Scope`]. `map_fn` is called once for each value that is
+/// inserted, set or replaced. Moving a value with [`ReactiveVec::swap`] keeps its scope.
+///
+/// This function is the underlying utility behind `KeyedVec`.
+///
+/// # Params
+/// * `vec` - The list to be mapped.
+/// * `map_fn` - A closure that maps from the input type to the output type.
+pub fn map_vec<'a, T, U>(
+    cx: Scope<'a>,
+    vec: &'a ReactiveVec<T>,
+    map_fn: impl for<'child_lifetime> Fn(BoundedScope<'child_lifetime, 'a>, T) -> U + 'a,
+) -> &'a ReadSignal<Vec<U>>
+where
+    T: Clone + 'a,
+    U: Clone + 'a,
+{
+    let diffs = vec.listen();
+    let mut mapped = Vec::new();
+    let mut disposers: Vec<ScopeDisposer<'a>> = Vec::new();
+    let mut initialized = false;
+
+    let map_item = move |item: T| {
+        let mut tmp = None;
+        let disposer = create_child_scope(cx, |cx| tmp = Some(map_fn(cx, item)));
+        (tmp.unwrap(), disposer)
+    };
+
+    let signal = create_signal(cx, Vec::new());
+
+    create_effect(cx, move || {
+        vec.track();
+        let diffs = mem::take(&mut *diffs.borrow_mut());
+        untrack(|| {
+            if !initialized {
+                initialized = true;
+                let values = vec.with_untracked(<[T]>::to_vec);
+                (mapped, disposers) = values.into_iter().map(&map_item).unzip();
+                return;
+            }
+            for diff in diffs {
+                match diff {
+                    VecDiff::Insert { index, value } => {
+                        let (item, disposer) = map_item(value);
+                        mapped.insert(index, item);
+                        disposers.insert(index, disposer);
+                    }
+                    VecDiff::Remove { index } => {
+                        mapped.remove(index);
+                        unsafe { disposers.remove(index).dispose() };
+                    }
+                    VecDiff::Swap { a, b } => {
+                        mapped.swap(a, b);
+                        disposers.swap(a, b);
+                    }
+                    VecDiff::Set { index, value } => {
+                        let (item, disposer) = map_item(value);
+                        mapped[index] = item;
+                        unsafe { mem::replace(&mut disposers[index], disposer).dispose() };
+                    }
+                    VecDiff::Replace { values } => {
+                        for disposer in mem::take(&mut disposers) {
+                            unsafe { disposer.dispose() };
+                        }
+                        (mapped, disposers) = values.into_iter().map(&map_item).unzip();
+                    }
+                }
+            }
+        });
+        debug_assert_eq!(mapped.len(), disposers.len());
+
         signal.set(mapped.clone());
     });
 
@@ -296,7 +396,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::cell::Cell;
+    use core::cell::Cell;
 
     use super::*;
 
@@ -428,18 +528,37 @@ mod tests {
         });
     }
 
+    /// Maps each index to an [`RcSignal`] that tracks the value at that index, multiplied by 2.
+    fn double<'a>(
+        cx: Scope<'a>,
+        list: &'a ReadSignal<Vec<i32>>,
+    ) -> &'a ReadSignal<Vec<RcSignal<i32>>> {
+        map_indexed(cx, list, |cx, x| {
+            let doubled = create_rc_signal(0);
+            create_effect(cx, {
+                let doubled = doubled.clone();
+                move || doubled.set(*x.get() * 2)
+            });
+            doubled
+        })
+    }
+
+    fn values(mapped: &ReadSignal<Vec<RcSignal<i32>>>) -> Vec<i32> {
+        mapped.get().iter().map(|x| *x.get()).collect()
+    }
+
     #[test]
     fn indexed() {
         create_scope_immediate(|cx| {
             let a = create_signal(cx, vec![1, 2, 3]);
-            let mapped = map_indexed(cx, a, |_, x| x * 2);
-            assert_eq!(*mapped.get(), vec![2, 4, 6]);
+            let mapped = double(cx, a);
+            assert_eq!(values(mapped), vec![2, 4, 6]);
 
             a.set(vec![1, 2, 3, 4]);
-            assert_eq!(*mapped.get(), vec![2, 4, 6, 8]);
+            assert_eq!(values(mapped), vec![2, 4, 6, 8]);
 
             a.set(vec![2, 2, 3, 4]);
-            assert_eq!(*mapped.get(), vec![4, 4, 6, 8]);
+            assert_eq!(values(mapped), vec![4, 4, 6, 8]);
         });
     }
 
@@ -448,10 +567,10 @@ mod tests {
     fn indexed_clear() {
         create_scope_immediate(|cx| {
             let a = create_signal(cx, vec![1, 2, 3]);
-            let mapped = map_indexed(cx, a, |_, x| x * 2);
+            let mapped = double(cx, a);
 
             a.set(Vec::new());
-            assert_eq!(*mapped.get(), Vec::<i32>::new());
+            assert_eq!(values(mapped), Vec::<i32>::new());
         });
     }
 
@@ -460,7 +579,7 @@ mod tests {
     fn indexed_react() {
         create_scope_immediate(|cx| {
             let a = create_signal(cx, vec![1, 2, 3]);
-            let mapped = map_indexed(cx, a, |_, x| x * 2);
+            let mapped = double(cx, a);
 
             let counter = create_signal(cx, 0);
             create_effect(cx, || {
@@ -474,6 +593,26 @@ mod tests {
         });
     }
 
+    /// Test that the mapped signal is not triggered when only values change.
+    #[test]
+    fn indexed_no_react_on_value_change() {
+        create_scope_immediate(|cx| {
+            let a = create_signal(cx, vec![1, 2, 3]);
+            let mapped = double(cx, a);
+
+            let counter = create_signal(cx, 0);
+            create_effect(cx, || {
+                counter.set(*counter.get_untracked() + 1);
+                mapped.track();
+            });
+
+            assert_eq!(*counter.get(), 1);
+            a.set(vec![1, 5, 3]);
+            assert_eq!(*counter.get(), 1);
+            assert_eq!(values(mapped), vec![2, 10, 6]);
+        });
+    }
+
     /// Test that using [`map_indexed`] will reuse previous computations.
     #[test]
     fn indexed_use_previous_computation() {
@@ -495,8 +634,36 @@ mod tests {
             a.set(vec![1, 2, 4]);
             assert_eq!(*mapped.get(), vec![1, 2, 4]);
 
+            // Changing a value in the middle does not call the map function again.
             a.set(vec![1, 3, 4]);
-            assert_eq!(*mapped.get(), vec![1, 5, 4]);
+            assert_eq!(*mapped.get(), vec![1, 2, 4]);
+            assert_eq!(counter.get(), 4);
+        });
+    }
+
+    /// Test that only the signal of the changed index is updated.
+    #[test]
+    fn indexed_update_in_place() {
+        create_scope_immediate(|cx| {
+            let a = create_signal(cx, vec![1, 2, 3]);
+            let counter = Rc::new(Cell::new(0));
+            let _mapped = map_indexed(cx, a, {
+                let counter = Rc::clone(&counter);
+                move |cx, x| {
+                    let counter = Rc::clone(&counter);
+                    create_effect(cx, move || {
+                        x.track();
+                        counter.set(counter.get() + 1);
+                    });
+                }
+            });
+            assert_eq!(counter.get(), 3);
+
+            a.set(vec![1, 5, 3]);
+            assert_eq!(counter.get(), 4);
+
+            a.set(vec![1, 5, 3, 4]);
+            assert_eq!(counter.get(), 5);
         });
     }
 
@@ -522,8 +689,9 @@ mod tests {
             a.set(vec![1, 2, 3]);
             assert_eq!(counter.get(), 1);
 
+            // Only the last index is removed. The scope at index 1 is reused.
             a.set(vec![1, 3]);
-            assert_eq!(counter.get(), 3);
+            assert_eq!(counter.get(), 2);
         });
     }
 
@@ -547,4 +715,52 @@ mod tests {
             assert_eq!(counter.get(), 3);
         });
     }
+
+    #[test]
+    fn map_vec_applies_diffs() {
+        create_scope_immediate(|cx| {
+            let vec = create_signal_vec(cx, vec![1, 2, 3]);
+            let calls = create_ref(cx, Cell::new(0));
+            let mapped = map_vec(cx, vec, |_, x| {
+                calls.set(calls.get() + 1);
+                x * 2
+            });
+            assert_eq!(*mapped.get(), [2, 4, 6]);
+            assert_eq!(calls.get(), 3);
+
+            vec.push(4);
+            vec.insert(0, 0);
+            assert_eq!(*mapped.get(), [0, 2, 4, 6, 8]);
+            assert_eq!(calls.get(), 5);
+
+            vec.remove(2);
+            vec.swap(0, 3);
+            assert_eq!(*mapped.get(), [8, 2, 6, 0]);
+            assert_eq!(calls.get(), 5);
+
+            vec.set(1, 5);
+            assert_eq!(*mapped.get(), [8, 10, 6, 0]);
+            vec.replace(vec![7]);
+            assert_eq!(*mapped.get(), [14]);
+            assert_eq!(calls.get(), 7);
+        });
+    }
+
+    #[test]
+    fn map_vec_disposes_scopes() {
+        create_scope_immediate(|cx| {
+            let vec = create_signal_vec(cx, vec![1, 2, 3]);
+            let disposed = create_ref(cx, RefCell::new(Vec::new()));
+            map_vec(cx, vec, move |cx, x| {
+                on_cleanup(cx, move || disposed.borrow_mut().push(x));
+            });
+            vec.swap(0, 2);
+            vec.remove(0);
+            assert_eq!(*disposed.borrow(), [3]);
+            vec.set(0, 4);
+            assert_eq!(*disposed.borrow(), [3, 2]);
+            vec.clear();
+            assert_eq!(*disposed.borrow(), [3, 2, 4, 1]);
+        });
+    }
 }
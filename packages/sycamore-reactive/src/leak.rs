@@ -0,0 +1,223 @@
+//! Leak detection for scopes, signals and effects. Only available with the `leak-detection`
+//! feature.
+
+use alloc::collections::BTreeMap;
+use core::cell::Cell;
+use core::fmt;
+use core::panic::Location;
+
+use crate::*;
+
+/// The kinds of reactive nodes that are counted.
+#[derive(Clone, Copy)]
+pub(crate) enum Kind {
+    Scope,
+    Signal,
+    Effect,
+}
+
+#[derive(Default)]
+struct LiveState {
+    /// The number of live nodes, indexed by [`Kind`].
+    counts: [usize; 3],
+    /// Root scopes that have not been disposed, by id.
+    root_scopes: BTreeMap<u64, LeakedScope>,
+    /// Creation locations of the [`RcSignal`]s that are alive, by id.
+    rc_signals: BTreeMap<u64, &'static Location<'static>>,
+}
+
+thread_local! {
+    static LIVE: RefCell<LiveState> = Default::default();
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Counts a reactive node as alive until it is dropped.
+pub(crate) struct LiveToken {
+    kind: Kind,
+    id: u64,
+}
+
+impl LiveToken {
+    pub(crate) fn new(kind: Kind) -> Self {
+        let id = NEXT_ID.with(|next| {
+            next.set(next.get() + 1);
+            next.get()
+        });
+        LIVE.with(|live| live.borrow_mut().counts[kind as usize] += 1);
+        Self { kind, id }
+    }
+
+    /// Reports the scope as a root scope that was created at `location` until it is disposed.
+    pub(crate) fn track_root_scope(&self, location: &'static Location<'static>) {
+        let scope = LeakedScope {
+            name: None,
+            location,
+        };
+        LIVE.with(|live| live.borrow_mut().root_scopes.insert(self.id, scope));
+    }
+
+    /// Reports the signal as an [`RcSignal`] that was created at `location` until it is dropped.
+    pub(crate) fn track_rc_signal(&self, location: &'static Location<'static>) {
+        LIVE.with(|live| live.borrow_mut().rc_signals.insert(self.id, location));
+    }
+}
+
+impl Drop for LiveToken {
+    fn drop(&mut self) {
+        LIVE.with(|live| {
+            let mut live = live.borrow_mut();
+            live.counts[self.kind as usize] -= 1;
+            match self.kind {
+                Kind::Scope => {
+                    live.root_scopes.remove(&self.id);
+                }
+                Kind::Signal => {
+                    live.rc_signals.remove(&self.id);
+                }
+                Kind::Effect => {}
+            }
+        });
+    }
+}
+
+/// A root scope that has not been disposed. See [`LeakReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeakedScope {
+    /// The name given with [`set_scope_debug_name`], if any.
+    pub name: Option<&'static str>,
+    /// Where [`create_scope`] was called.
+    pub location: &'static Location<'static>,
+}
+
+/// The reactive nodes that are alive on the current thread. Created with [`leak_report`].
+///
+/// Child scopes are disposed with their parent, so only root scopes, i.e. scopes created with
+/// [`create_scope`], are listed. [`RcSignal`]s are listed as well because they are not bound to
+/// a scope and are commonly leaked by storing them in long-lived globals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LeakReport {
+    /// The number of scopes, including child scopes, that have not been disposed.
+    pub scopes: usize,
+    /// The number of signals, including [`RcSignal`]s, that are alive.
+    pub signals: usize,
+    /// The number of effects that are alive.
+    pub effects: usize,
+    /// The root scopes that have not been disposed, in the order in which they were created.
+    pub leaked_scopes: Vec<LeakedScope>,
+    /// Where the [`RcSignal`]s that are alive were created, in the order in which they were
+    /// created.
+    pub rc_signals: Vec<&'static Location<'static>>,
+}
+
+impl LeakReport {
+    /// Returns `true` if nothing is alive.
+    pub fn is_empty(&self) -> bool {
+        self.scopes == 0 && self.signals == 0 && self.effects == 0
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[sycamore-reactive] live scopes: {}, signals: {}, effects: {}",
+            self.scopes, self.signals, self.effects
+        )?;
+        if !self.leaked_scopes.is_empty() {
+            write!(f, "\nroot scopes that were never disposed:")?;
+            for scope in &self.leaked_scopes {
+                match scope.name {
+                    Some(name) => write!(f, "\n  `{name}` created at {}", scope.location)?,
+                    None => write!(f, "\n  created at {}", scope.location)?,
+                }
+            }
+        }
+        if !self.rc_signals.is_empty() {
+            write!(f, "\nRcSignals that are still alive:")?;
+            for location in &self.rc_signals {
+                write!(f, "\n  created at {location}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the reactive nodes that are alive on the current thread.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let disposer = create_scope(|cx| {
+///     set_scope_debug_name(cx, "app");
+///     let _ = create_signal(cx, 0);
+/// });
+/// let report = leak_report();
+/// assert_eq!(report.leaked_scopes[0].name, Some("app"));
+/// println!("{report}");
+///
+/// unsafe { disposer.dispose() };
+/// assert!(leak_report().is_empty());
+/// ```
+pub fn leak_report() -> LeakReport {
+    LIVE.with(|live| {
+        let live = live.borrow();
+        LeakReport {
+            scopes: live.counts[Kind::Scope as usize],
+            signals: live.counts[Kind::Signal as usize],
+            effects: live.counts[Kind::Effect as usize],
+            leaked_scopes: live.root_scopes.values().copied().collect(),
+            rc_signals: live.rc_signals.values().copied().collect(),
+        }
+    })
+}
+
+/// Gives the scope a name that is used to identify it in [`leak_report`]. Does nothing if `cx`
+/// is not a root scope, since child scopes are never reported.
+pub fn set_scope_debug_name(cx: Scope, name: &'static str) {
+    LIVE.with(|live| {
+        if let Some(scope) = live.borrow_mut().root_scopes.get_mut(&cx.raw.leak.id) {
+            scope.name = Some(name);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_live_nodes() {
+        let before = leak_report();
+        let disposer = create_scope(|cx| {
+            let signal = create_signal(cx, 0);
+            create_effect(cx, || signal.track());
+            let _ = create_child_scope(cx, |_| {});
+        });
+        let report = leak_report();
+        assert_eq!(report.scopes, before.scopes + 2);
+        assert_eq!(report.signals, before.signals + 1);
+        assert_eq!(report.effects, before.effects + 1);
+        assert_eq!(report.leaked_scopes.len(), before.leaked_scopes.len() + 1);
+        assert_eq!(
+            report.leaked_scopes.last().unwrap().location.file(),
+            file!()
+        );
+
+        unsafe { disposer.dispose() };
+        assert_eq!(leak_report(), before);
+    }
+
+    #[test]
+    fn reports_rc_signals() {
+        let signal = create_rc_signal(0);
+        let line = line!() - 1;
+        let report = leak_report();
+        assert_eq!(report.rc_signals.last().unwrap().line(), line);
+        assert!(report
+            .to_string()
+            .contains(&format!("created at {}:{line}", file!())));
+
+        drop(signal);
+        assert!(leak_report().rc_signals.is_empty());
+    }
+}
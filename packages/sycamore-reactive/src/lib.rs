@@ -1,30 +1,76 @@
 //! Reactive primitives for Sycamore.
-
+//!
+//! # Features
+//! - `std` (_default_) - Uses thread-locals for the global state of the reactive runtime. Without
+//!   this feature, the crate is `no_std` and only requires `alloc`. The runtime is then assumed to
+//!   only ever be used from a single thread, and a panic that unwinds out of the runtime leaves its
+//!   global state inconsistent.
+//! - `diagnostics` (_default_) - Detects update cycles and tracks which signal triggered an effect
+//!   in debug builds. See [`why_did_you_update`]. Disabling it has no effect on release builds.
+//! - `serde` - Implements `Serialize` and `Deserialize` for signals.
+//! - `leak-detection` - Counts the scopes, signals and effects that are alive and records where
+//!   root scopes and [`RcSignal`]s are created. See [`leak_report`].
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 #![deny(missing_debug_implementations)]
 
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+mod local;
+
 mod arena;
+mod channel;
 mod context;
+mod debug;
 mod effect;
 mod iter;
+#[cfg(feature = "leak-detection")]
+mod leak;
+mod machine;
+mod maybe_dyn;
 mod memo;
+mod prop;
+mod reactive_vec;
+mod runtime;
 mod signal;
+mod store;
+mod strict;
 
-use std::any::{Any, TypeId};
-use std::cell::RefCell;
-use std::marker::PhantomData;
-use std::mem;
-use std::rc::{Rc, Weak};
+use alloc::rc::{Rc, Weak};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+use core::any::{Any, TypeId};
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use core::mem;
 
-use ahash::AHashMap;
 use arena::*;
+pub use channel::*;
 pub use context::*;
+pub use debug::*;
 pub use effect::*;
 use indexmap::IndexMap;
 pub use iter::*;
+#[cfg(feature = "leak-detection")]
+pub use leak::*;
+pub use machine::*;
+pub use maybe_dyn::*;
 pub use memo::*;
+pub use prop::*;
+pub use reactive_vec::*;
+pub use runtime::*;
 pub use signal::*;
+pub use store::*;
 use slotmap::{DefaultKey, SlotMap};
+pub use strict::*;
+
+/// A hash map using `ahash`. Unlike [`ahash::AHashMap`], this is also available without `std`.
+type AHashMap<K, V> = hashbrown::HashMap<K, V, ahash::RandomState>;
+/// A hash set using `ahash`. Unlike [`ahash::AHashSet`], this is also available without `std`.
+type AHashSet<T> = hashbrown::HashSet<T, ahash::RandomState>;
 
 /// A wrapper type around a lifetime that forces the lifetime to be invariant.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -36,6 +82,8 @@ struct InvariantLifetime<'id>(PhantomData<&'id mut &'id ()>);
 struct ScopeInner<'a> {
     /// Cleanup functions.
     cleanups: Vec<Box<dyn FnOnce() + 'a>>,
+    /// The order in which the cleanup functions are called.
+    cleanup_order: CleanupOrder,
     /// Child scopes.
     ///
     /// The raw pointer is owned by this field.
@@ -61,6 +109,8 @@ struct ScopeRaw<'a> {
     /// # Safety
     /// The parent scope does not actually have the right lifetime.
     parent: Option<*const ScopeRaw<'a>>,
+    #[cfg(feature = "leak-detection")]
+    leak: leak::LiveToken,
 }
 
 /// A reference to a reactive scope. This reference is `Copy`, allowing it to be copied into
@@ -86,8 +136,8 @@ pub struct BoundedScope<'a, 'b: 'a> {
     /// `&'b` for covariance!
     _phantom: PhantomData<&'b ()>,
 }
-impl<'a, 'b: 'a> std::fmt::Debug for BoundedScope<'a, 'b> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a, 'b: 'a> core::fmt::Debug for BoundedScope<'a, 'b> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("BoundedScope").finish()
     }
 }
@@ -112,7 +162,7 @@ pub type Scope<'a> = BoundedScope<'a, 'a>;
 impl<'a> ScopeRaw<'a> {
     /// Create a new [`ScopeRaw`]. This function is deliberately not `pub` because it should not be
     /// possible to access a [`ScopeRaw`] directly on the stack.
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(pool: Rc<ScopePool>) -> Self {
         // Even though the initialization code below is same as deriving Default::default(), we
         // can't do that because accessing a raw Scope outside of a scope closure breaks
         // safety contracts.
@@ -121,12 +171,15 @@ impl<'a> ScopeRaw<'a> {
         Self {
             inner: RefCell::new(ScopeInner {
                 cleanups: Default::default(),
+                cleanup_order: CleanupOrder::Fifo,
                 child_scopes: Default::default(),
                 contexts: None,
                 _phantom: Default::default(),
             }),
-            arena: Default::default(),
+            arena: ScopeArena::new(pool),
             parent: None,
+            #[cfg(feature = "leak-detection")]
+            leak: leak::LiveToken::new(leak::Kind::Scope),
         }
     }
 }
@@ -135,8 +188,8 @@ impl<'a> ScopeRaw<'a> {
 pub struct ScopeDisposer<'a> {
     f: Box<dyn FnOnce() + 'a>,
 }
-impl<'a> std::fmt::Debug for ScopeDisposer<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<'a> core::fmt::Debug for ScopeDisposer<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("ScopeDisposer").finish()
     }
 }
@@ -208,8 +261,11 @@ impl<'a> ScopeDisposer<'a> {
 /// unsafe { disposer.dispose(); }
 /// ```
 #[must_use = "not calling the disposer function will result in a memory leak"]
+#[cfg_attr(feature = "leak-detection", track_caller)]
 pub fn create_scope<'disposer>(f: impl for<'a> FnOnce(Scope<'a>)) -> ScopeDisposer<'disposer> {
-    let cx = ScopeRaw::new();
+    let cx = ScopeRaw::new(Rc::new(ScopePool::default()));
+    #[cfg(feature = "leak-detection")]
+    cx.leak.track_root_scope(core::panic::Location::caller());
     let boxed = Box::new(cx);
     let ptr = Box::into_raw(boxed);
     // SAFETY: Safe because heap allocated value has stable address.
@@ -277,12 +333,11 @@ pub fn create_child_scope<'a, F>(cx: Scope<'a>, f: F) -> ScopeDisposer<'a>
 where
     F: for<'child_lifetime> FnOnce(BoundedScope<'child_lifetime, 'a>),
 {
-    let mut child = ScopeRaw::new();
+    let mut child = ScopeRaw::new(Rc::clone(cx.raw.arena.pool()));
     // SAFETY: The only fields that are accessed on self from child is `context` which does not
     // have any lifetime annotations.
-    child.parent = Some(unsafe { std::mem::transmute(cx.raw as *const _) });
-    let boxed = Box::new(child);
-    let ptr = Box::into_raw(boxed);
+    child.parent = Some(unsafe { core::mem::transmute(cx.raw as *const _) });
+    let ptr = cx.raw.arena.pool().alloc_scope(child);
 
     let key = cx
         .raw
@@ -291,7 +346,7 @@ where
         .child_scopes
         // SAFETY: None of the fields of ptr are accessed through child_scopes therefore we can
         // safely transmute the lifetime.
-        .insert(unsafe { std::mem::transmute(ptr) });
+        .insert(unsafe { core::mem::transmute(ptr) });
 
     // SAFETY: the address of the cx lives as long as 'a because:
     // - It is allocated on the heap and therefore has a stable address.
@@ -301,11 +356,9 @@ where
     //                      ^^^ -> `ptr` is still accessible here after call to f.
     ScopeDisposer::new(move || unsafe {
         let cx = cx.raw.inner.borrow_mut().child_scopes.remove(key).unwrap();
-        // SAFETY: Safe because ptr created using Box::into_raw and closure cannot live longer
-        // than 'a.
-        let cx = Box::from_raw(cx);
-        // SAFETY: Outside of call to f.
-        cx.dispose();
+        // SAFETY: Safe because ptr created using ScopePool::alloc_scope and closure cannot live
+        // longer than 'a. Outside of call to f.
+        ScopePool::free_scope(cx);
     })
 }
 
@@ -353,19 +406,49 @@ pub fn create_ref<T>(cx: Scope, value: T) -> &T {
     cx.raw.arena.alloc(value)
 }
 
-/// Adds a callback that is called when the scope is destroyed.
+/// Adds a callback that is called when the scope is destroyed. The callbacks of a scope are called
+/// in the order in which they were added, unless the order is changed with [`set_cleanup_order`].
 pub fn on_cleanup<'a>(cx: Scope<'a>, f: impl FnOnce() + 'a) {
     cx.raw.inner.borrow_mut().cleanups.push(Box::new(f));
 }
 
+/// The order in which the [`on_cleanup`] callbacks of a scope are called.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupOrder {
+    /// The callbacks are called in the order in which they were added.
+    #[default]
+    Fifo,
+    /// The callbacks are called in the reverse order in which they were added, like destructors.
+    /// Useful when a resource is set up in steps that depend on each other, e.g. when a
+    /// connection must be closed after the subscriptions on it are cancelled.
+    Lifo,
+}
+
+/// Sets the order in which the [`on_cleanup`] callbacks of the scope are called, including the
+/// callbacks that were added before. Does not affect the child scopes, which are always disposed
+/// before the callbacks are called.
+pub fn set_cleanup_order(cx: Scope<'_>, order: CleanupOrder) {
+    cx.raw.inner.borrow_mut().cleanup_order = order;
+}
+
 /// Returns a [`RcSignal`] that is `true` when the scope is still valid and `false` once it is
 /// disposed.
+///
+/// The signal is created the first time this is called in a scope. The next calls in the same
+/// scope return the same signal, so this can be called repeatedly without allocating.
 pub fn use_scope_status(cx: Scope) -> RcSignal<bool> {
+    /// The status of a scope, provided as a context of the scope itself.
+    struct ScopeStatus(RcSignal<bool>);
+
+    if let Some(status) = context::use_own_context::<ScopeStatus>(cx) {
+        return status.0.clone();
+    }
     let status = create_rc_signal(true);
     on_cleanup(cx, {
         let status = status.clone();
         move || status.set(false)
     });
+    replace_context(cx, ScopeStatus(status.clone()));
     status
 }
 
@@ -381,14 +464,14 @@ impl<'a> ScopeRaw<'a> {
         // Drop child scopes.
         for &child in mem::take(&mut inner.child_scopes).values() {
             // SAFETY: These pointers were allocated in Self::create_child_scope.
-            let cx = Box::from_raw(child);
-            // Dispose of cx if it has not already been disposed.
-            cx.dispose();
+            ScopePool::free_scope(child);
         }
         // Call cleanup functions in an untracked scope.
         untrack(|| {
-            for cb in mem::take(&mut inner.cleanups) {
-                cb();
+            let cleanups = mem::take(&mut inner.cleanups);
+            match inner.cleanup_order {
+                CleanupOrder::Fifo => cleanups.into_iter().for_each(|cb| cb()),
+                CleanupOrder::Lifo => cleanups.into_iter().rev().for_each(|cb| cb()),
             }
         });
         // Cleanup signals and refs allocated on the arena.
@@ -436,7 +519,7 @@ pub fn on<'a, U, const N: usize>(
 
 #[cfg(test)]
 mod tests {
-    use std::cell::Cell;
+    use core::cell::Cell;
 
     use super::*;
 
@@ -518,6 +601,41 @@ mod tests {
         });
     }
 
+    #[test]
+    fn scope_status() {
+        create_scope_immediate(|cx| {
+            let status = use_scope_status(cx);
+            let child_statuses = create_ref(cx, RefCell::new(Vec::new()));
+            let child = create_child_scope(cx, |cx| {
+                child_statuses.borrow_mut().push(use_scope_status(cx));
+                child_statuses.borrow_mut().push(use_scope_status(cx));
+            });
+            unsafe { child.dispose() };
+            assert!(child_statuses.borrow().iter().all(|status| !*status.get()));
+            assert!(*status.get());
+
+            // The same signal is returned for the same scope.
+            status.set(false);
+            assert!(!*use_scope_status(cx).get());
+        });
+    }
+
+    #[test]
+    fn cleanup_order() {
+        let order = Rc::new(RefCell::new(Vec::new()));
+        for cleanup_order in [CleanupOrder::Fifo, CleanupOrder::Lifo] {
+            let disposer = create_scope(|cx| {
+                for i in 0..3 {
+                    let order = Rc::clone(&order);
+                    on_cleanup(cx, move || order.borrow_mut().push(i));
+                }
+                set_cleanup_order(cx, cleanup_order);
+            });
+            unsafe { disposer.dispose() };
+        }
+        assert_eq!(*order.borrow(), [0, 1, 2, 2, 1, 0]);
+    }
+
     #[test]
     fn can_store_disposer_in_own_signal() {
         create_scope_immediate(|cx| {
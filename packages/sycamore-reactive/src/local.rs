@@ -0,0 +1,67 @@
+//! A replacement for [`thread_local!`] when `std` is not available.
+//!
+//! Without `std` there are no threads to speak of, so the reactive runtime is assumed to only ever
+//! be used from a single thread and the "thread-locals" are plain global variables.
+
+use core::cell::UnsafeCell;
+
+/// A global variable that is lazily initialized on first access. Mirrors the API of
+/// `std::thread::LocalKey`.
+pub struct LocalKey<T: 'static> {
+    init: fn() -> T,
+    value: UnsafeCell<Option<T>>,
+}
+
+// SAFETY: without `std`, the reactive runtime is only used from a single thread, so the value is
+// never accessed concurrently.
+unsafe impl<T> Sync for LocalKey<T> {}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            init,
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Calls `f` with a reference to the value, initializing it first if needed.
+    pub fn with<F, R>(&'static self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        // SAFETY: the value is only written once, before any reference to it is handed out. After
+        // that, only shared references are created.
+        unsafe {
+            if (*self.value.get()).is_none() {
+                let value = (self.init)();
+                *self.value.get() = Some(value);
+            }
+            f((*self.value.get()).as_ref().unwrap())
+        }
+    }
+}
+
+impl<T: 'static> core::fmt::Debug for LocalKey<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LocalKey").finish_non_exhaustive()
+    }
+}
+
+/// Declares globals with the same syntax as [`thread_local!`].
+macro_rules! thread_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = const { $init:expr }; $($rest:tt)*) => {
+        thread_local!($(#[$attr])* $vis static $name: $ty = $init; $($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::local::LocalKey<$ty> = $crate::local::LocalKey::new({
+            fn init() -> $ty {
+                $init
+            }
+            init
+        });
+        thread_local!($($rest)*);
+    };
+}
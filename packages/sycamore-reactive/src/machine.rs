@@ -0,0 +1,283 @@
+//! Finite state machines whose current state is a signal.
+
+use core::fmt;
+
+use crate::*;
+
+/// A transition of a [`MachineConfig`].
+struct Transition<'a, S, E> {
+    from: S,
+    event: E,
+    to: S,
+    guard: Option<Box<dyn Fn() -> bool + 'a>>,
+}
+
+/// The states, transitions and actions of a state machine. Passed to [`create_machine`].
+pub struct MachineConfig<'a, S, E> {
+    initial: S,
+    transitions: Vec<Transition<'a, S, E>>,
+    on_enter: Vec<(S, Box<dyn Fn() + 'a>)>,
+    on_exit: Vec<(S, Box<dyn Fn() + 'a>)>,
+}
+
+impl<'a, S, E> MachineConfig<'a, S, E> {
+    /// Creates a new config for a machine starting in the `initial` state.
+    pub fn new(initial: S) -> Self {
+        Self {
+            initial,
+            transitions: Vec::new(),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+
+    /// Adds a transition from `from` to `to` when `event` is sent.
+    pub fn transition(mut self, from: S, event: E, to: S) -> Self {
+        self.transitions.push(Transition {
+            from,
+            event,
+            to,
+            guard: None,
+        });
+        self
+    }
+
+    /// Adds a transition from `from` to `to` when `event` is sent and `guard` returns `true`.
+    ///
+    /// Transitions are tried in the order they were added, so a guarded transition can be followed
+    /// by a fallback transition for the same state and event.
+    pub fn transition_if(
+        mut self,
+        from: S,
+        event: E,
+        to: S,
+        guard: impl Fn() -> bool + 'a,
+    ) -> Self {
+        self.transitions.push(Transition {
+            from,
+            event,
+            to,
+            guard: Some(Box::new(guard)),
+        });
+        self
+    }
+
+    /// Adds an action that is run when the machine enters `state`, including when it is created
+    /// in that state.
+    pub fn on_enter(mut self, state: S, action: impl Fn() + 'a) -> Self {
+        self.on_enter.push((state, Box::new(action)));
+        self
+    }
+
+    /// Adds an action that is run when the machine leaves `state`.
+    pub fn on_exit(mut self, state: S, action: impl Fn() + 'a) -> Self {
+        self.on_exit.push((state, Box::new(action)));
+        self
+    }
+}
+
+impl<'a, S: fmt::Debug, E> fmt::Debug for MachineConfig<'a, S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MachineConfig")
+            .field("initial", &self.initial)
+            .field("transitions", &self.transitions.len())
+            .finish()
+    }
+}
+
+/// A finite state machine created with [`create_machine`].
+pub struct Machine<'a, S, E> {
+    state: &'a Signal<S>,
+    transitions: Vec<Transition<'a, S, E>>,
+    on_enter: Vec<(S, Box<dyn Fn() + 'a>)>,
+    on_exit: Vec<(S, Box<dyn Fn() + 'a>)>,
+}
+
+impl<'a, S: Clone + PartialEq, E: PartialEq> Machine<'a, S, E> {
+    /// The current state of the machine.
+    pub fn state(&self) -> &'a ReadSignal<S> {
+        self.state
+    }
+
+    /// Returns `true` if the machine is in `state`. This value can be tracked.
+    pub fn matches(&self, state: &S) -> bool {
+        *self.state.get() == *state
+    }
+
+    /// Returns the transition taken if `event` is sent in the current state, if any.
+    fn find(&self, event: &E) -> Option<&Transition<'a, S, E>> {
+        let current = self.state.get_untracked();
+        self.transitions.iter().find(|transition| {
+            transition.from == *current
+                && transition.event == *event
+                && transition.guard.as_ref().map_or(true, |guard| guard())
+        })
+    }
+
+    /// Returns `true` if sending `event` would change the state. Useful for disabling buttons.
+    /// This value can be tracked.
+    pub fn can(&self, event: &E) -> bool {
+        self.state.track();
+        self.find(event).is_some()
+    }
+
+    /// Sends `event` to the machine. If a transition matches the current state and `event`, the
+    /// exit actions of the current state are run, the state is updated and the entry actions of
+    /// the new state are run.
+    ///
+    /// Returns `true` if a transition was taken. Events without a matching transition are
+    /// ignored.
+    pub fn send(&self, event: E) -> bool {
+        let Some(transition) = self.find(&event) else {
+            return false;
+        };
+        let from = self.state.get_untracked();
+        for (_, action) in self.on_exit.iter().filter(|(state, _)| *state == *from) {
+            action();
+        }
+        self.state.set(transition.to.clone());
+        self.enter(&transition.to);
+        true
+    }
+
+    fn enter(&self, to: &S) {
+        for (_, action) in self.on_enter.iter().filter(|(state, _)| state == to) {
+            action();
+        }
+    }
+}
+
+impl<'a, S: fmt::Debug, E> fmt::Debug for Machine<'a, S, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Machine")
+            .field("state", &self.state.get_untracked())
+            .finish()
+    }
+}
+
+/// Creates a new [`Machine`] from `config`. The entry actions of the initial state are run
+/// immediately.
+///
+/// The current state is a signal, which means that a view can simply `match` on it to render the
+/// current step of a wizard or the status of a connection.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum State {
+///     Disconnected,
+///     Connecting,
+///     Connected,
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Event {
+///     Connect,
+///     Success,
+///     Failure,
+/// }
+///
+/// # create_scope_immediate(|cx| {
+/// let retries = create_signal(cx, 0);
+/// let machine = create_machine(
+///     cx,
+///     MachineConfig::new(State::Disconnected)
+///         .transition(State::Disconnected, Event::Connect, State::Connecting)
+///         .transition(State::Connecting, Event::Success, State::Connected)
+///         .transition_if(State::Connecting, Event::Failure, State::Connecting, || {
+///             *retries.get() < 3
+///         })
+///         .transition(State::Connecting, Event::Failure, State::Disconnected)
+///         .on_enter(State::Connecting, || retries.set(*retries.get_untracked() + 1)),
+/// );
+///
+/// assert!(!machine.send(Event::Success));
+/// machine.send(Event::Connect);
+/// assert_eq!(*machine.state().get(), State::Connecting);
+/// machine.send(Event::Failure);
+/// machine.send(Event::Failure);
+/// machine.send(Event::Failure);
+/// assert_eq!(*machine.state().get(), State::Disconnected);
+/// # });
+/// ```
+pub fn create_machine<'a, S, E>(
+    cx: Scope<'a>,
+    config: MachineConfig<'a, S, E>,
+) -> &'a Machine<'a, S, E>
+where
+    S: Clone + PartialEq + 'a,
+    E: PartialEq + 'a,
+{
+    let MachineConfig {
+        initial,
+        transitions,
+        on_enter,
+        on_exit,
+    } = config;
+    let machine = create_ref(
+        cx,
+        Machine {
+            state: create_signal(cx, initial.clone()),
+            transitions,
+            on_enter,
+            on_exit,
+        },
+    );
+    machine.enter(&initial);
+    machine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Step {
+        Details,
+        Payment,
+        Done,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Next,
+        Back,
+    }
+
+    #[test]
+    fn machine_transitions() {
+        create_scope_immediate(|cx| {
+            let accepted = create_signal(cx, false);
+            let log = create_signal(cx, Vec::new());
+            let machine = create_machine(
+                cx,
+                MachineConfig::new(Step::Details)
+                    .transition(Step::Details, Event::Next, Step::Payment)
+                    .transition(Step::Payment, Event::Back, Step::Details)
+                    .transition_if(Step::Payment, Event::Next, Step::Done, || *accepted.get())
+                    .on_enter(Step::Details, || log.modify().push("enter details"))
+                    .on_exit(Step::Details, || log.modify().push("exit details"))
+                    .on_enter(Step::Done, || log.modify().push("enter done")),
+            );
+            assert_eq!(*log.get(), ["enter details"]);
+
+            let can_finish = create_memo(cx, || {
+                machine.matches(&Step::Payment) && machine.can(&Event::Next)
+            });
+            assert!(!*can_finish.get());
+
+            assert!(machine.send(Event::Next));
+            assert!(!*can_finish.get());
+            assert!(!machine.send(Event::Next));
+            assert_eq!(*machine.state().get(), Step::Payment);
+
+            accepted.set(true);
+            assert!(*can_finish.get());
+            assert!(machine.send(Event::Next));
+            assert_eq!(*machine.state().get(), Step::Done);
+            assert!(!machine.send(Event::Back));
+            assert_eq!(*log.get(), ["enter details", "exit details", "enter done"]);
+        });
+    }
+}
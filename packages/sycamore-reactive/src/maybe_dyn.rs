@@ -0,0 +1,137 @@
+//! Values that can be either static or reactive.
+
+use core::fmt;
+
+use crate::*;
+
+/// A value that is either static, a signal or a closure. This is mostly useful as the type of a
+/// component prop, letting the caller decide whether the prop is reactive.
+///
+/// Fields of type `MaybeDyn` in a struct deriving `Prop` automatically accept anything that can be
+/// converted into a `MaybeDyn`, i.e. a plain value, a signal or a [`MaybeDyn::derived`] closure.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// fn label(value: MaybeDyn<i32>) -> String {
+///     format!("Value: {}", value.get())
+/// }
+///
+/// # create_scope_immediate(|cx| {
+/// let count = create_signal(cx, 1);
+/// assert_eq!(label(2.into()), "Value: 2");
+/// assert_eq!(label(count.into()), "Value: 1");
+/// assert_eq!(label(MaybeDyn::derived(|| *count.get() * 10)), "Value: 10");
+/// # });
+/// ```
+pub enum MaybeDyn<'a, T> {
+    /// A value that never changes.
+    Static(T),
+    /// A value read from a signal.
+    Signal(&'a ReadSignal<T>),
+    /// A value computed by a closure. Reading it calls the closure.
+    Derived(Rc<dyn Fn() -> T + 'a>),
+}
+
+impl<'a, T> MaybeDyn<'a, T> {
+    /// Creates a [`MaybeDyn::Derived`] from a closure.
+    pub fn derived(f: impl Fn() -> T + 'a) -> Self {
+        Self::Derived(Rc::new(f))
+    }
+
+    /// Returns `true` if the value is [`MaybeDyn::Static`] and can therefore never change.
+    pub fn is_static(&self) -> bool {
+        matches!(self, Self::Static(_))
+    }
+}
+
+impl<'a, T: Clone> MaybeDyn<'a, T> {
+    /// Returns the current value. This value can be tracked.
+    pub fn get(&self) -> T {
+        match self {
+            Self::Static(value) => value.clone(),
+            Self::Signal(signal) => signal.get().as_ref().clone(),
+            Self::Derived(f) => f(),
+        }
+    }
+
+    /// Returns the current value without tracking it.
+    pub fn get_untracked(&self) -> T {
+        untrack(|| self.get())
+    }
+}
+
+impl<'a, T: Clone> Clone for MaybeDyn<'a, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Static(value) => Self::Static(value.clone()),
+            Self::Signal(signal) => Self::Signal(signal),
+            Self::Derived(f) => Self::Derived(Rc::clone(f)),
+        }
+    }
+}
+
+impl<'a, T: Default> Default for MaybeDyn<'a, T> {
+    fn default() -> Self {
+        Self::Static(T::default())
+    }
+}
+
+impl<'a, T> From<T> for MaybeDyn<'a, T> {
+    fn from(value: T) -> Self {
+        Self::Static(value)
+    }
+}
+
+impl<'a, T> From<&'a ReadSignal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: &'a ReadSignal<T>) -> Self {
+        Self::Signal(signal)
+    }
+}
+
+impl<'a, T> From<&'a Signal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: &'a Signal<T>) -> Self {
+        Self::Signal(signal)
+    }
+}
+
+impl<'a, T: Clone + 'a> From<RcSignal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: RcSignal<T>) -> Self {
+        Self::derived(move || signal.get().as_ref().clone())
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for MaybeDyn<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(value) => f.debug_tuple("Static").field(value).finish(),
+            Self::Signal(signal) => f.debug_tuple("Signal").field(signal).finish(),
+            Self::Derived(_) => f.debug_tuple("Derived").finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maybe_dyn_tracking() {
+        create_scope_immediate(|cx| {
+            let signal = create_signal(cx, 1);
+            let values: &[MaybeDyn<i32>; 3] = create_ref(
+                cx,
+                [
+                    0.into(),
+                    signal.into(),
+                    MaybeDyn::derived(|| *signal.get() + 1),
+                ],
+            );
+            let sum = create_memo(cx, || values.iter().map(MaybeDyn::get).sum::<i32>());
+            assert_eq!(*sum.get(), 3);
+            signal.set(2);
+            assert_eq!(*sum.get(), 5);
+            assert!(values[0].is_static() && !values[1].is_static());
+        });
+    }
+}
@@ -1,6 +1,6 @@
 //! Derived and computed data.
 
-use std::cell::Cell;
+use core::cell::Cell;
 
 use crate::*;
 
@@ -45,6 +45,7 @@ use crate::*;
 /// assert_eq!(*double.get(), 2);
 /// # });
 /// ```
+#[track_caller]
 pub fn create_memo<'a, U: 'a>(cx: Scope<'a>, f: impl FnMut() -> U + 'a) -> &'a ReadSignal<U> {
     create_selector_with(cx, f, |_, _| false)
 }
@@ -69,6 +70,7 @@ pub fn create_memo<'a, U: 'a>(cx: Scope<'a>, f: impl FnMut() -> U + 'a) -> &'a R
 /// assert_eq!(*double.get(), 2);
 /// # });
 /// ```
+#[track_caller]
 pub fn create_selector<'a, U: PartialEq + 'a>(
     cx: Scope<'a>,
     f: impl FnMut() -> U + 'a,
@@ -85,6 +87,38 @@ pub fn create_selector<'a, U: PartialEq + 'a>(
 ///
 /// To use the type's [`PartialEq`] implementation instead of a custom function, use
 /// [`create_selector`].
+///
+/// # Example
+/// Deriving a slice of a large struct without notifying dependents when unrelated fields change:
+/// ```
+/// # use sycamore_reactive::*;
+/// # use std::rc::Rc;
+/// struct Document {
+///     title: String,
+///     body: String,
+/// }
+///
+/// # create_scope_immediate(|cx| {
+/// let doc = create_signal(cx, Document { title: "Draft".into(), body: String::new() });
+/// let title = create_selector_with(
+///     cx,
+///     || doc.get(),
+///     |a: &Rc<Document>, b: &Rc<Document>| a.title == b.title,
+/// );
+///
+/// let renders = create_signal(cx, 0);
+/// create_effect(cx, || {
+///     title.track();
+///     renders.set(*renders.get_untracked() + 1);
+/// });
+///
+/// doc.set(Document { title: "Draft".into(), body: "Hello".into() });
+/// assert_eq!(*renders.get(), 1); // the title did not change
+/// doc.set(Document { title: "Final".into(), body: "Hello".into() });
+/// assert_eq!(*renders.get(), 2);
+/// # });
+/// ```
+#[track_caller]
 pub fn create_selector_with<'a, U: 'a>(
     cx: Scope<'a>,
     mut f: impl FnMut() -> U + 'a,
@@ -244,6 +278,30 @@ mod tests {
         });
     }
 
+    #[test]
+    fn selector_with_custom_eq() {
+        create_scope_immediate(|cx| {
+            let state = create_signal(cx, (0, 'a'));
+            // Only the first field is compared.
+            let selector = create_selector_with(cx, || *state.get(), |a, b| a.0 == b.0);
+
+            let counter = create_signal(cx, 0);
+            create_effect(cx, || {
+                counter.set(*counter.get_untracked() + 1);
+                selector.track();
+            });
+            assert_eq!(*counter.get(), 1);
+
+            state.set((0, 'b'));
+            assert_eq!(*selector.get(), (0, 'a')); // the old value is kept
+            assert_eq!(*counter.get(), 1);
+
+            state.set((1, 'c'));
+            assert_eq!(*selector.get(), (1, 'c'));
+            assert_eq!(*counter.get(), 2);
+        });
+    }
+
     #[test]
     fn reducer() {
         create_scope_immediate(|cx| {
@@ -0,0 +1,125 @@
+//! Signals for props that are plain values.
+
+use core::panic::Location;
+
+use crate::*;
+
+/// The signals created by [`create_prop_signal`] in the child scopes of a scope, by call site.
+#[derive(Default)]
+struct PropSignals(RefCell<AHashMap<&'static Location<'static>, Box<dyn Any>>>);
+
+/// Wraps a plain value prop into a signal that outlives the component instance.
+///
+/// When a component is rendered inside a dynamic view of its parent, it is instantiated again from
+/// scratch in a new scope every time the view is re-rendered and a plain value prop cannot be
+/// tracked. This function stores the signal in the parent scope instead, so that every instance of
+/// the component gets the same signal, holding the latest value.
+/// The subscribers of the signal are only notified when `value` is different from the value of the
+/// previous instance.
+///
+/// The signal is identified by the parent scope and the location of the call. Components that are
+/// rendered side by side in the same scope, e.g. the items of a list, share the same signal and
+/// should not use this function.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// fn child(cx: Scope, value: i32) -> i32 {
+///     let value = create_prop_signal(cx, value);
+///     let doubled = create_memo(cx, || *value.get() * 2);
+///     *doubled.get()
+/// }
+///
+/// # create_scope_immediate(|cx| {
+/// let value = create_signal(cx, 1);
+/// let output = create_signal(cx, 0);
+/// create_effect_scoped(cx, |cx| output.set(child(cx, *value.get())));
+/// assert_eq!(*output.get(), 2);
+/// value.set(2);
+/// assert_eq!(*output.get(), 4);
+/// # });
+/// ```
+#[track_caller]
+pub fn create_prop_signal<T: PartialEq + 'static>(cx: Scope<'_>, value: T) -> &ReadSignal<T> {
+    let location = Location::caller();
+    let signal = match cx.raw.parent {
+        Some(parent) => {
+            // SAFETY: `cx.raw.parent` necessarily lives longer than `cx`.
+            let parent = unsafe { &*parent };
+            let type_id = TypeId::of::<PropSignals>();
+            let existing = parent
+                .inner
+                .borrow()
+                .contexts
+                .as_ref()
+                .and_then(|contexts| contexts.get(&type_id).copied());
+            let props = match existing {
+                Some(props) => props.downcast_ref::<PropSignals>().unwrap(),
+                None => {
+                    let props: &PropSignals = parent.arena.alloc(PropSignals::default());
+                    parent
+                        .inner
+                        .borrow_mut()
+                        .contexts
+                        .get_or_insert_with(Default::default)
+                        .insert(type_id, props);
+                    props
+                }
+            };
+            let existing = props
+                .0
+                .borrow()
+                .get(location)
+                .and_then(|signal| signal.downcast_ref::<RcSignal<T>>())
+                .cloned();
+            match existing {
+                Some(signal) => {
+                    if *signal.get_untracked() != value {
+                        signal.set(value);
+                    }
+                    signal
+                }
+                None => {
+                    let signal = create_rc_signal(value);
+                    props
+                        .0
+                        .borrow_mut()
+                        .insert(location, Box::new(signal.clone()));
+                    signal
+                }
+            }
+        }
+        None => create_rc_signal(value),
+    };
+    let signal: &RcSignal<T> = create_ref(cx, signal);
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(cx: Scope, value: i32) -> *const ReadSignal<i32> {
+        let signal = create_prop_signal(cx, value);
+        assert_eq!(*signal.get(), value);
+        signal
+    }
+
+    #[test]
+    fn prop_signal_shared_between_instances() {
+        create_scope_immediate(|cx| {
+            let mut first = core::ptr::null();
+            let disposer = create_child_scope(cx, |cx| first = child(cx, 1));
+            unsafe { disposer.dispose() };
+            let mut second = core::ptr::null();
+            let _ = create_child_scope(cx, |cx| second = child(cx, 2));
+            assert_eq!(first, second);
+
+            // A different call site gets a different signal.
+            let _ = create_child_scope(cx, |cx| {
+                let signal = create_prop_signal(cx, 3);
+                assert_ne!(signal as *const ReadSignal<i32>, first);
+            });
+        });
+    }
+}
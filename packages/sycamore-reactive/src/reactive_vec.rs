@@ -0,0 +1,263 @@
+//! A reactive `Vec` that records the changes made to it.
+
+use core::fmt;
+
+use crate::*;
+
+/// A change made to a [`ReactiveVec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VecDiff<T> {
+    /// `value` was inserted at `index`. Pushing a value inserts it at the end.
+    Insert {
+        /// The index of the new value.
+        index: usize,
+        /// The new value.
+        value: T,
+    },
+    /// The value at `index` was removed.
+    Remove {
+        /// The index of the removed value.
+        index: usize,
+    },
+    /// The values at `a` and `b` were swapped.
+    Swap {
+        /// The index of the first value.
+        a: usize,
+        /// The index of the second value.
+        b: usize,
+    },
+    /// The value at `index` was replaced by `value`.
+    Set {
+        /// The index of the replaced value.
+        index: usize,
+        /// The new value.
+        value: T,
+    },
+    /// All the values were replaced by `values`.
+    Replace {
+        /// The new values.
+        values: Vec<T>,
+    },
+}
+
+/// Queue of the changes that have not been consumed yet by a listener.
+type DiffQueue<T> = RefCell<Vec<VecDiff<T>>>;
+
+/// A reactive `Vec` that records the changes made to it. Created with [`create_signal_vec`].
+///
+/// Unlike a `Signal<Vec<T>>`, which is replaced as a whole and has to be diffed against its
+/// previous value, a [`ReactiveVec`] is modified in place and reports each change as a
+/// [`VecDiff`]. [`map_vec`] (and the `KeyedVec` component in `sycamore`) applies these changes
+/// directly, which only costs as much as the change itself regardless of the length of the list.
+///
+/// Reading the values inside an effect or memo subscribes it to every change.
+///
+/// # Panics
+/// Modifying the vec while it is borrowed by [`ReactiveVec::with`] panics.
+pub struct ReactiveVec<T> {
+    values: RefCell<Vec<T>>,
+    /// Triggered every time the vec is modified.
+    notify: Signal<()>,
+    /// The queues of the listeners created with [`ReactiveVec::listen`].
+    listeners: RefCell<Vec<Weak<DiffQueue<T>>>>,
+}
+
+impl<T: Clone> ReactiveVec<T> {
+    /// Calls `f` with the values. Subscribes the current effect or memo to every change.
+    pub fn with<U>(&self, f: impl FnOnce(&[T]) -> U) -> U {
+        self.notify.track();
+        self.with_untracked(f)
+    }
+
+    /// Calls `f` with the values without subscribing to changes.
+    pub fn with_untracked<U>(&self, f: impl FnOnce(&[T]) -> U) -> U {
+        f(&self.values.borrow())
+    }
+
+    /// Returns a copy of the values. Subscribes the current effect or memo to every change.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.with(<[T]>::to_vec)
+    }
+
+    /// Returns a copy of the value at `index`, or `None` if it is out of bounds. Subscribes the
+    /// current effect or memo to every change.
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.with(|values| values.get(index).cloned())
+    }
+
+    /// Returns the number of values. Subscribes the current effect or memo to every change.
+    pub fn len(&self) -> usize {
+        self.with(<[T]>::len)
+    }
+
+    /// Returns `true` if there are no values. Subscribes the current effect or memo to every
+    /// change.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Subscribes the current effect or memo to every change without reading the values.
+    pub fn track(&self) {
+        self.notify.track();
+    }
+
+    /// Appends `value` at the end.
+    pub fn push(&self, value: T) {
+        let index = self.values.borrow().len();
+        self.insert(index, value);
+    }
+
+    /// Removes the last value and returns it, or `None` if the vec is empty.
+    pub fn pop(&self) -> Option<T> {
+        let len = self.values.borrow().len();
+        len.checked_sub(1).map(|index| self.remove(index))
+    }
+
+    /// Inserts `value` at `index`, shifting all the values after it to the right.
+    ///
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&self, index: usize, value: T) {
+        self.values.borrow_mut().insert(index, value.clone());
+        self.notify(VecDiff::Insert { index, value });
+    }
+
+    /// Removes the value at `index` and returns it, shifting all the values after it to the left.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&self, index: usize) -> T {
+        let value = self.values.borrow_mut().remove(index);
+        self.notify(VecDiff::Remove { index });
+        value
+    }
+
+    /// Swaps the values at `a` and `b`.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` are out of bounds.
+    pub fn swap(&self, a: usize, b: usize) {
+        self.values.borrow_mut().swap(a, b);
+        self.notify(VecDiff::Swap { a, b });
+    }
+
+    /// Replaces the value at `index` with `value`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&self, index: usize, value: T) {
+        self.values.borrow_mut()[index] = value.clone();
+        self.notify(VecDiff::Set { index, value });
+    }
+
+    /// Replaces all the values with `values`.
+    pub fn replace(&self, values: Vec<T>) {
+        *self.values.borrow_mut() = values.clone();
+        self.notify(VecDiff::Replace { values });
+    }
+
+    /// Removes all the values.
+    pub fn clear(&self) {
+        self.replace(Vec::new());
+    }
+
+    /// Returns a queue that receives every change made from now on. The queue stops receiving
+    /// changes once it is dropped.
+    pub(crate) fn listen(&self) -> Rc<DiffQueue<T>> {
+        let queue = Rc::new(RefCell::new(Vec::new()));
+        self.listeners.borrow_mut().push(Rc::downgrade(&queue));
+        queue
+    }
+
+    fn notify(&self, diff: VecDiff<T>) {
+        self.listeners
+            .borrow_mut()
+            .retain(|listener| match listener.upgrade() {
+                Some(queue) => {
+                    queue.borrow_mut().push(diff.clone());
+                    true
+                }
+                None => false,
+            });
+        self.notify.trigger_subscribers();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ReactiveVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReactiveVec")
+            .field(&self.values.borrow())
+            .finish()
+    }
+}
+
+/// Creates a new [`ReactiveVec`] with the initial `values`.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|cx| {
+/// let todos = create_signal_vec(cx, vec!["a", "b"]);
+/// let len = create_memo(cx, || todos.len());
+/// todos.push("c");
+/// assert_eq!(*len.get(), 3);
+/// todos.swap(0, 2);
+/// assert_eq!(todos.to_vec(), ["c", "b", "a"]);
+/// # });
+/// ```
+pub fn create_signal_vec<'a, T>(cx: Scope<'a>, values: Vec<T>) -> &'a ReactiveVec<T> {
+    create_ref(
+        cx,
+        ReactiveVec {
+            values: RefCell::new(values),
+            notify: Signal::new(()),
+            listeners: Default::default(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs() {
+        create_scope_immediate(|cx| {
+            let vec = create_signal_vec(cx, vec![1, 2]);
+            let queue = vec.listen();
+            vec.push(3);
+            vec.swap(0, 2);
+            vec.set(1, 4);
+            assert_eq!(vec.pop(), Some(1));
+            vec.clear();
+            assert_eq!(
+                *queue.borrow(),
+                [
+                    VecDiff::Insert { index: 2, value: 3 },
+                    VecDiff::Swap { a: 0, b: 2 },
+                    VecDiff::Set { index: 1, value: 4 },
+                    VecDiff::Remove { index: 2 },
+                    VecDiff::Replace { values: vec![] },
+                ]
+            );
+
+            // Dropped listeners are removed.
+            drop(queue);
+            vec.push(1);
+            assert!(vec.listeners.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn tracks_changes() {
+        create_scope_immediate(|cx| {
+            let vec = create_signal_vec(cx, vec![1, 2]);
+            let sum = create_memo(cx, || vec.with(|values| values.iter().sum::<i32>()));
+            assert_eq!(*sum.get(), 3);
+            vec.insert(0, 10);
+            assert_eq!(*sum.get(), 13);
+            vec.remove(1);
+            assert_eq!(*sum.get(), 12);
+        });
+    }
+}
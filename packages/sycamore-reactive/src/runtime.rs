@@ -0,0 +1,350 @@
+//! Configuration for how the reactive runtime propagates updates.
+
+use core::cell::Cell;
+
+use crate::*;
+
+thread_local! {
+    static CONFIG: Cell<RuntimeConfig> = Cell::new(RuntimeConfig::default());
+    /// The number of signals that are currently triggering their subscribers.
+    static UPDATE_DEPTH: Cell<usize> = Cell::new(0);
+    /// The number of effects that are currently running.
+    static EFFECT_DEPTH: Cell<usize> = Cell::new(0);
+    /// Signals whose subscribers should be notified once the outermost effect finishes running.
+    static DEFERRED: RefCell<Vec<WeakSignalEmitter>> = Default::default();
+    /// Called once an update has finished propagating. See [`add_after_update_hook`].
+    static AFTER_UPDATE: RefCell<Vec<fn()>> = Default::default();
+}
+
+/// How writes to signals that happen while an effect is running are handled. See
+/// [`RuntimeConfig::write_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritePolicy {
+    /// Subscribers are notified immediately, in the middle of the running effect. This is the
+    /// default.
+    #[default]
+    Immediate,
+    /// The new value is stored immediately but subscribers are only notified once the outermost
+    /// running effect has finished. Each signal is only notified once, even if it is written to
+    /// multiple times.
+    Deferred,
+}
+
+/// Configuration for how the reactive runtime propagates updates. The configuration is global to
+/// the current thread and can be changed with [`set_runtime_config`].
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// set_runtime_config(RuntimeConfig {
+///     max_update_depth: Some(1000),
+///     write_policy: WritePolicy::Deferred,
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RuntimeConfig {
+    /// The maximum number of nested signal updates, i.e. a signal update that re-executes an
+    /// effect that updates another signal, and so on. If the limit is exceeded, the runtime panics
+    /// with a descriptive message instead of overflowing the stack.
+    ///
+    /// Defaults to `None`, meaning that there is no limit.
+    pub max_update_depth: Option<usize>,
+    /// How writes to signals that happen while an effect is running are handled.
+    ///
+    /// Defaults to [`WritePolicy::Immediate`].
+    pub write_policy: WritePolicy,
+}
+
+/// Sets the configuration of the reactive runtime for the current thread.
+pub fn set_runtime_config(config: RuntimeConfig) {
+    CONFIG.with(|x| x.set(config));
+}
+
+/// Returns the configuration of the reactive runtime for the current thread.
+pub fn runtime_config() -> RuntimeConfig {
+    CONFIG.with(|x| x.get())
+}
+
+/// Increments the update depth and decrements it when dropped.
+pub(crate) struct UpdateDepthGuard(());
+
+impl UpdateDepthGuard {
+    /// # Panics
+    /// Panics if the new update depth exceeds [`RuntimeConfig::max_update_depth`].
+    pub(crate) fn new() -> Self {
+        let depth = UPDATE_DEPTH.with(|depth| {
+            depth.set(depth.get() + 1);
+            depth.get()
+        });
+        // Create the guard before panicking so that the depth is restored while unwinding.
+        let guard = Self(());
+        if let Some(max) = runtime_config().max_update_depth {
+            if depth > max {
+                panic!(
+                    "maximum update depth of {max} exceeded. This is probably caused by an effect \
+                     that updates a signal which causes the effect to be re-executed. The limit \
+                     can be changed with `set_runtime_config`."
+                );
+            }
+        }
+        guard
+    }
+}
+
+impl Drop for UpdateDepthGuard {
+    fn drop(&mut self) {
+        UPDATE_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Returns `true` while a signal update is propagating to its subscribers or an effect is
+/// running. The hooks added with [`add_after_update_hook`] are called once this is `false` again.
+pub fn is_updating() -> bool {
+    UPDATE_DEPTH.with(|depth| depth.get()) != 0 || EFFECT_DEPTH.with(|depth| depth.get()) != 0
+}
+
+/// Adds a hook that is called every time the runtime has finished propagating an update, i.e.
+/// once the outermost call to [`Signal::trigger_subscribers`] returns or the outermost effect is
+/// created, after all the effects that were re-executed have run.
+///
+/// This is used by renderers to run the work that must wait for all the components to be updated,
+/// e.g. measuring the layout of the DOM once it has been mutated by all the effects. The hook
+/// is not called while a panic unwinds.
+pub fn add_after_update_hook(hook: fn()) {
+    AFTER_UPDATE.with(|hooks| hooks.borrow_mut().push(hook));
+}
+
+/// Calls the hooks added with [`add_after_update_hook`] if no update is propagating.
+pub(crate) fn after_update_if_idle() {
+    if is_updating() || panicking() {
+        return;
+    }
+    // The hooks can add other hooks.
+    let hooks = AFTER_UPDATE.with(|hooks| hooks.borrow().clone());
+    for hook in hooks {
+        hook();
+    }
+}
+
+/// Keeps track of the running effects. The deferred signal updates are flushed by
+/// [`flush_deferred_if_idle`] once the outermost effect has finished.
+pub(crate) struct EffectDepthGuard(());
+
+impl EffectDepthGuard {
+    pub(crate) fn new() -> Self {
+        EFFECT_DEPTH.with(|depth| depth.set(depth.get() + 1));
+        Self(())
+    }
+}
+
+impl Drop for EffectDepthGuard {
+    fn drop(&mut self) {
+        EFFECT_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Notifies the subscribers of the deferred signal updates if no effect is running.
+///
+/// This must be called once the callback of the effect is no longer borrowed, not from inside the
+/// effect, because the effect itself is usually one of the subscribers, e.g. an effect that
+/// clamps the value of the signal that it reads.
+pub(crate) fn flush_deferred_if_idle() {
+    if EFFECT_DEPTH.with(|depth| depth.get()) == 0 && !panicking() {
+        flush_deferred();
+    }
+}
+
+/// Without `std` there is no unwinding to detect, so this is always `false`.
+fn panicking() -> bool {
+    #[cfg(feature = "std")]
+    return std::thread::panicking();
+    #[cfg(not(feature = "std"))]
+    return false;
+}
+
+/// Returns `true` if notifying the subscribers of `emitter` was deferred because of
+/// [`WritePolicy::Deferred`].
+pub(crate) fn defer_trigger(emitter: &SignalEmitter) -> bool {
+    if runtime_config().write_policy != WritePolicy::Deferred
+        || EFFECT_DEPTH.with(|depth| depth.get()) == 0
+    {
+        return false;
+    }
+    DEFERRED.with(|deferred| {
+        let mut deferred = deferred.borrow_mut();
+        let ptr = Rc::as_ptr(&emitter.0);
+        if !deferred.iter().any(|x| Weak::as_ptr(&x.0) == ptr) {
+            deferred.push(emitter.downgrade());
+        }
+    });
+    true
+}
+
+/// Notifies the subscribers of all the deferred signal updates.
+fn flush_deferred() {
+    loop {
+        let deferred = DEFERRED.with(|deferred| deferred.take());
+        if deferred.is_empty() {
+            break;
+        }
+        for emitter in deferred {
+            if let Some(emitter) = emitter.upgrade() {
+                emitter.trigger_subscribers();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Restores the default configuration when dropped.
+    struct ResetConfig;
+    impl Drop for ResetConfig {
+        fn drop(&mut self) {
+            set_runtime_config(RuntimeConfig::default());
+        }
+    }
+
+    fn with_config(config: RuntimeConfig) -> ResetConfig {
+        set_runtime_config(config);
+        ResetConfig
+    }
+
+    #[test]
+    fn immediate_writes() {
+        create_scope_immediate(|cx| {
+            let trigger = create_signal(cx, 0);
+            let state = create_signal(cx, 0);
+            let log = create_signal(cx, Vec::new());
+            create_effect(cx, || {
+                log.modify().push(*state.get());
+            });
+            create_effect(cx, || {
+                trigger.track();
+                state.set(1);
+                log.modify().push(-1);
+            });
+            assert_eq!(*log.get(), vec![0, 1, -1]);
+        });
+    }
+
+    #[test]
+    fn deferred_writes() {
+        let _reset = with_config(RuntimeConfig {
+            write_policy: WritePolicy::Deferred,
+            ..Default::default()
+        });
+        create_scope_immediate(|cx| {
+            let trigger = create_signal(cx, 0);
+            let state = create_signal(cx, 0);
+            let log = create_signal(cx, Vec::new());
+            create_effect(cx, || {
+                log.modify().push(*state.get());
+            });
+            create_effect(cx, || {
+                trigger.track();
+                state.set(1);
+                state.set(2);
+                // The new value is visible immediately.
+                assert_eq!(*state.get_untracked(), 2);
+                log.modify().push(-1);
+            });
+            // The first effect only re-runs once, after the second effect has finished.
+            assert_eq!(*log.get(), vec![0, -1, 2]);
+
+            // Writes outside of effects are not deferred.
+            state.set(3);
+            assert_eq!(*log.get(), vec![0, -1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn deferred_write_to_own_dependency() {
+        let _reset = with_config(RuntimeConfig {
+            write_policy: WritePolicy::Deferred,
+            ..Default::default()
+        });
+        create_scope_immediate(|cx| {
+            let state = create_signal(cx, 20);
+            let runs = create_signal(cx, 0);
+            create_effect(cx, move || {
+                runs.set_silent(*runs.get_untracked() + 1);
+                if *state.get() > 10 {
+                    state.set(10);
+                }
+            });
+            // The effect re-runs once with the clamped value.
+            assert_eq!(*state.get(), 10);
+            assert_eq!(*runs.get(), 2);
+
+            state.set(30);
+            assert_eq!(*state.get(), 10);
+            assert_eq!(*runs.get(), 4);
+        });
+    }
+
+    #[test]
+    fn after_update_hook() {
+        thread_local! {
+            static LOG: RefCell<Vec<&'static str>> = Default::default();
+        }
+        fn log(entry: &'static str) {
+            LOG.with(|log| log.borrow_mut().push(entry));
+        }
+
+        add_after_update_hook(|| log("after update"));
+        create_scope_immediate(|cx| {
+            let state = create_signal(cx, 0);
+            create_effect(cx, || {
+                state.track();
+                assert!(is_updating());
+                log("effect 1");
+            });
+            create_effect(cx, || {
+                state.track();
+                log("effect 2");
+            });
+            LOG.with(|log| log.borrow_mut().clear());
+            state.set(1);
+            assert!(!is_updating());
+        });
+        // The hook is only called once, after the effects.
+        LOG.with(|log| assert_eq!(*log.borrow(), ["effect 2", "effect 1", "after update"]));
+    }
+
+    #[test]
+    #[should_panic = "maximum update depth of 10 exceeded"]
+    fn max_update_depth() {
+        let _reset = with_config(RuntimeConfig {
+            max_update_depth: Some(10),
+            ..Default::default()
+        });
+        create_scope_immediate(|cx| {
+            let signals = (0..20).map(|_| create_signal(cx, 0)).collect::<Vec<_>>();
+            for pair in signals.windows(2) {
+                let (from, to) = (pair[0], pair[1]);
+                create_effect(cx, move || to.set(*from.get()));
+            }
+            signals[0].set(1);
+        });
+    }
+
+    #[test]
+    fn max_update_depth_not_exceeded() {
+        let _reset = with_config(RuntimeConfig {
+            max_update_depth: Some(10),
+            ..Default::default()
+        });
+        create_scope_immediate(|cx| {
+            let signals = (0..5).map(|_| create_signal(cx, 0)).collect::<Vec<_>>();
+            for pair in signals.windows(2) {
+                let (from, to) = (pair[0], pair[1]);
+                create_effect(cx, move || to.set(*from.get()));
+            }
+            signals[0].set(1);
+            assert_eq!(*signals[4].get(), 1);
+        });
+    }
+}
@@ -1,8 +1,8 @@
 //! Signals - The building blocks of reactivity.
 
-use std::fmt::{Debug, Display, Formatter};
-use std::hash::Hash;
-use std::ops::{AddAssign, Deref, DerefMut, DivAssign, MulAssign, SubAssign};
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::Hash;
+use core::ops::{AddAssign, Deref, DerefMut, DivAssign, MulAssign, SubAssign};
 
 use crate::effect::EFFECTS;
 use crate::*;
@@ -10,13 +10,14 @@ use crate::*;
 type WeakEffectCallback = Weak<RefCell<dyn FnMut()>>;
 type EffectCallbackPtr = *const RefCell<dyn FnMut()>;
 
-pub(crate) type SignalEmitterInner = RefCell<IndexMap<EffectCallbackPtr, WeakEffectCallback>>;
+pub(crate) type SignalEmitterInner =
+    RefCell<IndexMap<EffectCallbackPtr, WeakEffectCallback, ahash::RandomState>>;
 
 /// A struct for managing subscriptions to signals.
 #[derive(Default, Clone)]
 pub struct SignalEmitter(pub(crate) Rc<SignalEmitterInner>);
-impl std::fmt::Debug for SignalEmitter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for SignalEmitter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("SignalEmitter").finish()
     }
 }
@@ -65,11 +66,34 @@ impl SignalEmitter {
     ///
     /// This will also re-compute all the subscribers of this signal by calling all the dependency
     /// callbacks.
+    ///
+    /// If an effect is running and the [`WritePolicy`] is [`WritePolicy::Deferred`], the
+    /// subscribers are only called once the outermost effect has finished.
+    ///
+    /// In debug builds with the `diagnostics` feature, if the signal is triggered recursively too
+    /// many times because of an update cycle, the cycle is reported (see [`set_debug_logger`])
+    /// and the subscribers are not called.
+    ///
+    /// # Panics
+    /// Panics if [`RuntimeConfig::max_update_depth`] is exceeded.
     pub fn trigger_subscribers(&self) {
+        if crate::runtime::defer_trigger(self) {
+            return;
+        }
+        // Break update cycles instead of overflowing the stack.
+        #[cfg(all(debug_assertions, feature = "diagnostics"))]
+        if crate::debug::detect_cycle(self) {
+            return;
+        }
+        let depth = crate::runtime::UpdateDepthGuard::new();
+
         // Reset subscribers to prevent modifying the subscriber list while it is being read from.
         // We can completely wipe out the subscriber list because it will be constructed again when
         // each callback is called.
         let subscribers = self.0.take().into_values();
+        // Keep track of the signal that is triggering the subscribers for `why_did_you_update`.
+        #[cfg(all(debug_assertions, feature = "diagnostics"))]
+        let _guard = crate::debug::TriggerGuard::new(self);
         // Subscriber order is reversed because effects attach subscribers at the end of the
         // effect scope. This will ensure that outer effects re-execute before inner effects,
         // preventing inner effects from running twice.
@@ -78,8 +102,13 @@ impl SignalEmitter {
             if let Some(callback) = subscriber.upgrade() {
                 // Call the callback.
                 callback.borrow_mut()();
+                // The callback is no longer borrowed, so the effect can be notified of its own
+                // deferred writes.
+                crate::runtime::flush_deferred_if_idle();
             }
         }
+        drop(depth);
+        crate::runtime::after_update_if_idle();
     }
 }
 
@@ -87,6 +116,8 @@ impl SignalEmitter {
 pub struct ReadSignal<T> {
     value: RefCell<Rc<T>>,
     emitter: SignalEmitter,
+    #[cfg(feature = "leak-detection")]
+    leak: crate::leak::LiveToken,
 }
 
 impl<T> ReadSignal<T> {
@@ -132,6 +163,12 @@ impl<T> ReadSignal<T> {
         self.value.borrow().clone()
     }
 
+    /// Gives the signal a name that is used to identify it in debugging output, such as the logs
+    /// of [`why_did_you_update`].
+    pub fn set_debug_name(&self, name: &'static str) {
+        crate::debug::set_debug_name(&self.emitter, name);
+    }
+
     /// Creates a mapped [`ReadSignal`]. This is equivalent to using
     /// [`create_memo`].
     ///
@@ -174,6 +211,8 @@ impl<T> Signal<T> {
         Self(ReadSignal {
             value: RefCell::new(Rc::new(value)),
             emitter: Default::default(),
+            #[cfg(feature = "leak-detection")]
+            leak: crate::leak::LiveToken::new(crate::leak::Kind::Signal),
         })
     }
 
@@ -416,6 +455,8 @@ pub fn create_signal_from_rc<T>(cx: Scope, value: Rc<T>) -> &Signal<T> {
     let signal = Signal(ReadSignal {
         value: RefCell::new(value),
         emitter: Default::default(),
+        #[cfg(feature = "leak-detection")]
+        leak: crate::leak::LiveToken::new(crate::leak::Kind::Signal),
     });
     create_ref(cx, signal)
 }
@@ -476,34 +517,44 @@ impl<T> Clone for RcSignal<T> {
 /// Create a new [`RcSignal`] with the specified initial value.
 ///
 /// For more details, check the documentation for [`RcSignal`].
+#[cfg_attr(feature = "leak-detection", track_caller)]
 pub fn create_rc_signal<T>(value: T) -> RcSignal<T> {
-    RcSignal(Rc::new(Signal::new(value)))
+    let signal = Signal::new(value);
+    #[cfg(feature = "leak-detection")]
+    signal.leak.track_rc_signal(core::panic::Location::caller());
+    RcSignal(Rc::new(signal))
 }
 
 /// Create a new [`RcSignal`] with the specified initial value wrapped in a [`Rc`].
 ///
 /// For more details, check the documentation for [`RcSignal`].
+#[cfg_attr(feature = "leak-detection", track_caller)]
 pub fn create_rc_signal_from_rc<T>(value: Rc<T>) -> RcSignal<T> {
-    RcSignal(Rc::new(Signal(ReadSignal {
+    let signal = Signal(ReadSignal {
         value: RefCell::new(value),
         emitter: Default::default(),
-    })))
+        #[cfg(feature = "leak-detection")]
+        leak: crate::leak::LiveToken::new(crate::leak::Kind::Signal),
+    });
+    #[cfg(feature = "leak-detection")]
+    signal.leak.track_rc_signal(core::panic::Location::caller());
+    RcSignal(Rc::new(signal))
 }
 
 /* Display implementations */
 
 impl<T: Display> Display for RcSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
 impl<T: Display> Display for Signal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
 impl<T: Display> Display for ReadSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
@@ -511,17 +562,17 @@ impl<T: Display> Display for ReadSignal<T> {
 /* Debug implementations */
 
 impl<T: Debug> Debug for RcSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("RcSignal").field(&self.get()).finish()
     }
 }
 impl<T: Debug> Debug for Signal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Signal").field(&self.get()).finish()
     }
 }
 impl<T: Debug> Debug for ReadSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("ReadSignal").field(&self.get()).finish()
     }
 }
@@ -557,17 +608,17 @@ impl<T: Eq> Eq for Signal<T> {}
 impl<T: Eq> Eq for ReadSignal<T> {}
 
 impl<T: Hash> Hash for RcSignal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
 impl<T: Hash> Hash for Signal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
 impl<T: Hash> Hash for ReadSignal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
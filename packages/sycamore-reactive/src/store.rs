@@ -0,0 +1,66 @@
+//! Deeply reactive stores, usually created with the `Store` derive macro of `sycamore`.
+
+use crate::*;
+
+/// A type that can be turned into a store, a reactive version of the type where every field is a
+/// signal that is tracked individually.
+///
+/// This trait is implemented for structs with the `Store` derive macro of `sycamore`, which
+/// generates a `{Name}Store` struct with an accessor for every field. Fields are stored in an
+/// [`RcSignal`], except for the fields marked with `#[store(nested)]`, whose type must implement
+/// [`Store`] as well. This is also implemented for `Vec<T>`, so that a `Vec` of structs can be
+/// nested as well.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Store, Clone)]
+/// struct Address {
+///     city: String,
+/// }
+///
+/// #[derive(Store, Clone)]
+/// struct User {
+///     name: String,
+///     #[store(nested)]
+///     address: Address,
+/// }
+///
+/// let user = create_store(cx, User { name: "Ada".into(), address: Address { city: "London".into() } });
+/// create_effect(cx, || println!("{}", user.address().city().get()));
+/// user.name().set("Grace".into()); // Does not trigger the effect.
+/// ```
+pub trait Store: Sized {
+    /// The reactive version of the type.
+    type Store: Clone;
+
+    /// Turns the value into a store.
+    fn into_store(self) -> Self::Store;
+
+    /// Returns the current value of the store. This tracks every field of the store.
+    fn from_store(store: &Self::Store) -> Self;
+
+    /// Sets every field of the store to the fields of `value`.
+    fn set_store(store: &Self::Store, value: Self);
+}
+
+impl<T: Store> Store for Vec<T> {
+    type Store = RcSignal<Vec<T::Store>>;
+
+    fn into_store(self) -> Self::Store {
+        create_rc_signal(self.into_iter().map(T::into_store).collect())
+    }
+
+    fn from_store(store: &Self::Store) -> Self {
+        store.get().iter().map(T::from_store).collect()
+    }
+
+    /// Replaces the stores of the items. The stores that were accessed before are not updated.
+    fn set_store(store: &Self::Store, value: Self) {
+        store.set(value.into_iter().map(T::into_store).collect());
+    }
+}
+
+/// Creates a store from `value`. See [`Store`].
+pub fn create_store<T: Store>(cx: Scope<'_>, value: T) -> &T::Store {
+    create_ref(cx, value.into_store())
+}
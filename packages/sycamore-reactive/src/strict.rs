@@ -0,0 +1,127 @@
+//! Strict mode, which runs effects twice to catch effects that are not idempotent.
+
+use core::cell::Cell;
+
+use crate::*;
+
+/// The context provided by [`enable_strict_mode`].
+struct StrictMode;
+
+thread_local! {
+    /// The number of scopes in which strict mode is enabled. Used to avoid looking up the context
+    /// for every effect when strict mode is not used at all.
+    static STRICT_SCOPES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Enables strict mode in the scope `cx` and all of its descendants.
+///
+/// In strict mode, every effect that is created (including memos and scoped effects) is run twice
+/// right away. Scoped effects dispose their scope between the two runs, which runs their
+/// [`on_cleanup`] callbacks. Effects that are not idempotent, e.g. because they forget to clean
+/// up an event listener or an interval, therefore misbehave right away instead of when they are
+/// re-run much later. If the second run tracks different signals than the first one, a message
+/// with the location where the effect was created is logged using the logger set with
+/// [`set_debug_logger`].
+///
+/// Strict mode only has an effect in debug builds with the `diagnostics` feature.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|cx| {
+/// enable_strict_mode(cx);
+/// let runs = create_signal(cx, 0);
+/// create_effect(cx, || runs.set(*runs.get_untracked() + 1));
+/// # #[cfg(all(debug_assertions, feature = "diagnostics"))]
+/// assert_eq!(*runs.get(), 2);
+/// # });
+/// ```
+pub fn enable_strict_mode(cx: Scope<'_>) {
+    if cfg!(all(debug_assertions, feature = "diagnostics")) && !is_strict_mode(cx) {
+        provide_context(cx, StrictMode);
+        STRICT_SCOPES.with(|scopes| scopes.set(scopes.get() + 1));
+        on_cleanup(cx, || {
+            STRICT_SCOPES.with(|scopes| scopes.set(scopes.get() - 1))
+        });
+    }
+}
+
+/// Returns `true` if strict mode is enabled in `cx`. See [`enable_strict_mode`].
+pub fn is_strict_mode(cx: Scope<'_>) -> bool {
+    cfg!(all(debug_assertions, feature = "diagnostics"))
+        && STRICT_SCOPES.with(Cell::get) > 0
+        && try_use_context::<StrictMode>(cx).is_some()
+}
+
+#[cfg(all(test, debug_assertions, feature = "diagnostics"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_run_twice() {
+        create_scope_immediate(|cx| {
+            let runs = create_signal(cx, 0);
+            create_effect(cx, || runs.set(*runs.get_untracked() + 1));
+            assert_eq!(*runs.get(), 1);
+
+            let _ = create_child_scope(cx, |cx| {
+                enable_strict_mode(cx);
+                assert!(is_strict_mode(cx));
+                create_effect(cx, || runs.set(*runs.get_untracked() + 1));
+                assert_eq!(*runs.get(), 3);
+
+                let _ = create_child_scope(cx, |cx| {
+                    create_effect(cx, || runs.set(*runs.get_untracked() + 1));
+                    assert_eq!(*runs.get(), 5);
+                });
+            });
+            assert!(!is_strict_mode(cx));
+        });
+    }
+
+    #[test]
+    fn scoped_effects_are_cleaned_up() {
+        create_scope_immediate(|cx| {
+            enable_strict_mode(cx);
+            let listeners = create_signal(cx, 0);
+            create_effect_scoped(cx, |cx| {
+                listeners.set(*listeners.get_untracked() + 1);
+                on_cleanup(cx, || listeners.set(*listeners.get_untracked() - 1));
+            });
+            assert_eq!(*listeners.get(), 1);
+        });
+    }
+
+    #[test]
+    fn logs_effects_that_track_different_signals() {
+        let logs = Rc::new(RefCell::new(Vec::new()));
+        set_debug_logger({
+            let logs = Rc::clone(&logs);
+            move |msg| logs.borrow_mut().push(msg.to_string())
+        });
+        create_scope_immediate(|cx| {
+            enable_strict_mode(cx);
+            let a = create_signal(cx, 0);
+            a.set_debug_name("a");
+            let b = create_signal(cx, 0);
+            b.set_debug_name("b");
+            let first = create_ref(cx, Cell::new(true));
+            create_effect(cx, || {
+                set_effect_debug_name("flaky");
+                if first.replace(false) {
+                    a.track();
+                } else {
+                    b.track();
+                }
+            });
+            create_memo(cx, || *a.get() + *b.get());
+        });
+
+        let logs = logs.borrow();
+        assert_eq!(logs.len(), 1);
+        assert!(logs[0].contains("the effect `flaky` created at"));
+        assert!(logs[0].contains("strict.rs"));
+        assert!(logs[0].contains("first run: [`a`"));
+        assert!(logs[0].contains("second run: [`b`"));
+    }
+}
@@ -9,7 +9,9 @@ use crate::parser::{route, RoutePathAst, SegmentAst};
 pub fn route_impl(input: DeriveInput) -> syn::Result<TokenStream> {
     let mut quoted = TokenStream::new();
     let mut err_quoted = TokenStream::new();
-    // When the `#[not_found]` handler is found, this will store its name so we can use that as the `Default` implementation
+    let mut route_paths = Vec::new();
+    // When the `#[not_found]` handler is found, this will store its name so we can use that as the
+    // `Default` implementation
     let mut error_handler_name = None;
 
     match &input.data {
@@ -73,6 +75,21 @@ pub fn route_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                 }
                 if is_to_route {
                     let route_path_ast = route_path_ast.unwrap();
+                    let variant_name = variant_id.to_string();
+                    let names =
+                        route_path_ast
+                            .dyn_segments()
+                            .into_iter()
+                            .map(|segment| match segment {
+                                SegmentAst::Param(_) => unreachable!("not a dynamic segment"),
+                                SegmentAst::DynParam(name) | SegmentAst::DynSegments(name) => name,
+                            });
+                    route_paths.push(quote! {
+                        (
+                            #variant_name,
+                            #route_path_ast.with_names(::std::vec![#(::std::string::ToString::to_string(#names)),*]),
+                        )
+                    });
                     quoted.extend(quote! {
                         let __route = #route_path_ast;
                         if let Some(__captures) = __route.match_path(__segments) {
@@ -96,6 +113,10 @@ pub fn route_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                         #quoted
                         #err_quoted
                     }
+
+                    fn route_paths() -> ::std::vec::Vec<(&'static str, ::sycamore_router::RoutePath)> {
+                        ::std::vec![#(#route_paths),*]
+                    }
                 }
                 // We implement `Default` as well here for the `Router`/`RouterBase` distinction (`Router` needs to pass a default `impl Route` to `RouterBase`)
                 impl ::std::default::Default for #ty_name {
@@ -148,8 +169,9 @@ fn impl_to(
                             ));
                         }
                         let param_id: Ident = syn::parse_str(param)?;
-                        captures.push(quote! {
-                            let #param_id = match ::sycamore_router::TryFromParam::try_from_param(
+                        let ty = &field.ty;
+                        captures.push(quote_spanned! {ty.span()=>
+                            let #param_id = match <#ty as ::sycamore_router::TryFromParam>::try_from_param(
                                 __captures[#i].as_dyn_param().unwrap()
                             ) {
                                 ::std::option::Option::Some(__value) => __value,
@@ -169,8 +191,9 @@ fn impl_to(
                             ));
                         }
                         let param_id: Ident = syn::parse_str(param)?;
-                        captures.push(quote! {
-                            let #param_id = match ::sycamore_router::TryFromSegments::try_from_segments(
+                        let ty = &field.ty;
+                        captures.push(quote_spanned! {ty.span()=>
+                            let #param_id = match <#ty as ::sycamore_router::TryFromSegments>::try_from_segments(
                                 __captures[#i].as_dyn_segments().unwrap()
                             ) {
                                 ::std::option::Option::Some(__value) => __value,
@@ -194,22 +217,23 @@ fn impl_to(
             }
         }
         // For unnamed fields, captures must be in right order.
-        Fields::Unnamed(_) => {
+        Fields::Unnamed(f) => {
             let mut captures = Vec::new();
 
-            for (i, segment) in dyn_segments.iter().enumerate() {
+            for (i, (field, segment)) in f.unnamed.iter().zip(dyn_segments.iter()).enumerate() {
+                let ty = &field.ty;
                 match segment {
                     SegmentAst::Param(_) => unreachable!("not a dynamic segment"),
-                    SegmentAst::DynParam(_) => captures.push(quote! {{
-                        match ::sycamore_router::TryFromParam::try_from_param(
+                    SegmentAst::DynParam(_) => captures.push(quote_spanned! {ty.span()=> {
+                        match <#ty as ::sycamore_router::TryFromParam>::try_from_param(
                             __captures[#i].as_dyn_param().unwrap()
                         ) {
                             ::std::option::Option::Some(__value) => __value,
                             ::std::option::Option::None => break,
                         }
                     }}),
-                    SegmentAst::DynSegments(_) => captures.push(quote! {{
-                        match ::sycamore_router::TryFromSegments::try_from_segments(
+                    SegmentAst::DynSegments(_) => captures.push(quote_spanned! {ty.span()=> {
+                        match <#ty as ::sycamore_router::TryFromSegments>::try_from_segments(
                             __captures[#i].as_dyn_segments().unwrap()
                         ) {
                             ::std::option::Option::Some(__value) => __value,
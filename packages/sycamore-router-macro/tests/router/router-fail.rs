@@ -53,4 +53,14 @@ enum Routes8 {
     NotFound,
 }
 
+struct NotAParam;
+
+#[derive(Route)]
+enum Routes9 {
+    #[to("/<a>")]
+    Path { a: NotAParam }, // Does not implement `TryFromParam`
+    #[not_found]
+    NotFound,
+}
+
 fn main() {}
@@ -1,4 +1,4 @@
-use sycamore_router::Route;
+use sycamore_router::{Route, TryFromParam};
 
 #[derive(Route)]
 enum Routes1 {
@@ -36,4 +36,29 @@ enum Routes4 {
     NotFound,
 }
 
+enum Tab {
+    Overview,
+    Settings,
+}
+
+impl TryFromParam for Tab {
+    fn try_from_param(param: &str) -> Option<Self> {
+        match param {
+            "overview" => Some(Self::Overview),
+            "settings" => Some(Self::Settings),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Route)]
+enum Routes5 {
+    #[to("/project/<id>/<tab>")]
+    Project { id: u32, tab: Tab },
+    #[to("/tabs/<tabs..>")]
+    Tabs(Vec<Tab>),
+    #[not_found]
+    NotFound,
+}
+
 fn main() {}
@@ -0,0 +1,179 @@
+//! The position of the [`Router`](crate::Router) in the session history.
+//!
+//! Every history entry created by the router stores its index in `history.state`. When a back or
+//! forward navigation is cancelled, the browser has already moved to another entry, so the router
+//! goes back to the entry of the rendered route with `history.go` instead of adding a new entry,
+//! which would drop the forward history.
+
+use std::cell::Cell;
+
+use js_sys::{Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::NavigationKind;
+
+thread_local! {
+    /// The index of the current history entry of the browser.
+    static CURRENT: Cell<u32> = const { Cell::new(0) };
+    /// The index of the history entry whose route is rendered. Differs from `CURRENT` while a
+    /// back or forward navigation is pending.
+    static RENDERED: Cell<u32> = const { Cell::new(0) };
+}
+
+/// The `history.state` of a history entry created by the router.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct EntryState {
+    /// The index of the entry in the session history.
+    pub index: Option<u32>,
+    /// The key of the saved scroll position of the entry, see the `scroll` module.
+    pub key: Option<u64>,
+}
+
+impl EntryState {
+    /// Returns the state of the current history entry. The fields are `None` for the entries that
+    /// were not created by the router, e.g. by the browser for an anchor.
+    pub fn current(history: &web_sys::History) -> Self {
+        let state = history.state().unwrap_or(JsValue::UNDEFINED);
+        let field = |name: &str| {
+            if !state.is_object() {
+                return None;
+            }
+            Reflect::get(&state, &JsValue::from_str(name))
+                .ok()
+                .and_then(|value| value.as_f64())
+        };
+        Self {
+            index: field("index").map(|index| index as u32),
+            key: field("key").map(|key| key as u64),
+        }
+    }
+
+    /// Converts the state to the object that is stored in `history.state`.
+    pub fn to_js(self) -> JsValue {
+        let state = Object::new();
+        if let Some(index) = self.index {
+            Reflect::set(&state, &"index".into(), &JsValue::from_f64(index as f64)).unwrap_throw();
+        }
+        if let Some(key) = self.key {
+            Reflect::set(&state, &"key".into(), &JsValue::from_f64(key as f64)).unwrap_throw();
+        }
+        state.into()
+    }
+
+    /// Replaces the state of the current history entry, keeping its url.
+    pub fn replace(self, history: &web_sys::History) {
+        history.replace_state(&self.to_js(), "").unwrap_throw();
+    }
+}
+
+/// Reads the index of the current history entry when the router is created, giving the entry an
+/// index if it has none.
+pub(crate) fn init() {
+    let history = web_sys::window().unwrap_throw().history().unwrap_throw();
+    let state = EntryState::current(&history);
+    let index = match state.index {
+        Some(index) => index,
+        None => {
+            EntryState {
+                index: Some(0),
+                ..state
+            }
+            .replace(&history);
+            0
+        }
+    };
+    set_rendered(index);
+}
+
+/// Called on `popstate`, once the browser went back or forward to another entry. Returns `false`
+/// if the route of that entry is already rendered, e.g. because the browser returned to it after
+/// a cancelled navigation, in which case there is nothing to navigate to.
+pub(crate) fn on_popstate() -> bool {
+    let history = web_sys::window().unwrap_throw().history().unwrap_throw();
+    let state = EntryState::current(&history);
+    let index = match state.index {
+        Some(index) => index,
+        None => {
+            // The entry was added by the browser, e.g. for an anchor, after the current entry.
+            let index = CURRENT.with(Cell::get) + 1;
+            EntryState {
+                index: Some(index),
+                ..state
+            }
+            .replace(&history);
+            index
+        }
+    };
+    pop(index)
+}
+
+/// Records that the browser moved to the entry with `index`. See [`on_popstate`].
+fn pop(index: u32) -> bool {
+    CURRENT.with(|current| current.set(index));
+    RENDERED.with(Cell::get) != index
+}
+
+/// Records that a navigation was committed, and returns the index of the entry of the new route.
+/// [`NavigationKind::Push`] adds an entry after the current one.
+pub(crate) fn commit(kind: NavigationKind) -> u32 {
+    let index = match kind {
+        NavigationKind::Push => CURRENT.with(Cell::get) + 1,
+        NavigationKind::Replace | NavigationKind::Pop => CURRENT.with(Cell::get),
+    };
+    set_rendered(index);
+    index
+}
+
+/// Returns the delta to pass to `history.go` to return to the entry of the rendered route after a
+/// cancelled back or forward navigation.
+pub(crate) fn restore_delta() -> i32 {
+    RENDERED.with(Cell::get) as i32 - CURRENT.with(Cell::get) as i32
+}
+
+/// Returns the index of the current history entry.
+pub(crate) fn current_index() -> u32 {
+    CURRENT.with(Cell::get)
+}
+
+fn set_rendered(index: u32) {
+    CURRENT.with(|current| current.set(index));
+    RENDERED.with(|rendered| rendered.set(index));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelled_pop_returns_to_rendered_entry() {
+        set_rendered(0);
+        assert_eq!(commit(NavigationKind::Push), 1);
+        assert_eq!(commit(NavigationKind::Push), 2);
+
+        // Back to the entry 1, cancelled by a guard: `history.go(1)` returns to the entry 2.
+        assert!(pop(1));
+        assert_eq!(restore_delta(), 1);
+        // The `popstate` of `history.go(1)` does not start a navigation.
+        assert!(!pop(2));
+        assert_eq!(current_index(), 2);
+
+        // Back again, without any guard this time.
+        assert!(pop(1));
+        assert_eq!(commit(NavigationKind::Pop), 1);
+        // The forward history is kept.
+        assert!(pop(2));
+        assert_eq!(commit(NavigationKind::Pop), 2);
+    }
+
+    #[test]
+    fn cancelled_pop_over_several_entries() {
+        set_rendered(3);
+        // Back by 3 entries at once, e.g. from the history menu of the browser.
+        assert!(pop(0));
+        assert_eq!(restore_delta(), 3);
+        assert!(!pop(3));
+        // A push after a replace adds the entry after the current one.
+        assert_eq!(commit(NavigationKind::Replace), 3);
+        assert_eq!(commit(NavigationKind::Push), 4);
+    }
+}
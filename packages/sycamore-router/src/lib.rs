@@ -6,11 +6,21 @@
 // Alias self to sycamore_router for proc-macros.
 extern crate self as sycamore_router;
 
+mod entries;
+mod navigation;
+mod params;
 mod router;
+mod scroll;
+#[cfg(feature = "serde")]
+mod serde_query;
+mod sitemap;
 
 use std::str::FromStr;
 
+pub use navigation::*;
+pub use params::*;
 pub use router::*;
+pub use sitemap::*;
 pub use sycamore_router_macro::Route;
 
 /// Trait that is implemented for `enum`s that can match routes.
@@ -32,6 +42,13 @@ pub trait Route: Sized + Default {
             .collect::<Vec<_>>();
         self.match_route(&segments)
     }
+
+    /// Returns the name and the [`RoutePath`] of every variant with a `#[to(_)]` attribute, in the
+    /// order in which they are declared. This is used to enumerate the URLs of the app, e.g. by
+    /// [`Sitemap`].
+    fn route_paths() -> Vec<(&'static str, RoutePath)> {
+        Vec::new()
+    }
 }
 
 /// Represents an URL segment or segments.
@@ -78,12 +95,29 @@ impl<'a> Capture<'a> {
 #[derive(Clone, Debug)]
 pub struct RoutePath {
     segments: Vec<Segment>,
+    names: Vec<String>,
 }
 
 impl RoutePath {
     /// Create a new [`RoutePath`] from a list of [`Segment`]s.
     pub fn new(segments: Vec<Segment>) -> Self {
-        Self { segments }
+        Self {
+            segments,
+            names: Vec::new(),
+        }
+    }
+
+    /// Sets the names of the dynamic segments, in order. The names are used to look up the
+    /// captures by name, e.g. with [`use_params`].
+    pub fn with_names(mut self, names: Vec<String>) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Returns the names of the dynamic segments, if they were set with
+    /// [`with_names`](Self::with_names).
+    pub fn names(&self) -> &[String] {
+        &self.names
     }
 
     /// Attempt to match the path (url) with the current [`RoutePath`]. The path should already be
@@ -142,6 +176,36 @@ impl RoutePath {
 
         Some(captures)
     }
+
+    /// Returns the segments of the path.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Builds a concrete path, e.g. `/posts/1`, by filling the dynamic segments with `params`, in
+    /// order. The param of a [`Segment::DynSegments`] can contain several segments separated by
+    /// `/`. Returns `None` if the number of params does not match the number of dynamic segments.
+    pub fn to_path(&self, params: &[&str]) -> Option<String> {
+        let mut params = params.iter();
+        let mut path = String::new();
+        for segment in &self.segments {
+            let segment = match segment {
+                Segment::Param(param) => param.as_str(),
+                Segment::DynParam | Segment::DynSegments => params.next()?.trim_matches('/'),
+            };
+            if !segment.is_empty() {
+                path.push('/');
+                path.push_str(segment);
+            }
+        }
+        if params.next().is_some() {
+            return None;
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        Some(path)
+    }
 }
 
 /// Fallible conversion between a param capture into a value.
@@ -187,7 +251,8 @@ where
 
 impl<T: Route> TryFromSegments for T {
     fn try_from_segments(segments: &[&str]) -> Option<Self> {
-        // It's fine to use `default()` here for the Perseus use-case (TODO is there any situation where this wouldn't be fine?)
+        // It's fine to use `default()` here for the Perseus use-case (TODO is there any situation
+        // where this wouldn't be fine?)
         Some(Self::match_route(&Self::default(), segments))
     }
 }
@@ -407,6 +472,55 @@ mod tests {
             );
         }
 
+        #[test]
+        fn router_custom_param_type() {
+            #[derive(Debug, PartialEq, Eq)]
+            enum Tab {
+                Overview,
+                Settings,
+            }
+
+            impl TryFromParam for Tab {
+                fn try_from_param(param: &str) -> Option<Self> {
+                    match param {
+                        "overview" => Some(Self::Overview),
+                        "settings" => Some(Self::Settings),
+                        _ => None,
+                    }
+                }
+            }
+
+            #[derive(Debug, PartialEq, Eq, Route)]
+            enum Routes {
+                #[to("/project/<id>/<tab>")]
+                Project { id: u32, tab: Tab },
+                #[to("/project/<id>/<page>")]
+                Page { id: u32, page: String },
+                #[not_found]
+                NotFound,
+            }
+
+            assert_eq!(
+                Routes::match_route(&Routes::default(), &["project", "1", "settings"]),
+                Routes::Project {
+                    id: 1,
+                    tab: Tab::Settings
+                }
+            );
+            // Falls through to the next variant if the param cannot be parsed.
+            assert_eq!(
+                Routes::match_route(&Routes::default(), &["project", "1", "issues"]),
+                Routes::Page {
+                    id: 1,
+                    page: "issues".to_string()
+                }
+            );
+            assert_eq!(
+                Routes::match_route(&Routes::default(), &["project", "x", "issues"]),
+                Routes::NotFound
+            );
+        }
+
         #[test]
         fn router_multiple_dyn_params() {
             #[derive(Debug, PartialEq, Eq, Route)]
@@ -0,0 +1,323 @@
+//! Interception of navigations, e.g. to load data or to prompt about unsaved changes before the url
+//! changes.
+
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use sycamore::prelude::*;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::router::split_url;
+use crate::Route;
+
+/// How a navigation was started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationKind {
+    /// A link was clicked or [`navigate`](crate::navigate) was called. Adds a history entry.
+    Push,
+    /// [`navigate_replace`](crate::navigate_replace) was called. Replaces the current history
+    /// entry.
+    Replace,
+    /// The user went back or forward in the history. The url of the browser has already changed
+    /// and is restored if the navigation is cancelled.
+    Pop,
+}
+
+/// A navigation that is about to be committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Navigation {
+    /// The current path and query, e.g. `/posts?page=2`, without the base path of the app.
+    pub from: String,
+    /// The new path and query, without the base path of the app.
+    pub to: String,
+    /// How the navigation was started.
+    pub kind: NavigationKind,
+}
+
+/// The reason why a navigation was not committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationError {
+    /// The navigation was cancelled, e.g. by the user in an unsaved changes prompt.
+    Cancelled,
+    /// A guard failed, e.g. because the data of the new route could not be loaded.
+    Failed(String),
+    /// The navigation was replaced by a navigation to another url, e.g. to a login page. The url
+    /// is handled like the url passed to [`navigate`](crate::navigate), and the guards run again
+    /// for the new navigation.
+    Redirect(String),
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "navigation cancelled"),
+            Self::Failed(err) => write!(f, "navigation failed: {err}"),
+            Self::Redirect(url) => write!(f, "navigation redirected to {url}"),
+        }
+    }
+}
+
+impl Error for NavigationError {}
+
+/// The state of the latest navigation, returned by [`use_navigation`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum NavigationState {
+    /// No navigation is pending. The latest navigation, if any, was committed.
+    #[default]
+    Idle,
+    /// The guards of the navigation are running.
+    Pending(Navigation),
+    /// The navigation was not committed.
+    Failed(Navigation, NavigationError),
+}
+
+/// The future returned by navigation guards and by
+/// [`Integration::intercept`](crate::Integration::intercept). The navigation is only committed if
+/// it resolves to `Ok(())`.
+pub type NavigationFuture = Pin<Box<dyn Future<Output = Result<(), NavigationError>>>>;
+
+type Guard = Rc<dyn Fn(&Navigation) -> NavigationFuture>;
+type AfterNavigate = Rc<dyn Fn(&Navigation)>;
+
+thread_local! {
+    static GUARDS: RefCell<Vec<(u32, Guard)>> = const { RefCell::new(Vec::new()) };
+    static AFTER_NAVIGATE: RefCell<Vec<(u32, AfterNavigate)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_GUARD_ID: Cell<u32> = const { Cell::new(0) };
+    pub(crate) static NAVIGATION_STATE: RefCell<Option<RcSignal<NavigationState>>> =
+        const { RefCell::new(None) };
+}
+
+/// Returns a new id for a guard or an [`after_navigate`] hook.
+fn next_guard_id() -> u32 {
+    NEXT_GUARD_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+/// Adds a guard that runs before every navigation while `cx` is alive.
+pub(crate) fn add_guard(cx: Scope<'_>, guard: Guard) {
+    let id = next_guard_id();
+    GUARDS.with(|guards| guards.borrow_mut().push((id, guard)));
+    on_cleanup(cx, move || {
+        GUARDS.with(|guards| guards.borrow_mut().retain(|(guard_id, _)| *guard_id != id));
+    });
+}
+
+/// Returns the futures of all the guards for `navigation`, in the order in which the guards were
+/// added.
+pub(crate) fn run_guards(navigation: &Navigation) -> Vec<NavigationFuture> {
+    let guards = GUARDS.with(|guards| {
+        guards
+            .borrow()
+            .iter()
+            .map(|(_, guard)| Rc::clone(guard))
+            .collect::<Vec<_>>()
+    });
+    guards.iter().map(|guard| guard(navigation)).collect()
+}
+
+/// Runs the [`after_navigate`] hooks once `navigation` is committed.
+pub(crate) fn run_after_navigate(navigation: &Navigation) {
+    let hooks = AFTER_NAVIGATE.with(|hooks| {
+        hooks
+            .borrow()
+            .iter()
+            .map(|(_, hook)| Rc::clone(hook))
+            .collect::<Vec<_>>()
+    });
+    for hook in hooks {
+        hook(navigation);
+    }
+}
+
+/// Sets the state returned by [`use_navigation`].
+pub(crate) fn set_navigation_state(state: NavigationState) {
+    if let Some(signal) = NAVIGATION_STATE.with(|signal| signal.borrow().clone()) {
+        if *signal.get_untracked() != state {
+            signal.set(state);
+        }
+    }
+}
+
+/// Runs `guard` before every navigation of the [`Router`](crate::Router) while the component is
+/// alive. The url only changes once all the guards resolve to `Ok(())`, which can be used to load
+/// the data of the new route first. If a guard returns an error, the navigation is cancelled and
+/// the error is available from [`use_navigation`].
+///
+/// Guards that resolve immediately do not delay the navigation. If another navigation starts while
+/// the guards are pending, the pending navigation is abandoned.
+pub fn use_navigation_guard(
+    cx: Scope<'_>,
+    guard: impl Fn(&Navigation) -> NavigationFuture + 'static,
+) {
+    add_guard(cx, Rc::new(guard));
+}
+
+/// A [`Navigation`] with the routes that it goes from and to, passed to the hooks added with
+/// [`before_navigate`] and [`after_navigate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteNavigation<R> {
+    /// The current route.
+    pub from: R,
+    /// The new route.
+    pub to: R,
+    /// The urls of the routes and how the navigation was started.
+    pub navigation: Navigation,
+}
+
+/// Matches the urls of navigations with the route of the [`Router`](crate::Router). Provided as a
+/// context by the router.
+pub(crate) struct RouteMatcher<R>(pub(crate) Rc<R>);
+
+impl<R: Route> RouteMatcher<R> {
+    pub(crate) fn navigation(&self, navigation: &Navigation) -> RouteNavigation<R> {
+        RouteNavigation {
+            from: self.0.match_path(split_url(&navigation.from).0),
+            to: self.0.match_path(split_url(&navigation.to).0),
+            navigation: navigation.clone(),
+        }
+    }
+}
+
+/// Runs `f` before every navigation of the [`Router`](crate::Router) while the component is alive,
+/// with the routes that the navigation goes from and to. This is a
+/// [navigation guard](use_navigation_guard) that can check the routes directly, e.g. to redirect to
+/// a login page before a protected page is displayed by resolving to
+/// [`NavigationError::Redirect`].
+///
+/// Does nothing outside of a [`Router`](crate::Router) with the route type `R`, e.g. when
+/// rendering on the server.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore_router::{before_navigate, NavigationError, Route, RouteNavigation};
+///
+/// #[derive(Route, Clone, PartialEq, Eq)]
+/// enum Routes {
+///     #[to("/admin")]
+///     Admin,
+///     #[not_found]
+///     NotFound,
+/// }
+///
+/// # fn is_logged_in() -> bool { false }
+/// #[component]
+/// fn Guard<G: Html>(cx: Scope) -> View<G> {
+///     before_navigate(cx, |navigation: RouteNavigation<Routes>| async move {
+///         if navigation.to == Routes::Admin && !is_logged_in() {
+///             Err(NavigationError::Redirect("/login".to_string()))
+///         } else {
+///             Ok(())
+///         }
+///     });
+///     view! { cx, }
+/// }
+/// ```
+pub fn before_navigate<R, F, Fut>(cx: Scope<'_>, f: F)
+where
+    R: Route + 'static,
+    F: Fn(RouteNavigation<R>) -> Fut + 'static,
+    Fut: Future<Output = Result<(), NavigationError>> + 'static,
+{
+    let Some(matcher) = try_use_context::<RouteMatcher<R>>(cx) else {
+        return;
+    };
+    let matcher = RouteMatcher(Rc::clone(&matcher.0));
+    add_guard(
+        cx,
+        Rc::new(move |navigation| Box::pin(f(matcher.navigation(navigation)))),
+    );
+}
+
+/// Runs `f` after every navigation of the [`Router`](crate::Router) that is committed while the
+/// component is alive, with the routes that the navigation went from and to, e.g. to track page
+/// views. Navigations that are cancelled or redirected are not committed.
+///
+/// Does nothing outside of a [`Router`](crate::Router) with the route type `R`, e.g. when
+/// rendering on the server.
+pub fn after_navigate<R: Route + 'static>(cx: Scope<'_>, f: impl Fn(RouteNavigation<R>) + 'static) {
+    let Some(matcher) = try_use_context::<RouteMatcher<R>>(cx) else {
+        return;
+    };
+    let matcher = RouteMatcher(Rc::clone(&matcher.0));
+    let id = next_guard_id();
+    let hook: AfterNavigate = Rc::new(move |navigation| f(matcher.navigation(navigation)));
+    AFTER_NAVIGATE.with(|hooks| hooks.borrow_mut().push((id, hook)));
+    on_cleanup(cx, move || {
+        AFTER_NAVIGATE.with(|hooks| hooks.borrow_mut().retain(|(hook_id, _)| *hook_id != id));
+    });
+}
+
+/// Asks the user to confirm leaving the page with `message` while `when` returns `true`, e.g.
+/// when a form has unsaved changes. This applies to the navigations of the
+/// [`Router`](crate::Router), which are cancelled if the user does not confirm, and to leaving the
+/// app, e.g. by closing the tab, in which case the browser shows its own message.
+///
+/// Does nothing outside of a [`Router`](crate::Router), e.g. when rendering on the server.
+pub fn use_navigation_blocker(
+    cx: Scope<'_>,
+    when: impl Fn() -> bool + 'static,
+    message: impl Into<String>,
+) {
+    if NAVIGATION_STATE.with(|state| state.borrow().is_none()) {
+        return;
+    }
+    let when = Rc::new(when);
+    let message = message.into();
+    add_guard(
+        cx,
+        Rc::new({
+            let when = Rc::clone(&when);
+            move |_| {
+                let confirmed = !when()
+                    || web_sys::window()
+                        .unwrap_throw()
+                        .confirm_with_message(&message)
+                        .unwrap_or(false);
+                Box::pin(std::future::ready(if confirmed {
+                    Ok(())
+                } else {
+                    Err(NavigationError::Cancelled)
+                }))
+            }
+        }),
+    );
+
+    let on_before_unload = Closure::wrap(Box::new(move |ev: web_sys::BeforeUnloadEvent| {
+        if when() {
+            ev.prevent_default();
+            // Required by some browsers to show the prompt.
+            ev.set_return_value("unsaved changes");
+        }
+    }) as Box<dyn Fn(web_sys::BeforeUnloadEvent)>);
+    let window = web_sys::window().unwrap_throw();
+    window
+        .add_event_listener_with_callback("beforeunload", on_before_unload.as_ref().unchecked_ref())
+        .unwrap_throw();
+    on_cleanup(cx, move || {
+        window
+            .remove_event_listener_with_callback(
+                "beforeunload",
+                on_before_unload.as_ref().unchecked_ref(),
+            )
+            .unwrap_throw();
+    });
+}
+
+/// Returns the state of the latest navigation of the [`Router`](crate::Router): whether its guards
+/// are pending, e.g. to show a loading indicator, or whether it failed.
+///
+/// # Panics
+/// Panics if there is no enclosing [`Router`](crate::Router).
+pub fn use_navigation(cx: Scope<'_>) -> &ReadSignal<NavigationState> {
+    try_use_context::<ReadSignal<NavigationState>>(cx)
+        .expect("use_navigation must be used inside of a Router")
+}
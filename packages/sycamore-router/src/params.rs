@@ -0,0 +1,202 @@
+//! The path and query params of the current route, read with [`use_params`] and [`use_query`].
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::{Capture, Route, TryFromParam};
+
+/// A list of params, as name and value pairs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    /// Creates a new [`Params`] from name and value pairs.
+    pub fn new(params: Vec<(String, String)>) -> Self {
+        Self(params)
+    }
+
+    /// Returns the captures of the first `#[to(_)]` route of `R` that matches `path`. The value of
+    /// a dynamic segments capture is the captured segments, joined with `/`.
+    pub fn from_path<R: Route>(path: &str) -> Self {
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        for (_, route_path) in R::route_paths() {
+            if let Some(captures) = route_path.match_path(&segments) {
+                let values = captures.iter().map(|capture| match capture {
+                    Capture::DynParam(param) => param.to_string(),
+                    Capture::DynSegments(segments) => segments.join("/"),
+                });
+                return Self(route_path.names().iter().cloned().zip(values).collect());
+            }
+        }
+        Self::default()
+    }
+
+    /// Returns the value of the first param named `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the values of all the params named `name`, e.g. for `?tag=a&tag=b`.
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Converts the value of the first param named `name`. Returns `None` if there is no such
+    /// param or if it cannot be converted.
+    pub fn parse<T: TryFromParam>(&self, name: &str) -> Option<T> {
+        T::try_from_param(self.get(name)?)
+    }
+
+    /// Returns an iterator over the name and value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// The params of the query string of the URL, e.g. `?page=2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query(Params);
+
+impl Query {
+    /// Creates a new [`Query`] from name and value pairs.
+    pub fn new(params: Vec<(String, String)>) -> Self {
+        Self(Params::new(params))
+    }
+
+    /// Parses a query string. The leading `?` is optional. Names and values are percent-decoded and
+    /// `+` is decoded as a space.
+    pub fn parse(query: &str) -> Self {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        Self(Params(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (decode(name), decode(value))
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl fmt::Display for Query {
+    /// Formats the query string, without the leading `?`. Names and values are percent-encoded.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", encode(name), encode(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Query {
+    type Target = Params;
+
+    fn deref(&self) -> &Params {
+        &self.0
+    }
+}
+
+/// Encodes a component of a query string. Spaces are encoded as `+`.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Decodes a component of a query string.
+fn decode(s: &str) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = iter.clone().take(2).collect::<Vec<_>>();
+                match std::str::from_utf8(&hex)
+                    .ok()
+                    .filter(|hex| hex.len() == 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(decoded) => {
+                        bytes.push(decoded);
+                        iter.nth(1);
+                    }
+                    None => bytes.push(b'%'),
+                }
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Route)]
+    enum Routes {
+        #[to("/")]
+        Home,
+        #[to("/posts/<id>/<rest..>")]
+        Post { id: u32, rest: Vec<String> },
+        #[not_found]
+        NotFound,
+    }
+
+    #[test]
+    fn path_params() {
+        let params = Params::from_path::<Routes>("/posts/42/a/b");
+        assert_eq!(params.get("id"), Some("42"));
+        assert_eq!(params.parse::<u32>("id"), Some(42));
+        assert_eq!(params.get("rest"), Some("a/b"));
+        assert_eq!(params.get("other"), None);
+        assert_eq!(Params::from_path::<Routes>("/"), Params::default());
+        assert_eq!(Params::from_path::<Routes>("/404"), Params::default());
+    }
+
+    #[test]
+    fn query_params() {
+        let query = Query::parse("?q=hello+world&tag=a&tag=b%26c&empty&bad=%zz");
+        assert_eq!(query.get("q"), Some("hello world"));
+        assert_eq!(query.get_all("tag"), ["a", "b&c"]);
+        assert_eq!(query.get("empty"), Some(""));
+        assert_eq!(query.get("bad"), Some("%zz"));
+        assert_eq!(Query::parse("").iter().count(), 0);
+        assert_eq!(Query::parse("%C3%A9=1").get("é"), Some("1"));
+    }
+
+    #[test]
+    fn format_query() {
+        let query = Query::parse("?q=hello+world&tag=b%26c&%C3%A9=1&empty");
+        assert_eq!(query.to_string(), "q=hello+world&tag=b%26c&%C3%A9=1&empty=");
+        assert_eq!(Query::parse(&query.to_string()), query);
+        assert_eq!(Query::default().to_string(), "");
+    }
+}
@@ -1,13 +1,23 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
 use std::marker::PhantomData;
 use std::rc::Rc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
 use sycamore::prelude::*;
+use sycamore::web::view_transition::start_view_transition;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{Element, HtmlAnchorElement, HtmlBaseElement, KeyboardEvent};
 
-use crate::Route;
+use crate::entries::{self, EntryState};
+use crate::navigation::{
+    add_guard, run_after_navigate, run_guards, set_navigation_state, RouteMatcher, NAVIGATION_STATE,
+};
+use crate::{
+    scroll, Navigation, NavigationError, NavigationFuture, NavigationKind, NavigationState, Params,
+    Query, Route,
+};
 
 /// A router integration provides the methods for adapting a router to a certain environment (e.g.
 /// history API).
@@ -15,16 +25,258 @@ pub trait Integration {
     /// Get the current pathname.
     fn current_pathname(&self) -> String;
 
+    /// Get the current query string, without the leading `?`. Defaults to an empty string.
+    fn current_query(&self) -> String {
+        String::new()
+    }
+
+    /// Get the current fragment, without the leading `#`. Defaults to an empty string.
+    fn current_fragment(&self) -> String {
+        String::new()
+    }
+
     /// Add a callback for listening to the `popstate` event.
     fn on_popstate(&self, f: Box<dyn FnMut()>);
 
     /// Get the click handler that is run when links are clicked.
 
     fn click_handler(&self) -> Box<dyn Fn(web_sys::Event)>;
+
+    /// Whether route changes should be run inside `document.startViewTransition` when the browser
+    /// supports it. Defaults to `false`.
+    ///
+    /// See the [`view_transition`](sycamore::web::view_transition) module for more details.
+    fn view_transitions(&self) -> bool {
+        false
+    }
+
+    /// Whether the router manages the scroll position. Defaults to `true`.
+    ///
+    /// If enabled, the scroll position of every history entry is restored when going back or
+    /// forward to it, and other navigations scroll to the element whose id is the `#fragment` of
+    /// the url, or to the top of the page. If disabled, the router never scrolls.
+    fn scroll_restoration(&self) -> bool {
+        true
+    }
+
+    /// Intercepts a navigation before it is committed. The url only changes once the returned
+    /// future resolves to `Ok(())`, which can be used to load the data of the new route first. An
+    /// error cancels the navigation and is available from
+    /// [`use_navigation`](crate::use_navigation). Defaults to accepting every navigation.
+    ///
+    /// This runs before the guards added with
+    /// [`use_navigation_guard`](crate::use_navigation_guard).
+    fn intercept(&self, _navigation: &Navigation) -> NavigationFuture {
+        Box::pin(std::future::ready(Ok(())))
+    }
 }
 
 thread_local! {
     static PATHNAME: RefCell<Option<RcSignal<String>>> = RefCell::new(None);
+    static QUERY: RefCell<Option<RcSignal<String>>> = const { RefCell::new(None) };
+    static FRAGMENT: RefCell<Option<RcSignal<String>>> = const { RefCell::new(None) };
+    static VIEW_TRANSITIONS: Cell<bool> = Cell::new(false);
+    /// Incremented for every navigation, so that a pending navigation can tell whether it was
+    /// superseded.
+    static NAVIGATION_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+/// The signals of the current url of the [`Router`].
+#[derive(Clone)]
+struct Location {
+    pathname: RcSignal<String>,
+    query: RcSignal<String>,
+    fragment: RcSignal<String>,
+}
+
+impl Location {
+    /// Returns the signals of the [`Router`], if any.
+    fn get() -> Option<Self> {
+        Some(Self {
+            pathname: PATHNAME.with(|pathname| pathname.borrow().clone())?,
+            query: QUERY.with(|query| query.borrow().clone())?,
+            fragment: FRAGMENT.with(|fragment| fragment.borrow().clone())?,
+        })
+    }
+
+    /// Sets the signals, wrapping the update inside a view transition if enabled by the
+    /// [`Integration`]. `then` is called once the new route is rendered.
+    fn set(
+        self,
+        new_pathname: String,
+        new_query: String,
+        new_fragment: String,
+        then: impl FnOnce() + 'static,
+    ) {
+        let update = move || {
+            // The query and the fragment are set first so that the new route sees them.
+            self.set_query_and_fragment(new_query, new_fragment);
+            self.pathname.set(new_pathname);
+            then();
+        };
+        if VIEW_TRANSITIONS.with(Cell::get) {
+            start_view_transition(update);
+        } else {
+            update();
+        }
+    }
+
+    /// Sets the query and fragment signals if they changed.
+    fn set_query_and_fragment(&self, new_query: String, new_fragment: String) {
+        if *self.query.get_untracked() != new_query {
+            self.query.set(new_query);
+        }
+        if *self.fragment.get_untracked() != new_fragment {
+            self.fragment.set(new_fragment);
+        }
+    }
+
+    /// Returns the current url, including the base path of the app.
+    fn url(&self) -> String {
+        let mut url = base_pathname()
+            + &join_url(&self.pathname.get_untracked(), &self.query.get_untracked());
+        let fragment = self.fragment.get_untracked();
+        if !fragment.is_empty() {
+            url.push('#');
+            url.push_str(&fragment);
+        }
+        url
+    }
+}
+
+/// Splits an url into its path and its query string, without the leading `?`. The fragment is
+/// dropped.
+pub(crate) fn split_url(url: &str) -> (&str, &str) {
+    let url = url.split_once('#').map_or(url, |(url, _)| url);
+    url.split_once('?').unwrap_or((url, ""))
+}
+
+/// Joins a path and a query string.
+fn join_url(path: &str, query: &str) -> String {
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{path}?{query}")
+    }
+}
+
+/// Starts a navigation to `url`, which is committed once all the guards succeed. For
+/// [`NavigationKind::Pop`], `url` is the url that the browser already navigated to.
+fn start_navigation(kind: NavigationKind, url: String) {
+    let Some(location) = Location::get() else {
+        return;
+    };
+    let base_pathname = base_pathname();
+    let (path, search) = split_url(&url);
+    let fragment = url.split_once('#').map_or("", |(_, fragment)| fragment);
+    let fragment = fragment.to_string();
+    let path = path
+        .strip_prefix(&base_pathname)
+        .unwrap_or(path)
+        .to_string();
+    let search = search.to_string();
+    let from = join_url(
+        &location.pathname.get_untracked(),
+        &location.query.get_untracked(),
+    );
+    let navigation = Navigation {
+        from,
+        to: join_url(&path, &search),
+        kind,
+    };
+    let id = NAVIGATION_ID.with(|id| {
+        id.set(id.get() + 1);
+        id.get()
+    });
+
+    let guards = run_guards(&navigation);
+    let mut check = Box::pin(async move {
+        for guard in guards {
+            guard.await?;
+        }
+        Ok(())
+    });
+    let finish = {
+        let navigation = navigation.clone();
+        move |result: Result<(), NavigationError>| {
+            if NAVIGATION_ID.with(Cell::get) != id {
+                // Superseded by another navigation.
+                return;
+            }
+            let window = web_sys::window().unwrap_throw();
+            let history = window.history().unwrap_throw();
+            match result {
+                Ok(()) => {
+                    set_navigation_state(NavigationState::Idle);
+                    let scroll = scroll::enabled();
+                    if scroll {
+                        // The page still shows the route of the entry that is left.
+                        scroll::save_position();
+                    }
+                    let index = entries::commit(kind);
+                    let state = match kind {
+                        NavigationKind::Pop => JsValue::UNDEFINED,
+                        _ => EntryState {
+                            index: Some(index),
+                            key: Some(scroll::new_entry_key()),
+                        }
+                        .to_js(),
+                    };
+                    location.set(path, search, fragment.clone(), move || {
+                        if scroll {
+                            scroll::scroll_after_navigation(kind, &fragment);
+                        }
+                    });
+                    // Update History API.
+                    match kind {
+                        NavigationKind::Push => history
+                            .push_state_with_url(&state, "", Some(&url))
+                            .unwrap_throw(),
+                        NavigationKind::Replace => history
+                            .replace_state_with_url(&state, "", Some(&url))
+                            .unwrap_throw(),
+                        NavigationKind::Pop => {}
+                    }
+                    run_after_navigate(&navigation);
+                }
+                Err(NavigationError::Redirect(url)) => {
+                    // The url of the browser has already changed for a pop, so the redirect
+                    // replaces its history entry.
+                    let kind = match kind {
+                        NavigationKind::Pop => NavigationKind::Replace,
+                        kind => kind,
+                    };
+                    start_navigation(kind, url);
+                }
+                Err(err) => {
+                    let delta = entries::restore_delta();
+                    if kind == NavigationKind::Pop && delta != 0 {
+                        // Go back to the entry that the browser navigated away from, keeping the
+                        // forward history.
+                        history.go_with_delta(delta).unwrap_throw();
+                    }
+                    set_navigation_state(NavigationState::Failed(navigation, err));
+                }
+            }
+        }
+    };
+    // Commit immediately if no guard is pending.
+    match check.as_mut().poll(&mut Context::from_waker(&noop_waker())) {
+        Poll::Ready(result) => finish(result),
+        Poll::Pending => {
+            set_navigation_state(NavigationState::Pending(navigation));
+            wasm_bindgen_futures::spawn_local(async move { finish(check.await) });
+        }
+    }
+}
+
+/// Returns a [`Waker`] that does nothing, to poll the navigation guards once without scheduling a
+/// wake-up.
+fn noop_waker() -> Waker {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(|_| RAW, |_| {}, |_| {}, |_| {});
+    const RAW: RawWaker = RawWaker::new(std::ptr::null(), &VTABLE);
+    // SAFETY: the functions of the vtable do not use the data pointer.
+    unsafe { Waker::from_raw(RAW) }
 }
 
 /// A router integration that uses the
@@ -32,9 +284,10 @@ thread_local! {
 /// UI in sync with the URL.
 #[derive(Default, Debug)]
 pub struct HistoryIntegration {
-    /// This field is to prevent downstream users from creating a new `HistoryIntegration` without
-    /// the `new` method.
-    _internal: (),
+    /// Whether route changes are run inside `document.startViewTransition`.
+    view_transitions: bool,
+    /// Whether the scroll position is left to the browser and the app.
+    without_scroll_restoration: bool,
 }
 
 impl HistoryIntegration {
@@ -42,6 +295,21 @@ impl HistoryIntegration {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Run route changes inside `document.startViewTransition` when the browser supports it.
+    /// Falls back to updating the route immediately otherwise.
+    pub fn with_view_transitions(mut self) -> Self {
+        self.view_transitions = true;
+        self
+    }
+
+    /// Never scroll on navigations, e.g. because the app manages the scroll position itself. By
+    /// default, the scroll position of history entries is restored when going back or forward and
+    /// other navigations scroll to the `#fragment` of the url or to the top of the page.
+    pub fn without_scroll_restoration(mut self) -> Self {
+        self.without_scroll_restoration = true;
+        self
+    }
 }
 
 impl Integration for HistoryIntegration {
@@ -53,6 +321,24 @@ impl Integration for HistoryIntegration {
             .unwrap_throw()
     }
 
+    fn current_query(&self) -> String {
+        let search = web_sys::window()
+            .unwrap_throw()
+            .location()
+            .search()
+            .unwrap_throw();
+        search.strip_prefix('?').unwrap_or(&search).to_string()
+    }
+
+    fn current_fragment(&self) -> String {
+        let hash = web_sys::window()
+            .unwrap_throw()
+            .location()
+            .hash()
+            .unwrap_throw();
+        hash.strip_prefix('#').unwrap_or(&hash).to_string()
+    }
+
     fn on_popstate(&self, f: Box<dyn FnMut()>) {
         let closure = Closure::wrap(f);
         web_sys::window()
@@ -83,28 +369,20 @@ impl Integration for HistoryIntegration {
 
                 let origin = a.origin();
                 let a_pathname = a.pathname();
+                let a_search = a.search();
                 let hash = a.hash();
 
                 let meta_keys_pressed = meta_keys_pressed(ev.unchecked_ref::<KeyboardEvent>());
                 if !meta_keys_pressed && location.origin() == Ok(origin) {
-                    if location.pathname().as_ref() != Ok(&a_pathname) {
-                        // Same origin, different path.
+                    if location.pathname().as_ref() != Ok(&a_pathname)
+                        || location.search().as_ref() != Ok(&a_search)
+                    {
+                        // Same origin, different path or query.
                         ev.prevent_default();
-                        PATHNAME.with(|pathname| {
-                            let pathname = pathname.borrow().clone().unwrap_throw();
-                            let path = a_pathname
-                                .strip_prefix(&base_pathname())
-                                .unwrap_or(&a_pathname);
-                            pathname.set(path.to_string());
-
-                            // Update History API.
-                            let window = web_sys::window().unwrap_throw();
-                            let history = window.history().unwrap_throw();
-                            history
-                                .push_state_with_url(&JsValue::UNDEFINED, "", Some(&a_pathname))
-                                .unwrap_throw();
-                            window.scroll_to_with_x_and_y(0.0, 0.0);
-                        });
+                        start_navigation(
+                            NavigationKind::Push,
+                            format!("{a_pathname}{a_search}{hash}"),
+                        );
                     } else if Ok(&hash) != location.hash().as_ref() {
                         // Same origin, same path, different anchor.
                         // Use default browser behavior.
@@ -116,6 +394,14 @@ impl Integration for HistoryIntegration {
             }
         })
     }
+
+    fn view_transitions(&self) -> bool {
+        self.view_transitions
+    }
+
+    fn scroll_restoration(&self) -> bool {
+        !self.without_scroll_restoration
+    }
 }
 
 /// Gets the base pathname from `document.baseURI`.
@@ -211,7 +497,7 @@ where
 #[component]
 pub fn Router<'a, G: Html, R, F, I>(cx: Scope<'a>, props: RouterProps<'a, R, F, I, G>) -> View<G>
 where
-    R: Route + 'a,
+    R: Route + 'static,
     F: FnOnce(Scope<'a>, &'a ReadSignal<R>) -> View<G> + 'a,
     I: Integration + 'static,
 {
@@ -225,8 +511,8 @@ where
     }
 }
 
-/// A lower-level router component that takes an instance of your [`Route`] type. This is designed for `struct` [`Route`]s, which can be used to store
-/// additional information along with routes.
+/// A lower-level router component that takes an instance of your [`Route`] type. This is designed
+/// for `struct` [`Route`]s, which can be used to store additional information along with routes.
 ///
 /// This is a very specific use-case, and you probably actually want [`Router`]!
 #[component]
@@ -235,7 +521,7 @@ pub fn RouterBase<'a, G: Html, R, F, I>(
     props: RouterBaseProps<'a, R, F, I, G>,
 ) -> View<G>
 where
-    R: Route + 'a,
+    R: Route + 'static,
     F: FnOnce(Scope<'a>, &'a ReadSignal<R>) -> View<G> + 'a,
     I: Integration + 'static,
 {
@@ -246,6 +532,7 @@ where
         _phantom,
     } = props;
     let integration = Rc::new(integration);
+    let route = Rc::new(route);
     let base_pathname = base_pathname();
 
     PATHNAME.with(|pathname| {
@@ -258,24 +545,72 @@ where
         let path = path.strip_prefix(&base_pathname).unwrap_or(&path);
         *pathname.borrow_mut() = Some(create_rc_signal(path.to_string()));
     });
-    let pathname = PATHNAME.with(|p| p.borrow().clone().unwrap_throw());
+    QUERY.with(|query| *query.borrow_mut() = Some(create_rc_signal(integration.current_query())));
+    FRAGMENT.with(|fragment| {
+        *fragment.borrow_mut() = Some(create_rc_signal(integration.current_fragment()));
+    });
+    let Location {
+        pathname,
+        query,
+        fragment,
+    } = Location::get().unwrap_throw();
+    VIEW_TRANSITIONS.with(|v| v.set(integration.view_transitions()));
+    entries::init();
+    scroll::init(integration.scroll_restoration());
+    let navigation_state = create_rc_signal(NavigationState::Idle);
+    NAVIGATION_STATE.with(|state| *state.borrow_mut() = Some(navigation_state.clone()));
+    provide_context_ref(
+        cx,
+        create_memo(cx, move || navigation_state.get().as_ref().clone()),
+    );
+    add_guard(
+        cx,
+        Rc::new({
+            let integration = integration.clone();
+            move |navigation| integration.intercept(navigation)
+        }),
+    );
 
-    // Set PATHNAME to None when the Router is destroyed.
+    // Set PATHNAME, QUERY and FRAGMENT to None when the Router is destroyed.
     on_cleanup(cx, || {
-        PATHNAME.with(|pathname| *pathname.borrow_mut() = None)
+        PATHNAME.with(|pathname| *pathname.borrow_mut() = None);
+        QUERY.with(|query| *query.borrow_mut() = None);
+        FRAGMENT.with(|fragment| *fragment.borrow_mut() = None);
+        NAVIGATION_STATE.with(|state| *state.borrow_mut() = None);
+        // Abandon the pending navigation, if any.
+        NAVIGATION_ID.with(|id| id.set(id.get() + 1));
+        VIEW_TRANSITIONS.with(|v| v.set(false));
+        scroll::cleanup();
     });
 
     // Listen to popstate event.
     integration.on_popstate(Box::new({
         let integration = integration.clone();
-        let pathname = pathname.clone();
         move || {
-            let path = integration.current_pathname();
-            let path = path.strip_prefix(&base_pathname).unwrap_or(&path);
-            pathname.set(path.to_string());
+            if !entries::on_popstate() {
+                // Back to the entry of the rendered route after a cancelled navigation.
+                return;
+            }
+            let url = join_url(
+                &integration.current_pathname(),
+                &integration.current_query(),
+            );
+            let fragment = integration.current_fragment();
+            start_navigation(NavigationKind::Pop, format!("{url}#{fragment}"));
         }
     }));
-    let route_signal = create_memo(cx, move || route.match_path(&pathname.get()));
+    provide_context(cx, RouteMatcher(Rc::clone(&route)));
+    let route_signal = create_memo(cx, {
+        let pathname = pathname.clone();
+        move || route.match_path(&pathname.get())
+    });
+    provide_route_contexts(
+        cx,
+        route_signal,
+        create_memo(cx, move || Params::from_path::<R>(&pathname.get())),
+        create_memo(cx, move || Query::parse(&query.get())),
+    );
+    provide_context(cx, FragmentContext(fragment));
     // Delegate click events from child <a> tags.
     let view = view(cx, route_signal);
     if let Some(node) = view.as_node() {
@@ -297,6 +632,10 @@ where
 {
     view: F,
     route: R,
+    /// The url that `route` was matched from, e.g. `/posts/1?page=2`. Used for [`use_params`] and
+    /// [`use_query`], which are empty if not set.
+    #[builder(default, setter(into))]
+    url: String,
     #[builder(default, setter(skip))]
     _phantom: PhantomData<&'a (R, G)>,
 }
@@ -312,6 +651,7 @@ where
         Self {
             view,
             route,
+            url: String::new(),
             _phantom: PhantomData,
         }
     }
@@ -333,10 +673,250 @@ where
     let StaticRouterProps {
         view,
         route,
+        url,
         _phantom,
     } = props;
 
-    view(cx, create_signal(cx, route))
+    let route = create_signal(cx, route);
+    let (path, query) = split_url(&url);
+    provide_route_contexts(
+        cx,
+        route,
+        create_signal(cx, Params::from_path::<R>(path)),
+        create_signal(cx, Query::parse(query)),
+    );
+    let fragment = url.split_once('#').map_or("", |(_, fragment)| fragment);
+    provide_context(cx, FragmentContext(create_rc_signal(fragment.to_string())));
+    view(cx, route)
+}
+
+/// The fragment of the url of the enclosing router, read by [`use_fragment`].
+struct FragmentContext(RcSignal<String>);
+
+/// Provides the contexts read by [`use_route`], [`use_params`] and [`use_query`].
+fn provide_route_contexts<'a, R: Route + 'static>(
+    cx: Scope<'a>,
+    route: &'a ReadSignal<R>,
+    params: &'a ReadSignal<Params>,
+    query: &'a ReadSignal<Query>,
+) {
+    provide_context_ref(cx, route);
+    provide_context_ref(cx, params);
+    provide_context_ref(cx, query);
+}
+
+/// Returns the route matched by the enclosing [`Router`] or [`StaticRouter`]. This is the same
+/// signal that is passed to the `view` of the router, which saves passing it down as props to
+/// deeply nested components.
+///
+/// # Panics
+/// Panics if there is no enclosing router, or if its route type is not `R`.
+pub fn use_route<R: Route + 'static>(cx: Scope<'_>) -> &ReadSignal<R> {
+    try_use_context::<ReadSignal<R>>(cx)
+        .expect("use_route must be used inside of a Router with the same route type")
+}
+
+/// Returns the path params of the route matched by the enclosing [`Router`] or [`StaticRouter`],
+/// by the names of the captures in the `#[to(_)]` attribute. For example, with
+/// `#[to("/posts/<id>")]` and the path `/posts/1`, `use_params(cx).get().get("id")` is `Some("1")`.
+///
+/// The params are the captures of the first route whose path matches, including routes whose
+/// captures fail to convert to the types of the fields.
+///
+/// # Panics
+/// Panics if there is no enclosing router.
+pub fn use_params(cx: Scope<'_>) -> &ReadSignal<Params> {
+    try_use_context::<ReadSignal<Params>>(cx).expect("use_params must be used inside of a Router")
+}
+
+/// Returns the params of the query string of the current url, e.g. `?page=2`.
+///
+/// # Panics
+/// Panics if there is no enclosing router.
+pub fn use_query(cx: Scope<'_>) -> &ReadSignal<Query> {
+    try_use_context::<ReadSignal<Query>>(cx).expect("use_query must be used inside of a Router")
+}
+
+/// Returns the query params of the current url deserialized into `T`, e.g. a struct with a field
+/// for each param, or `None` if they fail to deserialize. See [`Query::deserialize`]. The params
+/// can be updated with [`replace_query_params`].
+///
+/// Requires the `serde` feature.
+///
+/// # Panics
+/// Panics if there is no enclosing router.
+#[cfg(feature = "serde")]
+pub fn use_query_params<T: serde::de::DeserializeOwned + 'static>(
+    cx: Scope<'_>,
+) -> &ReadSignal<Option<T>> {
+    let query = try_use_context::<ReadSignal<Query>>(cx)
+        .expect("use_query_params must be used inside of a Router");
+    create_memo(cx, || query.get().deserialize().ok())
+}
+
+/// Replaces the query string of the current url with `params`, serialized with
+/// [`Query::serialize`], e.g. to keep the url in sync with the filters of a search page. This
+/// updates [`use_query`] and [`use_query_params`] without running the navigation guards, and
+/// replaces the current history entry instead of adding one.
+///
+/// Requires the `serde` feature.
+///
+/// # Panics
+/// This function will `panic!()` if a [`Router`] has not yet been created.
+#[cfg(feature = "serde")]
+pub fn replace_query_params<T: serde::Serialize>(params: &T) {
+    let location = Location::get().expect("replace_query_params can only be used with a Router");
+    let fragment = location.fragment.get_untracked().as_ref().clone();
+    replace_url(location, Query::serialize(params).to_string(), fragment);
+}
+
+/// Returns the fragment of the current url, without the leading `#`. The fragment can be updated
+/// with [`replace_fragment`].
+///
+/// # Panics
+/// Panics if there is no enclosing router.
+pub fn use_fragment(cx: Scope<'_>) -> &ReadSignal<String> {
+    let fragment = try_use_context::<FragmentContext>(cx)
+        .expect("use_fragment must be used inside of a Router")
+        .0
+        .clone();
+    create_memo(cx, move || fragment.get().as_ref().clone())
+}
+
+/// Replaces the fragment of the current url with `fragment`, without the leading `#`. This updates
+/// [`use_fragment`] without running the navigation guards, and replaces the current history entry
+/// instead of adding one.
+///
+/// # Panics
+/// This function will `panic!()` if a [`Router`] has not yet been created.
+pub fn replace_fragment(fragment: &str) {
+    let location = Location::get().expect("replace_fragment can only be used with a Router");
+    let query = location.query.get_untracked().as_ref().clone();
+    replace_url(location, query, fragment.to_string());
+}
+
+/// Replaces the query string and the fragment of the current url with `replaceState`.
+fn replace_url(location: Location, query: String, fragment: String) {
+    location.set_query_and_fragment(query, fragment);
+    let state = EntryState {
+        index: Some(entries::current_index()),
+        key: scroll::current_key(),
+    };
+    web_sys::window()
+        .unwrap_throw()
+        .history()
+        .unwrap_throw()
+        .replace_state_with_url(&state.to_js(), "", Some(&location.url()))
+        .unwrap_throw();
+}
+
+/// Props for [`Outlet`].
+#[derive(Prop, Debug)]
+pub struct OutletProps<'a, R, C, S, F, G>
+where
+    R: 'a,
+    C: Clone + 'a,
+    S: Fn(&R) -> Option<C> + 'a,
+    F: for<'b> Fn(BoundedScope<'b, 'a>, &'b ReadSignal<C>) -> View<G> + 'a,
+    G: GenericNode,
+{
+    /// The route of the parent router.
+    route: &'a ReadSignal<R>,
+    /// Returns the child route if the parent route is one of the nested routes rendered by this
+    /// outlet, or `None` otherwise.
+    select: S,
+    /// Renders the child route, usually inside of a layout that is shared by all the child routes.
+    view: F,
+    #[builder(default, setter(skip))]
+    _phantom: PhantomData<&'a G>,
+}
+
+/// Renders a nested [`Route`], i.e. a variant of the parent [`Route`] that delegates the remaining
+/// path segments to a child [`Route`].
+///
+/// `view` is only called when the parent route switches to one of the nested routes, and is
+/// passed a signal with the child route. Navigating between the child routes updates that signal
+/// instead of calling `view` again, so a layout rendered by `view` is kept along with its state.
+/// Nothing is rendered while the parent route is not one of the nested routes.
+///
+/// # Example
+/// ```
+/// # use sycamore::prelude::*;
+/// # use sycamore_router::{Outlet, Route};
+/// #[derive(Route, Clone)]
+/// enum SettingsRoutes {
+///     #[to("/profile")]
+///     Profile,
+///     #[to("/account")]
+///     Account,
+///     #[not_found]
+///     NotFound,
+/// }
+///
+/// #[derive(Route)]
+/// enum AppRoutes {
+///     #[to("/")]
+///     Home,
+///     #[to("/settings/<_..>")]
+///     Settings(SettingsRoutes),
+///     #[not_found]
+///     NotFound,
+/// }
+///
+/// fn app<'a, G: Html>(cx: Scope<'a>, route: &'a ReadSignal<AppRoutes>) -> View<G> {
+///     view! { cx,
+///         Outlet {
+///             route: route,
+///             select: |route: &AppRoutes| match route {
+///                 AppRoutes::Settings(settings) => Some(settings.clone()),
+///                 _ => None,
+///             },
+///             view: |cx, settings: &ReadSignal<SettingsRoutes>| view! { cx,
+///                 nav {
+///                     a(href="/settings/profile") { "Profile" }
+///                     a(href="/settings/account") { "Account" }
+///                 }
+///                 (match settings.get().as_ref() {
+///                     SettingsRoutes::Profile => view! { cx, "Profile" },
+///                     SettingsRoutes::Account => view! { cx, "Account" },
+///                     SettingsRoutes::NotFound => view! { cx, "Not Found" },
+///                 })
+///             },
+///         }
+///     }
+/// }
+/// ```
+#[component]
+pub fn Outlet<'a, G: Html, R, C, S, F>(
+    cx: Scope<'a>,
+    props: OutletProps<'a, R, C, S, F, G>,
+) -> View<G>
+where
+    R: 'a,
+    C: Clone + 'a,
+    S: Fn(&R) -> Option<C> + 'a,
+    F: for<'b> Fn(BoundedScope<'b, 'a>, &'b ReadSignal<C>) -> View<G> + 'a,
+{
+    let OutletProps {
+        route,
+        select,
+        view,
+        _phantom,
+    } = props;
+    let selected = create_memo(cx, move || select(&route.get()));
+    let active = create_selector(cx, || selected.get().is_some());
+    View::new_dyn_scoped(cx, move |cx| {
+        if !*active.get() {
+            return View::empty();
+        }
+        let child = create_signal(cx, selected.get_untracked().as_ref().clone().unwrap());
+        create_effect(cx, || {
+            if let Some(route) = selected.get().as_ref() {
+                child.set(route.clone());
+            }
+        });
+        untrack(|| view(cx, child))
+    })
 }
 
 /// Navigates to the specified `url`. The url should have the same origin as the app.
@@ -347,24 +927,11 @@ where
 /// # Panics
 /// This function will `panic!()` if a [`Router`] has not yet been created.
 pub fn navigate(url: &str) {
-    PATHNAME.with(|pathname| {
-        assert!(
-            pathname.borrow().is_some(),
-            "navigate can only be used with a Router"
-        );
-
-        let pathname = pathname.borrow().clone().unwrap_throw();
-        let path = url.strip_prefix(&base_pathname()).unwrap_or(url);
-        pathname.set(path.to_string());
-
-        // Update History API.
-        let window = web_sys::window().unwrap_throw();
-        let history = window.history().unwrap_throw();
-        history
-            .push_state_with_url(&JsValue::UNDEFINED, "", Some(url))
-            .unwrap_throw();
-        window.scroll_to_with_x_and_y(0.0, 0.0);
-    });
+    assert!(
+        PATHNAME.with(|pathname| pathname.borrow().is_some()),
+        "navigate can only be used with a Router"
+    );
+    start_navigation(NavigationKind::Push, url.to_string());
 }
 
 /// Navigates to the specified `url` without adding a new history entry. Instead, this replaces the
@@ -376,24 +943,11 @@ pub fn navigate(url: &str) {
 /// # Panics
 /// This function will `panic!()` if a [`Router`] has not yet been created.
 pub fn navigate_replace(url: &str) {
-    PATHNAME.with(|pathname| {
-        assert!(
-            pathname.borrow().is_some(),
-            "navigate_replace can only be used with a Router"
-        );
-
-        let pathname = pathname.borrow().clone().unwrap_throw();
-        let path = url.strip_prefix(&base_pathname()).unwrap_or(url);
-        pathname.set(path.to_string());
-
-        // Update History API.
-        let window = web_sys::window().unwrap_throw();
-        let history = window.history().unwrap_throw();
-        history
-            .replace_state_with_url(&JsValue::UNDEFINED, "", Some(url))
-            .unwrap_throw();
-        window.scroll_to_with_x_and_y(0.0, 0.0);
-    });
+    assert!(
+        PATHNAME.with(|pathname| pathname.borrow().is_some()),
+        "navigate_replace can only be used with a Router"
+    );
+    start_navigation(NavigationKind::Replace, url.to_string());
 }
 
 fn meta_keys_pressed(kb_event: &KeyboardEvent) -> bool {
@@ -422,7 +976,8 @@ mod tests {
         fn Comp<G: Html>(cx: Scope, path: String) -> View<G> {
             let route = Routes::match_route(
                 // The user would never use this directly, so they'd never have to do this trick
-                // It doesn't matter which variant we provide here, it just needs to conform to `&self` (designed for `struct`s, as in Perseus' router)
+                // It doesn't matter which variant we provide here, it just needs to conform to
+                // `&self` (designed for `struct`s, as in Perseus' router)
                 &Routes::Home,
                 &path
                     .split('/')
@@ -465,4 +1020,176 @@ mod tests {
             "Not Found"
         );
     }
+
+    #[test]
+    fn outlet() {
+        #[derive(Route, Clone)]
+        enum SettingsRoutes {
+            #[to("/profile")]
+            Profile,
+            #[to("/account")]
+            Account,
+            #[not_found]
+            NotFound,
+        }
+
+        #[derive(Route)]
+        enum Routes {
+            #[to("/")]
+            Home,
+            #[to("/settings/<_..>")]
+            Settings(SettingsRoutes),
+            #[not_found]
+            NotFound,
+        }
+
+        create_scope_immediate(|cx| {
+            let route = create_signal(cx, Routes::Home);
+            let layouts = create_ref(cx, Cell::new(0));
+            let view: View<SsrNode> = view! { cx,
+                Outlet {
+                    route: route,
+                    select: |route: &Routes| match route {
+                        Routes::Settings(settings) => Some(settings.clone()),
+                        _ => None,
+                    },
+                    view: |cx, settings: &ReadSignal<SettingsRoutes>| {
+                        layouts.set(layouts.get() + 1);
+                        view! { cx,
+                            nav {
+                                (match settings.get().as_ref() {
+                                    SettingsRoutes::Profile => "Profile",
+                                    SettingsRoutes::Account => "Account",
+                                    SettingsRoutes::NotFound => "Not Found",
+                                })
+                            }
+                        }
+                    },
+                }
+            };
+            let render = || sycamore::render_to_string(|_| view.clone());
+
+            assert_eq!(render(), "<!---->");
+            route.set(Routes::match_path(&Routes::Home, "/settings/profile"));
+            assert_eq!(render(), "<nav>Profile</nav>");
+            route.set(Routes::match_path(&Routes::Home, "/settings/account"));
+            assert_eq!(render(), "<nav>Account</nav>");
+            // The layout is kept while navigating between the child routes.
+            assert_eq!(layouts.get(), 1);
+            route.set(Routes::match_path(&Routes::Home, "/404"));
+            assert_eq!(render(), "<!---->");
+            route.set(Routes::match_path(&Routes::Home, "/settings/404"));
+            assert_eq!(render(), "<nav>Not Found</nav>");
+            assert_eq!(layouts.get(), 2);
+        });
+    }
+
+    #[test]
+    fn route_contexts() {
+        #[derive(Route, Debug, PartialEq, Eq)]
+        enum Routes {
+            #[to("/posts/<id>")]
+            Post { id: u32 },
+            #[not_found]
+            NotFound,
+        }
+
+        #[component]
+        fn Nested<G: Html>(cx: Scope) -> View<G> {
+            let route = use_route::<Routes>(cx);
+            let params = use_params(cx);
+            let query = use_query(cx);
+            let fragment = use_fragment(cx);
+            assert_eq!(*route.get(), Routes::Post { id: 1 });
+            let id = params.get().get("id").unwrap_or_default().to_string();
+            let page = query.get().parse::<u32>("page").unwrap_or(1);
+            view! { cx, (format!("{id}:{page}:{fragment}", fragment = fragment.get())) }
+        }
+
+        let url = "/posts/1?page=2#top";
+        let html = sycamore::render_to_string(|cx| {
+            view! { cx,
+                StaticRouter {
+                    route: Routes::default().match_path(split_url(url).0),
+                    url: url,
+                    view: |cx, _| view! { cx, div { Nested {} } },
+                }
+            }
+        });
+        assert!(html.contains(">1:2:top</div>"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn query_params() {
+        #[derive(Route)]
+        enum Routes {
+            #[to("/search")]
+            Search,
+            #[not_found]
+            NotFound,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Filters {
+            page: u32,
+            tag: Option<String>,
+        }
+
+        #[component]
+        fn Search<G: Html>(cx: Scope) -> View<G> {
+            let filters = use_query_params::<Filters>(cx);
+            let (page, tag) = filters
+                .get()
+                .as_ref()
+                .as_ref()
+                .map(|filters| (filters.page, filters.tag.clone()))
+                .unwrap();
+            assert_eq!(tag, None);
+            view! { cx, (page) }
+        }
+
+        let url = "/search?page=3";
+        let html = sycamore::render_to_string(|cx| {
+            view! { cx,
+                StaticRouter {
+                    route: Routes::default().match_path(split_url(url).0),
+                    url: url,
+                    view: |cx, _| view! { cx, div { Search {} } },
+                }
+            }
+        });
+        assert!(html.contains(">3</div>"));
+    }
+
+    #[test]
+    fn route_navigation() {
+        #[derive(Route, Debug, PartialEq, Eq)]
+        enum Routes {
+            #[to("/")]
+            Home,
+            #[to("/posts/<id>")]
+            Post { id: u32 },
+            #[not_found]
+            NotFound,
+        }
+
+        let navigation = Navigation {
+            from: "/?tab=new".to_string(),
+            to: "/posts/1#comments".to_string(),
+            kind: NavigationKind::Push,
+        };
+        let routes = RouteMatcher(Rc::new(Routes::default())).navigation(&navigation);
+        assert_eq!(routes.from, Routes::Home);
+        assert_eq!(routes.to, Routes::Post { id: 1 });
+        assert_eq!(routes.navigation, navigation);
+    }
+
+    #[test]
+    fn split_and_join_url() {
+        assert_eq!(split_url("/posts?page=2#top"), ("/posts", "page=2"));
+        assert_eq!(split_url("/posts#a?b"), ("/posts", ""));
+        assert_eq!(join_url("/posts", "page=2"), "/posts?page=2");
+        assert_eq!(join_url("/posts", ""), "/posts");
+    }
 }
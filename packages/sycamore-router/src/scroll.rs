@@ -0,0 +1,129 @@
+//! Scroll restoration for the history entries of the [`Router`](crate::Router) and scrolling to
+//! the `#fragment` of the url.
+//!
+//! Every history entry created by the router is identified by a key stored in its
+//! [`EntryState`].
+//! The scroll position of the entry that is left is saved under its key and restored when the user
+//! goes back or forward to it.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+use web_sys::ScrollRestoration;
+
+use crate::entries::EntryState;
+use crate::NavigationKind;
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    /// The key of the current history entry, if it has one.
+    static CURRENT_ENTRY: Cell<Option<u64>> = const { Cell::new(None) };
+    static LAST_KEY: Cell<u64> = const { Cell::new(0) };
+    static POSITIONS: RefCell<HashMap<u64, (f64, f64)>> = RefCell::new(HashMap::new());
+}
+
+/// Returns a new key for a history entry. Keys are based on the current time so that they do not
+/// collide with the keys of the entries created before the page was reloaded.
+fn new_key() -> u64 {
+    LAST_KEY.with(|last| {
+        let key = (js_sys::Date::now() as u64).max(last.get() + 1);
+        last.set(key);
+        key
+    })
+}
+
+/// Returns the key of the current history entry, giving it a new one if it has none, e.g. when the
+/// entry was created by the browser for an anchor.
+fn current_entry_key(history: &web_sys::History) -> u64 {
+    let state = EntryState::current(history);
+    match state.key {
+        Some(key) => key,
+        None => {
+            let key = new_key();
+            EntryState {
+                key: Some(key),
+                ..state
+            }
+            .replace(history);
+            key
+        }
+    }
+}
+
+/// Enables the scroll restoration of the router if `enabled`, taking it over from the browser.
+pub(crate) fn init(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+    if enabled {
+        let history = web_sys::window().unwrap_throw().history().unwrap_throw();
+        history
+            .set_scroll_restoration(ScrollRestoration::Manual)
+            .unwrap_throw();
+        let key = current_entry_key(&history);
+        CURRENT_ENTRY.with(|current| current.set(Some(key)));
+    }
+}
+
+/// Gives the scroll restoration back to the browser when the router is destroyed.
+pub(crate) fn cleanup() {
+    if ENABLED.with(|e| e.replace(false)) {
+        let history = web_sys::window().unwrap_throw().history().unwrap_throw();
+        history
+            .set_scroll_restoration(ScrollRestoration::Auto)
+            .unwrap_throw();
+        POSITIONS.with(|positions| positions.borrow_mut().clear());
+    }
+}
+
+/// Whether the router manages the scroll position.
+pub(crate) fn enabled() -> bool {
+    ENABLED.with(Cell::get)
+}
+
+/// Saves the scroll position of the current history entry before it is left.
+pub(crate) fn save_position() {
+    if let Some(key) = CURRENT_ENTRY.with(Cell::get) {
+        let window = web_sys::window().unwrap_throw();
+        let position = (
+            window.scroll_x().unwrap_throw(),
+            window.scroll_y().unwrap_throw(),
+        );
+        POSITIONS.with(|positions| positions.borrow_mut().insert(key, position));
+    }
+}
+
+/// Returns the key of the current history entry, if it has one.
+pub(crate) fn current_key() -> Option<u64> {
+    CURRENT_ENTRY.with(Cell::get)
+}
+
+/// Returns the key of a new history entry, which becomes the current entry.
+pub(crate) fn new_entry_key() -> u64 {
+    let key = new_key();
+    CURRENT_ENTRY.with(|current| current.set(Some(key)));
+    key
+}
+
+/// Scrolls once a navigation is committed and the new route is rendered. Going back or forward
+/// restores the saved position of the history entry. Otherwise, the page is scrolled to the
+/// element whose id is `fragment`, or to the top if there is no such element.
+pub(crate) fn scroll_after_navigation(kind: NavigationKind, fragment: &str) {
+    let window = web_sys::window().unwrap_throw();
+    if kind == NavigationKind::Pop {
+        let key = current_entry_key(&window.history().unwrap_throw());
+        CURRENT_ENTRY.with(|current| current.set(Some(key)));
+        if let Some((x, y)) = POSITIONS.with(|positions| positions.borrow().get(&key).copied()) {
+            window.scroll_to_with_x_and_y(x, y);
+            return;
+        }
+    }
+    let anchor = (!fragment.is_empty())
+        .then(|| window.document().unwrap_throw().get_element_by_id(fragment))
+        .flatten();
+    match anchor {
+        Some(anchor) => anchor.scroll_into_view(),
+        // Entries without a saved position, e.g. from before a reload, keep the current position.
+        None if kind == NavigationKind::Pop => {}
+        None => window.scroll_to_with_x_and_y(0.0, 0.0),
+    }
+}
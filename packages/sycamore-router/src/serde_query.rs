@@ -0,0 +1,190 @@
+//! Typed query params with [`serde`], enabled by the `serde` feature.
+
+use serde::de::value::{Error, MapDeserializer};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::Query;
+
+impl Query {
+    /// Deserializes the params into `T`, e.g. a struct with a field for each param. Values are
+    /// parsed into the types of the fields, and missing params deserialize to `None` for `Option`
+    /// fields.
+    ///
+    /// Params that appear several times are not supported. Use [`Params::get_all`](crate::Params)
+    /// for these instead.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        let params = self.iter().map(|(name, value)| (name, QueryValue(value)));
+        T::deserialize(MapDeserializer::new(params))
+    }
+
+    /// Serializes `params` into a [`Query`], e.g. a struct with a field for each param. The params
+    /// are sorted by name, `None` values are omitted and sequences are serialized as a param that
+    /// appears several times.
+    ///
+    /// # Panics
+    /// Panics if `params` does not serialize to a map of values or of sequences of values.
+    pub fn serialize<T: Serialize>(params: &T) -> Self {
+        fn to_param(value: Value) -> Option<String> {
+            match value {
+                Value::Null => None,
+                Value::String(value) => Some(value),
+                Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+                Value::Array(_) | Value::Object(_) => {
+                    panic!("query params cannot be nested")
+                }
+            }
+        }
+
+        let value = serde_json::to_value(params).expect("failed to serialize query params");
+        let Value::Object(map) = value else {
+            panic!("query params must serialize to a map");
+        };
+        let mut pairs = Vec::new();
+        for (name, value) in map {
+            match value {
+                Value::Array(values) => pairs.extend(
+                    values
+                        .into_iter()
+                        .filter_map(to_param)
+                        .map(|value| (name.clone(), value)),
+                ),
+                value => pairs.extend(to_param(value).map(|value| (name, value))),
+            }
+        }
+        Self::new(pairs)
+    }
+}
+
+/// Deserializes the value of a param, parsing it into the type that is requested.
+struct QueryValue<'a>(&'a str);
+
+impl<'de, 'a> IntoDeserializer<'de, Error> for QueryValue<'a> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.0.parse().map_err(de::Error::custom)?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for QueryValue<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Sort {
+        Newest,
+        Oldest,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Search {
+        q: String,
+        page: u32,
+        sort: Option<Sort>,
+        exact: Option<bool>,
+    }
+
+    #[test]
+    fn deserialize_query() {
+        let query = Query::parse("q=hello+world&page=2&sort=oldest");
+        assert_eq!(
+            query.deserialize::<Search>().unwrap(),
+            Search {
+                q: "hello world".to_string(),
+                page: 2,
+                sort: Some(Sort::Oldest),
+                exact: None,
+            }
+        );
+        assert!(Query::parse("q=a&page=two")
+            .deserialize::<Search>()
+            .is_err());
+        assert!(Query::parse("page=1").deserialize::<Search>().is_err());
+    }
+
+    #[test]
+    fn serialize_query() {
+        let search = Search {
+            q: "a&b".to_string(),
+            page: 1,
+            sort: Some(Sort::Newest),
+            exact: None,
+        };
+        let query = Query::serialize(&search);
+        assert_eq!(query.to_string(), "page=1&q=a%26b&sort=newest");
+        assert_eq!(query.deserialize::<Search>().unwrap(), search);
+
+        #[derive(Serialize)]
+        struct Tags {
+            tag: Vec<&'static str>,
+        }
+        let query = Query::serialize(&Tags {
+            tag: vec!["a", "b"],
+        });
+        assert_eq!(query.get_all("tag"), ["a", "b"]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Generation of `sitemap.xml` and `robots.txt` from a [`Route`].
+
+use std::path::Path;
+use std::{fs, io};
+
+use crate::{Route, Segment};
+
+/// The URLs of an app, enumerated from the variants of a [`Route`], which can be written to
+/// `sitemap.xml` and `robots.txt`, e.g. when generating a static site.
+///
+/// # Example
+/// ```
+/// use sycamore_router::{Route, Sitemap};
+///
+/// #[derive(Route)]
+/// enum AppRoutes {
+///     #[to("/")]
+///     Home,
+///     #[to("/posts/<id>")]
+///     Post { id: u32 },
+///     #[to("/admin")]
+///     Admin,
+///     #[not_found]
+///     NotFound,
+/// }
+///
+/// let sitemap = Sitemap::new::<AppRoutes>("https://example.com", |variant| match variant {
+///     "Post" => vec![vec!["1".to_string()], vec!["2".to_string()]],
+///     _ => Vec::new(),
+/// })
+/// .with_disallow("/admin");
+/// assert_eq!(sitemap.paths(), ["/", "/posts/1", "/posts/2"]);
+/// assert!(sitemap.robots_txt().contains("Disallow: /admin"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sitemap {
+    base_url: String,
+    paths: Vec<String>,
+    disallow: Vec<String>,
+}
+
+impl Sitemap {
+    /// Enumerates the paths of the variants of `R` that have a `#[to(_)]` attribute. `base_url` is
+    /// the origin of the site, e.g. `https://example.com`.
+    ///
+    /// The routes without dynamic segments are included as is. For the other routes, `params` is
+    /// called with the name of the variant and returns the values of the dynamic segments of every
+    /// path to include, in the order of the segments. A route for which `params` returns no values
+    /// is not included. The values are inserted in the path as is, so they should already be
+    /// URL-encoded.
+    ///
+    /// # Panics
+    /// Panics if the number of values returned by `params` does not match the number of dynamic
+    /// segments of the route.
+    pub fn new<R: Route>(
+        base_url: impl Into<String>,
+        mut params: impl FnMut(&str) -> Vec<Vec<String>>,
+    ) -> Self {
+        let mut paths = Vec::new();
+        for (variant, route_path) in R::route_paths() {
+            let is_static = route_path
+                .segments()
+                .iter()
+                .all(|segment| matches!(segment, Segment::Param(_)));
+            if is_static {
+                paths.push(route_path.to_path(&[]).unwrap());
+                continue;
+            }
+            for values in params(variant) {
+                let values = values.iter().map(String::as_str).collect::<Vec<_>>();
+                match route_path.to_path(&values) {
+                    Some(path) => paths.push(path),
+                    None => panic!(
+                        "wrong number of params for route `{variant}` (found {} param(s))",
+                        values.len()
+                    ),
+                }
+            }
+        }
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            paths,
+            disallow: Vec::new(),
+        }
+    }
+
+    /// Adds a path that is not part of the routes, e.g. a static file.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Disallows crawling the paths that start with `prefix` in `robots.txt`. These paths are not
+    /// included in `sitemap.xml`.
+    pub fn with_disallow(mut self, prefix: impl Into<String>) -> Self {
+        self.disallow.push(prefix.into());
+        self
+    }
+
+    /// Returns the paths included in `sitemap.xml`, in the order in which the routes are declared.
+    pub fn paths(&self) -> Vec<&str> {
+        self.paths
+            .iter()
+            .filter(|path| {
+                !self
+                    .disallow
+                    .iter()
+                    .any(|prefix| path.starts_with(prefix.as_str()))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns the content of `sitemap.xml`.
+    pub fn sitemap_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for path in self.paths() {
+            xml.push_str("  <url><loc>");
+            xml.push_str(&escape_xml(&format!("{}{path}", self.base_url)));
+            xml.push_str("</loc></url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+
+    /// Returns the content of `robots.txt`, which points to `sitemap.xml` at the root of the site.
+    pub fn robots_txt(&self) -> String {
+        let mut txt = String::from("User-agent: *\n");
+        if self.disallow.is_empty() {
+            txt.push_str("Allow: /\n");
+        }
+        for prefix in &self.disallow {
+            txt.push_str(&format!("Disallow: {prefix}\n"));
+        }
+        txt.push_str(&format!("\nSitemap: {}/sitemap.xml\n", self.base_url));
+        txt
+    }
+
+    /// Writes `sitemap.xml` and `robots.txt` to `dir`, e.g. the output directory of a static site.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::write(dir.join("sitemap.xml"), self.sitemap_xml())?;
+        fs::write(dir.join("robots.txt"), self.robots_txt())
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Route)]
+    enum Routes {
+        #[to("/")]
+        Home,
+        #[to("/posts/<id>")]
+        Post { id: u32 },
+        #[to("/docs/<path..>")]
+        Docs { path: Vec<String> },
+        #[to("/private/<name>")]
+        Private(String),
+        #[not_found]
+        NotFound,
+    }
+
+    fn sitemap() -> Sitemap {
+        Sitemap::new::<Routes>("https://example.com/", |variant| match variant {
+            "Post" => vec![vec!["1".to_string()], vec!["2".to_string()]],
+            "Docs" => vec![vec!["intro/getting-started".to_string()]],
+            "Private" => vec![vec!["a&b".to_string()]],
+            _ => Vec::new(),
+        })
+    }
+
+    #[test]
+    fn route_paths() {
+        let paths = Routes::route_paths()
+            .into_iter()
+            .map(|(variant, path)| (variant, path.segments().len()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [("Home", 0), ("Post", 2), ("Docs", 2), ("Private", 2)]
+        );
+    }
+
+    #[test]
+    fn sitemap_xml() {
+        let sitemap = sitemap().with_path("/feed.xml");
+        assert_eq!(
+            sitemap.paths(),
+            [
+                "/",
+                "/posts/1",
+                "/posts/2",
+                "/docs/intro/getting-started",
+                "/private/a&b",
+                "/feed.xml"
+            ]
+        );
+        let xml = sitemap.sitemap_xml();
+        assert!(xml.contains("<url><loc>https://example.com/</loc></url>"));
+        assert!(xml.contains("<url><loc>https://example.com/private/a&amp;b</loc></url>"));
+    }
+
+    #[test]
+    fn robots_txt() {
+        assert_eq!(
+            sitemap().robots_txt(),
+            "User-agent: *\nAllow: /\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+        let sitemap = sitemap().with_disallow("/private");
+        assert_eq!(
+            sitemap.robots_txt(),
+            "User-agent: *\nDisallow: /private\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+        assert!(!sitemap.sitemap_xml().contains("/private"));
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong number of params for route `Post`")]
+    fn wrong_number_of_params() {
+        Sitemap::new::<Routes>("https://example.com", |_| vec![Vec::new()]);
+    }
+}
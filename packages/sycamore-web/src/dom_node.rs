@@ -1,18 +1,20 @@
 //! Rendering backend for the DOM.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
-use js_sys::Array;
-use sycamore_core::generic_node::{GenericNode, SycamoreElement};
+use js_sys::{Array, Function};
+use sycamore_core::generic_node::{EventListener, EventOptions, GenericNode, SycamoreElement};
 use sycamore_core::render::insert;
 use sycamore_core::view::View;
 use sycamore_reactive::*;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::{intern, JsCast};
-use web_sys::{Comment, Document, Element, Node, Text};
+use web_sys::{AddEventListenerOptions, Comment, Document, Element, Node, Text};
 
+use crate::render_hooks::{attribute_hooks, element_hooks};
 use crate::Html;
 
 #[wasm_bindgen]
@@ -53,6 +55,46 @@ impl NodeId {
     }
 }
 
+/// An event handler that is attached to a node, for [`GenericNode::event_listeners`].
+struct RegisteredListener {
+    id: usize,
+    name: String,
+    options: EventOptions,
+    callback: Function,
+}
+
+thread_local! {
+    /// The event handlers of the nodes that have any.
+    static EVENT_LISTENERS: RefCell<HashMap<NodeId, Vec<RegisteredListener>>> =
+        RefCell::new(HashMap::new());
+    static NEXT_LISTENER_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns a new id for [`EventListener::id`].
+fn next_listener_id() -> usize {
+    NEXT_LISTENER_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+/// Removes the handler `id` of the node `node_id` from [`EVENT_LISTENERS`] and returns it.
+fn unregister_listener(node_id: NodeId, id: usize) -> Option<RegisteredListener> {
+    EVENT_LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        let node_listeners = listeners.get_mut(&node_id)?;
+        let index = node_listeners
+            .iter()
+            .position(|listener| listener.id == id)?;
+        let listener = node_listeners.remove(index);
+        if node_listeners.is_empty() {
+            listeners.remove(&node_id);
+        }
+        Some(listener)
+    })
+}
+
 /// Rendering backend for the DOM.
 ///
 /// _This API requires the following crate features to be activated: `dom`_
@@ -93,6 +135,80 @@ impl DomNode {
             node,
         }
     }
+
+    /// Records the event handler `closure` for [`GenericNode::event_listeners`] until `cx` is
+    /// disposed.
+    fn register_listener(
+        &self,
+        cx: Scope<'_>,
+        id: usize,
+        name: &str,
+        options: EventOptions,
+        closure: &Closure<dyn FnMut(web_sys::Event)>,
+    ) {
+        let node_id = self.get_node_id();
+        let listener = RegisteredListener {
+            id,
+            name: name.to_string(),
+            options,
+            callback: closure.as_ref().unchecked_ref::<Function>().clone(),
+        };
+        EVENT_LISTENERS.with(|listeners| {
+            listeners
+                .borrow_mut()
+                .entry(node_id)
+                .or_default()
+                .push(listener);
+        });
+        on_cleanup(cx, move || {
+            unregister_listener(node_id, id);
+        });
+    }
+
+    /// Calls the [`RenderHook`](crate::render_hooks::RenderHook)s for this element. `key` is the
+    /// hydration key of the element, if any.
+    pub(crate) fn run_element_hooks(&self, key: Option<(usize, usize)>) {
+        let element = self.node.unchecked_ref::<Element>();
+        element_hooks(|| element.local_name(), key, &|name, value| {
+            element.set_attribute(name, value).unwrap_throw()
+        });
+    }
+
+    /// Create a new [`DomNode`] from a [`web_sys::Node`] that was just created by Sycamore.
+    fn new_managed(node: Node) -> Self {
+        #[cfg(all(debug_assertions, feature = "diagnostics"))]
+        MANAGED_NODES.with(|nodes| {
+            nodes.add(&node);
+        });
+        Self {
+            id: Default::default(),
+            node,
+        }
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "diagnostics"))]
+thread_local! {
+    /// All the nodes created by [`DomNode`]. Used to detect DOM mutations made by third-party
+    /// scripts.
+    static MANAGED_NODES: js_sys::WeakSet = js_sys::WeakSet::new();
+}
+
+/// Returns `true` if `node` was created by Sycamore, as opposed to being created by a third-party
+/// script or by hydration.
+///
+/// Nodes are only tracked in debug builds with the `diagnostics` feature. Otherwise, this always
+/// returns `false`.
+pub fn is_managed_node(node: &Node) -> bool {
+    #[cfg(all(debug_assertions, feature = "diagnostics"))]
+    {
+        MANAGED_NODES.with(|nodes| nodes.has(node))
+    }
+    #[cfg(not(all(debug_assertions, feature = "diagnostics")))]
+    {
+        let _ = node;
+        false
+    }
 }
 
 impl PartialEq for DomNode {
@@ -161,26 +277,31 @@ impl GenericNode for DomNode {
                 .unwrap_throw()
                 .into()
         };
-        DomNode {
-            id: Default::default(),
-            node,
-        }
+        let node = DomNode::new_managed(node);
+        node.run_element_hooks(None);
+        node
     }
 
     fn element_from_tag(tag: &str) -> Self {
         let node = document().create_element(intern(tag)).unwrap_throw().into();
-        DomNode {
-            id: Default::default(),
-            node,
-        }
+        let node = DomNode::new_managed(node);
+        node.run_element_hooks(None);
+        node
+    }
+
+    fn element_from_tag_ns(tag: &str, ns: Option<&str>) -> Self {
+        let node = document()
+            .create_element_ns(ns, intern(tag))
+            .unwrap_throw()
+            .into();
+        let node = DomNode::new_managed(node);
+        node.run_element_hooks(None);
+        node
     }
 
     fn text_node(text: &str) -> Self {
         let node = document().create_text_node(text).into();
-        DomNode {
-            id: Default::default(),
-            node,
-        }
+        DomNode::new_managed(node)
     }
 
     fn text_node_int(int: i32) -> Self {
@@ -188,25 +309,19 @@ impl GenericNode for DomNode {
             .unchecked_into::<DocumentCreateTextNodeInt>()
             .create_text_node_int(int)
             .into();
-        DomNode {
-            id: Default::default(),
-            node,
-        }
+        DomNode::new_managed(node)
     }
 
     fn marker_with_text(text: &str) -> Self {
         let node = document().create_comment(text).into();
-        DomNode {
-            id: Default::default(),
-            node,
-        }
+        DomNode::new_managed(node)
     }
 
     fn set_attribute(&self, name: &str, value: &str) {
-        self.node
-            .unchecked_ref::<Element>()
-            .set_attribute(intern(name), value)
-            .unwrap_throw();
+        let element = self.node.unchecked_ref::<Element>();
+        if let Some(value) = attribute_hooks(|| element.local_name(), name, value) {
+            element.set_attribute(intern(name), &value).unwrap_throw();
+        }
     }
 
     fn remove_attribute(&self, name: &str) {
@@ -217,6 +332,15 @@ impl GenericNode for DomNode {
     }
 
     fn set_class_name(&self, value: &str) {
+        let value = match attribute_hooks(
+            || self.node.unchecked_ref::<Element>().local_name(),
+            "class",
+            value,
+        ) {
+            Some(value) => value,
+            None => return,
+        };
+        let value = value.as_ref();
         if self
             .node
             .unchecked_ref::<ElementTrySetClassName>()
@@ -325,14 +449,88 @@ impl GenericNode for DomNode {
 
     fn event<'a, F: FnMut(Self::EventType) + 'a>(&self, cx: Scope<'a>, name: &str, handler: F) {
         let boxed: Box<dyn FnMut(Self::EventType)> = Box::new(handler);
-        // SAFETY: extend lifetime because the closure is dropped when the cx is disposed,
-        // preventing the handler from ever being accessed after its lifetime.
-        let handler: Box<dyn FnMut(Self::EventType) + 'static> =
-            unsafe { std::mem::transmute(boxed) };
-        let closure = create_ref(cx, Closure::wrap(handler));
+        let closure = create_event_closure(cx, boxed);
         self.node
             .add_event_listener_with_callback(intern(name), closure.as_ref().unchecked_ref())
             .unwrap_throw();
+        self.register_listener(
+            cx,
+            next_listener_id(),
+            name,
+            EventOptions::default(),
+            closure,
+        );
+    }
+
+    fn event_with_options<'a, F: FnMut(Self::EventType) + 'a>(
+        &self,
+        cx: Scope<'a>,
+        name: &str,
+        mut handler: F,
+        options: EventOptions,
+    ) {
+        let id = next_listener_id();
+        let node_id = self.get_node_id();
+        let boxed: Box<dyn FnMut(Self::EventType)> = Box::new(move |event: web_sys::Event| {
+            if options.once {
+                // The browser removes the listener after this call.
+                unregister_listener(node_id, id);
+            }
+            if options.prevent_default {
+                event.prevent_default();
+            }
+            if options.stop_propagation {
+                event.stop_propagation();
+            }
+            handler(event);
+        });
+        let closure = create_event_closure(cx, boxed);
+        let listener_options = AddEventListenerOptions::new();
+        listener_options.set_capture(options.capture);
+        listener_options.set_once(options.once);
+        listener_options.set_passive(options.passive);
+        self.node
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                intern(name),
+                closure.as_ref().unchecked_ref(),
+                &listener_options,
+            )
+            .unwrap_throw();
+        self.register_listener(cx, id, name, options, closure);
+    }
+
+    fn event_listeners(&self) -> Vec<EventListener> {
+        let node_id = self.get_node_id();
+        EVENT_LISTENERS.with(|listeners| {
+            listeners
+                .borrow()
+                .get(&node_id)
+                .map(|node_listeners| {
+                    node_listeners
+                        .iter()
+                        .map(|listener| EventListener {
+                            id: listener.id,
+                            name: listener.name.clone(),
+                            options: listener.options,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn remove_event_listener(&self, listener: &EventListener) -> bool {
+        let Some(listener) = unregister_listener(self.get_node_id(), listener.id) else {
+            return false;
+        };
+        self.node
+            .remove_event_listener_with_callback_and_bool(
+                &listener.name,
+                &listener.callback,
+                listener.options.capture,
+            )
+            .unwrap_throw();
+        true
     }
 
     fn update_inner_text(&self, text: &str) {
@@ -344,15 +542,35 @@ impl GenericNode for DomNode {
     }
 
     fn clone_node(&self) -> Self {
-        Self {
-            node: self.node.clone_node_with_deep(true).unwrap_throw(),
-            id: Default::default(),
-        }
+        Self::new_managed(self.node.clone_node_with_deep(true).unwrap_throw())
+    }
+
+    fn view_updated(nodes: &[Self], component: Option<&'static str>) {
+        let nodes = nodes
+            .iter()
+            .map(|node| node.node.clone())
+            .collect::<Vec<_>>();
+        crate::update_overlay::flash(&nodes, component);
     }
 }
 
 impl Html for DomNode {
     const IS_BROWSER: bool = true;
+
+    fn is_element(&self) -> bool {
+        self.node.node_type() == Node::ELEMENT_NODE
+    }
+}
+
+/// Wraps an event handler in a [`Closure`] that is dropped when `cx` is disposed.
+fn create_event_closure<'a>(
+    cx: Scope<'a>,
+    handler: Box<dyn FnMut(web_sys::Event) + 'a>,
+) -> &'a Closure<dyn FnMut(web_sys::Event)> {
+    // SAFETY: extend lifetime because the closure is dropped when the cx is disposed,
+    // preventing the handler from ever being accessed after its lifetime.
+    let handler: Box<dyn FnMut(web_sys::Event) + 'static> = unsafe { std::mem::transmute(handler) };
+    create_ref(cx, Closure::wrap(handler))
 }
 
 /// Render a [`View`] into the DOM.
@@ -401,3 +619,100 @@ pub fn render_get_scope<'a>(
         );
     })
 }
+
+/// Render a [`View`] into a detached [`DocumentFragment`](web_sys::DocumentFragment), e.g. to
+/// prepare it before it is shown. The view stays reactive and can later be moved into the document
+/// with [`DetachedView::attach`] without being created again.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+pub fn render_detached(view: impl FnOnce(Scope<'_>) -> View<DomNode>) -> DetachedView {
+    let mut rendered = None;
+    let disposer = create_scope(|cx| rendered = Some(view(cx)));
+    let fragment = document().create_document_fragment();
+    let mut detached = DetachedView {
+        fragment,
+        view: rendered.unwrap(),
+        insert_disposer: None,
+        disposer,
+    };
+    detached.detach();
+    detached
+}
+
+/// A [`View`] rendered with [`render_detached`], either in its detached
+/// [`DocumentFragment`](web_sys::DocumentFragment) or attached to a node in the document.
+///
+/// Dropping the [`DetachedView`] leaks its scope, like [`render_to`]. Use
+/// [`dispose`](Self::dispose) to clean it up.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+#[must_use = "please hold onto the DetachedView to attach it"]
+pub struct DetachedView {
+    fragment: web_sys::DocumentFragment,
+    view: View<DomNode>,
+    /// The scope of the insertion of the view into its current parent.
+    insert_disposer: Option<ScopeDisposer<'static>>,
+    disposer: ScopeDisposer<'static>,
+}
+
+impl DetachedView {
+    /// Returns the fragment which holds the nodes of the view while it is detached.
+    pub fn fragment(&self) -> &web_sys::DocumentFragment {
+        &self.fragment
+    }
+
+    /// Returns the nodes of the view.
+    pub fn nodes(&self) -> Vec<Node> {
+        untrack(|| self.view.clone().flatten())
+            .into_iter()
+            .map(|node| node.inner_element())
+            .collect()
+    }
+
+    /// Moves the nodes of the view under `parent`, before `before` or at the end if it is `None`.
+    /// The view keeps updating in its new position.
+    pub fn attach(&mut self, parent: &Node, before: Option<&Node>) {
+        let parent = DomNode::from_web_sys(parent.clone());
+        let before = before.map(|before| DomNode::from_web_sys(before.clone()));
+        // Stop updating the nodes in their previous parent.
+        if let Some(insert_disposer) = self.insert_disposer.take() {
+            // SAFETY: The insertion scope is not referenced anywhere else.
+            unsafe { insert_disposer.dispose() };
+        }
+        let nodes = untrack(|| self.view.clone().flatten());
+        for node in &nodes {
+            parent.insert_child_before(node, before.as_ref());
+        }
+        // The nodes are already in place so they are only reconciled when the view changes.
+        let initial = View::new_fragment(nodes.into_iter().map(View::new_node).collect());
+        let view = self.view.clone();
+        self.insert_disposer = Some(create_scope(move |cx| {
+            insert(cx, &parent, view, Some(initial), before.as_ref(), true);
+        }));
+    }
+
+    /// Moves the nodes of the view back into its fragment.
+    pub fn detach(&mut self) {
+        let fragment = self.fragment.clone();
+        self.attach(&fragment, None);
+    }
+
+    /// Disposes the scope of the view. The nodes are left where they are.
+    ///
+    /// # Safety
+    /// Same as [`ScopeDisposer::dispose`].
+    pub unsafe fn dispose(self) {
+        if let Some(insert_disposer) = self.insert_disposer {
+            insert_disposer.dispose();
+        }
+        self.disposer.dispose();
+    }
+}
+
+impl fmt::Debug for DetachedView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DetachedView")
+            .field("fragment", &self.fragment)
+            .finish()
+    }
+}
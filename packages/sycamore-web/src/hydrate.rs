@@ -1,26 +1,239 @@
 //! Utilities for client-side hydration in the browser.
 
+use std::cell::RefCell;
+use std::fmt;
+
 use sycamore_core::hydrate::{get_next_id, hydration_completed};
 use sycamore_core::view::View;
+use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{window, Comment, Element, Node};
 
 use crate::hydrate_node::HydrateNode;
 
+const ELEMENT_NODE_TYPE: u16 = 1;
+const TEXT_NODE_TYPE: u16 = 3;
 const COMMENT_NODE_TYPE: u16 = 8;
 
+/// A tiny script that records the events that happen before hydration is finished, so that they
+/// are not lost. The events are replayed to their handlers once hydration is finished.
+///
+/// Only the events that have a handler on the element they happen on (or on one of its ancestors)
+/// are recorded. These elements are annotated with a `data-on` attribute when rendering on the
+/// server. The following events are supported: `click`, `dblclick`, `input`, `change`, `submit`,
+/// `keydown` and `keyup`. The default action of the recorded `click` and `submit` events is
+/// prevented. A replayed `click` performs its default action, unless the handler prevents it, but
+/// a replayed `submit` never does.
+///
+/// To use it, add it inline, inside of a `<script>` tag, at the top of the `<body>` of the page
+/// rendered on the server:
+/// ```
+/// # use sycamore::prelude::*;
+/// # use sycamore::web::hydrate::EVENT_REPLAY_SCRIPT;
+/// # fn app<G: Html>(cx: Scope) -> View<G> { view! { cx, } }
+/// let body = sycamore::render_to_string(app);
+/// let html = format!("<body><script>{EVENT_REPLAY_SCRIPT}</script>{body}</body>");
+/// ```
+pub const EVENT_REPLAY_SCRIPT: &str = "(function(){\
+var d=document,q=[],t=[\"click\",\"dblclick\",\"input\",\"change\",\"submit\",\"keydown\",\"keyup\"];\
+function h(e){\
+if(e.target.closest&&e.target.closest(\"[data-on~=\"+e.type+\"]\")){\
+q.push(e);\
+if(e.type==\"click\"||e.type==\"submit\")e.preventDefault()}}\
+t.forEach(function(n){d.addEventListener(n,h,true)});\
+window.__sycamoreReplay=function(){\
+t.forEach(function(n){d.removeEventListener(n,h,true)});\
+delete window.__sycamoreReplay;\
+q.splice(0).forEach(function(e){e.target.dispatchEvent(new e.constructor(e.type,e))})}\
+})()";
+
+/// Replays the events recorded by [`EVENT_REPLAY_SCRIPT`] and stops recording new ones. Does
+/// nothing if the script was not added to the page.
+///
+/// This is called automatically once hydration is finished.
+pub fn replay_events() {
+    let replay = js_sys::Reflect::get(&window().unwrap(), &"__sycamoreReplay".into()).unwrap();
+    if let Some(replay) = replay.dyn_ref::<js_sys::Function>() {
+        replay.call0(&JsValue::UNDEFINED).unwrap_throw();
+    }
+}
+
 /// Gets the element with the next hydration-key or `None` if not found.
 /// This method basically queries elements with the `data-hk` attribute.
 pub fn get_next_element() -> Option<Element> {
-    if let Some(hk) = get_next_id() {
-        window()
-            .unwrap()
-            .document()
-            .unwrap()
-            .query_selector(&format!("[data-hk=\"{}.{}\"]", hk.0, hk.1))
-            .unwrap()
-    } else {
-        None
+    get_next_id().and_then(get_element)
+}
+
+/// Gets the element with the hydration key `hk`.
+pub(crate) fn get_element(hk: (usize, usize)) -> Option<Element> {
+    window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .query_selector(&format!("[data-hk=\"{}.{}\"]", hk.0, hk.1))
+        .unwrap()
+}
+
+/// A difference between the view that is hydrated and the HTML that was rendered on the server,
+/// e.g. because the view depends on data that is different in the browser. Hydration keeps going
+/// after a mismatch, but the page may not be updated correctly afterwards.
+///
+/// Mismatches are only detected in debug builds. They are logged to the console and can be read
+/// with [`take_hydration_mismatches`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HydrationMismatch {
+    /// The server did not render an element for a hydration key. A new element is created
+    /// instead, but it is not added to the page.
+    MissingElement {
+        /// The hydration key, e.g. `0.3`.
+        key: String,
+        /// The tag of the element that was expected.
+        expected: String,
+    },
+    /// The element rendered by the server has a different tag.
+    Tag {
+        /// The path of the element in the document.
+        path: String,
+        /// The tag of the element that was expected.
+        expected: String,
+        /// The tag of the element rendered by the server.
+        actual: String,
+    },
+    /// The element rendered by the server is not a child of the element it belongs to.
+    Parent {
+        /// The path of the element in the document.
+        path: String,
+        /// The path of the element it should be a child of.
+        expected_parent: String,
+    },
+    /// The text of an element rendered by the server is different.
+    Text {
+        /// The path of the element in the document.
+        path: String,
+        /// The text that was expected.
+        expected: String,
+        /// The text rendered by the server.
+        actual: String,
+    },
+}
+
+impl fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingElement { key, expected } => write!(
+                f,
+                "expected <{expected}> with data-hk=\"{key}\", but the server did not render it"
+            ),
+            Self::Tag {
+                path,
+                expected,
+                actual,
+            } => write!(f, "at {path}: expected <{expected}>, found <{actual}>"),
+            Self::Parent {
+                path,
+                expected_parent,
+            } => write!(
+                f,
+                "at {path}: expected the element to be in {expected_parent}"
+            ),
+            Self::Text {
+                path,
+                expected,
+                actual,
+            } => write!(f, "at {path}: expected text {expected:?}, found {actual:?}"),
+        }
+    }
+}
+
+thread_local! {
+    static MISMATCHES: RefCell<Vec<HydrationMismatch>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the [`HydrationMismatch`]es that were detected since the last call. Always empty in
+/// release builds.
+pub fn take_hydration_mismatches() -> Vec<HydrationMismatch> {
+    MISMATCHES.with(|mismatches| mismatches.take())
+}
+
+/// Logs `mismatch` and records it for [`take_hydration_mismatches`].
+pub(crate) fn report_mismatch(mismatch: HydrationMismatch) {
+    web_sys::console::error_1(&format!("hydration mismatch {mismatch}").into());
+    MISMATCHES.with(|mismatches| mismatches.borrow_mut().push(mismatch));
+}
+
+/// Returns the path of `node` in the document, e.g. `body > div > p:nth-child(2)`.
+pub(crate) fn node_path(node: &Node) -> String {
+    let mut segments = Vec::new();
+    let mut node = Some(node.clone());
+    while let Some(current) = node {
+        if let Some(element) = current.dyn_ref::<Element>() {
+            let tag = element.tag_name().to_ascii_lowercase();
+            if tag == "html" {
+                break;
+            }
+            let mut segment = tag;
+            if let Some(hk) = element.get_attribute("data-hk") {
+                segment.push_str(&format!("[data-hk=\"{hk}\"]"));
+            } else if let Some(parent) = element.parent_element() {
+                let siblings = parent.children();
+                if siblings.length() > 1 {
+                    let index = (0..siblings.length())
+                        .position(|i| siblings.item(i).as_ref() == Some(element))
+                        .unwrap_or_default();
+                    segment.push_str(&format!(":nth-child({})", index + 1));
+                }
+            }
+            segments.push(segment);
+        }
+        node = current.parent_node();
+    }
+    segments.reverse();
+    segments.join(" > ")
+}
+
+/// Checks that `child`, which is appended to `parent` while hydrating, was rendered by the server
+/// in `parent`.
+pub(crate) fn check_child(parent: &Node, child: &Node) {
+    if !parent.is_connected() {
+        return;
+    }
+    match child.node_type() {
+        ELEMENT_NODE_TYPE
+            if child.is_connected() && child.parent_node().as_ref() != Some(parent) =>
+        {
+            report_mismatch(HydrationMismatch::Parent {
+                path: node_path(child),
+                expected_parent: node_path(parent),
+            });
+        }
+        TEXT_NODE_TYPE => {
+            let expected = child.text_content().unwrap_or_default();
+            let actual = parent.text_content().unwrap_or_default();
+            if !actual.contains(&expected) {
+                report_mismatch(HydrationMismatch::Text {
+                    path: node_path(parent),
+                    expected,
+                    actual,
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks that the text of `node`, which is set to `text` while hydrating, was rendered by the
+/// server.
+pub(crate) fn check_text(node: &Node, text: &str) {
+    if !node.is_connected() {
+        return;
+    }
+    let actual = node.text_content().unwrap_or_default();
+    if actual != text {
+        report_mismatch(HydrationMismatch::Text {
+            path: node_path(node),
+            expected: text.to_string(),
+            actual,
+        });
     }
 }
 
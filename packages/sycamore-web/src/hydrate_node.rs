@@ -3,8 +3,8 @@
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
-use sycamore_core::generic_node::{GenericNode, SycamoreElement};
-use sycamore_core::hydrate::{hydration_completed, with_hydration_context};
+use sycamore_core::generic_node::{EventListener, EventOptions, GenericNode, SycamoreElement};
+use sycamore_core::hydrate::{get_next_id, hydration_completed, with_hydration_context};
 use sycamore_core::render::insert;
 use sycamore_core::view::View;
 use sycamore_reactive::*;
@@ -13,7 +13,10 @@ use wasm_bindgen::JsCast;
 use web_sys::Node;
 
 use crate::dom_node::{DomNode, NodeId};
-use crate::hydrate::get_next_element;
+use crate::hydrate::{
+    check_child, check_text, get_element, node_path, replay_events, report_mismatch,
+    HydrationMismatch,
+};
 use crate::Html;
 
 /// Rendering backend for the DOM with hydration support.
@@ -91,18 +94,26 @@ impl GenericNode for HydrateNode {
     /// When hydrating, instead of creating a new node, this will attempt to hydrate an existing
     /// node.
     fn element<T: SycamoreElement>() -> Self {
-        let el = get_next_element();
-        if let Some(el) = el {
+        let hk = get_next_id();
+        if let Some(el) = hk.and_then(get_element) {
             // If in debug mode, check that the hydrate element has the same tag as the argument.
-            debug_assert_eq!(
-                el.tag_name().to_ascii_lowercase(),
-                T::TAG_NAME,
-                "hydration error, mismatched element tag"
-            );
-            Self {
-                node: DomNode::from_web_sys(el.into()),
+            if cfg!(debug_assertions) && !el.tag_name().eq_ignore_ascii_case(T::TAG_NAME) {
+                report_mismatch(HydrationMismatch::Tag {
+                    path: node_path(&el),
+                    expected: T::TAG_NAME.to_string(),
+                    actual: el.tag_name().to_ascii_lowercase(),
+                });
             }
+            let node = DomNode::from_web_sys(el.into());
+            node.run_element_hooks(hk);
+            Self { node }
         } else {
+            if let (true, Some(hk)) = (cfg!(debug_assertions), hk) {
+                report_mismatch(HydrationMismatch::MissingElement {
+                    key: format!("{}.{}", hk.0, hk.1),
+                    expected: T::TAG_NAME.to_string(),
+                });
+            }
             Self {
                 node: DomNode::element::<T>(),
             }
@@ -115,6 +126,12 @@ impl GenericNode for HydrateNode {
         }
     }
 
+    fn element_from_tag_ns(tag: &str, ns: Option<&str>) -> Self {
+        Self {
+            node: DomNode::element_from_tag_ns(tag, ns),
+        }
+    }
+
     /// When hydrating, instead of creating a new node, this will attempt to hydrate an existing
     /// node.
     fn text_node(text: &str) -> Self {
@@ -178,6 +195,8 @@ impl GenericNode for HydrateNode {
         if hydration_completed() {
             // Do not append nodes during hydration as that will result in duplicate text nodes.
             self.node.append_child(&child.node);
+        } else if cfg!(debug_assertions) {
+            check_child(&self.inner_element(), &child.inner_element());
         }
     }
 
@@ -227,8 +246,32 @@ impl GenericNode for HydrateNode {
         self.node.event(cx, name, handler);
     }
 
+    #[inline]
+    fn event_with_options<'a, F: FnMut(Self::EventType) + 'a>(
+        &self,
+        cx: Scope<'a>,
+        name: &str,
+        handler: F,
+        options: EventOptions,
+    ) {
+        self.node.event_with_options(cx, name, handler, options);
+    }
+
+    #[inline]
+    fn event_listeners(&self) -> Vec<EventListener> {
+        self.node.event_listeners()
+    }
+
+    #[inline]
+    fn remove_event_listener(&self, listener: &EventListener) -> bool {
+        self.node.remove_event_listener(listener)
+    }
+
     #[inline]
     fn update_inner_text(&self, text: &str) {
+        if cfg!(debug_assertions) && !hydration_completed() {
+            check_text(&self.inner_element(), text);
+        }
         self.node.update_inner_text(text);
     }
 
@@ -243,10 +286,23 @@ impl GenericNode for HydrateNode {
             node: self.node.clone_node(),
         }
     }
+
+    #[inline]
+    fn view_updated(nodes: &[Self], component: Option<&'static str>) {
+        let nodes = nodes
+            .iter()
+            .map(|node| node.node.clone())
+            .collect::<Vec<_>>();
+        DomNode::view_updated(&nodes, component);
+    }
 }
 
 impl Html for HydrateNode {
     const IS_BROWSER: bool = true;
+
+    fn is_element(&self) -> bool {
+        self.node.is_element()
+    }
 }
 
 /// Render a [`View`] under a `parent` node by reusing existing nodes (client side
@@ -295,7 +351,7 @@ pub fn hydrate_get_scope<'a>(
         .map(|x| View::new_node(HydrateNode::from_web_sys(x)))
         .collect::<Vec<_>>();
 
-    create_scope(|cx| {
+    let disposer = create_scope(|cx| {
         insert(
             cx,
             &HydrateNode::from_web_sys(parent.clone()),
@@ -304,5 +360,8 @@ pub fn hydrate_get_scope<'a>(
             None,
             false,
         );
-    })
+    });
+    // Now that the event handlers are attached, replay the events that happened before.
+    replay_events();
+    disposer
 }
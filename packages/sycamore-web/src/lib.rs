@@ -15,14 +15,19 @@ mod dom_node;
 pub mod hydrate;
 #[cfg(feature = "hydrate")]
 mod hydrate_node;
+pub mod markers;
+mod noderef;
+pub mod render_hooks;
 #[cfg(feature = "ssr")]
 mod ssr_node;
+pub mod update_overlay;
 
 use std::any::{Any, TypeId};
 
 pub use dom_node::*;
 #[cfg(feature = "hydrate")]
 pub use hydrate_node::*;
+pub use noderef::*;
 #[cfg(feature = "ssr")]
 pub use ssr_node::*;
 use sycamore_core::generic_node::GenericNode;
@@ -37,6 +42,9 @@ pub trait Html: GenericNode<EventType = Event, PropertyType = JsValue> {
     /// A value of `false` does not necessarily mean that it is not being rendered in WASM or even
     /// in the browser. It only means that it does not create DOM nodes.
     const IS_BROWSER: bool;
+
+    /// Returns `true` if the node is an element, as opposed to e.g. a text node or a comment.
+    fn is_element(&self) -> bool;
 }
 
 /// Create a generic `Html` node from a `web_sys::Node`.
@@ -81,6 +89,11 @@ pub fn from_web_sys<G: Html>(node: web_sys::Node) -> G {
 
 /// Queue up a callback to be executed when the component is mounted.
 ///
+/// The callback is called in a microtask, once the view of the component has been created. By
+/// then, all the [`NodeRef`](sycamore_core::noderef::NodeRef)s of the component are set. To react
+/// to a [`NodeRef`](sycamore_core::noderef::NodeRef) as soon as it is set instead, use
+/// [`NodeRef::on_set`](sycamore_core::noderef::NodeRef::on_set).
+///
 /// If not on `wasm32` target, does nothing.
 ///
 /// # Potential Pitfalls
@@ -88,7 +101,7 @@ pub fn from_web_sys<G: Html>(node: web_sys::Node) -> G {
 /// If called inside an async-component, the callback will be called after the next suspension
 /// point (when there is an `.await`).
 pub fn on_mount<'a>(cx: Scope<'a>, f: impl Fn() + 'a) {
-    if cfg!(target_arch = "wasm32") {
+    if cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
         let scope_status = use_scope_status(cx);
 
         #[wasm_bindgen]
@@ -0,0 +1,347 @@
+//! The format of the hydration keys and markers in the HTML rendered on the server.
+//!
+//! This is a stable format, for tools that post-process the rendered HTML, e.g. to cache parts of
+//! a page and reassemble them later:
+//! - Every element created by a component has a `data-hk="C.N"` attribute, its [`HydrationKey`].
+//!   `C` is the id of the component, in the order in which the components were rendered, and `N` is
+//!   the index of the element within the component. The client finds the elements to hydrate with
+//!   these keys, so they must be unique in the page.
+//! - Dynamic content, e.g. an interpolated signal, is surrounded by the `<!--#-->` and `<!--/-->`
+//!   comments ([`Marker::DynamicStart`] and [`Marker::DynamicEnd`]).
+//! - The fallback of a `Suspense` that is streamed is surrounded by the `<!--s:I-->` and
+//!   `<!--/s:I-->` comments ([`Marker::SuspenseStart`] and [`Marker::SuspenseEnd`]).
+//!
+//! A fragment of HTML can be cut out and reassembled safely if its markers are balanced, see
+//! [`is_balanced`], and if its hydration keys do not collide with the rest of the page, see
+//! [`map_hydration_keys`].
+//!
+//! # Example
+//! ```
+//! use sycamore_web::markers::{map_hydration_keys, HydrationKey};
+//!
+//! // Move a cached fragment, rendered as component 1, to component 5.
+//! let cached = r#"<p data-hk="1.0"><!--#-->Hello<!--/--></p>"#;
+//! let html = map_hydration_keys(cached, |key| HydrationKey {
+//!     component: key.component + 4,
+//!     ..key
+//! });
+//! assert_eq!(html, r#"<p data-hk="5.0"><!--#-->Hello<!--/--></p>"#);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// The name of the attribute that contains the [`HydrationKey`] of an element.
+pub const HYDRATION_KEY_ATTRIBUTE: &str = "data-hk";
+
+/// The key of an element rendered on the server, written as `C.N` in its `data-hk` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HydrationKey {
+    /// The id of the component that created the element.
+    pub component: usize,
+    /// The index of the element within the component.
+    pub node: usize,
+}
+
+impl fmt::Display for HydrationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.component, self.node)
+    }
+}
+
+/// The error returned when parsing an invalid [`HydrationKey`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHydrationKeyError;
+
+impl fmt::Display for ParseHydrationKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid hydration key, expected `C.N`")
+    }
+}
+
+impl Error for ParseHydrationKeyError {}
+
+impl FromStr for HydrationKey {
+    type Err = ParseHydrationKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (component, node) = s.split_once('.').ok_or(ParseHydrationKeyError)?;
+        Ok(Self {
+            component: component.parse().map_err(|_| ParseHydrationKeyError)?,
+            node: node.parse().map_err(|_| ParseHydrationKeyError)?,
+        })
+    }
+}
+
+/// A comment that marks a range of nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Marker {
+    /// `<!--#-->`, the start of dynamic content.
+    DynamicStart,
+    /// `<!--/-->`, the end of dynamic content.
+    DynamicEnd,
+    /// `<!--s:I-->`, the start of the fallback of the streamed `Suspense` with the id `I`.
+    SuspenseStart(u32),
+    /// `<!--/s:I-->`, the end of the fallback of the streamed `Suspense` with the id `I`.
+    SuspenseEnd(u32),
+}
+
+impl Marker {
+    /// Returns the marker whose comment contains `text`, e.g. `#` for [`Marker::DynamicStart`].
+    pub fn from_comment(text: &str) -> Option<Self> {
+        match text {
+            "#" => Some(Self::DynamicStart),
+            "/" => Some(Self::DynamicEnd),
+            _ => match text.strip_prefix("/s:") {
+                Some(id) => id.parse().ok().map(Self::SuspenseEnd),
+                None => text
+                    .strip_prefix("s:")?
+                    .parse()
+                    .ok()
+                    .map(Self::SuspenseStart),
+            },
+        }
+    }
+
+    /// Returns the text of the comment of the marker.
+    pub fn comment(&self) -> String {
+        match self {
+            Self::DynamicStart => "#".to_string(),
+            Self::DynamicEnd => "/".to_string(),
+            Self::SuspenseStart(id) => format!("s:{id}"),
+            Self::SuspenseEnd(id) => format!("/s:{id}"),
+        }
+    }
+}
+
+impl fmt::Display for Marker {
+    /// Writes the marker as an HTML comment.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<!--{}-->", self.comment())
+    }
+}
+
+/// A hydration key or a marker found by [`find_markers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Found {
+    /// The value of a `data-hk` attribute.
+    Key(HydrationKey),
+    /// A marker comment.
+    Marker(Marker),
+}
+
+/// Returns the hydration keys and the markers of `html`, in order, with their byte ranges. The
+/// range of a hydration key is the value of its attribute, without the quotes, and the range of a
+/// marker is the whole comment.
+///
+/// Text, other comments and the content of `<script>` and `<style>` elements are skipped, so that
+/// text that looks like a marker is not mistaken for one.
+pub fn find_markers(html: &str) -> Vec<(Range<usize>, Found)> {
+    let mut found = Vec::new();
+    let mut i = 0;
+    while let Some(offset) = html[i..].find('<') {
+        let start = i + offset;
+        let rest = &html[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let len = comment.find("-->").unwrap_or(comment.len());
+            let end = (start + "<!--".len() + len + "-->".len()).min(html.len());
+            if let Some(marker) = Marker::from_comment(&comment[..len]) {
+                found.push((start..end, Found::Marker(marker)));
+            }
+            i = end;
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let (end, name) = scan_tag(html, start, &mut found);
+            i = end;
+            if name.eq_ignore_ascii_case("script") || name.eq_ignore_ascii_case("style") {
+                // The content of raw text elements is not HTML.
+                let close = format!("</{}", name.to_ascii_lowercase());
+                i += html[i..]
+                    .to_ascii_lowercase()
+                    .find(&close)
+                    .unwrap_or(html.len() - i);
+            }
+        } else {
+            i = start + 1;
+        }
+    }
+    found
+}
+
+/// Scans the start tag at `start` and adds its hydration key to `found`. Returns the end of the
+/// tag and the name of the element.
+fn scan_tag<'a>(
+    html: &'a str,
+    start: usize,
+    found: &mut Vec<(Range<usize>, Found)>,
+) -> (usize, &'a str) {
+    let bytes = html.as_bytes();
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || b == b'/' || b == b'>' || b == b'=';
+    let mut i = start + 1;
+    while i < bytes.len() && !is_name_end(bytes[i]) {
+        i += 1;
+    }
+    let name = &html[start + 1..i];
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+        if i >= bytes.len() {
+            return (bytes.len(), name);
+        }
+        if bytes[i] == b'>' {
+            return (i + 1, name);
+        }
+        let attr_start = i;
+        while i < bytes.len() && !is_name_end(bytes[i]) {
+            i += 1;
+        }
+        let attr = &html[attr_start..i];
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() || bytes[i] != b'=' {
+            continue;
+        }
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let value = match bytes.get(i) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let value_start = i + 1;
+                let len = html[value_start..]
+                    .find(quote as char)
+                    .unwrap_or(bytes.len() - value_start);
+                i = (value_start + len + 1).min(bytes.len());
+                value_start..value_start + len
+            }
+            _ => {
+                let value_start = i;
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                    i += 1;
+                }
+                value_start..i
+            }
+        };
+        if attr == HYDRATION_KEY_ATTRIBUTE {
+            if let Ok(key) = html[value.clone()].parse() {
+                found.push((value, Found::Key(key)));
+            }
+        }
+    }
+}
+
+/// Returns `html` with every hydration key replaced with the key returned by `f`, e.g. to move a
+/// cached fragment to another component id.
+pub fn map_hydration_keys(html: &str, mut f: impl FnMut(HydrationKey) -> HydrationKey) -> String {
+    let mut result = String::with_capacity(html.len());
+    let mut last = 0;
+    for (range, found) in find_markers(html) {
+        if let Found::Key(key) = found {
+            result.push_str(&html[last..range.start]);
+            result.push_str(&f(key).to_string());
+            last = range.end;
+        }
+    }
+    result.push_str(&html[last..]);
+    result
+}
+
+/// Returns `true` if every start marker of `html` is closed by the matching end marker, in order.
+/// Only a balanced fragment can be cached and reassembled without breaking hydration.
+pub fn is_balanced(html: &str) -> bool {
+    let mut open = Vec::new();
+    for (_, found) in find_markers(html) {
+        match found {
+            Found::Marker(marker @ (Marker::DynamicStart | Marker::SuspenseStart(_))) => {
+                open.push(marker);
+            }
+            Found::Marker(Marker::DynamicEnd) => {
+                if open.pop() != Some(Marker::DynamicStart) {
+                    return false;
+                }
+            }
+            Found::Marker(Marker::SuspenseEnd(id)) => {
+                if open.pop() != Some(Marker::SuspenseStart(id)) {
+                    return false;
+                }
+            }
+            Found::Key(_) => {}
+        }
+    }
+    open.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(component: usize, node: usize) -> HydrationKey {
+        HydrationKey { component, node }
+    }
+
+    #[test]
+    fn parse_hydration_key() {
+        assert_eq!("1.2".parse(), Ok(key(1, 2)));
+        assert_eq!(key(1, 2).to_string(), "1.2");
+        assert_eq!("1".parse::<HydrationKey>(), Err(ParseHydrationKeyError));
+        assert_eq!("a.2".parse::<HydrationKey>(), Err(ParseHydrationKeyError));
+    }
+
+    #[test]
+    fn parse_marker() {
+        for marker in [
+            Marker::DynamicStart,
+            Marker::DynamicEnd,
+            Marker::SuspenseStart(3),
+            Marker::SuspenseEnd(3),
+        ] {
+            assert_eq!(Marker::from_comment(&marker.comment()), Some(marker));
+        }
+        assert_eq!(Marker::SuspenseEnd(3).to_string(), "<!--/s:3-->");
+        assert_eq!(Marker::from_comment(""), None);
+        assert_eq!(Marker::from_comment("s:x"), None);
+    }
+
+    #[test]
+    fn find() {
+        let html = "<div data-hk=\"0.0\" class='a>b'><!--#-->x<!--/--><!-- data-hk=\"9.9\" -->\
+            <script>let s = '<p data-hk=\"9.9\">';</script><input data-hk=1.2 disabled/></div>";
+        let found = find_markers(html)
+            .into_iter()
+            .map(|(range, found)| (&html[range], found))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("0.0", Found::Key(key(0, 0))),
+                ("<!--#-->", Found::Marker(Marker::DynamicStart)),
+                ("<!--/-->", Found::Marker(Marker::DynamicEnd)),
+                ("1.2", Found::Key(key(1, 2))),
+            ]
+        );
+    }
+
+    #[test]
+    fn map_keys() {
+        let html = "<p data-hk=\"1.0\">data-hk=\"1.0\"<b data-hk=\"1.1\"></b></p>";
+        assert_eq!(
+            map_hydration_keys(html, |key| HydrationKey {
+                component: key.component + 1,
+                ..key
+            }),
+            "<p data-hk=\"2.0\">data-hk=\"1.0\"<b data-hk=\"2.1\"></b></p>"
+        );
+    }
+
+    #[test]
+    fn balanced() {
+        assert!(is_balanced("<p><!--#--><!--s:0-->a<!--/s:0--><!--/--></p>"));
+        assert!(!is_balanced("<p><!--#-->"));
+        assert!(!is_balanced("<!--/-->"));
+        assert!(!is_balanced("<!--s:0--><!--/s:1-->"));
+        assert!(!is_balanced("<!--#--><!--s:0--><!--/--><!--/s:0-->"));
+    }
+}
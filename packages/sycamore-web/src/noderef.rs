@@ -0,0 +1,82 @@
+//! Accessing the `web_sys` nodes of [`NodeRef`]s.
+
+use std::any::Any;
+
+use sycamore_core::noderef::NodeRef;
+use wasm_bindgen::JsCast;
+
+use crate::*;
+
+/// Extension methods for getting the node of a [`NodeRef`] as a `web_sys` type, e.g.
+/// [`web_sys::HtmlInputElement`], without going through [`DomNode`] or [`HydrateNode`].
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// #[component]
+/// fn Component<G: Html>(cx: Scope) -> View<G> {
+///     let input_ref = create_node_ref(cx);
+///     let clear = move |_| input_ref.get_as::<HtmlInputElement>().set_value("");
+///     view! { cx,
+///         input(ref=input_ref)
+///         button(on:click=clear) { "Clear" }
+///     }
+/// }
+/// ```
+pub trait NodeRefExt {
+    /// Gets the node stored inside the [`NodeRef`] as a `T`.
+    ///
+    /// # Panics
+    /// Panics if the [`NodeRef`] is not set yet, if it is not backed by a DOM node, e.g. when
+    /// rendering to a string, or if the node is not a `T`.
+    ///
+    /// For a non panicking version, see [`NodeRefExt::try_get_as`].
+    #[track_caller]
+    fn get_as<T: JsCast>(&self) -> T {
+        self.try_get_as()
+            .expect("NodeRef is not set or does not have the expected type")
+    }
+
+    /// Tries to get the node stored inside the [`NodeRef`] as a `T`, or `None` if it is not yet
+    /// set, if it is not backed by a DOM node or if the node is not a `T`.
+    ///
+    /// For a panicking version, see [`NodeRefExt::get_as`].
+    fn try_get_as<T: JsCast>(&self) -> Option<T>;
+}
+
+impl<G: Html> NodeRefExt for NodeRef<G> {
+    fn try_get_as<T: JsCast>(&self) -> Option<T> {
+        let node = self.try_get_raw()?;
+        to_web_sys(&node)?.dyn_into().ok()
+    }
+}
+
+/// Returns the `web_sys` node of `node`, or `None` if it is not a [`DomNode`] or a
+/// [`HydrateNode`].
+fn to_web_sys(node: &dyn Any) -> Option<web_sys::Node> {
+    if let Some(node) = node.downcast_ref::<DomNode>() {
+        return Some(node.inner_element());
+    }
+    #[cfg(feature = "hydrate")]
+    if let Some(node) = node.downcast_ref::<HydrateNode>() {
+        return Some(node.inner_element());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use sycamore::prelude::*;
+
+    #[test]
+    fn ssr_node_is_not_a_web_sys_node() {
+        create_scope_immediate(|cx| {
+            let node_ref = create_node_ref(cx);
+            let _: View<SsrNode> = view! { cx, div(ref=node_ref) };
+            assert!(node_ref.try_get_raw().is_some());
+            assert!(node_ref.try_get_as::<web_sys::Node>().is_none());
+        });
+    }
+}
@@ -0,0 +1,153 @@
+//! Hooks that observe and modify the elements created by all the rendering backends.
+//!
+//! A [`RenderHook`] registered with [`add_render_hook`] is called every time an element is created
+//! and every time an attribute is set, whether the view is rendered to the DOM, hydrated or
+//! rendered on the server. This can be used, for example, to automatically add a `data-testid`
+//! attribute to every element, to only allow the classes of a design system or to collect the
+//! elements that need critical CSS.
+//!
+//! Hooks are registered per thread and apply to every view rendered on that thread afterwards.
+//!
+//! # Example
+//! ```
+//! use std::borrow::Cow;
+//!
+//! use sycamore::prelude::*;
+//! use sycamore::web::render_hooks::{add_render_hook, HookElement, RenderHook};
+//!
+//! struct TestIds;
+//!
+//! impl RenderHook for TestIds {
+//!     fn element(&self, element: &HookElement) {
+//!         element.set_attribute("data-testid", element.tag());
+//!     }
+//!
+//!     fn attribute<'v>(&self, _tag: &str, name: &str, value: Cow<'v, str>) -> Option<Cow<'v, str>> {
+//!         // Drop inline styles.
+//!         (name != "style").then_some(value)
+//!     }
+//! }
+//!
+//! add_render_hook(TestIds);
+//! let html = sycamore::render_to_string(|cx| view! { cx, p(style="color: red") });
+//! assert_eq!(html, r#"<p data-hk="0.0" data-testid="p"></p>"#);
+//! # sycamore::web::render_hooks::clear_render_hooks();
+//! ```
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A hook that is called by the rendering backends when elements are created and when attributes
+/// are set. See the [module-level documentation](self).
+pub trait RenderHook {
+    /// Called after an element is created, or when an existing element is hydrated. Attributes
+    /// can be added with [`HookElement::set_attribute`].
+    fn element(&self, element: &HookElement<'_>) {
+        let _ = element;
+    }
+
+    /// Called before the attribute `name` is set on an element with the tag `tag`. Returns the
+    /// value to set, or `None` to not set the attribute at all.
+    ///
+    /// The `class` attribute is passed to this hook as well, even when it is set as a property.
+    fn attribute<'v>(&self, tag: &str, name: &str, value: Cow<'v, str>) -> Option<Cow<'v, str>> {
+        let _ = (tag, name);
+        Some(value)
+    }
+}
+
+/// An element that was just created, passed to [`RenderHook::element`].
+pub struct HookElement<'a> {
+    tag: &'a str,
+    set_attribute: &'a dyn Fn(&str, &str),
+}
+
+impl<'a> HookElement<'a> {
+    /// Returns the tag name of the element, in lowercase for HTML elements.
+    pub fn tag(&self) -> &str {
+        self.tag
+    }
+
+    /// Sets an attribute on the element. The attribute is not passed to
+    /// [`RenderHook::attribute`].
+    pub fn set_attribute(&self, name: &str, value: &str) {
+        (self.set_attribute)(name, value);
+    }
+}
+
+impl<'a> fmt::Debug for HookElement<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookElement")
+            .field("tag", &self.tag)
+            .finish_non_exhaustive()
+    }
+}
+
+thread_local! {
+    static RENDER_HOOKS: RefCell<Vec<Rc<dyn RenderHook>>> = RefCell::new(Vec::new());
+}
+
+/// Registers a [`RenderHook`] on the current thread. Hooks are called in the order they were
+/// registered.
+pub fn add_render_hook(hook: impl RenderHook + 'static) {
+    RENDER_HOOKS.with(|hooks| hooks.borrow_mut().push(Rc::new(hook)));
+}
+
+/// Removes all the [`RenderHook`]s registered on the current thread.
+pub fn clear_render_hooks() {
+    RENDER_HOOKS.with(|hooks| hooks.borrow_mut().clear());
+}
+
+/// Returns the registered hooks, or `None` if there are none. The hooks are cloned so that they
+/// can register other hooks.
+fn hooks() -> Option<Vec<Rc<dyn RenderHook>>> {
+    RENDER_HOOKS.with(|hooks| {
+        let hooks = hooks.borrow();
+        (!hooks.is_empty()).then(|| hooks.clone())
+    })
+}
+
+/// Calls [`RenderHook::element`] for every registered hook. Also adds the `data-testid` attribute
+/// if the `test-ids` feature is enabled and test ids are enabled with `set_test_ids`. `key` is the
+/// hydration key of the element, if any.
+pub(crate) fn element_hooks(
+    tag: impl FnOnce() -> String,
+    key: Option<(usize, usize)>,
+    set_attribute: &dyn Fn(&str, &str),
+) {
+    #[cfg(feature = "test-ids")]
+    if let Some(id) = sycamore_core::component::test_id(key) {
+        set_attribute("data-testid", &id);
+    }
+    #[cfg(not(feature = "test-ids"))]
+    let _ = key;
+    if let Some(hooks) = hooks() {
+        let tag = tag();
+        let element = HookElement {
+            tag: &tag,
+            set_attribute,
+        };
+        for hook in hooks {
+            hook.element(&element);
+        }
+    }
+}
+
+/// Calls [`RenderHook::attribute`] for every registered hook. `tag` is only evaluated if there are
+/// hooks.
+pub(crate) fn attribute_hooks<'v>(
+    tag: impl FnOnce() -> String,
+    name: &str,
+    value: &'v str,
+) -> Option<Cow<'v, str>> {
+    let mut value = Cow::Borrowed(value);
+    if let Some(hooks) = hooks() {
+        let tag = tag();
+        for hook in hooks {
+            value = hook.attribute(&tag, name, value)?;
+        }
+    }
+    Some(value)
+}
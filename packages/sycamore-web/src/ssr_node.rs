@@ -4,7 +4,6 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
-use std::iter::FromIterator;
 use std::rc::{Rc, Weak};
 
 use indexmap::map::IndexMap;
@@ -15,6 +14,8 @@ use sycamore_core::view::View;
 use sycamore_reactive::*;
 use wasm_bindgen::prelude::*;
 
+use crate::markers::{HydrationKey, HYDRATION_KEY_ATTRIBUTE};
+use crate::render_hooks::{attribute_hooks, element_hooks};
 use crate::Html;
 
 static VOID_ELEMENTS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
@@ -103,6 +104,18 @@ impl SsrNode {
         }
     }
 
+    /// Calls the [`RenderHook`](crate::render_hooks::RenderHook)s for this element. `key` is the
+    /// hydration key of the element, if any.
+    fn run_element_hooks(&self, key: Option<(usize, usize)>) {
+        let element = self.unwrap_element();
+        element_hooks(|| element.borrow().name.to_string(), key, &|name, value| {
+            element
+                .borrow_mut()
+                .attributes
+                .insert(name.to_string(), value.to_string());
+        });
+    }
+
     /// Create a new raw text node.
     ///
     /// Do not pass unsanitized user input to this function. When the node is rendered, no escaping
@@ -128,26 +141,44 @@ impl GenericNode for SsrNode {
         let hk = get_next_id();
         let mut attributes = IndexMap::new();
         if let Some(hk) = hk {
-            attributes.insert("data-hk".to_string(), format!("{}.{}", hk.0, hk.1));
+            attributes.insert(
+                HYDRATION_KEY_ATTRIBUTE.to_string(),
+                HydrationKey {
+                    component: hk.0,
+                    node: hk.1,
+                }
+                .to_string(),
+            );
         }
-        Self::new(SsrNodeType::Element(RefCell::new(Element {
+        let node = Self::new(SsrNodeType::Element(RefCell::new(Element {
             name: Cow::Borrowed(T::TAG_NAME),
             attributes,
             children: Default::default(),
-        })))
+        })));
+        node.run_element_hooks(hk);
+        node
     }
 
     fn element_from_tag(tag: &str) -> Self {
         let hk = get_next_id();
         let mut attributes = IndexMap::new();
         if let Some(hk) = hk {
-            attributes.insert("data-hk".to_string(), format!("{}.{}", hk.0, hk.1));
+            attributes.insert(
+                HYDRATION_KEY_ATTRIBUTE.to_string(),
+                HydrationKey {
+                    component: hk.0,
+                    node: hk.1,
+                }
+                .to_string(),
+            );
         }
-        Self::new(SsrNodeType::Element(RefCell::new(Element {
+        let node = Self::new(SsrNodeType::Element(RefCell::new(Element {
             name: Cow::Owned(tag.to_string()),
             attributes,
             children: Default::default(),
-        })))
+        })));
+        node.run_element_hooks(hk);
+        node
     }
 
     fn text_node(text: &str) -> Self {
@@ -161,10 +192,13 @@ impl GenericNode for SsrNode {
     }
 
     fn set_attribute(&self, name: &str, value: &str) {
-        self.unwrap_element()
-            .borrow_mut()
-            .attributes
-            .insert(name.to_string(), value.to_string());
+        let element = self.unwrap_element();
+        if let Some(value) = attribute_hooks(|| element.borrow().name.to_string(), name, value) {
+            element
+                .borrow_mut()
+                .attributes
+                .insert(name.to_string(), value.into_owned());
+        }
     }
 
     fn remove_attribute(&self, name: &str) {
@@ -181,12 +215,15 @@ impl GenericNode for SsrNode {
         let classes = attributes.get_mut("class");
 
         if let Some(classes) = classes {
-            // Make sure classes are unique.
-            let mut class_set = HashSet::<_>::from_iter(classes.split(' '));
-
-            class_set.insert(class);
-
-            *classes = class_set.drain().collect::<Vec<_>>().join(" ");
+            // Make sure classes are unique, and keep them in the order in which they were added so
+            // that the output is deterministic.
+            let mut class_list = classes.split_ascii_whitespace().collect::<Vec<_>>();
+            for class in class.split_ascii_whitespace() {
+                if !class_list.contains(&class) {
+                    class_list.push(class);
+                }
+            }
+            *classes = class_list.join(" ");
         } else {
             attributes.insert("class".to_string(), class.to_owned());
         }
@@ -198,12 +235,12 @@ impl GenericNode for SsrNode {
         let classes = attributes.get_mut("class");
 
         if let Some(classes) = classes {
-            // Make sure classes are unique.
-            let mut class_set = HashSet::<_>::from_iter(classes.split(' '));
-
-            class_set.remove(class);
-
-            *classes = class_set.drain().collect::<Vec<_>>().join(" ");
+            let removed = class.split_ascii_whitespace().collect::<Vec<_>>();
+            *classes = classes
+                .split_ascii_whitespace()
+                .filter(|class| !removed.contains(class))
+                .collect::<Vec<_>>()
+                .join(" ");
         }
     }
 
@@ -316,13 +353,29 @@ impl GenericNode for SsrNode {
             .remove_child(self);
     }
 
-    fn event<'a, F: FnMut(Self::EventType) + 'a>(&self, _cx: Scope<'a>, _name: &str, _handler: F) {
-        // Noop. Events are attached on client side.
+    fn event<'a, F: FnMut(Self::EventType) + 'a>(&self, _cx: Scope<'a>, name: &str, _handler: F) {
+        // Events are attached on client side. Hydrated elements are annotated with the names of
+        // their events so that they can be replayed if they happen before hydration is finished.
+        // See `hydrate::EVENT_REPLAY_SCRIPT`.
+        let mut el = self.unwrap_element().borrow_mut();
+        if el.attributes.contains_key(HYDRATION_KEY_ATTRIBUTE) {
+            let events = el.attributes.entry("data-on".to_string()).or_default();
+            if !events.split(' ').any(|event| event == name) {
+                if !events.is_empty() {
+                    events.push(' ');
+                }
+                events.push_str(name);
+            }
+        }
     }
 
     fn update_inner_text(&self, text: &str) {
         match self.0.ty.as_ref() {
-            SsrNodeType::Element(el) => el.borrow_mut().children = vec![SsrNode::text_node(text)],
+            SsrNodeType::Element(el) => {
+                let text = SsrNode::text_node(text);
+                text.set_parent(Rc::downgrade(&self.0));
+                el.borrow_mut().children = vec![text];
+            }
             SsrNodeType::Comment(_c) => panic!("cannot update inner text on comment node"),
             SsrNodeType::Text(t) => t.borrow_mut().0 = text.to_string(),
             SsrNodeType::RawText(_t) => panic!("cannot update inner text on raw text node"),
@@ -345,12 +398,24 @@ impl GenericNode for SsrNode {
             ty: Rc::new(self.0.ty.as_ref().clone()),
             parent: RefCell::new(Weak::new()),
         };
-        Self(Rc::new(inner))
+        let node = Self(Rc::new(inner));
+        // The children are shared with `self` after the shallow clone above.
+        if let SsrNodeType::Element(element) = node.0.ty.as_ref() {
+            for child in &mut element.borrow_mut().children {
+                *child = child.clone_node();
+                child.set_parent(Rc::downgrade(&node.0));
+            }
+        }
+        node
     }
 }
 
 impl Html for SsrNode {
     const IS_BROWSER: bool = false;
+
+    fn is_element(&self) -> bool {
+        matches!(self.0.ty.as_ref(), SsrNodeType::Element(_))
+    }
 }
 
 /// Write the [`SsrNode`] to a string buffer.
@@ -537,6 +602,35 @@ mod tests {
         assert_eq!(node.first_child().as_ref(), None);
     }
 
+    #[test]
+    fn clone_node() {
+        let node = SsrNode::element::<html::div>();
+        let p = SsrNode::element::<html::p>();
+        node.append_child(&p);
+
+        let clone = node.clone_node();
+        let p_clone = clone.first_child().unwrap();
+        // the children should be cloned too
+        assert_ne!(p_clone, p);
+        assert_eq!(p_clone.parent_node().as_ref(), Some(&clone));
+        p_clone.set_attribute("class", "cloned");
+        assert_eq!(render_to_string(|_| View::new_node(node)), "<div><p></p></div>");
+        assert_eq!(
+            render_to_string(|_| View::new_node(clone)),
+            "<div><p class=\"cloned\"></p></div>"
+        );
+    }
+
+    #[test]
+    fn update_inner_text() {
+        let node = SsrNode::element::<html::div>();
+        node.update_inner_text("text");
+        // the text node parent should be node
+        let text = node.first_child().unwrap();
+        assert_eq!(text.parent_node().as_ref(), Some(&node));
+        assert_eq!(text.next_sibling(), None);
+    }
+
     #[test]
     fn remove_child_2() {
         let node = SsrNode::element::<html::div>();
@@ -0,0 +1,203 @@
+//! A development overlay that highlights the parts of the page that are updated by dynamic views.
+//!
+//! Once enabled with [`enable_update_overlay`], every time a dynamic view is re-rendered in the
+//! browser, the updated nodes briefly flash with an outline labelled with the name of the
+//! component in which the dynamic view was created. A panel in the bottom right corner of the
+//! page lists the components with the most updates so far, which helps finding components that
+//! update unexpectedly often.
+//!
+//! Updates are only tracked in debug builds with the `diagnostics` feature. The overlay is not
+//! part of the view tree: its elements are added directly to the `<body>` and never receive
+//! pointer events.
+//!
+//! # Example
+//! ```no_run
+//! use sycamore::prelude::*;
+//! use sycamore::web::update_overlay::enable_update_overlay;
+//!
+//! #[component]
+//! fn Counter<G: Html>(cx: Scope) -> View<G> {
+//!     let count = create_signal(cx, 0);
+//!     view! { cx,
+//!         button(on:click=|_| count.set(*count.get() + 1)) { (count.get()) }
+//!     }
+//! }
+//!
+//! enable_update_overlay();
+//! sycamore::render(|cx| view! { cx, Counter {} });
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::Reverse;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+/// How long an update is highlighted, in milliseconds.
+const FLASH_DURATION_MS: i32 = 600;
+/// The number of components listed in the panel.
+const PANEL_ROWS: usize = 10;
+/// The name used for dynamic views that are not created inside of a component.
+const NO_COMPONENT: &str = "<root>";
+
+const FLASH_STYLE: &str = "position:fixed;pointer-events:none;z-index:2147483647;\
+    box-sizing:border-box;border:2px solid #f97316;background:rgba(249,115,22,0.15);\
+    font:10px/1.4 monospace;color:#fff;";
+const LABEL_STYLE: &str = "position:absolute;top:-1.4em;left:-2px;padding:0 3px;\
+    background:#f97316;white-space:nowrap;";
+const PANEL_STYLE: &str = "position:fixed;pointer-events:none;z-index:2147483647;right:8px;\
+    bottom:8px;padding:4px 8px;background:rgba(0,0,0,0.75);color:#fff;font:11px/1.4 monospace;\
+    white-space:pre;";
+
+/// The state of the overlay while it is enabled.
+#[derive(Default)]
+struct Overlay {
+    /// The panel listing the components with the most updates. Created with the first update.
+    panel: Option<Element>,
+    /// The number of updates per component, in the order the components were first updated.
+    counts: Vec<(&'static str, usize)>,
+}
+
+thread_local! {
+    static OVERLAY: RefCell<Option<Overlay>> = const { RefCell::new(None) };
+}
+
+/// Enables the update overlay. See the [module-level documentation](self).
+pub fn enable_update_overlay() {
+    OVERLAY.with(|overlay| {
+        overlay.borrow_mut().get_or_insert_with(Default::default);
+    });
+}
+
+/// Disables the update overlay, removes its panel and forgets the update counts.
+pub fn disable_update_overlay() {
+    if let Some(Overlay {
+        panel: Some(panel), ..
+    }) = OVERLAY.with(|overlay| overlay.borrow_mut().take())
+    {
+        panel.remove();
+    }
+}
+
+/// Returns the number of updates per component since the overlay was enabled, the most updated
+/// component first. Returns an empty `Vec` if the overlay is disabled.
+pub fn update_counts() -> Vec<(&'static str, usize)> {
+    let mut counts = OVERLAY.with(|overlay| {
+        overlay
+            .borrow()
+            .as_ref()
+            .map(|overlay| overlay.counts.clone())
+            .unwrap_or_default()
+    });
+    // The sort is stable, so components with the same count stay in the order they were first
+    // updated.
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+    counts
+}
+
+/// Records an update of a dynamic view and highlights its `nodes`. Does nothing if the overlay is
+/// disabled.
+pub(crate) fn flash(nodes: &[Node], component: Option<&'static str>) {
+    let component = component.unwrap_or(NO_COMPONENT);
+    let count = OVERLAY.with(|overlay| {
+        let mut overlay = overlay.borrow_mut();
+        let overlay = overlay.as_mut()?;
+        let count = match overlay
+            .counts
+            .iter_mut()
+            .find(|(name, _)| *name == component)
+        {
+            Some((_, count)) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                overlay.counts.push((component, 1));
+                1
+            }
+        };
+        Some(count)
+    });
+    let Some(count) = count else {
+        return;
+    };
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let Some(body) = document.body() else {
+        return;
+    };
+
+    // Text and comment nodes are highlighted with their parent element. Each element is only
+    // highlighted once.
+    let mut elements: Vec<Element> = Vec::new();
+    for node in nodes {
+        let element = match node.dyn_ref::<Element>() {
+            Some(element) => Some(element.clone()),
+            None => node.parent_element(),
+        };
+        if let Some(element) = element {
+            if !elements.iter().any(|e| e.is_same_node(Some(&element))) {
+                elements.push(element);
+            }
+        }
+    }
+
+    let label = format!("{component} ×{count}");
+    for element in elements {
+        let rect = element.get_bounding_client_rect();
+        if rect.width() == 0.0 && rect.height() == 0.0 {
+            continue;
+        }
+        let flash = document.create_element("div").unwrap_throw();
+        let style = format!(
+            "{FLASH_STYLE}left:{}px;top:{}px;width:{}px;height:{}px;",
+            rect.left(),
+            rect.top(),
+            rect.width(),
+            rect.height()
+        );
+        flash.set_attribute("style", &style).unwrap_throw();
+        let text = document.create_element("span").unwrap_throw();
+        text.set_attribute("style", LABEL_STYLE).unwrap_throw();
+        text.set_text_content(Some(&label));
+        flash.append_child(&text).unwrap_throw();
+        body.append_child(&flash).unwrap_throw();
+
+        let remove = Closure::once_into_js(move || flash.remove());
+        web_sys::window()
+            .unwrap_throw()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                remove.unchecked_ref(),
+                FLASH_DURATION_MS,
+            )
+            .unwrap_throw();
+    }
+
+    update_panel(&document, &body);
+}
+
+/// Updates the panel listing the components with the most updates, creating it if necessary.
+fn update_panel(document: &web_sys::Document, body: &web_sys::HtmlElement) {
+    let text = update_counts()
+        .into_iter()
+        .take(PANEL_ROWS)
+        .map(|(name, count)| format!("{count:>5} {name}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    OVERLAY.with(|overlay| {
+        if let Some(overlay) = overlay.borrow_mut().as_mut() {
+            let panel = overlay.panel.get_or_insert_with(|| {
+                let panel = document.create_element("div").unwrap_throw();
+                panel.set_attribute("style", PANEL_STYLE).unwrap_throw();
+                panel
+            });
+            panel.set_text_content(Some(&text));
+            // Keep the panel in the page, e.g. if the body was cleared.
+            if !body.contains(Some(panel)) {
+                body.append_child(panel).unwrap_throw();
+            }
+        }
+    });
+}
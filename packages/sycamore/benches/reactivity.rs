@@ -33,7 +33,7 @@ pub fn bench(c: &mut Criterion) {
         b.iter(|| {
             create_scope_immediate(|cx| {
                 let v = create_signal(cx, (0..100).collect());
-                let mapped = map_indexed(cx, v, |_, x| x * 2);
+                let mapped = map_indexed(cx, v, |_, x| *x.get() * 2);
                 mapped.track();
 
                 v.set((100..200).collect());
@@ -41,7 +41,7 @@ pub fn bench(c: &mut Criterion) {
                         Indexed {
                             iterable: values,
                             view: |cx, x| view! { cx,
-                                ListItem(x)
+                                ListItem(*x.get())
                             }
                         }
                     }
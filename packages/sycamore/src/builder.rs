@@ -7,6 +7,7 @@ use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
+use crate::attributes::{AttributeValue, Attributes};
 use crate::component::component_scope;
 use crate::generic_node::GenericNode;
 use crate::noderef::NodeRef;
@@ -28,6 +29,7 @@ use crate::web::Html;
 /// ```
 pub mod prelude {
     pub use super::{component, dyn_t, fragment, t, tag};
+    pub use crate::t_fmt;
     #[cfg(feature = "web")]
     pub use crate::web::html::*;
 }
@@ -84,7 +86,7 @@ impl<'a, G: GenericNode, F: FnOnce(Scope<'a>) -> G + 'a> ElementBuilderOrView<'a
 /// // etc...
 /// ```
 pub fn tag<'a, G: GenericNode>(
-    t: impl AsRef<str>,
+    t: impl AsRef<str> + 'a,
 ) -> ElementBuilder<'a, G, impl FnOnce(Scope<'a>) -> G> {
     ElementBuilder::new(move |_| G::element_from_tag(t.as_ref()))
 }
@@ -124,6 +126,55 @@ impl<'a, G: GenericNode, F: FnOnce(Scope<'a>) -> G + 'a> ElementBuilder<'a, G, F
         self.map(move |_, el| el.set_attribute(name, value.as_ref()))
     }
 
+    /// Set the attribute of the element from any [`AttributeValue`]. Unlike [`Self::attr`], the
+    /// value can be a signal or a [`MaybeDyn`], in which case the attribute is updated whenever the
+    /// value changes. Booleans are set as boolean attributes and `None` removes the attribute.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore::builder::prelude::*;
+    /// # use sycamore::prelude::*;
+    /// # fn _test<G: GenericNode>(cx: Scope) -> View<G> {
+    /// let count = create_signal(cx, 0);
+    /// let disabled = create_signal(cx, false);
+    /// button()
+    ///     .attr_value("data-count", count)
+    ///     .attr_value("disabled", disabled)
+    ///     .attr_value("title", MaybeDyn::derived(|| format!("Clicked {} times", count.get())))
+    /// # .view(cx) }
+    /// ```
+    pub fn attr_value(
+        self,
+        name: &'static str,
+        value: impl AttributeValue<'a> + 'a,
+    ) -> ElementBuilder<'a, G, impl FnOnce(Scope<'a>) -> G + 'a> {
+        self.map(move |cx, el| value.set_attribute(cx, el, name))
+    }
+
+    /// Set all the attributes of a struct implementing [`Attributes`](trait@Attributes) on the
+    /// element. This is the equivalent of spreading attributes with `..` in the `view!` macro.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore::builder::prelude::*;
+    /// # use sycamore::prelude::*;
+    /// #[derive(Attributes)]
+    /// struct Link {
+    ///     href: &'static str,
+    ///     target: &'static str,
+    /// }
+    ///
+    /// # fn _test<G: GenericNode>(cx: Scope) -> View<G> {
+    /// a().attrs(Link { href: "https://sycamore-rs.netlify.app", target: "_blank" })
+    /// # .view(cx) }
+    /// ```
+    pub fn attrs(
+        self,
+        attributes: impl Attributes<'a, G> + 'a,
+    ) -> ElementBuilder<'a, G, impl FnOnce(Scope<'a>) -> G + 'a> {
+        self.map(move |cx, el| attributes.apply_attributes(cx, el))
+    }
+
     /// Set the boolean attribute of the element.
     ///
     /// # Example
@@ -386,6 +437,9 @@ impl<'a, G: GenericNode, F: FnOnce(Scope<'a>) -> G + 'a> ElementBuilder<'a, G, F
 
     /// Adds a dynamic text node.
     ///
+    /// The [`t_fmt!`](crate::t_fmt) macro can be used to create the closure with the same syntax
+    /// as [`format!`].
+    ///
     /// # Example
     /// ```
     /// # use sycamore::builder::prelude::*;
@@ -395,6 +449,7 @@ impl<'a, G: GenericNode, F: FnOnce(Scope<'a>) -> G + 'a> ElementBuilder<'a, G, F
     /// p()
     ///     .t("Name: ")
     ///     .dyn_t(|| name.get().to_string())
+    ///     .dyn_t(t_fmt!(" ({} characters)", name.get().len()))
     /// # .view(cx) }
     /// ```
     pub fn dyn_t<S: AsRef<str> + 'a>(
@@ -423,7 +478,7 @@ impl<'a, G: GenericNode, F: FnOnce(Scope<'a>) -> G + 'a> ElementBuilder<'a, G, F
     /// ```
     pub fn c(
         self,
-        c: impl ElementBuilderOrView<'a, G>,
+        c: impl ElementBuilderOrView<'a, G> + 'a,
     ) -> ElementBuilder<'a, G, impl FnOnce(Scope<'a>) -> G + 'a> {
         self.map(|cx, el| render::insert(cx, el, c.into_view(cx), None, None, true))
     }
@@ -839,3 +894,23 @@ pub fn dyn_t<'a, G: GenericNode, S: AsRef<str>>(
 ) -> View<G> {
     View::new_dyn(cx, move || View::new_node(G::text_node(f().as_ref())))
 }
+
+/// Creates a closure that formats its arguments with [`format!`], for use with
+/// [`ElementBuilder::dyn_t`] and [`dyn_t`]. Signals read in the arguments are tracked, so the text
+/// is updated whenever they change.
+///
+/// # Example
+/// ```
+/// # use sycamore::builder::prelude::*;
+/// # use sycamore::prelude::*;
+/// # fn _test<G: GenericNode>(cx: Scope) -> View<G> {
+/// let count = create_signal(cx, 0);
+/// p().dyn_t(t_fmt!("Count: {}", count.get()))
+/// # .view(cx) }
+/// ```
+#[macro_export]
+macro_rules! t_fmt {
+    ($($arg:tt)*) => {
+        move || ::std::format!($($arg)*)
+    };
+}
@@ -0,0 +1,70 @@
+//! Catching errors with the [`ErrorBoundary`] component.
+//!
+//! See the [`sycamore_core::error_boundary`] module for more details about which errors are
+//! caught.
+
+use std::marker::PhantomData;
+
+pub use sycamore_core::error_boundary::{
+    throw_error, try_error_boundary, view_or_throw, BoundaryError, ErrorReset,
+};
+
+use crate::prelude::*;
+
+/// Props for [`ErrorBoundary`].
+#[derive(Prop, Debug)]
+pub struct ErrorBoundaryProps<'a, G: GenericNode, V, F>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+    F: Fn(BoundedScope<'_, 'a>, BoundaryError, ErrorReset) -> View<G> + 'a,
+{
+    /// Renders the children of the boundary. Called again every time the boundary is reset.
+    view: V,
+    /// Renders the [`View`] that is displayed instead of the children once an error is caught.
+    /// Receives the error and an [`ErrorReset`] that renders the children again.
+    fallback: F,
+    #[builder(default)]
+    _marker: PhantomData<&'a ()>,
+}
+
+/// Catches the panics, the errors passed to [`view_or_throw`] and the errors thrown with
+/// [`throw_error`] by the views rendered inside of it and renders `fallback` instead, so that a
+/// failing component does not take down the entire app.
+///
+/// Unlike other components, the children are passed as a `view` closure so that they can be
+/// rendered again when the boundary is reset.
+///
+/// # Example
+/// ```
+/// # use sycamore::prelude::*;
+/// # use sycamore::error_boundary::ErrorBoundary;
+/// #[component]
+/// fn Profile<G: Html>(cx: Scope) -> View<G> {
+///     throw_error(cx, "could not load the profile");
+///     view! { cx, }
+/// }
+///
+/// # fn app<G: Html>(cx: Scope) -> View<G> {
+/// view! { cx,
+///     ErrorBoundary {
+///         view: |cx| view! { cx, Profile {} },
+///         fallback: |cx, err, reset| view! { cx,
+///             p { (err.to_string()) }
+///             button(on:click=move |_| reset.reset()) { "Retry" }
+///         },
+///     }
+/// }
+/// # }
+/// ```
+#[component]
+pub fn ErrorBoundary<'a, G: GenericNode, V, F>(
+    cx: Scope<'a>,
+    props: ErrorBoundaryProps<'a, G, V, F>,
+) -> View<G>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+    F: Fn(BoundedScope<'_, 'a>, BoundaryError, ErrorReset) -> View<G> + 'a,
+{
+    let ErrorBoundaryProps { view, fallback, .. } = props;
+    sycamore_core::error_boundary::error_boundary(cx, view, fallback)
+}
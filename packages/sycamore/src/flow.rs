@@ -1,14 +1,26 @@
 //! Iteration utility components for [view!](crate::view!).
 //!
 //! Iteration can be either _"keyed"_ or _"non keyed"_.
-//! Use the [`Keyed`] and [`Indexed`] utility components respectively.
+//! Use the [`Keyed`] and [`Indexed`] utility components respectively. Lists stored in a
+//! [`ReactiveVec`] are iterated with [`KeyedVec`]. Views that are not lists, e.g. components in
+//! positions that depend on some state, can be keyed with [`KeyedFragments`].
 
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::hash::Hash;
+use std::rc::Rc;
+
+use ahash::{AHashMap, AHashSet};
 
 use crate::prelude::*;
 
+/// Called by [`Keyed`] with the nodes of a row that was inserted.
+type EnterHook<'a, G> = Box<dyn Fn(&[G]) + 'a>;
+/// Called by [`Keyed`] with the nodes of a row that was removed and a function that detaches them.
+type ExitHook<'a, G> = Box<dyn Fn(&[G], Box<dyn FnOnce()>) + 'a>;
+
 /// Props for [`Keyed`].
-#[derive(Prop, Debug)]
+#[derive(Prop)]
 pub struct KeyedProps<'a, T, F, G: GenericNode, K, Key>
 where
     F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
@@ -21,12 +33,46 @@ where
     view: F,
     /// The key function that assigns each element in `iterable` an unique key.
     key: K,
+    /// Called with the nodes of every row that is inserted after the initial render. The nodes
+    /// are not attached yet, so this can be used to set the initial state of an enter
+    /// transition, e.g. by adding a class that is removed on the next animation frame.
+    #[builder(default, setter(strip_option))]
+    on_enter: Option<EnterHook<'a, G>>,
+    /// Called with the nodes of every row that is removed. The nodes are kept in the list, at
+    /// their previous position, until the function passed as the second argument is called. This
+    /// can be used to run an exit transition before the row is detached.
+    ///
+    /// The scope of the row is disposed immediately, so the nodes are no longer updated while the
+    /// transition runs.
+    #[builder(default, setter(strip_option))]
+    on_exit: Option<ExitHook<'a, G>>,
+}
+
+impl<'a, T, F, G: GenericNode, K, Key> fmt::Debug for KeyedProps<'a, T, F, G, K, Key>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    K: Fn(&T) -> Key + 'a,
+    Key: Clone + Hash + Eq,
+    T: Clone + PartialEq,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedProps")
+            .field("on_enter", &self.on_enter.as_ref().map(|_| "Fn(&[G])"))
+            .field(
+                "on_exit",
+                &self.on_exit.as_ref().map(|_| "Fn(&[G], Box<dyn FnOnce()>)"),
+            )
+            .finish_non_exhaustive()
+    }
 }
 
 /// Keyed iteration. Use this instead of directly rendering an array of [`View`]s.
 /// Using this will minimize re-renders instead of re-rendering every view node on every
 /// state change.
 ///
+/// Rows can be animated when they are inserted or removed with the `on_enter` and `on_exit`
+/// props.
+///
 /// For non keyed iteration, see [`Indexed`].
 #[component]
 pub fn Keyed<'a, G: GenericNode, T, F, K, Key>(
@@ -43,9 +89,161 @@ where
         iterable,
         view,
         key,
+        on_enter,
+        on_exit,
     } = props;
 
     let mapped = map_keyed(cx, iterable, view, key);
+    if on_enter.is_none() && on_exit.is_none() {
+        return View::new_dyn(cx, || View::new_fragment(mapped.get().as_ref().clone()));
+    }
+
+    let rows = Rc::new(RefCell::new(KeyedRows::default()));
+    let displayed = create_rc_signal(Vec::new());
+    let next_id = Cell::new(0);
+    // `None` before the initial render.
+    let mut prev = None;
+    create_effect(cx, {
+        let displayed = displayed.clone();
+        move || {
+            let new = mapped.get();
+            rows.borrow_mut().current = new.as_ref().clone();
+            let Some(prev) = prev.replace(Rc::clone(&new)) else {
+                displayed.set(rows.borrow_mut().update());
+                return;
+            };
+            let new_nodes: AHashSet<G> = new.iter().filter_map(first_node).collect();
+            let prev_nodes: AHashSet<G> = prev.iter().filter_map(first_node).collect();
+            let mut removed = Vec::new();
+            if on_exit.is_some() {
+                for view in prev.iter() {
+                    if first_node(view).map_or(false, |node| !new_nodes.contains(&node)) {
+                        let id = next_id.get();
+                        next_id.set(id + 1);
+                        rows.borrow_mut().exiting.push((id, view.clone()));
+                        removed.push((id, view.clone()));
+                    }
+                }
+            }
+            displayed.set(rows.borrow_mut().update());
+
+            if let Some(on_exit) = &on_exit {
+                for (id, view) in removed {
+                    let done = Box::new({
+                        let rows = Rc::clone(&rows);
+                        let displayed = displayed.clone();
+                        move || {
+                            rows.borrow_mut().exiting.retain(|(row, _)| *row != id);
+                            displayed.set(rows.borrow_mut().update());
+                        }
+                    });
+                    untrack(|| on_exit(&view.flatten(), done));
+                }
+            }
+            if let Some(on_enter) = &on_enter {
+                for view in new.iter() {
+                    if first_node(view).map_or(false, |node| !prev_nodes.contains(&node)) {
+                        untrack(|| on_enter(&view.clone().flatten()));
+                    }
+                }
+            }
+        }
+    });
+
+    View::new_dyn(cx, move || {
+        View::new_fragment(displayed.get().as_ref().clone())
+    })
+}
+
+fn first_node<G: GenericNode>(view: &View<G>) -> Option<G> {
+    view.clone().flatten().into_iter().next()
+}
+
+/// The rows displayed by [`Keyed`] when it has an `on_exit` hook.
+struct KeyedRows<G: GenericNode> {
+    /// The rows that are in the list.
+    current: Vec<View<G>>,
+    /// The rows that were removed but are kept until their exit transition is done, by id.
+    exiting: Vec<(u32, View<G>)>,
+    /// The rows that are displayed, i.e. the current and the exiting rows.
+    displayed: Vec<View<G>>,
+}
+
+impl<G: GenericNode> Default for KeyedRows<G> {
+    fn default() -> Self {
+        Self {
+            current: Vec::new(),
+            exiting: Vec::new(),
+            displayed: Vec::new(),
+        }
+    }
+}
+
+impl<G: GenericNode> KeyedRows<G> {
+    /// Updates and returns the displayed rows. The exiting rows are kept after the row that was
+    /// displayed before them.
+    fn update(&mut self) -> Vec<View<G>> {
+        let current: AHashSet<G> = self.current.iter().filter_map(first_node).collect();
+        let exiting: AHashSet<G> = self
+            .exiting
+            .iter()
+            .filter_map(|(_, view)| first_node(view))
+            .collect();
+        let mut views = self.current.clone();
+        let mut anchor = None;
+        for view in &self.displayed {
+            let Some(node) = first_node(view) else {
+                continue;
+            };
+            if exiting.contains(&node) {
+                let index = anchor
+                    .as_ref()
+                    .and_then(|anchor| {
+                        views
+                            .iter()
+                            .position(|view| first_node(view).as_ref() == Some(anchor))
+                    })
+                    .map_or(0, |index| index + 1);
+                views.insert(index, view.clone());
+            }
+            if current.contains(&node) || exiting.contains(&node) {
+                anchor = Some(node);
+            }
+        }
+        self.displayed = views.clone();
+        views
+    }
+}
+
+/// Props for [`KeyedVec`].
+#[derive(Prop, Debug)]
+pub struct KeyedVecProps<'a, G: GenericNode, T, F>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+{
+    iterable: &'a ReactiveVec<T>,
+    /// The map function that renders a [`View`] for each element in `iterable`.
+    view: F,
+}
+
+/// Iteration over a [`ReactiveVec`]. This is the same as [`Keyed`] but instead of diffing the
+/// whole list against its previous value on every change, the changes recorded by the
+/// [`ReactiveVec`] are applied directly. This is much faster for long lists where only a few
+/// items change at a time.
+///
+/// No key function is needed because the [`ReactiveVec`] knows which item moved where.
+#[component]
+pub fn KeyedVec<'a, G: GenericNode, T, F>(
+    cx: Scope<'a>,
+    props: KeyedVecProps<'a, G, T, F>,
+) -> View<G>
+where
+    T: Clone + 'a,
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+{
+    let KeyedVecProps { iterable, view } = props;
+
+    let mapped = map_vec(cx, iterable, view);
     View::new_dyn(cx, || View::new_fragment(mapped.get().as_ref().clone()))
 }
 
@@ -53,10 +251,11 @@ where
 #[derive(Prop, Debug)]
 pub struct IndexedProps<'a, G: GenericNode, T, F>
 where
-    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    F: for<'child> Fn(BoundedScope<'child, 'a>, &'child ReadSignal<T>) -> View<G> + 'a,
 {
     iterable: &'a ReadSignal<Vec<T>>,
-    /// The map function that renders a [`View`] for each element in `iterable`.
+    /// The map function that renders a [`View`] for each index in `iterable`. The map function
+    /// receives a signal that is updated whenever the value at that index changes.
     view: F,
 }
 
@@ -64,15 +263,158 @@ where
 /// [`View`]s. Using this will minimize re-renders instead of re-rendering every single
 /// node on every state change.
 ///
+/// Each index keeps the same scope and nodes for as long as it exists. When the value at an index
+/// changes, only the signal passed to `view` for that index is updated. Scopes are only created or
+/// disposed when the length of `iterable` changes, so appending or removing items at the end of
+/// the list only touches the affected rows.
+///
 /// For keyed iteration, see [`Keyed`].
 #[component]
 pub fn Indexed<'a, G: GenericNode, T, F>(cx: Scope<'a>, props: IndexedProps<'a, G, T, F>) -> View<G>
 where
-    T: Clone + PartialEq,
-    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    T: Clone + PartialEq + 'a,
+    F: for<'child> Fn(BoundedScope<'child, 'a>, &'child ReadSignal<T>) -> View<G> + 'a,
 {
     let IndexedProps { iterable, view } = props;
 
     let mapped = map_indexed(cx, iterable, view);
     View::new_dyn(cx, || View::new_fragment(mapped.get().as_ref().clone()))
 }
+
+/// The keyed fragments of a [`KeyedFragments`] view.
+pub struct Fragments<'a, G: GenericNode, Key> {
+    cx: Scope<'a>,
+    fragments: RefCell<AHashMap<Key, (View<G>, ScopeDisposer<'a>)>>,
+    /// The keys of the fragments that are used by the current render.
+    used: RefCell<AHashSet<Key>>,
+}
+
+impl<'a, G: GenericNode, Key: Clone + Hash + Eq> Fragments<'a, G, Key> {
+    /// Returns the fragment with the key `key`. If there was a fragment with this key in the
+    /// previous render, its view is reused, including its nodes and its scope. Otherwise, `view`
+    /// is called to render the fragment in a new scope.
+    pub fn get(
+        &self,
+        key: Key,
+        view: impl for<'child> FnOnce(BoundedScope<'child, 'a>) -> View<G>,
+    ) -> View<G> {
+        self.used.borrow_mut().insert(key.clone());
+        if let Some((view, _)) = self.fragments.borrow().get(&key) {
+            return view.clone();
+        }
+        let mut rendered = None;
+        let disposer = untrack(|| {
+            create_child_scope(self.cx, |cx| {
+                rendered = Some(view(cx));
+            })
+        });
+        let rendered = rendered.unwrap();
+        self.fragments
+            .borrow_mut()
+            .insert(key, (rendered.clone(), disposer));
+        rendered
+    }
+
+    /// Disposes the fragments that were not used by the current render.
+    fn dispose_unused(&self) {
+        let used = std::mem::take(&mut *self.used.borrow_mut());
+        let unused = {
+            let mut fragments = self.fragments.borrow_mut();
+            let keys = fragments
+                .keys()
+                .filter(|key| !used.contains(key))
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.into_iter()
+                .filter_map(|key| fragments.remove(&key))
+                .collect::<Vec<_>>()
+        };
+        for (_, disposer) in unused {
+            // SAFETY: the view of the fragment is no longer rendered.
+            unsafe { disposer.dispose() };
+        }
+    }
+}
+
+impl<'a, G: GenericNode, Key> fmt::Debug for Fragments<'a, G, Key> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Fragments")
+            .field("len", &self.fragments.borrow().len())
+            .finish()
+    }
+}
+
+/// Props for [`KeyedFragments`].
+#[derive(Prop)]
+pub struct KeyedFragmentsProps<'a, G: GenericNode, Key, F>
+where
+    F: for<'child> FnMut(BoundedScope<'child, 'a>, &'child Fragments<'a, G, Key>) -> View<G> + 'a,
+{
+    /// Renders the view, using [`Fragments::get`] for the keyed parts of it. Called again
+    /// whenever the signals that it uses change.
+    view: F,
+    #[builder(default, setter(skip))]
+    _phantom: std::marker::PhantomData<&'a (G, Key)>,
+}
+
+impl<'a, G: GenericNode, Key, F> fmt::Debug for KeyedFragmentsProps<'a, G, Key, F>
+where
+    F: for<'child> FnMut(BoundedScope<'child, 'a>, &'child Fragments<'a, G, Key>) -> View<G> + 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyedFragmentsProps")
+            .finish_non_exhaustive()
+    }
+}
+
+/// A dynamic view whose parts are keyed, like the rows of [`Keyed`]. Each part, or fragment, is
+/// rendered with [`Fragments::get`] and a key. When the view is rendered again, the fragments with
+/// the same keys are reused and moved to their new position instead of being rendered again, so
+/// that their nodes and state are kept. The fragments that are not used anymore are disposed.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+///
+/// #[component]
+/// fn Layout<G: Html>(cx: Scope) -> View<G> {
+///     let sidebar_first = create_signal(cx, true);
+///     view! { cx,
+///         KeyedFragments {
+///             view: |cx, fragments| {
+///                 let sidebar = fragments.get("sidebar", |cx| view! { cx, aside { "Sidebar" } });
+///                 let main = fragments.get("main", |cx| view! { cx, main { "Content" } });
+///                 if *sidebar_first.get() {
+///                     view! { cx, (sidebar) (main) }
+///                 } else {
+///                     view! { cx, (main) (sidebar) }
+///                 }
+///             },
+///         }
+///     }
+/// }
+/// ```
+#[component]
+pub fn KeyedFragments<'a, G: GenericNode, Key, F>(
+    cx: Scope<'a>,
+    props: KeyedFragmentsProps<'a, G, Key, F>,
+) -> View<G>
+where
+    Key: Clone + Hash + Eq + 'a,
+    F: for<'child> FnMut(BoundedScope<'child, 'a>, &'child Fragments<'a, G, Key>) -> View<G> + 'a,
+{
+    let KeyedFragmentsProps { mut view, .. } = props;
+    let fragments = create_ref(
+        cx,
+        Fragments {
+            cx,
+            fragments: RefCell::new(AHashMap::new()),
+            used: RefCell::new(AHashSet::new()),
+        },
+    );
+    View::new_dyn_scoped(cx, move |cx| {
+        let rendered = view(cx, fragments);
+        fragments.dispose_unused();
+        rendered
+    })
+}
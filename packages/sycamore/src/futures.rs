@@ -12,7 +12,10 @@
 //!
 //! To find out more about suspense, read the [docs for the suspense module](crate::suspense).
 
+use std::cell::Cell;
+use std::fmt;
 use std::future::Future;
+use std::rc::Rc;
 
 // Re-export `sycamore-futures` crate.
 pub use sycamore_futures::*;
@@ -21,11 +24,12 @@ pub use wasm_bindgen_futures::*;
 
 use crate::prelude::*;
 
-/// Create a new async resource.
+/// Creates a new async resource that resolves once. The returned signal is `None` until the
+/// future is resolved.
 ///
-/// TODO: docs + example
-
-pub fn create_resource<'a, U: 'a, F>(cx: Scope<'a>, f: F) -> RcSignal<Option<U>>
+/// To refetch the data when a signal changes, or to handle errors, use [`create_resource`]
+/// instead.
+pub fn create_resource_once<'a, U: 'a, F>(cx: Scope<'a>, f: F) -> RcSignal<Option<U>>
 where
     F: Future<Output = U> + 'a,
 {
@@ -40,3 +44,271 @@ where
 
     signal
 }
+
+/// A change applied with [`Optimistic::apply`] that has not been confirmed yet.
+type Change<'a, T> = (u32, Rc<dyn Fn(&mut T) + 'a>);
+
+/// A value shown optimistically while changes are being confirmed. Created with
+/// [`create_optimistic`].
+pub struct Optimistic<'a, T, E> {
+    cx: Scope<'a>,
+    source: &'a Signal<T>,
+    changes: &'a Signal<Vec<Change<'a, T>>>,
+    value: &'a ReadSignal<T>,
+    pending: &'a ReadSignal<usize>,
+    error: &'a Signal<Option<E>>,
+    next_id: Cell<u32>,
+}
+
+impl<'a, T: Clone + 'a, E: 'a> Optimistic<'a, T, E> {
+    /// The value of the source signal with all the pending changes applied. This is the value
+    /// that should be displayed.
+    pub fn value(&self) -> &'a ReadSignal<T> {
+        self.value
+    }
+
+    /// The number of changes that have not been confirmed yet. Useful for showing a "Saving..."
+    /// indicator.
+    pub fn pending(&self) -> &'a ReadSignal<usize> {
+        self.pending
+    }
+
+    /// The error of the latest change that was rolled back, or `None` if no change was rolled
+    /// back since the latest successful one.
+    pub fn error(&self) -> &'a ReadSignal<Option<E>> {
+        self.error
+    }
+
+    /// Shows `update` immediately and waits for `confirm` to resolve. If `confirm` succeeds,
+    /// `update` is applied to the source signal. Otherwise, it is rolled back.
+    ///
+    /// While the change is pending, `update` is applied again every time the source signal
+    /// changes, e.g. because the data was refetched from the server.
+    pub fn apply<F, Fut>(&'a self, update: F, confirm: Fut)
+    where
+        F: Fn(&mut T) + 'a,
+        Fut: Future<Output = Result<(), E>> + 'a,
+    {
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+        let update: Rc<dyn Fn(&mut T) + 'a> = Rc::new(update);
+        self.changes.modify().push((id, Rc::clone(&update)));
+
+        spawn_local_scoped(self.cx, async move {
+            let result = confirm.await;
+            // Remove the change without notifying the subscribers so that the value is only
+            // recomputed once the source signal is updated.
+            let mut changes = self.changes.get_untracked().as_ref().clone();
+            changes.retain(|(change, _)| *change != id);
+            self.changes.set_silent(changes);
+            match result {
+                Ok(()) => {
+                    self.error.set(None);
+                    update(&mut self.source.modify());
+                }
+                Err(err) => self.error.set(Some(err)),
+            }
+            self.changes.trigger_subscribers();
+        });
+    }
+}
+
+impl<'a, T: fmt::Debug, E> fmt::Debug for Optimistic<'a, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Optimistic")
+            .field("value", &*self.value.get_untracked())
+            .field("pending", &*self.pending.get_untracked())
+            .finish()
+    }
+}
+
+/// Creates an [`Optimistic`] overlay on top of `source`, usually a signal holding data fetched
+/// from the server.
+///
+/// # Example
+/// ```
+/// use sycamore::futures::create_optimistic;
+/// use sycamore::prelude::*;
+///
+/// async fn save_likes(likes: u32) -> Result<(), String> {
+///     // Send the new value to the server...
+///     Ok(())
+/// }
+///
+/// #[component]
+/// fn Likes<G: Html>(cx: Scope) -> View<G> {
+///     let likes = create_signal(cx, 0);
+///     let optimistic = create_optimistic(cx, likes);
+///     let like = move |_| {
+///         let new_likes = *optimistic.value().get() + 1;
+///         optimistic.apply(|likes| *likes += 1, save_likes(new_likes));
+///     };
+///     view! { cx,
+///         button(on:click=like) { (optimistic.value().get()) " likes" }
+///         (if *optimistic.pending().get() > 0 { "Saving..." } else { "" })
+///     }
+/// }
+/// ```
+pub fn create_optimistic<'a, T: Clone + 'a, E: 'a>(
+    cx: Scope<'a>,
+    source: &'a Signal<T>,
+) -> &'a Optimistic<'a, T, E> {
+    let changes: &Signal<Vec<Change<'a, T>>> = create_signal(cx, Vec::new());
+    let value = create_memo(cx, move || {
+        let mut value = source.get().as_ref().clone();
+        for (_, update) in changes.get().iter() {
+            update(&mut value);
+        }
+        value
+    });
+    let pending = create_memo(cx, move || changes.get().len());
+    create_ref(
+        cx,
+        Optimistic {
+            cx,
+            source,
+            changes,
+            value,
+            pending,
+            error: create_signal(cx, None),
+            next_id: Cell::new(0),
+        },
+    )
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use futures::channel::oneshot;
+    use sycamore_futures::{provide_executor_scope, spawn_local_scoped};
+
+    use super::{create_optimistic, create_resource};
+    use crate::prelude::*;
+
+    #[tokio::test]
+    async fn commit_and_rollback() {
+        provide_executor_scope(async {
+            let (sender, receiver) = oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let source = create_signal(cx, 1);
+                let optimistic = create_optimistic(cx, source);
+                let (commit, confirm) = oneshot::channel::<Result<(), &str>>();
+                let (rollback, reject) = oneshot::channel::<Result<(), &str>>();
+                optimistic.apply(|n| *n += 1, async { confirm.await.unwrap() });
+                optimistic.apply(|n| *n *= 10, async { reject.await.unwrap() });
+                assert_eq!(*optimistic.value().get(), 20);
+                assert_eq!(*optimistic.pending().get(), 2);
+
+                // Pending changes are applied on top of the new source value.
+                source.set(2);
+                assert_eq!(*optimistic.value().get(), 30);
+
+                spawn_local_scoped(cx, async move {
+                    commit.send(Ok(())).unwrap();
+                    while *optimistic.pending().get() == 2 {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*source.get(), 3);
+                    assert_eq!(*optimistic.value().get(), 30);
+
+                    rollback.send(Err("error")).unwrap();
+                    while *optimistic.pending().get() == 1 {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*optimistic.value().get(), 3);
+                    assert_eq!(*optimistic.error().get(), Some("error"));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn resource() {
+        provide_executor_scope(async {
+            let (sender, receiver) = oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let id = create_signal(cx, 1);
+                let (first, first_result) = oneshot::channel::<Result<u32, &str>>();
+                let (second, second_result) = oneshot::channel::<Result<u32, &str>>();
+                let results = create_ref(
+                    cx,
+                    std::cell::RefCell::new(vec![second_result, first_result]),
+                );
+                let resource = create_resource(
+                    cx,
+                    || *id.get(),
+                    |_| {
+                        let result = results.borrow_mut().pop().unwrap();
+                        async move { result.await.unwrap() }
+                    },
+                );
+                assert!(*resource.loading().get());
+                assert_eq!(*resource.value().get(), None);
+
+                // Changing the source starts a new fetch.
+                id.set(2);
+
+                spawn_local_scoped(cx, async move {
+                    // The result of the first fetch is ignored.
+                    first.send(Ok(10)).unwrap();
+                    tokio::task::yield_now().await;
+                    assert_eq!(*resource.value().get(), None);
+                    assert!(*resource.loading().get());
+
+                    second.send(Ok(20)).unwrap();
+                    while *resource.loading().get() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*resource.value().get(), Some(20));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn resource_error_and_refetch() {
+        provide_executor_scope(async {
+            let (sender, receiver) = oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let fail = create_ref(cx, std::cell::Cell::new(true));
+                let resource = create_resource(
+                    cx,
+                    || (),
+                    |_| async {
+                        if fail.get() {
+                            Err("failed")
+                        } else {
+                            Ok(1)
+                        }
+                    },
+                );
+                spawn_local_scoped(cx, async move {
+                    while *resource.loading().get() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*resource.error().get(), Some("failed"));
+                    assert_eq!(*resource.value().get(), None);
+
+                    fail.set(false);
+                    resource.refetch();
+                    while *resource.loading().get() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert_eq!(*resource.error().get(), None);
+                    assert_eq!(*resource.value().get(), Some(1));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+}
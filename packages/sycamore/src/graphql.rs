@@ -0,0 +1,653 @@
+//! A GraphQL client with a normalized reactive cache.
+//!
+//! The client does not make any network requests itself. Instead, it is created with a transport
+//! function which sends a [`GraphQLRequest`] to the server (e.g. with `fetch`) and returns the JSON
+//! response. The client is then provided to the component tree using [`provide_context`] and used
+//! by [`create_query`] and [`create_graphql_mutation`].
+//!
+//! # Normalization
+//! Every object in a response that has an `id` field is stored in the cache of the client, keyed
+//! by its `__typename` and `id`. Queries read the fields of these objects from the cache, which
+//! means that when a query or a mutation returns an object that is already in the cache, all the
+//! queries that contain this object are updated. Only the fields selected by a query are read
+//! from the cache.
+//!
+//! _This API requires the following crate features to be activated: `graphql`_
+//!
+//! # Example
+//! ```
+//! use sycamore::graphql::{create_query, GraphQLClient, GraphQLError};
+//! use sycamore::prelude::*;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! #[derive(serde::Deserialize)]
+//! struct UserQuery {
+//!     user: User,
+//! }
+//!
+//! #[component]
+//! fn UserName<G: Html>(cx: Scope) -> View<G> {
+//!     let id = create_signal(cx, serde_json::json!({ "id": "1" }));
+//!     let user = create_query::<_, UserQuery>(
+//!         cx,
+//!         "query User($id: ID!) { user(id: $id) { id __typename name } }",
+//!         id,
+//!     );
+//!     view! { cx,
+//!         (match user.get().as_ref() {
+//!             Some(Ok(data)) => data.user.name.clone(),
+//!             Some(Err(err)) => err.to_string(),
+//!             None => "Loading...".to_string(),
+//!         })
+//!     }
+//! }
+//!
+//! # fn app<G: Html>(cx: Scope) -> View<G> {
+//! provide_context(
+//!     cx,
+//!     GraphQLClient::new(|request| async move {
+//!         // Send `request` to the GraphQL endpoint and return the JSON response.
+//!         # let _ = request;
+//!         Err::<serde_json::Value, _>(GraphQLError::new("not implemented"))
+//!     }),
+//! );
+//! view! { cx, UserName {} }
+//! # }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sycamore_futures::{create_resource, spawn_local_scoped};
+
+use crate::prelude::*;
+
+/// The future returned by the transport of a [`GraphQLClient`].
+pub type GraphQLResponseFuture = Pin<Box<dyn Future<Output = Result<Value, GraphQLError>>>>;
+
+/// A request sent to a GraphQL server. Serializes to the standard JSON body of a GraphQL request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphQLRequest {
+    /// The GraphQL document.
+    pub query: String,
+    /// The variables of the document.
+    pub variables: Value,
+}
+
+/// An error returned by a GraphQL server, or an error while sending the request or converting
+/// values to or from JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQLError {
+    messages: Vec<String>,
+}
+
+impl GraphQLError {
+    /// Creates a new error with a single message. Use this to report errors from the transport.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            messages: vec![message.into()],
+        }
+    }
+
+    /// Returns the error messages. A GraphQL response can contain multiple errors.
+    pub fn messages(&self) -> &[String] {
+        &self.messages
+    }
+}
+
+impl fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.messages.join("; "))
+    }
+}
+
+impl std::error::Error for GraphQLError {}
+
+impl From<serde_json::Error> for GraphQLError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(err.to_string())
+    }
+}
+
+/// The fields selected by a query, derived from the shape of its response.
+#[derive(Debug, Clone, Default)]
+enum Selection {
+    #[default]
+    Leaf,
+    Object(HashMap<String, Selection>),
+}
+
+impl Selection {
+    fn of(value: &Value) -> Self {
+        let mut selection = Self::Leaf;
+        selection.merge(value);
+        selection
+    }
+
+    /// Adds the fields of `value` to the selection. Elements of arrays are merged together.
+    fn merge(&mut self, value: &Value) {
+        match value {
+            Value::Array(values) => values.iter().for_each(|value| self.merge(value)),
+            Value::Object(fields) => {
+                if let Self::Leaf = self {
+                    *self = Self::Object(HashMap::new());
+                }
+                if let Self::Object(selection) = self {
+                    for (name, value) in fields {
+                        selection.entry(name.clone()).or_default().merge(value);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A normalized response: the objects with an id are replaced with references to the cache.
+#[derive(Debug, Clone)]
+struct Normalized {
+    data: Value,
+    selection: Selection,
+}
+
+/// The key of the reference objects that replace cached objects in normalized responses.
+const REF: &str = "__ref";
+
+/// Cache of the objects with an id, keyed by `__typename` and `id`.
+#[derive(Default)]
+struct GraphQLCache {
+    entities: RefCell<HashMap<String, RcSignal<Map<String, Value>>>>,
+}
+
+impl GraphQLCache {
+    fn key(fields: &Map<String, Value>) -> Option<String> {
+        let id = match fields.get("id")? {
+            Value::String(id) => id.clone(),
+            Value::Number(id) => id.to_string(),
+            _ => return None,
+        };
+        match fields.get("__typename").and_then(Value::as_str) {
+            Some(typename) => Some(format!("{typename}:{id}")),
+            None => Some(id),
+        }
+    }
+
+    /// Stores the objects of `value` in the cache, updating all the queries that read them, and
+    /// returns `value` with the cached objects replaced by references.
+    fn normalize(&self, value: &Value) -> Value {
+        match value {
+            Value::Array(values) => values.iter().map(|value| self.normalize(value)).collect(),
+            Value::Object(fields) => {
+                let fields: Map<String, Value> = fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), self.normalize(value)))
+                    .collect();
+                let Some(key) = Self::key(&fields) else {
+                    return Value::Object(fields);
+                };
+                // Clone the signal out of the map so that queries can read the cache when the
+                // entity is updated.
+                let entity = self.entities.borrow().get(&key).cloned();
+                match entity {
+                    Some(entity) => {
+                        let mut merged = entity.get_untracked().as_ref().clone();
+                        merged.extend(fields);
+                        if merged != *entity.get_untracked() {
+                            entity.set(merged);
+                        }
+                    }
+                    None => {
+                        self.entities
+                            .borrow_mut()
+                            .insert(key.clone(), create_rc_signal(fields));
+                    }
+                }
+                serde_json::json!({ REF: key })
+            }
+            value => value.clone(),
+        }
+    }
+
+    /// Reads the fields in `selection` from `value`, following references to the cache. Reading
+    /// a cached object subscribes the current effect or memo to it.
+    fn denormalize(&self, value: &Value, selection: &Selection) -> Value {
+        let fields_selection = match selection {
+            Selection::Leaf => return value.clone(),
+            Selection::Object(fields_selection) => fields_selection,
+        };
+        match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| self.denormalize(value, selection))
+                .collect(),
+            Value::Object(fields) => {
+                let entity = fields
+                    .get(REF)
+                    .and_then(Value::as_str)
+                    .and_then(|key| self.entities.borrow().get(key).cloned());
+                let entity = entity.map(|entity| entity.get());
+                let fields = entity.as_deref().unwrap_or(fields);
+                fields_selection
+                    .iter()
+                    .map(|(name, selection)| {
+                        let value = fields.get(name).unwrap_or(&Value::Null);
+                        (name.clone(), self.denormalize(value, selection))
+                    })
+                    .collect()
+            }
+            value => value.clone(),
+        }
+    }
+}
+
+struct ClientInner {
+    transport: Box<dyn Fn(GraphQLRequest) -> GraphQLResponseFuture>,
+    cache: GraphQLCache,
+}
+
+/// A GraphQL client. Cloning the client is cheap and the clones share the same cache.
+///
+/// The client should be provided to the component tree with [`provide_context`] to be used by
+/// [`create_query`] and [`create_graphql_mutation`].
+#[derive(Clone)]
+pub struct GraphQLClient {
+    inner: Rc<ClientInner>,
+}
+
+impl GraphQLClient {
+    /// Creates a new client with an empty cache. `transport` sends a request to the server and
+    /// returns the JSON body of the response, which is an object with `data` and/or `errors`
+    /// fields.
+    pub fn new<F, Fut>(transport: F) -> Self
+    where
+        F: Fn(GraphQLRequest) -> Fut + 'static,
+        Fut: Future<Output = Result<Value, GraphQLError>> + 'static,
+    {
+        Self {
+            inner: Rc::new(ClientInner {
+                transport: Box::new(move |request| Box::pin(transport(request))),
+                cache: GraphQLCache::default(),
+            }),
+        }
+    }
+
+    /// Executes `document` with `variables` and stores the objects of the response in the cache.
+    ///
+    /// The result is not updated when the cache changes. Use [`create_query`] for that.
+    pub async fn execute<V: Serialize, R: DeserializeOwned>(
+        &self,
+        document: &str,
+        variables: &V,
+    ) -> Result<R, GraphQLError> {
+        let request = GraphQLRequest {
+            query: document.to_string(),
+            variables: serde_json::to_value(variables)?,
+        };
+        let normalized = self.execute_normalized(request).await?;
+        self.read(&normalized)
+    }
+
+    async fn execute_normalized(
+        &self,
+        request: GraphQLRequest,
+    ) -> Result<Normalized, GraphQLError> {
+        let mut response = (self.inner.transport)(request).await?;
+        if let Some(Value::Array(errors)) = response.get("errors") {
+            if !errors.is_empty() {
+                let messages = errors
+                    .iter()
+                    .map(|error| match error.get("message").and_then(Value::as_str) {
+                        Some(message) => message.to_string(),
+                        None => error.to_string(),
+                    })
+                    .collect();
+                return Err(GraphQLError { messages });
+            }
+        }
+        let data = match response.get_mut("data").map(Value::take) {
+            Some(data) if !data.is_null() => data,
+            _ => return Err(GraphQLError::new("missing `data` in GraphQL response")),
+        };
+        Ok(Normalized {
+            selection: Selection::of(&data),
+            data: self.inner.cache.normalize(&data),
+        })
+    }
+
+    fn read<R: DeserializeOwned>(&self, normalized: &Normalized) -> Result<R, GraphQLError> {
+        let data = self
+            .inner
+            .cache
+            .denormalize(&normalized.data, &normalized.selection);
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Returns the fields of the cached object with the given key (`"{__typename}:{id}"`, or just
+    /// the id if the object has no `__typename`). Nested cached objects are returned as
+    /// `{ "__ref": key }`.
+    ///
+    /// Subscribes the current effect or memo to changes of the object.
+    pub fn cached(&self, key: &str) -> Option<Map<String, Value>> {
+        let entity = self.inner.cache.entities.borrow().get(key).cloned();
+        entity.map(|entity| entity.get().as_ref().clone())
+    }
+}
+
+impl fmt::Debug for GraphQLClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphQLClient")
+            .field("cached", &self.inner.cache.entities.borrow().len())
+            .finish()
+    }
+}
+
+/// Creates a resource that executes the GraphQL query `document` with the [`GraphQLClient`]
+/// provided in the context.
+///
+/// `variables` is tracked: the query is executed again every time it changes. Results of outdated
+/// executions are ignored. Executions are awaited by the closest `Suspense`.
+///
+/// The result is updated every time an object of the response is updated in the cache, e.g. by a
+/// mutation created with [`create_graphql_mutation`]. The returned signal is `None` until the
+/// first execution resolves.
+///
+/// # Panics
+/// This function panics if no [`GraphQLClient`] is provided in the context.
+pub fn create_query<'a, V, R>(
+    cx: Scope<'a>,
+    document: &'a str,
+    variables: &'a ReadSignal<V>,
+) -> &'a ReadSignal<Option<Result<R, GraphQLError>>>
+where
+    V: Serialize,
+    R: DeserializeOwned + 'a,
+{
+    let client = use_context::<GraphQLClient>(cx);
+    let normalized = create_resource(
+        cx,
+        move || serde_json::to_value(&*variables.get()),
+        move |variables| async move {
+            let result = match variables {
+                Ok(variables) => {
+                    let request = GraphQLRequest {
+                        query: document.to_string(),
+                        variables,
+                    };
+                    client.execute_normalized(request).await
+                }
+                Err(err) => Err(err.into()),
+            };
+            Ok::<_, Infallible>(result)
+        },
+    );
+    create_memo(cx, move || {
+        normalized
+            .value()
+            .get()
+            .as_ref()
+            .as_ref()
+            .map(|result| match result {
+                Ok(normalized) => client.read(normalized),
+                Err(err) => Err(err.clone()),
+            })
+    })
+}
+
+/// A GraphQL mutation created with [`create_graphql_mutation`].
+pub struct GraphQLMutation<'a, V, R> {
+    cx: Scope<'a>,
+    client: &'a GraphQLClient,
+    document: &'a str,
+    data: &'a Signal<Option<Result<R, GraphQLError>>>,
+    pending: &'a Signal<u32>,
+    generation: Cell<u32>,
+    _variables: std::marker::PhantomData<fn(V)>,
+}
+
+impl<'a, V: Serialize, R: DeserializeOwned + 'a> GraphQLMutation<'a, V, R> {
+    /// Executes the mutation with `variables`. The objects returned by the mutation are stored in
+    /// the cache, which updates all the queries that contain them.
+    ///
+    /// If the mutation is executed again before the previous execution resolves, only the result
+    /// of the latest execution is stored in [`GraphQLMutation::data`].
+    pub fn mutate(&'a self, variables: V) {
+        let request = serde_json::to_value(&variables).map(|variables| GraphQLRequest {
+            query: self.document.to_string(),
+            variables,
+        });
+        self.generation.set(self.generation.get() + 1);
+        let current = self.generation.get();
+        self.pending.set(*self.pending.get_untracked() + 1);
+        spawn_local_scoped(self.cx, async move {
+            let result = match request {
+                Ok(request) => match self.client.execute_normalized(request).await {
+                    Ok(normalized) => self.client.read(&normalized),
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err.into()),
+            };
+            if self.generation.get() == current {
+                self.data.set(Some(result));
+            }
+            self.pending.set(*self.pending.get_untracked() - 1);
+        });
+    }
+
+    /// The result of the latest execution. `None` until an execution resolves.
+    pub fn data(&self) -> &'a ReadSignal<Option<Result<R, GraphQLError>>> {
+        self.data
+    }
+
+    /// Returns `true` while an execution is in progress. This value can be tracked.
+    pub fn is_loading(&self) -> bool {
+        *self.pending.get() > 0
+    }
+}
+
+impl<'a, V, R> fmt::Debug for GraphQLMutation<'a, V, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphQLMutation")
+            .field("document", &self.document)
+            .field("pending", &*self.pending.get_untracked())
+            .finish()
+    }
+}
+
+/// Creates a GraphQL mutation that executes `document` with the [`GraphQLClient`] provided in the
+/// context. The mutation is executed by calling [`GraphQLMutation::mutate`].
+///
+/// # Example
+/// ```
+/// use sycamore::graphql::create_graphql_mutation;
+/// use sycamore::prelude::*;
+///
+/// #[component]
+/// fn Rename<G: Html>(cx: Scope) -> View<G> {
+///     let rename = create_graphql_mutation::<_, serde_json::Value>(
+///         cx,
+///         "mutation Rename($name: String!) { rename(id: \"1\", name: $name) { id __typename name } }",
+///     );
+///     view! { cx,
+///         button(on:click=move |_| rename.mutate(serde_json::json!({ "name": "Bob" }))) {
+///             (if rename.is_loading() { "Renaming..." } else { "Rename" })
+///         }
+///     }
+/// }
+/// ```
+///
+/// # Panics
+/// This function panics if no [`GraphQLClient`] is provided in the context.
+pub fn create_graphql_mutation<'a, V, R>(
+    cx: Scope<'a>,
+    document: &'a str,
+) -> &'a GraphQLMutation<'a, V, R>
+where
+    V: 'a,
+    R: 'a,
+{
+    create_ref(
+        cx,
+        GraphQLMutation {
+            cx,
+            client: use_context::<GraphQLClient>(cx),
+            document,
+            data: create_signal(cx, None),
+            pending: create_signal(cx, 0),
+            generation: Cell::new(0),
+            _variables: std::marker::PhantomData,
+        },
+    )
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+    use sycamore_futures::provide_executor_scope;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        id: String,
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct UserQuery {
+        user: User,
+    }
+
+    /// A transport that answers with a user named after the `name` variable, or `Alice`.
+    fn client() -> GraphQLClient {
+        GraphQLClient::new(|request: GraphQLRequest| async move {
+            tokio::task::yield_now().await;
+            if request.query.contains("fail") {
+                return Ok(json!({ "errors": [{ "message": "boom" }] }));
+            }
+            let name = request.variables.get("name").cloned();
+            let user = json!({
+                "__typename": "User",
+                "id": "1",
+                "name": name.unwrap_or_else(|| "Alice".into()),
+                "friends": [{ "__typename": "User", "id": "1" }],
+            });
+            Ok(json!({ "data": { "user": user } }))
+        })
+    }
+
+    async fn wait_until(f: impl Fn() -> bool) {
+        while !f() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn mutation_updates_query() {
+        provide_executor_scope(async {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let disposer = create_scope(|cx| {
+                provide_context(cx, client());
+                let variables = create_signal(cx, json!({}));
+                let query = create_query::<_, UserQuery>(cx, "query", variables);
+                let mutation = create_graphql_mutation::<_, Value>(cx, "mutation");
+                let names = create_ref(cx, RefCell::new(Vec::new()));
+                create_effect(cx, || {
+                    if let Some(Ok(data)) = query.get().as_ref() {
+                        names.borrow_mut().push(data.user.name.clone());
+                    }
+                });
+                spawn_local_scoped(cx, async move {
+                    wait_until(|| query.get().is_some()).await;
+                    mutation.mutate(json!({ "name": "Bob" }));
+                    assert!(mutation.is_loading());
+                    wait_until(|| !mutation.is_loading()).await;
+                    assert_eq!(*names.borrow(), ["Alice", "Bob"]);
+                    assert!(mutation.data().get().as_ref().as_ref().unwrap().is_ok());
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn query_variables_and_errors() {
+        provide_executor_scope(async {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let client = create_ref(cx, client());
+                provide_context(cx, client.clone());
+                let variables = create_signal(cx, json!({ "name": "Carol" }));
+                let query = create_query::<_, UserQuery>(cx, "query", variables);
+                let failing = create_query::<_, UserQuery>(cx, "fail", variables);
+                spawn_local_scoped(cx, async move {
+                    wait_until(|| query.get().is_some()).await;
+                    assert_eq!(
+                        query
+                            .get()
+                            .as_ref()
+                            .as_ref()
+                            .unwrap()
+                            .as_ref()
+                            .unwrap()
+                            .user,
+                        User {
+                            id: "1".to_string(),
+                            name: "Carol".to_string()
+                        }
+                    );
+                    // The response references the same user, which is stored once.
+                    let cached = client.cached("User:1").unwrap();
+                    assert_eq!(cached["friends"], json!([{ REF: "User:1" }]));
+
+                    variables.set(json!({ "name": "Dave" }));
+                    wait_until(|| client.cached("User:1").unwrap()["name"] == "Dave").await;
+                    let user = client.execute::<_, UserQuery>("query", &json!({})).await;
+                    assert_eq!(user.unwrap().user.name, "Alice");
+                    assert_eq!(
+                        query
+                            .get()
+                            .as_ref()
+                            .as_ref()
+                            .unwrap()
+                            .as_ref()
+                            .unwrap()
+                            .user
+                            .name,
+                        "Alice"
+                    );
+
+                    wait_until(|| failing.get().is_some()).await;
+                    let err = failing
+                        .get()
+                        .as_ref()
+                        .as_ref()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap_err()
+                        .clone();
+                    assert_eq!(err.messages(), ["boom"]);
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+}
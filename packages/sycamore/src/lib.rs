@@ -15,6 +15,43 @@
 //! - `ssr` - Enables rendering templates to static strings (useful for Server Side Rendering /
 //!   Pre-rendering).
 //!
+//! - `auth` - Enables the [`web::auth`] module for managing the session of the logged in user.
+//!
+//! - `axum` - Enables the [`web::server::axum`] module for rendering pages in `axum` handlers.
+//!   This feature requires Rust 1.80, the minimum Rust version of `axum` 0.8, instead of the
+//!   Rust 1.65 required by the rest of Sycamore.
+//!
+//! - `builder` (_default_) - Enables the [`builder`] API, an alternative to the `view!` macro, and
+//!   the element builder functions in [`web::html`].
+//!
+//! - `custom-events` - Enables the [`web::custom_event`] module for dispatching and listening to
+//!   `CustomEvent`s with a `detail` serialized using `serde`.
+//!
+//! - `diagnostics` (_default_) - Enables sanity checks and debugging aids in debug builds, such as
+//!   update cycle detection and warnings about DOM nodes inserted by third-party scripts. Release
+//!   builds never include them.
+//!
+//! - `graphql` - Enables the [`graphql`] module, a GraphQL client with a normalized reactive cache.
+//!
+//! - `islands` - Enables the [`web::island`] module for hydrating only the components annotated
+//!   with `#[component(island)]`.
+//!
+//! - `leak-detection` - Counts the reactive scopes, signals and effects that are alive. See
+//!   [`reactive::leak_report`].
+//!
+//! - `rest` - Enables the [`rest`] module for managing collections fetched from a REST endpoint.
+//!
+//! - `tauri` - Enables the [`web::tauri`] module for using Sycamore as a [Tauri](https://tauri.app)
+//!   frontend.
+//!
+//! - `test-ids` - Allows adding a `data-testid` attribute to every element created by a
+//!   component, made of the name of the component and the hydration key of the element (e.g.
+//!   `Counter-1.2`), once enabled with `component::set_test_ids`. This gives end-to-end tests
+//!   stable selectors. Should only be enabled in test builds.
+//!
+//! - `server` - Enables the [`web::server`] module for rendering pages on the server and handing
+//!   state over to the client.
+//!
 //! - `serde` - Enables serializing and deserializing `Signal`s and other wrapper types using
 //!   `serde`.
 //!
@@ -38,12 +75,19 @@
 #[allow(unused_extern_crates)] // False positive
 extern crate self as sycamore;
 
+#[cfg(feature = "builder")]
 pub mod builder;
 pub mod easing;
+pub mod error_boundary;
 pub mod flow;
 #[cfg(feature = "suspense")]
 pub mod futures;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 pub mod motion;
+#[cfg(feature = "rest")]
+pub mod rest;
+pub mod strict_mode;
 #[cfg(feature = "suspense")]
 pub mod suspense;
 pub mod utils;
@@ -51,7 +95,7 @@ pub mod utils;
 pub mod web;
 
 /* Re-export modules from sycamore-core */
-pub use sycamore_core::{component, generic_node, noderef, view};
+pub use sycamore_core::{attributes, component, generic_node, noderef, template, view};
 /* Re-export of the sycamore-macro crate */
 pub use sycamore_macro::*;
 
@@ -64,12 +108,12 @@ pub mod reactive {
 
 #[cfg(feature = "ssr")]
 pub use web::render_to_string;
-#[cfg(all(feature = "ssr", feature = "suspense"))]
-pub use web::render_to_string_await_suspense;
 #[cfg(all(feature = "web", feature = "hydrate"))]
 pub use web::{hydrate, hydrate_get_scope, hydrate_to};
 #[cfg(feature = "web")]
-pub use web::{render, render_get_scope, render_to};
+pub use web::{render, render_detached, render_get_scope, render_to};
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+pub use web::{render_to_string_await_suspense, render_to_string_stream};
 
 /// The sycamore prelude.
 ///
@@ -82,11 +126,14 @@ pub use web::{render, render_get_scope, render_to};
 pub mod prelude {
     pub use sycamore_macro::*;
 
-    pub use crate::component::Children;
+    pub use crate::attributes::{AttributeMap, Attributes};
+    pub use crate::component::{Children, ChildrenWith};
+    pub use crate::error_boundary::{throw_error, view_or_throw, ErrorBoundary};
     pub use crate::flow::*;
     pub use crate::generic_node::GenericNode;
-    pub use crate::noderef::{create_node_ref, NodeRef};
+    pub use crate::noderef::{create_node_ref, merge_node_refs, provide_exit_handler, NodeRef};
     pub use crate::reactive::*;
+    pub use crate::strict_mode::StrictMode;
     pub use crate::view::View;
     #[cfg(feature = "web")]
     pub use crate::web::on_mount;
@@ -95,7 +142,7 @@ pub mod prelude {
     #[cfg(feature = "ssr")]
     pub use crate::web::SsrNode;
     #[cfg(feature = "web")]
-    pub use crate::web::{DomNode, Html};
+    pub use crate::web::{DomNode, Html, NodeRefExt};
 }
 
 /// Re-exports for use by `sycamore-macro`. Not intended for use by end-users.
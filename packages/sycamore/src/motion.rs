@@ -22,7 +22,7 @@ pub fn create_raf<'a>(cx: Scope<'a>, _f: impl FnMut() + 'a) -> RafState<'a> {
     let stop: &dyn Fn();
 
     // Only run on wasm32 architecture.
-    #[cfg(all(target_arch = "wasm32", feature = "web"))]
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
     {
         use wasm_bindgen::prelude::*;
         use wasm_bindgen::JsCast;
@@ -66,7 +66,7 @@ pub fn create_raf<'a>(cx: Scope<'a>, _f: impl FnMut() + 'a) -> RafState<'a> {
         });
         stop = create_ref(cx, || running.set(false));
     }
-    #[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
     {
         start = create_ref(cx, || running.set(true));
         stop = create_ref(cx, || running.set(false));
@@ -206,7 +206,7 @@ impl<'a, T: Lerp + Clone + 'a> Tweened<'a, T> {
     ///
     /// If not running on `wasm32-unknown-unknown`, does nothing.
     pub fn set(&self, _new_value: T) {
-        #[cfg(all(target_arch = "wasm32", feature = "web"))]
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
         {
             use js_sys::Date;
 
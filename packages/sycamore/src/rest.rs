@@ -0,0 +1,624 @@
+//! Reactive collections of items fetched from a REST endpoint.
+//!
+//! [`create_collection`] fetches a list of items from an endpoint and keeps it in a signal. Items
+//! are created, updated and deleted with the methods of [`Collection`], which update the list
+//! immediately (optimistic updates) and roll back the change if the request fails.
+//!
+//! Requests are sent with the [`RestClient`] provided in the context, or with `fetch` if no client
+//! is provided. Items are sent and received as JSON using `serde`:
+//! - `GET {endpoint}` returns the list of items.
+//! - `POST {endpoint}` creates an item and returns the created item.
+//! - `PUT {endpoint}/{id}` updates an item and returns the updated item.
+//! - `DELETE {endpoint}/{id}` deletes an item.
+//!
+//! _This API requires the following crate features to be activated: `rest`_
+
+use std::fmt;
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sycamore_futures::spawn_local_scoped;
+
+use crate::prelude::*;
+use crate::suspense::suspense_scope;
+
+/// An item of a [`Collection`].
+pub trait CollectionItem: Clone + Eq + Serialize + DeserializeOwned + 'static {
+    /// The type of the id of the item.
+    type Id: Clone + Hash + Eq + fmt::Display + 'static;
+
+    /// Returns the id of the item. The id is used as the key when rendering the collection and
+    /// in the url of the requests for this item.
+    fn id(&self) -> Self::Id;
+}
+
+/// The HTTP method of a [`RestRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestMethod {
+    /// `GET`
+    Get,
+    /// `POST`
+    Post,
+    /// `PUT`
+    Put,
+    /// `DELETE`
+    Delete,
+}
+
+impl RestMethod {
+    /// Returns the name of the method, e.g. `"GET"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+/// A request sent by a [`Collection`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestRequest {
+    /// The HTTP method.
+    pub method: RestMethod,
+    /// The url of the request.
+    pub url: String,
+    /// The JSON body of the request, if any.
+    pub body: Option<Value>,
+}
+
+/// An error returned by a REST endpoint, or an error while sending the request or converting
+/// values to or from JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestError {
+    status: Option<u16>,
+    message: String,
+}
+
+impl RestError {
+    /// Creates a new error. `status` is the HTTP status of the response, if any.
+    pub fn new(status: Option<u16>, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the HTTP status of the response, or `None` if the request failed before receiving
+    /// a response.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for RestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "{status}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for RestError {}
+
+impl From<serde_json::Error> for RestError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(None, err.to_string())
+    }
+}
+
+/// The future returned by the transport of a [`RestClient`].
+pub type RestResponseFuture = Pin<Box<dyn Future<Output = Result<Value, RestError>>>>;
+
+/// Sends the requests of [`Collection`]s. Provide a client in the context to customize how
+/// requests are sent, e.g. to add authentication headers or to render collections on the server.
+#[derive(Clone)]
+pub struct RestClient {
+    transport: Rc<dyn Fn(RestRequest) -> RestResponseFuture>,
+}
+
+impl RestClient {
+    /// Creates a new client. `transport` sends a request and returns the JSON body of the
+    /// response, or [`Value::Null`] if the response has no body.
+    pub fn new<F, Fut>(transport: F) -> Self
+    where
+        F: Fn(RestRequest) -> Fut + 'static,
+        Fut: Future<Output = Result<Value, RestError>> + 'static,
+    {
+        Self {
+            transport: Rc::new(move |request| Box::pin(transport(request))),
+        }
+    }
+
+    /// Sends `request` and returns the JSON body of the response.
+    pub async fn send(&self, request: RestRequest) -> Result<Value, RestError> {
+        (self.transport)(request).await
+    }
+}
+
+/// Uses `fetch` on `wasm32` target. Other targets have no default transport and every request
+/// fails.
+impl Default for RestClient {
+    fn default() -> Self {
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        return Self::new(fetch::fetch);
+        #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+        Self::new(|request: RestRequest| async move {
+            Err(RestError::new(
+                None,
+                format!("no RestClient provided to send {}", request.url),
+            ))
+        })
+    }
+}
+
+impl fmt::Debug for RestClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestClient").finish()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+mod fetch {
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    use super::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = fetch)]
+        fn fetch_with_init(url: &str, init: &JsValue) -> js_sys::Promise;
+
+        type Response;
+        #[wasm_bindgen(method, getter)]
+        fn ok(this: &Response) -> bool;
+        #[wasm_bindgen(method, getter)]
+        fn status(this: &Response) -> u16;
+        #[wasm_bindgen(method)]
+        fn text(this: &Response) -> js_sys::Promise;
+    }
+
+    impl From<JsValue> for RestError {
+        fn from(value: JsValue) -> Self {
+            let message = value
+                .dyn_ref::<js_sys::Error>()
+                .map(|err| String::from(err.message()))
+                .or_else(|| value.as_string())
+                .unwrap_or_else(|| "unknown error".to_string());
+            Self::new(None, message)
+        }
+    }
+
+    pub async fn fetch(request: RestRequest) -> Result<Value, RestError> {
+        let init = js_sys::Object::new();
+        js_sys::Reflect::set(&init, &"method".into(), &request.method.as_str().into())?;
+        if let Some(body) = &request.body {
+            let headers = js_sys::Object::new();
+            js_sys::Reflect::set(&headers, &"Content-Type".into(), &"application/json".into())?;
+            js_sys::Reflect::set(&init, &"headers".into(), &headers)?;
+            js_sys::Reflect::set(&init, &"body".into(), &serde_json::to_string(body)?.into())?;
+        }
+        let response: Response = JsFuture::from(fetch_with_init(&request.url, &init))
+            .await?
+            .unchecked_into();
+        let text = JsFuture::from(response.text())
+            .await?
+            .as_string()
+            .unwrap_or_default();
+        if !response.ok() {
+            return Err(RestError::new(Some(response.status()), text));
+        }
+        if text.is_empty() {
+            Ok(Value::Null)
+        } else {
+            Ok(serde_json::from_str(&text)?)
+        }
+    }
+}
+
+/// A reactive list of items fetched from a REST endpoint. Created with [`create_collection`].
+pub struct Collection<'a, T: CollectionItem> {
+    cx: Scope<'a>,
+    client: RestClient,
+    endpoint: String,
+    items: &'a Signal<Vec<T>>,
+    error: &'a Signal<Option<RestError>>,
+    pending: &'a Signal<u32>,
+}
+
+impl<'a, T: CollectionItem> Collection<'a, T> {
+    /// The items of the collection, including the changes that are still in progress.
+    pub fn items(&self) -> &'a ReadSignal<Vec<T>> {
+        self.items
+    }
+
+    /// The error of the latest failed request, if any. Cleared when a request succeeds.
+    pub fn error(&self) -> &'a ReadSignal<Option<RestError>> {
+        self.error
+    }
+
+    /// Returns `true` while a request is in progress. This value can be tracked.
+    pub fn is_loading(&self) -> bool {
+        *self.pending.get() > 0
+    }
+
+    /// Renders the items with [`Keyed`], using the ids of the items as keys.
+    pub fn view<G: GenericNode>(
+        &'a self,
+        view: impl Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    ) -> View<G> {
+        let cx = self.cx;
+        view! { cx,
+            Keyed {
+                iterable: self.items,
+                view: view,
+                key: T::id,
+            }
+        }
+    }
+
+    /// Fetches the items again, replacing the current items.
+    pub fn refetch(&'a self) {
+        spawn_local_scoped(self.cx, self.fetch());
+    }
+
+    /// Adds `item` to the collection and creates it on the server. The item is replaced with the
+    /// item returned by the server, e.g. with an id assigned by the server. The id of `item`
+    /// should therefore be a temporary id that is unique within the collection.
+    ///
+    /// The item is removed if the request fails.
+    pub fn create(&'a self, item: T) {
+        let id = item.id();
+        let body = serde_json::to_value(&item);
+        self.items.modify().push(item);
+        self.send(RestMethod::Post, self.endpoint.clone(), body, move |res| {
+            let mut items = self.items.modify();
+            let index = items.iter().position(|item| item.id() == id);
+            match (res, index) {
+                (Ok(created), Some(index)) => items[index] = created,
+                (Err(_), Some(index)) => {
+                    items.remove(index);
+                }
+                _ => {}
+            }
+        });
+    }
+
+    /// Replaces the item with the same id as `item` and updates it on the server. The item is
+    /// replaced with the item returned by the server.
+    ///
+    /// The previous item is restored if the request fails.
+    pub fn update(&'a self, item: T) {
+        let id = item.id();
+        let body = serde_json::to_value(&item);
+        let previous = {
+            let mut items = self.items.modify();
+            match items.iter_mut().find(|old| old.id() == id) {
+                Some(old) => std::mem::replace(old, item),
+                None => return,
+            }
+        };
+        let url = self.item_url(&id);
+        self.send(RestMethod::Put, url, body, move |res| {
+            let mut items = self.items.modify();
+            if let Some(item) = items.iter_mut().find(|item| item.id() == id) {
+                match res {
+                    Ok(updated) => *item = updated,
+                    Err(_) => *item = previous,
+                }
+            }
+        });
+    }
+
+    /// Removes the item with the id `id` and deletes it on the server.
+    ///
+    /// The item is inserted back at its previous position if the request fails.
+    pub fn delete(&'a self, id: T::Id) {
+        let (index, previous) = {
+            let mut items = self.items.modify();
+            match items.iter().position(|item| item.id() == id) {
+                Some(index) => (index, items.remove(index)),
+                None => return,
+            }
+        };
+        let request = RestRequest {
+            method: RestMethod::Delete,
+            url: self.item_url(&id),
+            body: None,
+        };
+        self.pending.set(*self.pending.get_untracked() + 1);
+        spawn_local_scoped(self.cx, async move {
+            let res = self.client.send(request).await;
+            if res.is_err() {
+                let mut items = self.items.modify();
+                let index = index.min(items.len());
+                items.insert(index, previous);
+            }
+            self.error.set(res.err());
+            self.pending.set(*self.pending.get_untracked() - 1);
+        });
+    }
+
+    fn item_url(&self, id: &T::Id) -> String {
+        format!("{}/{id}", self.endpoint.trim_end_matches('/'))
+    }
+
+    /// Sends a request with an item as body and calls `done` with the item returned by the
+    /// server.
+    fn send(
+        &'a self,
+        method: RestMethod,
+        url: String,
+        body: Result<Value, serde_json::Error>,
+        done: impl FnOnce(Result<T, RestError>) + 'a,
+    ) {
+        self.pending.set(*self.pending.get_untracked() + 1);
+        spawn_local_scoped(self.cx, async move {
+            let res = match body {
+                Ok(body) => {
+                    let request = RestRequest {
+                        method,
+                        url,
+                        body: Some(body),
+                    };
+                    match self.client.send(request).await {
+                        Ok(value) => serde_json::from_value(value).map_err(RestError::from),
+                        Err(err) => Err(err),
+                    }
+                }
+                Err(err) => Err(err.into()),
+            };
+            self.error.set(res.as_ref().err().cloned());
+            done(res);
+            self.pending.set(*self.pending.get_untracked() - 1);
+        });
+    }
+
+    async fn fetch(&'a self) {
+        self.pending.set(*self.pending.get_untracked() + 1);
+        let request = RestRequest {
+            method: RestMethod::Get,
+            url: self.endpoint.clone(),
+            body: None,
+        };
+        let res = match self.client.send(request).await {
+            Ok(value) => serde_json::from_value(value).map_err(RestError::from),
+            Err(err) => Err(err),
+        };
+        match res {
+            Ok(items) => {
+                self.items.set(items);
+                self.error.set(None);
+            }
+            Err(err) => self.error.set(Some(err)),
+        }
+        self.pending.set(*self.pending.get_untracked() - 1);
+    }
+}
+
+impl<'a, T: CollectionItem> fmt::Debug for Collection<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Collection")
+            .field("endpoint", &self.endpoint)
+            .field("len", &self.items.get_untracked().len())
+            .field("pending", &*self.pending.get_untracked())
+            .finish()
+    }
+}
+
+/// Creates a [`Collection`] of the items fetched from `endpoint`. The items are fetched
+/// immediately and the request is awaited by the closest `Suspense`.
+///
+/// # Example
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use sycamore::prelude::*;
+/// use sycamore::rest::{create_collection, CollectionItem};
+///
+/// #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// struct Todo {
+///     id: u32,
+///     title: String,
+/// }
+///
+/// impl CollectionItem for Todo {
+///     type Id = u32;
+///
+///     fn id(&self) -> u32 {
+///         self.id
+///     }
+/// }
+///
+/// #[component]
+/// fn Todos<G: Html>(cx: Scope) -> View<G> {
+///     let todos = create_collection::<Todo>(cx, "/api/todos");
+///     let list = todos.view(move |cx, todo| {
+///         let id = todo.id;
+///         view! { cx,
+///             li {
+///                 (todo.title)
+///                 button(on:click=move |_| todos.delete(id)) { "Delete" }
+///             }
+///         }
+///     });
+///     view! { cx, ul { (list) } }
+/// }
+/// ```
+pub fn create_collection<'a, T: CollectionItem>(
+    cx: Scope<'a>,
+    endpoint: impl Into<String>,
+) -> &'a Collection<'a, T> {
+    let collection = create_ref(
+        cx,
+        Collection {
+            cx,
+            client: use_context_or_else(cx, RestClient::default).clone(),
+            endpoint: endpoint.into(),
+            items: create_signal(cx, Vec::new()),
+            error: create_signal(cx, None),
+            pending: create_signal(cx, 0),
+        },
+    );
+    suspense_scope(cx, collection.fetch());
+    collection
+}
+
+#[cfg(all(test, not(miri)))]
+mod tests {
+    use std::cell::RefCell;
+
+    use serde::Deserialize;
+    use serde_json::json;
+    use sycamore_futures::provide_executor_scope;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Todo {
+        id: u32,
+        title: String,
+    }
+
+    impl CollectionItem for Todo {
+        type Id = u32;
+
+        fn id(&self) -> u32 {
+            self.id
+        }
+    }
+
+    fn todo(id: u32, title: &str) -> Todo {
+        Todo {
+            id,
+            title: title.to_string(),
+        }
+    }
+
+    /// A server with two todos. Created todos get the id 10 and requests for the todo 2 fail.
+    fn client(log: Rc<RefCell<Vec<String>>>) -> RestClient {
+        RestClient::new(move |request: RestRequest| {
+            let log = Rc::clone(&log);
+            async move {
+                tokio::task::yield_now().await;
+                log.borrow_mut()
+                    .push(format!("{} {}", request.method.as_str(), request.url));
+                match request.method {
+                    RestMethod::Get => Ok(json!([
+                        { "id": 1, "title": "Write code" },
+                        { "id": 2, "title": "Test code" },
+                    ])),
+                    _ if request.url.ends_with("/2") => Err(RestError::new(Some(500), "oops")),
+                    RestMethod::Post => {
+                        let mut body = request.body.unwrap();
+                        body["id"] = json!(10);
+                        Ok(body)
+                    }
+                    RestMethod::Put => Ok(request.body.unwrap()),
+                    RestMethod::Delete => Ok(Value::Null),
+                }
+            }
+        })
+    }
+
+    async fn wait_until(f: impl Fn() -> bool) {
+        while !f() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn optimistic_updates() {
+        provide_executor_scope(async {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let log = Rc::new(RefCell::new(Vec::new()));
+            let disposer = create_scope({
+                let log = Rc::clone(&log);
+                |cx| {
+                    provide_context(cx, client(log));
+                    let todos = create_collection::<Todo>(cx, "/todos/");
+                    spawn_local_scoped(cx, async move {
+                        wait_until(|| !todos.is_loading()).await;
+                        assert_eq!(todos.items().get().len(), 2);
+
+                        // Created items are added immediately and replaced by the server's item.
+                        todos.create(todo(0, "Ship it"));
+                        assert_eq!(todos.items().get()[2], todo(0, "Ship it"));
+                        wait_until(|| !todos.is_loading()).await;
+                        assert_eq!(todos.items().get()[2], todo(10, "Ship it"));
+
+                        todos.update(todo(1, "Write more code"));
+                        todos.delete(10);
+                        assert_eq!(
+                            *todos.items().get(),
+                            [todo(1, "Write more code"), todo(2, "Test code")]
+                        );
+                        wait_until(|| !todos.is_loading()).await;
+                        assert_eq!(todos.error().get().as_ref(), &None);
+                        sender.send(()).unwrap();
+                    });
+                }
+            });
+            receiver.await.unwrap();
+            // The update and the delete are sent concurrently.
+            log.borrow_mut()[2..].sort();
+            assert_eq!(
+                *log.borrow(),
+                [
+                    "GET /todos/",
+                    "POST /todos/",
+                    "DELETE /todos/10",
+                    "PUT /todos/1"
+                ]
+            );
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn rollback_on_failure() {
+        provide_executor_scope(async {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let disposer = create_scope(|cx| {
+                provide_context(cx, client(Rc::default()));
+                let todos = create_collection::<Todo>(cx, "/todos");
+                spawn_local_scoped(cx, async move {
+                    wait_until(|| !todos.is_loading()).await;
+                    let initial = todos.items().get().as_ref().clone();
+
+                    todos.update(todo(2, "Skip tests"));
+                    assert_eq!(todos.items().get()[1], todo(2, "Skip tests"));
+                    wait_until(|| !todos.is_loading()).await;
+                    assert_eq!(*todos.items().get(), initial);
+                    assert_eq!(
+                        todos.error().get().as_ref().as_ref().unwrap().status(),
+                        Some(500)
+                    );
+
+                    todos.delete(2);
+                    assert_eq!(todos.items().get().len(), 1);
+                    wait_until(|| !todos.is_loading()).await;
+                    assert_eq!(*todos.items().get(), initial);
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+}
@@ -0,0 +1,83 @@
+//! The [`StrictMode`] component, which helps finding components and effects that do not clean up
+//! after themselves.
+//!
+//! See [`enable_strict_mode`] for more details about how effects are checked.
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// Props for [`StrictMode`].
+#[derive(Prop, Debug)]
+pub struct StrictModeProps<'a, G: GenericNode, V>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+{
+    /// Renders the children of the strict mode boundary. Called twice.
+    view: V,
+    #[builder(default)]
+    _marker: PhantomData<&'a ()>,
+}
+
+/// Enables strict mode for the views rendered inside of it. This is a development tool which only
+/// has an effect in debug builds with the `diagnostics` feature.
+///
+/// In strict mode:
+/// - the children are rendered once and disposed right away, which runs all of their [`on_cleanup`]
+///   callbacks, before being rendered again,
+/// - every effect is run twice when it is created, see [`enable_strict_mode`].
+///
+/// Components and effects that do not clean up after themselves, or that have side effects that
+/// are not idempotent, therefore misbehave right away, e.g. by registering an event listener twice
+/// or by incrementing a counter twice.
+///
+/// When hydrating or rendering on the server, the children are only rendered once so that the
+/// hydration keys match, but effects are still run twice.
+///
+/// Unlike other components, the children are passed as a `view` closure so that they can be
+/// rendered twice.
+///
+/// # Example
+/// ```
+/// # use sycamore::prelude::*;
+/// # #[component]
+/// # fn App<G: Html>(cx: Scope) -> View<G> { view! { cx, } }
+/// # fn root<G: Html>(cx: Scope) -> View<G> {
+/// view! { cx,
+///     StrictMode {
+///         view: |cx| view! { cx, App {} },
+///     }
+/// }
+/// # }
+/// ```
+#[component]
+pub fn StrictMode<'a, G: GenericNode, V>(cx: Scope<'a>, props: StrictModeProps<'a, G, V>) -> View<G>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+{
+    let StrictModeProps { view, .. } = props;
+    if !cfg!(all(debug_assertions, feature = "diagnostics")) {
+        return view(cx);
+    }
+
+    if !G::USE_HYDRATION_CONTEXT {
+        // Render the children once and dispose them right away.
+        let disposer = create_child_scope(cx, |child| {
+            enable_strict_mode(child);
+            // SAFETY: the child scope does not outlive `cx`, which does not outlive `'a`.
+            let child = unsafe { std::mem::transmute::<Scope<'_>, BoundedScope<'_, 'a>>(child) };
+            let _ = view(child);
+        });
+        // SAFETY: the child scope is not used anymore.
+        unsafe { disposer.dispose() };
+    }
+
+    let mut rendered = None;
+    let _ = create_child_scope(cx, |child| {
+        enable_strict_mode(child);
+        // SAFETY: the child scope does not outlive `cx`, which does not outlive `'a`.
+        let child = unsafe { std::mem::transmute::<Scope<'_>, BoundedScope<'_, 'a>>(child) };
+        rendered = Some(view(child));
+    });
+    rendered.unwrap()
+}
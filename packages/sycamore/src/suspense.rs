@@ -3,28 +3,147 @@
 //! The [`Suspense`] component is used to "suspend" execution and wait until async tasks are
 //! finished before rendering.
 
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Duration;
 
 use futures::channel::oneshot;
+use futures::future::{FutureExt, LocalBoxFuture};
 use futures::Future;
-use sycamore_futures::spawn_local_scoped;
+use sycamore_futures::{deterministic, spawn_local_scoped, ResourceSpawner};
 
 use crate::prelude::*;
+#[cfg(feature = "ssr")]
+use crate::web::markers::{find_markers, Found, Marker};
 
 #[derive(Default)]
 struct SuspenseState {
     async_counts: RefCell<Vec<RcSignal<u32>>>,
 }
 
+/// Returns the [`SuspenseState`] of the closest suspense, or provides a new one in the current
+/// scope along with a [`ResourceSpawner`] so that the fetches of the resources created with
+/// [`create_resource`](sycamore_futures::create_resource) are suspense scopes.
+fn use_suspense_state(cx: Scope<'_>) -> &SuspenseState {
+    try_use_context(cx).unwrap_or_else(|| {
+        provide_context(cx, ResourceSpawner(spawn_suspense_scope));
+        provide_context(cx, SuspenseState::default())
+    })
+}
+
+fn spawn_suspense_scope<'a>(cx: Scope<'a>, f: Pin<Box<dyn Future<Output = ()> + 'a>>) {
+    suspense_scope(cx, f);
+}
+
+/// A task that failed inside a [`Suspense`].
+#[derive(Clone)]
+struct SuspenseFailure {
+    message: String,
+    retry: Rc<dyn Fn()>,
+}
+
+/// The failed resources inside a [`Suspense`]. This is passed to the `error` view of the
+/// [`Suspense`].
+///
+/// Resources report their failure to the closest [`Suspense`] if they are created with
+/// [`create_fallible_resource`]. The children of a [`Suspense`] are created in a child scope which
+/// provides its [`SuspenseErrors`] as a context, so that the resources that are created later, e.g.
+/// by async components or dynamic views, find it as well.
+#[derive(Clone)]
+pub struct SuspenseErrors {
+    failures: RcSignal<Vec<SuspenseFailure>>,
+    /// The number of failed resources that are being retried.
+    retrying: RcSignal<u32>,
+}
+
+impl SuspenseErrors {
+    fn new() -> Self {
+        Self {
+            failures: create_rc_signal(Vec::new()),
+            retrying: create_rc_signal(0),
+        }
+    }
+
+    /// Returns the error messages of the failed resources. This value can be tracked from a
+    /// listener scope.
+    pub fn messages(&self) -> Vec<String> {
+        self.failures
+            .get()
+            .iter()
+            .map(|failure| failure.message.clone())
+            .collect()
+    }
+
+    /// Returns whether any resource has failed. This value can be tracked from a listener scope.
+    pub fn has_failed(&self) -> bool {
+        !self.failures.get().is_empty()
+    }
+
+    /// Re-runs all the failed resources. The fallback of the [`Suspense`] is displayed until they
+    /// are all resolved.
+    pub fn retry(&self) {
+        let failures = self.failures.get_untracked();
+        self.retrying
+            .set(*self.retrying.get_untracked() + failures.len() as u32);
+        self.failures.set(Vec::new());
+        for failure in failures.iter() {
+            (failure.retry)();
+        }
+    }
+}
+
+impl fmt::Debug for SuspenseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuspenseErrors")
+            .field("messages", &self.messages())
+            .field("retrying", &*self.retrying.get_untracked())
+            .finish()
+    }
+}
+
 /// Props for [`Suspense`].
-#[derive(Prop, Debug)]
+#[derive(Prop)]
 pub struct SuspenseProps<'a, G: GenericNode> {
     /// The fallback [`View`] to display while the child nodes are being awaited.
     #[builder(default)]
     fallback: View<G>,
+    /// The number of milliseconds to wait before displaying the fallback. If the children are
+    /// resolved before the delay has elapsed, the fallback is never displayed. This avoids
+    /// flashing a loading indicator when loading is fast. Nothing is displayed during the delay.
+    ///
+    /// Only applies when rendering in the browser or when the
+    /// [deterministic scheduler](sycamore_futures::deterministic) is enabled. Defaults to `0`.
+    #[builder(default)]
+    fallback_delay_ms: u32,
+    /// The [`View`] to display if a resource inside the [`Suspense`] has failed. The failed
+    /// resources can be re-run with [`SuspenseErrors::retry`].
+    ///
+    /// If not set, the children are displayed even if a resource has failed.
+    #[builder(default, setter(strip_option))]
+    #[allow(clippy::type_complexity)]
+    error: Option<Box<dyn Fn(SuspenseErrors) -> View<G> + 'a>>,
     children: Children<'a, G>,
 }
 
+impl<'a, G: GenericNode> fmt::Debug for SuspenseProps<'a, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuspenseProps")
+            .field("fallback", &self.fallback)
+            .field("fallback_delay_ms", &self.fallback_delay_ms)
+            .field(
+                "error",
+                &self.error.as_ref().map(|_| "Fn(SuspenseErrors) -> View"),
+            )
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
 /// `Suspense` lets you wait for `async` tasks to complete before rendering the UI. This is useful
 /// for asynchronous data-fetching or other asynchronous tasks.
 ///
@@ -34,6 +153,10 @@ pub struct SuspenseProps<'a, G: GenericNode> {
 /// be rendered once all individual async components are rendered. This is useful for showing a
 /// loading indicator while the data is being loaded.
 ///
+/// If an `error` view is provided, it is displayed instead of the children when a resource created
+/// with [`create_fallible_resource`] fails. The view receives a [`SuspenseErrors`] that can be used
+/// to retry the failed resources.
+///
 /// # Example
 /// ```
 /// use sycamore::prelude::*;
@@ -54,30 +177,361 @@ pub struct SuspenseProps<'a, G: GenericNode> {
 ///     }
 /// }
 /// ```
+///
+/// # Example with errors
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::suspense::{create_fallible_resource, Suspense};
+///
+/// async fn fetch_user() -> Result<String, String> {
+///     Err("network error".to_string())
+/// }
+///
+/// #[component]
+/// fn User<G: Html>(cx: Scope) -> View<G> {
+///     let user = create_fallible_resource(cx, fetch_user);
+///     view! { cx,
+///         (match user.get().as_ref() {
+///             Some(Ok(user)) => user.clone(),
+///             _ => String::new(),
+///         })
+///     }
+/// }
+///
+/// #[component]
+/// fn App<G: Html>(cx: Scope) -> View<G> {
+///     view! { cx,
+///         Suspense {
+///             fallback: view! { cx, "Loading..." },
+///             error: Box::new(move |errors| {
+///                 let messages = errors.messages().join(", ");
+///                 view! { cx,
+///                     p { (messages) }
+///                     button(on:click=move |_| errors.retry()) { "Retry" }
+///                 }
+///             }),
+///             User {}
+///         }
+///     }
+/// }
+/// ```
 #[component]
 pub fn Suspense<'a, G: GenericNode>(cx: Scope<'a>, props: SuspenseProps<'a, G>) -> View<G> {
-    let v = create_signal(cx, None);
+    let SuspenseProps {
+        fallback,
+        fallback_delay_ms,
+        error,
+        children,
+    } = props;
+    let v = create_signal(cx, None::<View<G>>);
+    let show_fallback = create_signal(cx, true);
+    if fallback_delay_ms > 0 && has_timers() {
+        show_fallback.set(false);
+        // Not a suspense scope because an outer Suspense should not wait for the delay.
+        spawn_local_scoped(cx, async move {
+            sleep(fallback_delay_ms).await;
+            show_fallback.set(true);
+        });
+    }
+    let errors = create_ref(cx, SuspenseErrors::new());
+    // When streaming, every Suspense is sent separately once it is resolved, even if it is nested
+    // under another Suspense.
+    #[cfg(feature = "ssr")]
+    let stream = try_use_context::<StreamState>(cx).map(|stream| (stream, stream.register()));
+    let fallback_view = move || {
+        #[cfg(feature = "ssr")]
+        if let Some((_, id)) = stream {
+            return StreamState::wrap_fallback(id, fallback.clone());
+        }
+        fallback.clone()
+    };
+
+    let view = view! { cx,
+        ({
+            match &error {
+                Some(error) if errors.has_failed() => error(errors.clone()),
+                _ => match v.get().as_ref() {
+                    Some(v) if *errors.retrying.get() == 0 => v.clone(),
+                    _ if *show_fallback.get() => fallback_view(),
+                    _ => View::empty(),
+                },
+            }
+        })
+    };
+
+    let task = {
+        let view = view.clone();
+        async move {
+            let res = await_suspense(cx, async move {
+                let mut res = None;
+                // Resources created by the children report their failures to this Suspense.
+                let _ = create_child_scope(cx, |cx| {
+                    provide_context(cx, errors.clone());
+                    res = Some(children.call(cx));
+                });
+                res.unwrap()
+            })
+            .await;
+            v.set(Some(res));
+            #[cfg(feature = "ssr")]
+            if let Some((stream, id)) = stream {
+                stream.resolve(id, &view);
+            }
+            #[cfg(not(feature = "ssr"))]
+            let _ = view;
+        }
+    };
     // If the Suspense is nested under another Suspense, we want the other Suspense to await this
-    // one as well.
+    // one as well, unless this one is streamed on its own.
+    #[cfg(feature = "ssr")]
+    if stream.is_some() {
+        spawn_local_scoped(cx, task);
+        return view;
+    }
+    suspense_scope(cx, task);
+
+    view
+}
+
+/// Props for [`Await`].
+#[derive(Prop)]
+pub struct AwaitProps<'a, G: GenericNode, U, Fut, F>
+where
+    Fut: Future<Output = U> + 'a,
+    F: FnOnce(Scope<'a>, U) -> View<G> + 'a,
+{
+    /// The future to await.
+    future: Fut,
+    /// The view to display until the future is resolved. Nothing is displayed by default.
+    #[builder(default)]
+    fallback: View<G>,
+    /// Renders the output of the future.
+    view: F,
+    #[builder(default, setter(skip))]
+    _phantom: std::marker::PhantomData<&'a U>,
+}
+
+impl<'a, G: GenericNode, U, Fut, F> fmt::Debug for AwaitProps<'a, G, U, Fut, F>
+where
+    Fut: Future<Output = U> + 'a,
+    F: FnOnce(Scope<'a>, U) -> View<G> + 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwaitProps")
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+/// Renders the output of a single future once it is resolved, and the `fallback` until then.
+///
+/// Like an async component, the future is awaited by the closest [`Suspense`], if any, so it is
+/// resolved before the page is rendered with
+/// [`render_to_string_await_suspense`](crate::render_to_string_await_suspense). For data that
+/// should be refetched, use a resource instead, e.g.
+/// [`create_resource`](crate::futures::create_resource).
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::suspense::Await;
+///
+/// async fn fetch_greeting() -> String {
+///     "Hello!".to_string()
+/// }
+///
+/// #[component]
+/// fn Greeting<G: Html>(cx: Scope) -> View<G> {
+///     view! { cx,
+///         Await {
+///             future: fetch_greeting(),
+///             fallback: view! { cx, "Loading..." },
+///             view: |cx, greeting| view! { cx, p { (greeting) } },
+///         }
+///     }
+/// }
+/// ```
+#[component]
+pub fn Await<'a, G: GenericNode, U, Fut, F>(
+    cx: Scope<'a>,
+    props: AwaitProps<'a, G, U, Fut, F>,
+) -> View<G>
+where
+    Fut: Future<Output = U> + 'a,
+    F: FnOnce(Scope<'a>, U) -> View<G> + 'a,
+{
+    let AwaitProps {
+        future,
+        fallback,
+        view,
+        ..
+    } = props;
+    let resolved = create_signal(cx, None::<View<G>>);
     suspense_scope(cx, async move {
-        let res = await_suspense(cx, async move { props.children.call(cx) }).await;
-        v.set(Some(res));
+        let value = future.await;
+        resolved.set(Some(view(cx, value)));
     });
+    View::new_dyn(cx, move || match resolved.get().as_ref() {
+        Some(view) => view.clone(),
+        None => fallback.clone(),
+    })
+}
+
+/// Replaces the nodes between the `<!--s:N-->` and `<!--/s:N-->` markers with the content of the
+/// `<template id="sycamore-s-N">` element. Sent with the shell when streaming.
+#[cfg(feature = "ssr")]
+const STREAM_SWAP_SCRIPT: &str = "function __sycamoreSwap(i){\
+var t=document.getElementById(\"sycamore-s-\"+i),w=document.createTreeWalker(document,128),m;\
+while((m=w.nextNode())&&m.data!=\"s:\"+i);\
+if(!m)return;\
+for(var n=m.nextSibling;n&&!(n.nodeType==8&&n.data==\"/s:\"+i);n=m.nextSibling)n.remove();\
+m.replaceWith(t.content);\
+if(n)n.remove();\
+t.remove()}";
 
-    view! { cx,
-        (if let Some(v) = v.get().as_ref() { v.clone() } else { props.fallback.clone() })
+/// The state of [`render_to_string_stream`](crate::web::render_to_string_stream), provided as a
+/// context to the rendered view.
+#[cfg(feature = "ssr")]
+pub(crate) struct StreamState {
+    next_id: std::cell::Cell<u32>,
+    /// The number of [`Suspense`] components that are not resolved yet.
+    pending: std::cell::Cell<u32>,
+    shell_sent: std::cell::Cell<bool>,
+    /// The ids of the [`Suspense`] components whose fallback has been sent to the client.
+    sent_fallbacks: RefCell<std::collections::HashSet<u32>>,
+    chunks: RefCell<Option<futures::channel::mpsc::UnboundedSender<String>>>,
+    done: RefCell<Option<oneshot::Sender<()>>>,
+}
+
+#[cfg(feature = "ssr")]
+impl StreamState {
+    pub(crate) fn new(
+        chunks: futures::channel::mpsc::UnboundedSender<String>,
+        done: oneshot::Sender<()>,
+    ) -> Self {
+        Self {
+            next_id: Default::default(),
+            pending: Default::default(),
+            shell_sent: Default::default(),
+            sent_fallbacks: Default::default(),
+            chunks: RefCell::new(Some(chunks)),
+            done: RefCell::new(Some(done)),
+        }
     }
+
+    /// Registers a [`Suspense`] that is streamed once it is resolved and returns its id.
+    fn register(&self) -> u32 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.pending.set(self.pending.get() + 1);
+        id
+    }
+
+    /// Surrounds the fallback of a streamed [`Suspense`] with markers, so that it can be replaced
+    /// once the [`Suspense`] is resolved.
+    fn wrap_fallback<G: GenericNode>(id: u32, fallback: View<G>) -> View<G> {
+        View::new_fragment(vec![
+            View::new_node(G::marker_with_text(&Marker::SuspenseStart(id).comment())),
+            fallback,
+            View::new_node(G::marker_with_text(&Marker::SuspenseEnd(id).comment())),
+        ])
+    }
+
+    /// Sends a chunk and records the fallbacks that it contains.
+    fn send(&self, chunk: String) {
+        let mut sent_fallbacks = self.sent_fallbacks.borrow_mut();
+        for (_, found) in find_markers(&chunk) {
+            if let Found::Marker(Marker::SuspenseStart(id)) = found {
+                sent_fallbacks.insert(id);
+            }
+        }
+        drop(sent_fallbacks);
+        if let Some(chunks) = self.chunks.borrow().as_ref() {
+            let _ = chunks.unbounded_send(chunk);
+        }
+    }
+
+    /// Sends the view rendered synchronously, with the fallbacks of the pending [`Suspense`]
+    /// components.
+    pub(crate) fn send_shell(&self, mut html: String) {
+        if self.pending.get() > 0 {
+            html.push_str("<script>");
+            html.push_str(STREAM_SWAP_SCRIPT);
+            html.push_str("</script>");
+        }
+        self.send(html);
+        self.shell_sent.set(true);
+        self.finish_if_done();
+    }
+
+    /// Sends the content of a resolved [`Suspense`], along with the script that swaps it with the
+    /// fallback.
+    fn resolve<G: GenericNode>(&self, id: u32, view: &View<G>) {
+        // If the fallback was not sent, e.g. because the Suspense was resolved before the shell or
+        // before the Suspense it is nested under, the content is sent in place of the fallback.
+        if self.sent_fallbacks.borrow().contains(&id) {
+            let mut html = format!("<template id=\"sycamore-s-{id}\">");
+            for node in view.clone().flatten() {
+                if let Some(node) = (&node as &dyn std::any::Any).downcast_ref::<SsrNode>() {
+                    sycamore_web::WriteToString::write_to_string(node, &mut html);
+                }
+            }
+            html.push_str(&format!("</template><script>__sycamoreSwap({id})</script>"));
+            self.send(html);
+        }
+        self.pending.set(self.pending.get() - 1);
+        self.finish_if_done();
+    }
+
+    /// Closes the stream once the shell is sent and all the [`Suspense`] components are resolved.
+    fn finish_if_done(&self) {
+        if self.shell_sent.get() && self.pending.get() == 0 {
+            self.chunks.borrow_mut().take();
+            if let Some(done) = self.done.borrow_mut().take() {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+/// Returns `true` if [`sleep`] can be used, i.e. when running in a JavaScript host or when the
+/// deterministic scheduler is enabled.
+pub(crate) fn has_timers() -> bool {
+    cfg!(all(
+        target_arch = "wasm32",
+        not(target_os = "wasi"),
+        feature = "web"
+    )) || deterministic::is_deterministic()
+}
+
+/// Resolves after `ms` milliseconds. Uses the virtual clock if the deterministic scheduler is
+/// enabled.
+///
+/// # Panics
+/// Panics if [`has_timers`] is `false`.
+pub(crate) async fn sleep(ms: u32) {
+    assert!(
+        has_timers(),
+        "timers are not available outside of the browser"
+    );
+    sycamore_futures::sleep(Duration::from_millis(ms.into())).await;
 }
 
 /// Creates a new "suspense scope". This scope is used to signal to a [`Suspense`] component higher
 /// up in the component hierarchy that there is some async task that should be awaited before
 /// rendering the UI.
 ///
-/// The scope ends when the future is resolved.
+/// The scope ends when the future is resolved. Suspense scopes created by the future itself, e.g.
+/// async components rendered after an `.await`, are awaited by the same [`Suspense`].
 pub fn suspense_scope<'a>(cx: Scope<'a>, f: impl Future<Output = ()> + 'a) {
     if let Some(state) = try_use_context::<SuspenseState>(cx) {
         if let Some(count) = state.async_counts.borrow().last().cloned() {
             count.set(*count.get() + 1);
+            let f = CountedFuture {
+                state,
+                count: count.clone(),
+                f: Box::pin(f),
+            };
             spawn_local_scoped(cx, async move {
                 f.await;
                 count.set(*count.get() - 1);
@@ -88,20 +542,40 @@ pub fn suspense_scope<'a>(cx: Scope<'a>, f: impl Future<Output = ()> + 'a) {
     spawn_local_scoped(cx, f);
 }
 
+/// A future that pushes `count` onto the suspense stack every time it is polled. This makes
+/// suspense scopes that are created by the future after an `.await` count towards the same
+/// [`Suspense`] instead of letting it resolve prematurely.
+struct CountedFuture<'a> {
+    state: &'a SuspenseState,
+    count: RcSignal<u32>,
+    f: Pin<Box<dyn Future<Output = ()> + 'a>>,
+}
+
+impl<'a> Future for CountedFuture<'a> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.state
+            .async_counts
+            .borrow_mut()
+            .push(self.count.clone());
+        let poll = self.f.as_mut().poll(cx);
+        self.state.async_counts.borrow_mut().pop().unwrap();
+        poll
+    }
+}
+
 /// Waits until all suspense tasks created within the scope are finished.
 /// If called inside an outer suspense scope, this will also make the outer suspense scope suspend
 /// until this resolves.
 pub async fn await_suspense<U>(cx: Scope<'_>, f: impl Future<Output = U>) -> U {
-    let state = use_context_or_else(cx, SuspenseState::default);
+    let state = use_suspense_state(cx);
     // Get the outer suspense state.
     let outer_count = state.async_counts.borrow().last().cloned();
     // Push a new suspense state.
     let count = create_rc_signal(0);
     state.async_counts.borrow_mut().push(count.clone());
-    let ready = create_selector(cx, {
-        let count = count.clone();
-        move || *count.get() == 0
-    });
+    let ready = create_selector(cx, move || *count.get() == 0);
 
     if let Some(outer_count) = &outer_count {
         outer_count.set(*outer_count.get() + 1);
@@ -110,6 +584,15 @@ pub async fn await_suspense<U>(cx: Scope<'_>, f: impl Future<Output = U>) -> U {
     // Pop the suspense state.
     state.async_counts.borrow_mut().pop().unwrap();
 
+    wait_until(cx, ready).await;
+    if let Some(outer_count) = outer_count {
+        outer_count.set(*outer_count.get() - 1);
+    }
+    ret
+}
+
+/// Waits until `ready` is `true`.
+async fn wait_until<'a>(cx: Scope<'a>, ready: &'a ReadSignal<bool>) {
     let (sender, receiver) = oneshot::channel();
     let mut sender = Some(sender);
 
@@ -121,10 +604,92 @@ pub async fn await_suspense<U>(cx: Scope<'_>, f: impl Future<Output = U>) -> U {
         }
     });
     let _ = receiver.await;
-    if let Some(outer_count) = outer_count {
-        outer_count.set(*outer_count.get() - 1);
+}
+
+/// A resource created with [`create_fallible_resource`].
+struct FallibleResource<'a, U, E, F> {
+    cx: Scope<'a>,
+    f: F,
+    value: &'a Signal<Option<Result<U, E>>>,
+    /// The errors of the closest [`Suspense`], if any.
+    boundary: Option<SuspenseErrors>,
+    alive: RcSignal<bool>,
+}
+
+impl<'a, U, E, F, Fut> FallibleResource<'a, U, E, F>
+where
+    U: 'a,
+    E: fmt::Display + 'a,
+    F: Fn() -> Fut + 'a,
+    Fut: Future<Output = Result<U, E>> + 'a,
+{
+    fn run(&'a self, is_retry: bool) {
+        suspense_scope(self.cx, async move {
+            let res = (self.f)().await;
+            if let Some(boundary) = &self.boundary {
+                if let Err(err) = &res {
+                    let retry: Rc<dyn Fn() + 'a> = Rc::new({
+                        let alive = self.alive.clone();
+                        let boundary = boundary.clone();
+                        move || {
+                            if *alive.get_untracked() {
+                                self.run(true);
+                            } else {
+                                boundary
+                                    .retrying
+                                    .set(*boundary.retrying.get_untracked() - 1);
+                            }
+                        }
+                    });
+                    // SAFETY: `self` is only accessed if the scope is still alive.
+                    let retry: Rc<dyn Fn()> = unsafe { std::mem::transmute(retry) };
+                    boundary.failures.modify().push(SuspenseFailure {
+                        message: err.to_string(),
+                        retry,
+                    });
+                }
+                if is_retry {
+                    boundary
+                        .retrying
+                        .set(*boundary.retrying.get_untracked() - 1);
+                }
+            }
+            self.value.set(Some(res));
+        });
     }
-    ret
+}
+
+/// Creates a new async resource that can fail. The resource is awaited by the closest
+/// [`Suspense`], like an async component.
+///
+/// If the future returned by `f` resolves to an error, the error is reported to the closest
+/// [`Suspense`] which then displays its `error` view. Retrying from the `error` view calls `f`
+/// again.
+///
+/// The returned signal is `None` until the future is resolved.
+pub fn create_fallible_resource<'a, U, E, F, Fut>(
+    cx: Scope<'a>,
+    f: F,
+) -> &'a ReadSignal<Option<Result<U, E>>>
+where
+    U: 'a,
+    E: fmt::Display + 'a,
+    F: Fn() -> Fut + 'a,
+    Fut: Future<Output = Result<U, E>> + 'a,
+{
+    let boundary = try_use_context::<SuspenseErrors>(cx).cloned();
+    let resource = create_ref(
+        cx,
+        FallibleResource {
+            cx,
+            f,
+            value: create_signal(cx, None),
+            boundary,
+            alive: use_scope_status(cx),
+        },
+    );
+    resource.run(false);
+    resource.value
 }
 
 /// A struct to handle transitions. Created using
@@ -161,9 +726,298 @@ pub fn use_transition(cx: Scope<'_>) -> &TransitionHandle<'_> {
     create_ref(cx, TransitionHandle { cx, is_pending })
 }
 
+/// Props for [`Transition`].
+#[derive(Prop)]
+pub struct TransitionProps<'a, G: GenericNode, V>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+{
+    /// Renders the children of the transition. Called again every time a signal that it tracks
+    /// changes.
+    view: V,
+    /// The [`View`] to display while the children are loaded for the first time.
+    #[builder(default)]
+    fallback: View<G>,
+    /// Set to `true` while new children are loaded and back to `false` once they are displayed.
+    #[builder(default, setter(strip_option))]
+    is_pending: Option<&'a Signal<bool>>,
+}
+
+impl<'a, G: GenericNode, V> fmt::Debug for TransitionProps<'a, G, V>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransitionProps")
+            .field("fallback", &self.fallback)
+            .field(
+                "is_pending",
+                &self.is_pending.map(|is_pending| *is_pending.get()),
+            )
+            .finish()
+    }
+}
+
+/// Like [`Suspense`], but keeps displaying the previous children while new ones are loaded
+/// instead of going back to the `fallback`. The `fallback` is only displayed while the children
+/// are loaded for the first time.
+///
+/// The children are rendered again by the `view` closure every time a signal that it tracks
+/// changes. Signals read inside of `view!` are tracked by the view itself instead, so they have to
+/// be read before, as in the example below. The new children are only
+/// displayed once all the async tasks that they created are finished, and `is_pending` is `true`
+/// in the meantime.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::suspense::Transition;
+///
+/// #[derive(Prop)]
+/// struct UserProps {
+///     id: u32,
+/// }
+///
+/// #[component]
+/// async fn User<G: Html>(cx: Scope<'_>, props: UserProps) -> View<G> {
+///     view! { cx, "User " (props.id) }
+/// }
+///
+/// #[component]
+/// fn App<G: Html>(cx: Scope) -> View<G> {
+///     let id = create_signal(cx, 0);
+///     let is_pending = create_signal(cx, false);
+///     view! { cx,
+///         button(on:click=|_| id.set(*id.get() + 1), disabled=*is_pending.get()) { "Next" }
+///         Transition {
+///             view: move |cx| {
+///                 let id = *id.get();
+///                 view! { cx, User { id: id } }
+///             },
+///             fallback: view! { cx, "Loading..." },
+///             is_pending: is_pending,
+///         }
+///     }
+/// }
+/// ```
+#[component]
+pub fn Transition<'a, G: GenericNode, V>(cx: Scope<'a>, props: TransitionProps<'a, G, V>) -> View<G>
+where
+    V: Fn(BoundedScope<'_, 'a>) -> View<G> + 'a,
+{
+    let TransitionProps {
+        view,
+        fallback,
+        is_pending,
+    } = props;
+    let is_pending = is_pending.unwrap_or_else(|| create_signal(cx, false));
+    let state = use_suspense_state(cx);
+    let current = create_signal(cx, None::<View<G>>);
+    // The scopes of the displayed children and of the children that are being loaded.
+    let displayed = create_ref(cx, RefCell::new(None::<ScopeDisposer<'a>>));
+    let loading = create_ref(cx, RefCell::new(None::<ScopeDisposer<'a>>));
+    // Incremented every time the children are rendered, so that children that are still loading
+    // when they are rendered again are never displayed.
+    let generation = create_signal(cx, 0u32);
+
+    let display = move |children: View<G>, disposer: ScopeDisposer<'a>| {
+        current.set(Some(children));
+        if let Some(previous) = displayed.borrow_mut().replace(disposer) {
+            // SAFETY: the previous children are no longer displayed.
+            unsafe { previous.dispose() };
+        }
+        is_pending.set(false);
+    };
+
+    create_effect(cx, move || {
+        if let Some(superseded) = loading.borrow_mut().take() {
+            // SAFETY: the superseded children were never displayed.
+            unsafe { superseded.dispose() };
+        }
+        let id = *generation.get_untracked() + 1;
+        generation.set(id);
+
+        // Like await_suspense, but the children are rendered synchronously so that the signals
+        // that they read are tracked by the effect.
+        let count = create_rc_signal(0);
+        state.async_counts.borrow_mut().push(count.clone());
+        let mut children = None;
+        let disposer = create_child_scope(cx, |cx| children = Some(view(cx)));
+        state.async_counts.borrow_mut().pop().unwrap();
+        let children = children.unwrap();
+
+        if *count.get_untracked() == 0 {
+            display(children, disposer);
+            return;
+        }
+        *loading.borrow_mut() = Some(disposer);
+        is_pending.set(true);
+        // Awaited by the closest Suspense when the children are loaded for the first time.
+        suspense_scope(cx, async move {
+            let ready = create_selector(cx, move || *count.get() == 0 || *generation.get() != id);
+            wait_until(cx, ready).await;
+            if *generation.get_untracked() == id {
+                let disposer = loading.borrow_mut().take().unwrap();
+                display(children, disposer);
+            }
+        });
+    });
+
+    View::new_dyn(cx, move || match current.get().as_ref() {
+        Some(children) => children.clone(),
+        None => fallback.clone(),
+    })
+}
+
+/// The function of a component without props, as loaded by a [`LazyComponent`].
+pub type ComponentFn<G> = for<'a> fn(Scope<'a>) -> View<G>;
+
+thread_local! {
+    /// The [`ComponentFn`]s that were loaded by [`LazyComponent`]s, by the type of their loader.
+    static LOADED_COMPONENTS: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// A component that is loaded the first time it is rendered with [`Lazy`], e.g. from a wasm module
+/// that is split from the main bundle. Once loaded, the component is rendered right away.
+///
+/// Usually created with [`lazy_component!`](crate::lazy_component). For code splitting, the loader
+/// passed to [`new`](Self::new) should load the module of the component, e.g. a function annotated
+/// with `#[wasm_split]`.
+pub struct LazyComponent<G: GenericNode> {
+    /// The type of the loader, which identifies the component in [`LOADED_COMPONENTS`].
+    id: TypeId,
+    load: Rc<dyn Fn() -> LocalBoxFuture<'static, ComponentFn<G>>>,
+}
+
+impl<G: GenericNode> LazyComponent<G> {
+    /// Creates a new [`LazyComponent`] that is loaded with `load`. The component is only loaded
+    /// once, even if several [`LazyComponent`]s are created with the same loader.
+    pub fn new<F, Fut>(load: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ComponentFn<G>> + 'static,
+    {
+        Self {
+            id: TypeId::of::<F>(),
+            load: Rc::new(move || load().boxed_local()),
+        }
+    }
+
+    /// Returns the component if it is loaded already.
+    pub fn get(&self) -> Option<ComponentFn<G>> {
+        LOADED_COMPONENTS.with(|loaded| {
+            loaded
+                .borrow()
+                .get(&self.id)
+                .and_then(|component| component.downcast_ref().copied())
+        })
+    }
+
+    /// Loads the component, or returns it right away if it is loaded already. This can be used to
+    /// load the component ahead of time, e.g. when hovering a link to the page that renders it.
+    pub async fn load(&self) -> ComponentFn<G> {
+        if let Some(component) = self.get() {
+            return component;
+        }
+        let component = (self.load)().await;
+        LOADED_COMPONENTS.with(|loaded| {
+            loaded.borrow_mut().insert(self.id, Box::new(component));
+        });
+        component
+    }
+}
+
+impl<G: GenericNode> Clone for LazyComponent<G> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            load: Rc::clone(&self.load),
+        }
+    }
+}
+
+impl<G: GenericNode> fmt::Debug for LazyComponent<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyComponent")
+            .field("loaded", &self.get().is_some())
+            .finish()
+    }
+}
+
+/// Creates a [`LazyComponent`] for a component without props.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::suspense::Lazy;
+///
+/// #[component]
+/// fn Settings<G: Html>(cx: Scope) -> View<G> {
+///     view! { cx, "Settings" }
+/// }
+///
+/// #[component]
+/// fn App<G: Html>(cx: Scope) -> View<G> {
+///     view! { cx,
+///         Lazy {
+///             component: sycamore::lazy_component!(Settings),
+///             fallback: view! { cx, "Loading..." },
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! lazy_component {
+    ($component:path) => {
+        $crate::suspense::LazyComponent::new(|| async {
+            let component: $crate::suspense::ComponentFn<_> = $component;
+            component
+        })
+    };
+}
+
+/// Props for [`Lazy`].
+#[derive(Prop, Debug)]
+pub struct LazyProps<G: GenericNode> {
+    /// The component to load and render.
+    component: LazyComponent<G>,
+    /// The view to display until the component is loaded. Nothing is displayed by default.
+    #[builder(default)]
+    fallback: View<G>,
+}
+
+/// Renders a [`LazyComponent`], loading it first if needed. The `fallback` is displayed until the
+/// component is loaded.
+///
+/// Like an async component, the loading is awaited by the closest [`Suspense`], if any.
+#[component]
+pub fn Lazy<G: GenericNode>(cx: Scope<'_>, props: LazyProps<G>) -> View<G> {
+    let LazyProps {
+        component,
+        fallback,
+    } = props;
+    if let Some(component) = component.get() {
+        return component(cx);
+    }
+    let resolved = create_signal(cx, None::<View<G>>);
+    suspense_scope(cx, async move {
+        let component = component.load().await;
+        resolved.set(Some(untrack(|| component(cx))));
+    });
+    View::new_dyn(cx, move || match resolved.get().as_ref() {
+        Some(view) => view.clone(),
+        None => fallback.clone(),
+    })
+}
+
 #[cfg(all(test, feature = "ssr", not(miri)))]
 mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
     use sycamore_futures::provide_executor_scope;
+    use sycamore_web::WriteToString;
 
     use super::*;
     use crate::web::render_to_string_await_suspense;
@@ -190,6 +1044,187 @@ mod tests {
         assert_eq!(view, "Hello Suspense!");
     }
 
+    #[tokio::test]
+    async fn concurrent_renders() {
+        #[component]
+        async fn Comp<G: Html>(cx: Scope<'_>) -> View<G> {
+            tokio::task::yield_now().await;
+            view! { cx, p { "Loaded" } }
+        }
+
+        let render = || {
+            render_to_string_await_suspense(|cx| {
+                view! { cx,
+                    div {
+                        Suspense {
+                            fallback: view! { cx, "Loading..." },
+                            Comp {}
+                        }
+                    }
+                }
+            })
+        };
+        let (alone, concurrent) = provide_executor_scope(async {
+            let alone = render().await;
+            let concurrent = futures::join!(render(), render());
+            (alone, concurrent)
+        })
+        .await;
+        // The hydration keys of a render are not affected by the other render.
+        assert_eq!(concurrent, (alone.clone(), alone));
+    }
+
+    #[tokio::test]
+    async fn lazy_component() {
+        #[component]
+        fn Page<G: Html>(cx: Scope<'_>) -> View<G> {
+            view! { cx, "Lazy page" }
+        }
+
+        let loads = Rc::new(Cell::new(0));
+        let component = LazyComponent::<SsrNode>::new({
+            let loads = Rc::clone(&loads);
+            move || {
+                loads.set(loads.get() + 1);
+                async {
+                    let page: ComponentFn<SsrNode> = Page;
+                    page
+                }
+            }
+        });
+        assert!(component.get().is_none());
+
+        let render = || {
+            let component = component.clone();
+            provide_executor_scope(async {
+                render_to_string_await_suspense(|cx| {
+                    view! { cx,
+                        Suspense {
+                            fallback: view! { cx, "Loading..." },
+                            Lazy { component: component, fallback: view! { cx, "Loading page..." } }
+                        }
+                    }
+                })
+                .await
+            })
+        };
+        assert_eq!(render().await, "Lazy page");
+        assert_eq!(loads.get(), 1);
+        assert!(component.get().is_some());
+
+        // The component is only loaded once and rendered right away afterwards.
+        assert_eq!(render().await, "Lazy page");
+        let view = crate::render_to_string(|cx| view! { cx, Lazy { component: component } });
+        assert_eq!(view, "Lazy page");
+        assert_eq!(loads.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn suspense_stream() {
+        use futures::StreamExt;
+
+        use crate::web::render_to_string_stream;
+
+        #[component]
+        async fn Comp<G: Html>(cx: Scope<'_>) -> View<G> {
+            view! { cx, "Hello Suspense!" }
+        }
+
+        let chunks: Vec<String> = provide_executor_scope(async {
+            render_to_string_stream(|cx| {
+                view! { cx,
+                    p { "Shell" }
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        Comp {}
+                    }
+                }
+            })
+            .collect()
+            .await
+        })
+        .await;
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0],
+            format!(
+                "<p data-hk=\"0.0\">Shell</p><!--s:0-->Loading...<!--/s:0-->\
+                 <script>{STREAM_SWAP_SCRIPT}</script>"
+            )
+        );
+        assert_eq!(
+            chunks[1],
+            "<template id=\"sycamore-s-0\">Hello Suspense!</template>\
+             <script>__sycamoreSwap(0)</script>"
+        );
+    }
+
+    #[tokio::test]
+    async fn suspense_stream_nested() {
+        use futures::StreamExt;
+
+        use crate::web::render_to_string_stream;
+
+        #[component]
+        async fn Slow<G: Html>(cx: Scope<'_>) -> View<G> {
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            view! { cx, "Inner" }
+        }
+
+        #[component]
+        async fn Fast<G: Html>(cx: Scope<'_>) -> View<G> {
+            view! { cx, "Outer" }
+        }
+
+        let chunks: Vec<String> = provide_executor_scope(async {
+            render_to_string_stream(|cx| {
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading outer..." },
+                        {
+                            Fast {}
+                            Suspense {
+                                fallback: view! { cx, "Loading inner..." },
+                                Slow {}
+                            }
+                        }
+                    }
+                }
+            })
+            .collect()
+            .await
+        })
+        .await;
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("<!--s:0-->Loading outer...<!--/s:0--><script>"));
+        assert_eq!(
+            chunks[1],
+            "<template id=\"sycamore-s-0\">Outer<!--s:1-->Loading inner...<!--/s:1--></template>\
+             <script>__sycamoreSwap(0)</script>"
+        );
+        assert_eq!(
+            chunks[2],
+            "<template id=\"sycamore-s-1\">Inner</template><script>__sycamoreSwap(1)</script>"
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_without_suspense() {
+        use futures::StreamExt;
+
+        use crate::web::render_to_string_stream;
+
+        let chunks: Vec<String> = provide_executor_scope(async {
+            render_to_string_stream(|cx| view! { cx, p { "Hello" } })
+                .collect()
+                .await
+        })
+        .await;
+        assert_eq!(chunks, vec!["<p data-hk=\"0.0\">Hello</p>".to_string()]);
+    }
+
     #[tokio::test]
     async fn transition() {
         provide_executor_scope(async {
@@ -222,4 +1257,441 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn suspense_resource() {
+        #[component]
+        fn Comp<G: Html>(cx: Scope<'_>) -> View<G> {
+            let user = crate::futures::create_resource(
+                cx,
+                || 1,
+                |id| async move {
+                    tokio::task::yield_now().await;
+                    Ok::<_, ()>(format!("User {id}"))
+                },
+            );
+            view! { cx,
+                (user.value().get().as_ref().clone().unwrap_or_default())
+            }
+        }
+
+        let view = provide_executor_scope(async {
+            render_to_string_await_suspense(|cx| {
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        Comp {}
+                    }
+                }
+            })
+            .await
+        })
+        .await;
+        assert_eq!(view, "User 1");
+    }
+
+    #[tokio::test]
+    async fn suspense_error() {
+        #[component]
+        fn Comp<G: Html>(cx: Scope<'_>) -> View<G> {
+            let res = create_fallible_resource(cx, || async { Err::<(), _>("boom") });
+            create_effect(cx, || {
+                res.track();
+            });
+            view! { cx, "Content" }
+        }
+
+        let view = provide_executor_scope(async {
+            render_to_string_await_suspense(|cx| {
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        error: Box::new(move |errors| {
+                            let messages = errors.messages().join(", ");
+                            view! { cx, "Error: " (messages) }
+                        }),
+                        Comp {}
+                    }
+                }
+            })
+            .await
+        })
+        .await;
+        assert_eq!(view, "Error: boom");
+    }
+
+    #[tokio::test]
+    async fn suspense_error_in_async_component() {
+        #[component]
+        async fn Comp<G: Html>(cx: Scope<'_>) -> View<G> {
+            tokio::task::yield_now().await;
+            // Created after the children of the Suspense are created.
+            let res = create_fallible_resource(cx, || async { Err::<(), _>("boom") });
+            create_effect(cx, || {
+                res.track();
+            });
+            view! { cx, "Content" }
+        }
+
+        let view = provide_executor_scope(async {
+            render_to_string_await_suspense(|cx| {
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        error: Box::new(move |errors| {
+                            let messages = errors.messages().join(", ");
+                            view! { cx, "Error: " (messages) }
+                        }),
+                        Comp {}
+                    }
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        error: Box::new(move |_| view! { cx, "Unexpected error" }),
+                        "Other"
+                    }
+                }
+            })
+            .await
+        })
+        .await;
+        assert_eq!(view, "Error: boomOther");
+    }
+
+    #[tokio::test]
+    async fn suspense_retry() {
+        fn render(view: &View<SsrNode>) -> String {
+            let mut s = String::new();
+            for node in view.clone().flatten() {
+                node.write_to_string(&mut s);
+            }
+            s
+        }
+
+        provide_executor_scope(async {
+            let errors = Rc::new(RefCell::new(None));
+            let mut view = None;
+            let disposer = create_scope({
+                let errors = Rc::clone(&errors);
+                |cx| {
+                    let attempts = create_ref(cx, Cell::new(0));
+                    view = Some(view! { cx,
+                        Suspense {
+                            fallback: view! { cx, "Loading..." },
+                            error: Box::new(move |e| {
+                                *errors.borrow_mut() = Some(e);
+                                view! { cx, "Error" }
+                            }),
+                            children: Children::new(cx, move |cx| {
+                                let res = create_fallible_resource(cx, move || async move {
+                                    attempts.set(attempts.get() + 1);
+                                    if attempts.get() == 1 {
+                                        Err("boom")
+                                    } else {
+                                        Ok(42)
+                                    }
+                                });
+                                view! { cx, (format!("{:?}", res.get())) }
+                            })
+                        }
+                    });
+                }
+            });
+            let view: View<SsrNode> = view.unwrap();
+
+            while errors.borrow().is_none() {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(render(&view), "Error");
+
+            let errors = errors.borrow_mut().take().unwrap();
+            assert_eq!(errors.messages(), vec!["boom".to_string()]);
+            errors.retry();
+            assert_eq!(render(&view), "Loading...");
+
+            while *errors.retrying.get() > 0 {
+                tokio::task::yield_now().await;
+            }
+            assert!(!errors.has_failed());
+            assert_eq!(render(&view), "Some(Ok(42))");
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+
+    /// Receivers that are awaited by [`Wait`], keyed by name.
+    struct Channels(RefCell<HashMap<&'static str, oneshot::Receiver<()>>>);
+
+    #[derive(Prop)]
+    struct WaitProps {
+        name: &'static str,
+    }
+
+    /// Renders its name once the channel with the same name is resolved.
+    #[component]
+    async fn Wait<G: Html>(cx: Scope<'_>, props: WaitProps) -> View<G> {
+        let receiver = use_context::<Channels>(cx)
+            .0
+            .borrow_mut()
+            .remove(props.name)
+            .unwrap();
+        receiver.await.unwrap();
+        let name = props.name;
+        view! { cx, (name) }
+    }
+
+    /// Renders another async component once `c` is resolved.
+    #[component]
+    async fn Nested<G: Html>(cx: Scope<'_>) -> View<G> {
+        let receiver = use_context::<Channels>(cx)
+            .0
+            .borrow_mut()
+            .remove("c")
+            .unwrap();
+        receiver.await.unwrap();
+        view! { cx, "c" Wait { name: "d" } }
+    }
+
+    /// Renders nested async components and Suspense boundaries while the channels are resolved in
+    /// `order`, yielding to the executor between each.
+    async fn render_in_order(order: [&'static str; 4]) -> String {
+        let mut senders = HashMap::new();
+        let mut receivers = HashMap::new();
+        for name in ["a", "b", "c", "d"] {
+            let (sender, receiver) = oneshot::channel();
+            senders.insert(name, sender);
+            receivers.insert(name, receiver);
+        }
+        provide_executor_scope(async move {
+            tokio::task::spawn_local(async move {
+                for name in order {
+                    tokio::task::yield_now().await;
+                    senders.remove(name).unwrap().send(()).unwrap();
+                }
+            });
+            render_to_string_await_suspense(move |cx| {
+                provide_context(cx, Channels(RefCell::new(receivers)));
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        {
+                            Wait { name: "a" }
+                            Suspense {
+                                fallback: view! { cx, "Loading inner..." },
+                                Wait { name: "b" }
+                            }
+                            Nested {}
+                        }
+                    }
+                }
+            })
+            .await
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn nested_suspense_interleaved() {
+        let orders = [
+            ["a", "b", "c", "d"],
+            ["d", "c", "b", "a"],
+            ["b", "a", "d", "c"],
+            ["c", "d", "a", "b"],
+            ["c", "a", "d", "b"],
+            ["b", "d", "c", "a"],
+        ];
+        for order in orders {
+            assert_eq!(render_in_order(order).await, "abcd", "order: {order:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn nested_async_component_is_awaited() {
+        let view = provide_executor_scope(async {
+            render_to_string_await_suspense(|cx| {
+                #[component]
+                async fn Outer<G: Html>(cx: Scope<'_>) -> View<G> {
+                    tokio::task::yield_now().await;
+                    view! { cx, "outer " Inner {} }
+                }
+
+                #[component]
+                async fn Inner<G: Html>(cx: Scope<'_>) -> View<G> {
+                    tokio::task::yield_now().await;
+                    view! { cx, "inner" }
+                }
+
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        Outer {}
+                    }
+                }
+            })
+            .await
+        })
+        .await;
+        assert_eq!(view, "outer inner");
+    }
+
+    #[tokio::test]
+    async fn await_future() {
+        let view = provide_executor_scope(async {
+            render_to_string_await_suspense(|cx| {
+                view! { cx,
+                    Suspense {
+                        fallback: view! { cx, "Loading..." },
+                        Await {
+                            future: async { 42 },
+                            fallback: view! { cx, "Waiting..." },
+                            view: |cx, value| view! { cx, "Value: " (value) },
+                        }
+                    }
+                }
+            })
+            .await
+        })
+        .await;
+        assert_eq!(view, "Value: 42");
+    }
+
+    #[test]
+    fn await_fallback() {
+        fn render(view: &View<SsrNode>) -> String {
+            let mut s = String::new();
+            for node in view.clone().flatten() {
+                node.write_to_string(&mut s);
+            }
+            s
+        }
+
+        let scheduler = deterministic::enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let view = view! { cx,
+                Await {
+                    future: deterministic::sleep(Duration::from_millis(100)),
+                    fallback: view! { cx, "Waiting..." },
+                    view: |cx, _| view! { cx, "Done" },
+                }
+            };
+            assert_eq!(render(&view), "Waiting...");
+            scheduler.advance(Duration::from_millis(100));
+            assert_eq!(render(&view), "Done");
+        });
+    }
+
+    #[test]
+    fn deterministic_fallback_delay() {
+        fn render(view: &View<SsrNode>) -> String {
+            let mut s = String::new();
+            for node in view.clone().flatten() {
+                node.write_to_string(&mut s);
+            }
+            s
+        }
+
+        #[component]
+        async fn Slow<G: Html>(cx: Scope<'_>) -> View<G> {
+            deterministic::sleep(Duration::from_millis(300)).await;
+            view! { cx, "Loaded" }
+        }
+
+        let scheduler = deterministic::enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let view = view! { cx,
+                Suspense {
+                    fallback: view! { cx, "Loading..." },
+                    fallback_delay_ms: 100,
+                    Slow {}
+                }
+            };
+            scheduler.tick();
+            assert_eq!(render(&view), "<!---->");
+            scheduler.advance(Duration::from_millis(100));
+            assert_eq!(render(&view), "Loading...");
+            scheduler.advance(Duration::from_millis(200));
+            assert_eq!(render(&view), "Loaded");
+        });
+    }
+
+    #[test]
+    fn transition_keeps_previous_children() {
+        fn render(view: &View<SsrNode>) -> String {
+            let mut s = String::new();
+            for node in view.clone().flatten() {
+                node.write_to_string(&mut s);
+            }
+            s
+        }
+
+        #[derive(Prop)]
+        struct UserProps {
+            id: u32,
+        }
+
+        #[component]
+        async fn User<G: Html>(cx: Scope<'_>, props: UserProps) -> View<G> {
+            deterministic::sleep(Duration::from_millis(100)).await;
+            view! { cx, "User " (props.id) }
+        }
+
+        let scheduler = deterministic::enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let id = create_signal(cx, 0);
+            let is_pending = create_signal(cx, false);
+            let view = view! { cx,
+                Transition {
+                    view: move |cx| {
+                        let id = *id.get();
+                        view! { cx, User { id: id } }
+                    },
+                    fallback: view! { cx, "Loading..." },
+                    is_pending: is_pending,
+                }
+            };
+            assert_eq!(render(&view), "Loading...");
+            assert!(*is_pending.get());
+            scheduler.advance(Duration::from_millis(100));
+            assert_eq!(render(&view), "User 0");
+            assert!(!*is_pending.get());
+
+            // The previous user is displayed until the next one is loaded.
+            id.set(1);
+            scheduler.tick();
+            assert_eq!(render(&view), "User 0");
+            assert!(*is_pending.get());
+            scheduler.advance(Duration::from_millis(100));
+            assert_eq!(render(&view), "User 1");
+            assert!(!*is_pending.get());
+
+            // A user that is still loading is never displayed once the next one is requested.
+            id.set(2);
+            scheduler.advance(Duration::from_millis(50));
+            id.set(3);
+            scheduler.advance(Duration::from_millis(50));
+            assert_eq!(render(&view), "User 1");
+            assert!(*is_pending.get());
+            scheduler.advance(Duration::from_millis(50));
+            assert_eq!(render(&view), "User 3");
+            assert!(!*is_pending.get());
+        });
+    }
+
+    #[test]
+    fn transition_without_async_children() {
+        create_scope_immediate(|cx| {
+            let count = create_signal(cx, 0);
+            let view: View<SsrNode> = view! { cx,
+                Transition {
+                    view: move |cx| {
+                        let count = *count.get();
+                        view! { cx, p { (count) } }
+                    },
+                    fallback: view! { cx, "Loading..." },
+                }
+            };
+            assert_eq!(crate::render_to_string(|_| view.clone()), "<p>0</p>");
+            count.set(1);
+            assert_eq!(crate::render_to_string(|_| view), "<p>1</p>");
+        });
+    }
 }
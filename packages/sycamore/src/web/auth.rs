@@ -0,0 +1,558 @@
+//! Authentication and session management.
+//!
+//! [`create_auth`] creates an [`Auth`] handle holding the current [`Session`] and provides it to
+//! the component tree. The session is persisted with a [`TokenStorage`] and refreshed
+//! automatically before it expires if a refresh function is configured. [`RequireAuth`] only
+//! renders its children if a user is logged in.
+//!
+//! # Server side rendering
+//! On the server, the session is usually read from the request (e.g. from a cookie) and passed to
+//! [`AuthConfig::session`]. Rendering [`Auth::session_script`] embeds the session in the page so
+//! that the client starts with the same session when hydrating. Note that this includes the token.
+//!
+//! _This API requires the following crate features to be activated: `auth`_
+
+use std::cell::Cell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::component::Children;
+use crate::prelude::*;
+
+/// The id of the `<script>` element rendered by [`Auth::session_script`].
+const SESSION_SCRIPT_ID: &str = "__sycamore_session";
+
+/// A logged in user with its token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session<U> {
+    /// The logged in user.
+    pub user: U,
+    /// The access token, e.g. sent in the `Authorization` header of requests.
+    pub token: String,
+    /// The token used to get a new access token when it expires.
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// When the access token expires, in milliseconds since the Unix epoch.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+/// Where the session is persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenStorage {
+    /// The session is only kept in memory and is lost when the page is reloaded.
+    #[default]
+    Memory,
+    /// The session is stored as JSON in `localStorage` under the given key. Does nothing if not
+    /// on `wasm32` target.
+    LocalStorage(&'static str),
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+mod local_storage {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = localStorage, js_name = getItem, catch)]
+        pub fn get_item(key: &str) -> Result<Option<String>, JsValue>;
+        #[wasm_bindgen(js_namespace = localStorage, js_name = setItem, catch)]
+        pub fn set_item(key: &str, value: &str) -> Result<(), JsValue>;
+        #[wasm_bindgen(js_namespace = localStorage, js_name = removeItem, catch)]
+        pub fn remove_item(key: &str) -> Result<(), JsValue>;
+    }
+}
+
+impl TokenStorage {
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    fn load<U: DeserializeOwned>(self) -> Option<Session<U>> {
+        match self {
+            Self::Memory => None,
+            Self::LocalStorage(key) => local_storage::get_item(key)
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str(&json).ok()),
+        }
+    }
+
+    fn save<U: Serialize>(self, session: Option<&Session<U>>) {
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        if let Self::LocalStorage(key) = self {
+            // Storage errors (e.g. quota exceeded or disabled storage) are ignored: the session
+            // is still kept in memory.
+            let _ = match session.map(serde_json::to_string) {
+                Some(Ok(json)) => local_storage::set_item(key, &json),
+                _ => local_storage::remove_item(key),
+            };
+        }
+        #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+        let _ = session;
+    }
+}
+
+/// An error returned by the refresh function of an [`AuthConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthError {
+    message: String,
+}
+
+impl AuthError {
+    /// Creates a new error.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+type RefreshFuture<U> = Pin<Box<dyn Future<Output = Result<Session<U>, AuthError>>>>;
+type RefreshFn<U> = Rc<dyn Fn(Session<U>) -> RefreshFuture<U>>;
+
+/// Configuration for [`create_auth`].
+pub struct AuthConfig<U> {
+    storage: TokenStorage,
+    refresh: Option<RefreshFn<U>>,
+    refresh_margin_ms: u64,
+    session: Option<Session<U>>,
+}
+
+impl<U> Default for AuthConfig<U> {
+    fn default() -> Self {
+        Self {
+            storage: TokenStorage::default(),
+            refresh: None,
+            refresh_margin_ms: 30_000,
+            session: None,
+        }
+    }
+}
+
+impl<U> AuthConfig<U> {
+    /// Creates a new configuration with the session kept in memory and no refresh function.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets where the session is persisted. Defaults to [`TokenStorage::Memory`].
+    pub fn storage(mut self, storage: TokenStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets the function that exchanges the current session for a new one, e.g. using the
+    /// refresh token. It is called before the session expires.
+    pub fn refresh<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Session<U>) -> Fut + 'static,
+        Fut: Future<Output = Result<Session<U>, AuthError>> + 'static,
+    {
+        self.refresh = Some(Rc::new(move |session| Box::pin(f(session))));
+        self
+    }
+
+    /// Sets how long before the expiry of the session it is refreshed. Defaults to 30 seconds.
+    pub fn refresh_margin_ms(mut self, ms: u64) -> Self {
+        self.refresh_margin_ms = ms;
+        self
+    }
+
+    /// Sets the initial session, e.g. read from the request on the server. If not set, the
+    /// session is read from the page rendered by the server and then from the [`TokenStorage`].
+    pub fn session(mut self, session: Option<Session<U>>) -> Self {
+        self.session = session;
+        self
+    }
+}
+
+impl<U> fmt::Debug for AuthConfig<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthConfig")
+            .field("storage", &self.storage)
+            .field("refresh", &self.refresh.is_some())
+            .field("refresh_margin_ms", &self.refresh_margin_ms)
+            .finish()
+    }
+}
+
+/// A handle to the current session. Created with [`create_auth`] and retrieved with
+/// [`use_auth`].
+pub struct Auth<U: 'static> {
+    session: RcSignal<Option<Session<U>>>,
+    storage: TokenStorage,
+    refresh: Option<RefreshFn<U>>,
+}
+
+impl<U> Clone for Auth<U> {
+    fn clone(&self) -> Self {
+        Self {
+            session: self.session.clone(),
+            storage: self.storage,
+            refresh: self.refresh.clone(),
+        }
+    }
+}
+
+impl<U: fmt::Debug> fmt::Debug for Auth<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Auth")
+            .field("session", &self.session.get_untracked())
+            .field("storage", &self.storage)
+            .finish()
+    }
+}
+
+impl<U: Clone + Serialize + DeserializeOwned> Auth<U> {
+    /// The current session, or `None` if no user is logged in.
+    pub fn session(&self) -> &ReadSignal<Option<Session<U>>> {
+        &self.session
+    }
+
+    /// Returns the logged in user. This value can be tracked.
+    pub fn user(&self) -> Option<U> {
+        self.session.get().as_ref().as_ref().map(|s| s.user.clone())
+    }
+
+    /// Returns the access token of the current session. This value can be tracked.
+    pub fn token(&self) -> Option<String> {
+        self.session
+            .get()
+            .as_ref()
+            .as_ref()
+            .map(|s| s.token.clone())
+    }
+
+    /// Returns `true` if a user is logged in. This value can be tracked.
+    pub fn is_authenticated(&self) -> bool {
+        self.session.get().is_some()
+    }
+
+    /// Replaces the current session and persists it.
+    pub fn login(&self, session: Session<U>) {
+        self.storage.save(Some(&session));
+        self.session.set(Some(session));
+    }
+
+    /// Clears the current session.
+    pub fn logout(&self) {
+        self.storage.save::<U>(None);
+        self.session.set(None);
+    }
+
+    /// Exchanges the current session for a new one with the refresh function of the
+    /// [`AuthConfig`]. The user is logged out if the refresh fails.
+    pub async fn refresh(&self) -> Result<(), AuthError> {
+        let refresh = self
+            .refresh
+            .clone()
+            .ok_or_else(|| AuthError::new("no refresh function configured"))?;
+        let session = self
+            .session
+            .get_untracked()
+            .as_ref()
+            .clone()
+            .ok_or_else(|| AuthError::new("not logged in"))?;
+        match refresh(session).await {
+            Ok(session) => {
+                self.login(session);
+                Ok(())
+            }
+            Err(err) => {
+                self.logout();
+                Err(err)
+            }
+        }
+    }
+
+    /// Renders a `<script>` element containing the current session so that the client can
+    /// restore it when hydrating. Render this once on the page, both on the server and on the
+    /// client.
+    pub fn session_script<'a, G: Html>(&self, cx: Scope<'a>) -> View<G> {
+        let json = serde_json::to_string(self.session.get_untracked().as_ref())
+            .unwrap_or_else(|_| "null".to_string())
+            // Prevent the JSON from closing the script element.
+            .replace('<', "\\u003c");
+        let json: &'a str = create_ref(cx, json);
+        view! { cx,
+            script(id=SESSION_SCRIPT_ID, type="application/json", dangerously_set_inner_html=json)
+        }
+    }
+}
+
+/// Reads the session embedded in the page by [`Auth::session_script`].
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+fn read_session_script<U: DeserializeOwned>() -> Option<Session<U>> {
+    let json = web_sys::window()?
+        .document()?
+        .get_element_by_id(SESSION_SCRIPT_ID)?
+        .text_content()?;
+    serde_json::from_str(&json).ok().flatten()
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+fn now_ms() -> u64 {
+    js_sys::Date::now() as u64
+}
+
+/// Returns `true` if a user is logged in. Provided in the context for [`RequireAuth`], which does
+/// not know the type of the user.
+#[derive(Clone)]
+struct AuthGuard(Rc<dyn Fn() -> bool>);
+
+/// Creates an [`Auth`] handle and provides it to the component tree. Child components retrieve it
+/// with [`use_auth`].
+///
+/// If the session has an expiry time and a refresh function is configured, the session is
+/// refreshed before it expires. This only happens on `wasm32` target.
+///
+/// # Example
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use sycamore::prelude::*;
+/// use sycamore::web::auth::{create_auth, AuthConfig, RequireAuth, Session, TokenStorage};
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[component]
+/// fn App<G: Html>(cx: Scope) -> View<G> {
+///     let auth = create_auth::<User>(
+///         cx,
+///         AuthConfig::new().storage(TokenStorage::LocalStorage("session")),
+///     );
+///     let login = move |_| {
+///         auth.login(Session {
+///             user: User { name: "Alice".to_string() },
+///             token: "token".to_string(),
+///             refresh_token: None,
+///             expires_at: None,
+///         })
+///     };
+///     view! { cx,
+///         RequireAuth {
+///             fallback: view! { cx, button(on:click=login) { "Log in" } },
+///             p { "Hello " (auth.user().map(|user| user.name).unwrap_or_default()) }
+///         }
+///     }
+/// }
+/// ```
+pub fn create_auth<U>(cx: Scope<'_>, config: AuthConfig<U>) -> &Auth<U>
+where
+    U: Clone + Serialize + DeserializeOwned + 'static,
+{
+    let AuthConfig {
+        storage,
+        refresh,
+        refresh_margin_ms,
+        session,
+    } = config;
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    let session = session
+        .or_else(read_session_script)
+        .or_else(|| storage.load());
+    storage.save(session.as_ref());
+
+    let auth = Auth {
+        session: create_rc_signal(session),
+        storage,
+        refresh,
+    };
+    provide_context(cx, auth.clone());
+    provide_context(
+        cx,
+        AuthGuard(Rc::new({
+            let auth = auth.clone();
+            move || auth.is_authenticated()
+        })),
+    );
+    let auth = create_ref(cx, auth);
+
+    // Schedule a refresh every time the session changes. Incremented so that outdated refreshes
+    // are cancelled.
+    let generation = create_ref(cx, Cell::new(0u32));
+    create_effect(cx, move || {
+        generation.set(generation.get() + 1);
+        let expires_at = auth
+            .session
+            .get()
+            .as_ref()
+            .as_ref()
+            .and_then(|s| s.expires_at);
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        if let (Some(expires_at), Some(_)) = (expires_at, &auth.refresh) {
+            let current = generation.get();
+            let delay = expires_at
+                .saturating_sub(refresh_margin_ms)
+                .saturating_sub(now_ms())
+                .min(i32::MAX as u64) as u32;
+            sycamore_futures::spawn_local_scoped(cx, async move {
+                crate::suspense::sleep(delay).await;
+                if generation.get() == current {
+                    // Errors log the user out, which is visible through the session signal.
+                    let _ = auth.refresh().await;
+                }
+            });
+        }
+        #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+        let _ = (expires_at, refresh_margin_ms);
+    });
+    auth
+}
+
+/// Retrieves the [`Auth`] handle created by [`create_auth`] in a parent component.
+///
+/// # Panics
+/// This function panics if [`create_auth`] was not called with the same user type in a parent
+/// component.
+pub fn use_auth<U: 'static>(cx: Scope<'_>) -> &Auth<U> {
+    use_context::<Auth<U>>(cx)
+}
+
+/// Props for [`RequireAuth`].
+#[derive(Prop, Debug)]
+pub struct RequireAuthProps<'a, G: GenericNode> {
+    children: Children<'a, G>,
+    /// Rendered instead of the children if no user is logged in, e.g. a login form or a
+    /// component that navigates to the login page.
+    #[builder(default)]
+    fallback: View<G>,
+}
+
+/// Renders its children only if a user is logged in, and `fallback` otherwise. Use this to guard
+/// routes that require authentication.
+///
+/// The children are only created once a user is logged in.
+///
+/// # Panics
+/// This component panics if [`create_auth`] was not called in a parent component.
+#[component]
+pub fn RequireAuth<'a, G: GenericNode>(cx: Scope<'a>, props: RequireAuthProps<'a, G>) -> View<G> {
+    let AuthGuard(is_authenticated) = use_context::<AuthGuard>(cx);
+    let mut children = Some(props.children);
+    let mut view = None;
+    let fallback = props.fallback;
+    View::new_dyn(cx, move || {
+        if is_authenticated() {
+            view.get_or_insert_with(|| children.take().unwrap().call(cx))
+                .clone()
+        } else {
+            fallback.clone()
+        }
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct User {
+        name: String,
+    }
+
+    fn session(name: &str) -> Session<User> {
+        Session {
+            user: User {
+                name: name.to_string(),
+            },
+            token: format!("token-{name}"),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: None,
+        }
+    }
+
+    #[component]
+    fn Greeting<G: Html>(cx: Scope<'_>) -> View<G> {
+        let created = use_context::<Cell<u32>>(cx);
+        created.set(created.get() + 1);
+        let auth = use_auth::<User>(cx);
+        view! { cx, "Hello " (auth.user().map(|user| user.name).unwrap_or_default()) }
+    }
+
+    #[test]
+    fn require_auth() {
+        create_scope_immediate(|cx| {
+            let auth = create_auth::<User>(cx, AuthConfig::new());
+            let created = provide_context(cx, Cell::new(0u32));
+            let view: View<SsrNode> = view! { cx,
+                RequireAuth {
+                    fallback: view! { cx, "Log in" },
+                    Greeting {}
+                }
+            };
+            let render = || crate::web::render_to_string(|_| view.clone());
+            assert_eq!(render(), "Log in");
+            assert_eq!(created.get(), 0);
+
+            auth.login(session("Alice"));
+            assert_eq!(render(), "Hello Alice");
+            auth.logout();
+            assert_eq!(render(), "Log in");
+            // The children are only created once.
+            auth.login(session("Bob"));
+            assert_eq!(render(), "Hello Bob");
+            assert_eq!(created.get(), 1);
+        });
+    }
+
+    #[tokio::test]
+    async fn refresh() {
+        let auth = create_scope_immediate_auth(AuthConfig::new().refresh(|session| async move {
+            assert_eq!(session.refresh_token.as_deref(), Some("refresh"));
+            Ok(Session {
+                token: "token-new".to_string(),
+                ..session
+            })
+        }));
+        auth.login(session("Alice"));
+        auth.refresh().await.unwrap();
+        assert_eq!(auth.token().as_deref(), Some("token-new"));
+
+        let auth = create_scope_immediate_auth(
+            AuthConfig::new()
+                .session(Some(session("Alice")))
+                .refresh(|_| async { Err(AuthError::new("expired")) }),
+        );
+        assert!(auth.is_authenticated());
+        assert_eq!(auth.refresh().await, Err(AuthError::new("expired")));
+        assert!(!auth.is_authenticated());
+    }
+
+    /// Returns a clone of the [`Auth`] created in a disposed scope.
+    fn create_scope_immediate_auth(config: AuthConfig<User>) -> Auth<User> {
+        let mut auth = None;
+        create_scope_immediate(|cx| auth = Some(create_auth(cx, config).clone()));
+        auth.unwrap()
+    }
+
+    #[test]
+    fn session_script() {
+        create_scope_immediate(|cx| {
+            let auth = create_auth(cx, AuthConfig::new().session(Some(session("</script>"))));
+            let html = crate::web::render_to_string(|cx| auth.session_script(cx));
+            assert!(html
+                .contains(r#"{"user":{"name":"\u003c/script>"},"token":"token-\u003c/script>""#));
+            assert_eq!(html.matches("</script>").count(), 1);
+        });
+    }
+}
@@ -0,0 +1,235 @@
+//! Reactive tracking of an element's position and size in the viewport.
+//!
+//! Useful for anchoring overlays (tooltips, popovers, dropdowns) to an element or for drawing
+//! connection lines between nodes.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::bounding_rect::create_bounding_rect;
+//!
+//! #[component]
+//! fn Tooltip<G: Html>(cx: Scope) -> View<G> {
+//!     let anchor = create_node_ref(cx);
+//!     let rect = create_bounding_rect(cx, anchor);
+//!     let style = create_memo(cx, || {
+//!         format!("position: fixed; top: {}px; left: {}px", rect.get().bottom(), rect.get().x)
+//!     });
+//!     view! { cx,
+//!         button(ref=anchor) { "Hover me" }
+//!         div(style=style.get()) { "Tooltip" }
+//!     }
+//! }
+//! ```
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::mutation_observer::{MutationObserver, MutationObserverOptions};
+use super::node_ref_node;
+use crate::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    type ResizeObserver;
+    #[wasm_bindgen(constructor)]
+    fn new(cb: &js_sys::Function) -> ResizeObserver;
+    #[wasm_bindgen(method)]
+    fn observe(this: &ResizeObserver, target: &web_sys::Element);
+    #[wasm_bindgen(method)]
+    fn disconnect(this: &ResizeObserver);
+}
+
+/// The position and size of an element relative to the viewport, in CSS pixels. This is the
+/// value returned by `Element.getBoundingClientRect()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BoundingRect {
+    /// The x coordinate of the left edge.
+    pub x: f64,
+    /// The y coordinate of the top edge.
+    pub y: f64,
+    /// The width of the element, including transforms.
+    pub width: f64,
+    /// The height of the element, including transforms.
+    pub height: f64,
+}
+
+impl BoundingRect {
+    /// The y coordinate of the top edge. Same as [`BoundingRect::y`].
+    pub fn top(&self) -> f64 {
+        self.y
+    }
+
+    /// The x coordinate of the right edge.
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    /// The y coordinate of the bottom edge.
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// The x coordinate of the left edge. Same as [`BoundingRect::x`].
+    pub fn left(&self) -> f64 {
+        self.x
+    }
+
+    /// The coordinates of the center of the rect.
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+impl From<web_sys::DomRect> for BoundingRect {
+    fn from(rect: web_sys::DomRect) -> Self {
+        Self {
+            x: rect.x(),
+            y: rect.y(),
+            width: rect.width(),
+            height: rect.height(),
+        }
+    }
+}
+
+/// Observers and event listeners that keep a [`BoundingRect`] signal up to date. Everything is
+/// disconnected when dropped.
+struct RectTracker {
+    resize_observer: ResizeObserver,
+    mutation_observer: MutationObserver,
+    on_change: Closure<dyn Fn()>,
+    /// Handle of the pending animation frame, if any.
+    frame: Rc<Cell<Option<i32>>>,
+}
+
+impl RectTracker {
+    fn new(element: web_sys::Element, rect: RcSignal<BoundingRect>) -> Self {
+        let window = web_sys::window().unwrap_throw();
+
+        let measure = {
+            let element = element.clone();
+            move || {
+                let new = BoundingRect::from(element.get_bounding_client_rect());
+                if *rect.get_untracked() != new {
+                    rect.set(new);
+                }
+            }
+        };
+        measure();
+
+        let frame = Rc::new(Cell::new(None));
+        let on_frame = Closure::wrap(Box::new({
+            let frame = Rc::clone(&frame);
+            move || {
+                frame.set(None);
+                measure();
+            }
+        }) as Box<dyn Fn()>);
+        // Changes are coalesced so that the rect is measured at most once per animation frame.
+        let on_change = Closure::wrap(Box::new({
+            let frame = Rc::clone(&frame);
+            move || {
+                if frame.get().is_none() {
+                    let handle = web_sys::window()
+                        .unwrap_throw()
+                        .request_animation_frame(on_frame.as_ref().unchecked_ref())
+                        .unwrap_throw();
+                    frame.set(Some(handle));
+                }
+            }
+        }) as Box<dyn Fn()>);
+        let cb: &js_sys::Function = on_change.as_ref().unchecked_ref();
+
+        // Changes to the size of the element itself.
+        let resize_observer = ResizeObserver::new(cb);
+        resize_observer.observe(&element);
+
+        // Changes to `style` and `class` anywhere in the document, e.g. a transform on the element
+        // or on one of its ancestors.
+        let mutation_observer = MutationObserver::new(cb);
+        let options = MutationObserverOptions {
+            attributes: true,
+            subtree: true,
+            attribute_filter: Some(vec!["style".to_string(), "class".to_string()]),
+            ..Default::default()
+        };
+        mutation_observer.observe(
+            &window
+                .document()
+                .unwrap_throw()
+                .document_element()
+                .unwrap_throw(),
+            &options.to_js(),
+        );
+
+        // Scrolling of the page or of any scrollable ancestor. Scroll events do not bubble so the
+        // listener is added in the capture phase.
+        window
+            .add_event_listener_with_callback_and_bool("scroll", cb, true)
+            .unwrap_throw();
+        window
+            .add_event_listener_with_callback("resize", cb)
+            .unwrap_throw();
+
+        Self {
+            resize_observer,
+            mutation_observer,
+            on_change,
+            frame,
+        }
+    }
+}
+
+impl Drop for RectTracker {
+    fn drop(&mut self) {
+        let window = web_sys::window().unwrap_throw();
+        if let Some(handle) = self.frame.take() {
+            window.cancel_animation_frame(handle).unwrap_throw();
+        }
+        self.resize_observer.disconnect();
+        self.mutation_observer.disconnect();
+        let cb: &js_sys::Function = self.on_change.as_ref().unchecked_ref();
+        window
+            .remove_event_listener_with_callback_and_bool("scroll", cb, true)
+            .unwrap_throw();
+        window
+            .remove_event_listener_with_callback("resize", cb)
+            .unwrap_throw();
+    }
+}
+
+/// Creates a signal that tracks the bounding rect of the element referenced by `node_ref`,
+/// relative to the viewport.
+///
+/// The rect is measured once the component is mounted and is then kept up to date when:
+/// * the element is resized (using a `ResizeObserver`),
+/// * the page or any scrollable ancestor is scrolled,
+/// * the window is resized,
+/// * a `style` or `class` attribute changes anywhere in the document, which catches transforms
+///   applied to the element or to one of its ancestors.
+///
+/// Updates are throttled to at most once per animation frame, and the signal is only updated
+/// when the rect actually changes. Everything is disconnected when the scope is disposed.
+///
+/// If not rendering in a browser, or if `node_ref` does not reference an element once mounted,
+/// the signal always holds [`BoundingRect::default()`].
+pub fn create_bounding_rect<'a, G: Html>(
+    cx: Scope<'a>,
+    node_ref: &'a NodeRef<G>,
+) -> &'a ReadSignal<BoundingRect> {
+    let rect: &RcSignal<BoundingRect> = create_ref(cx, create_rc_signal(BoundingRect::default()));
+
+    if G::IS_BROWSER {
+        on_mount(cx, move || {
+            if let Some(element) = node_ref_node(node_ref).and_then(|node| node.dyn_into().ok()) {
+                let tracker = RectTracker::new(element, rect.clone());
+                on_cleanup(cx, move || drop(tracker));
+            }
+        });
+    }
+
+    rect
+}
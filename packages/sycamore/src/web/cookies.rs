@@ -0,0 +1,330 @@
+//! Reading and writing cookies, both on the server and on the client.
+//!
+//! On the client, cookies are read from and written to `document.cookie`. On the server, cookies
+//! are read from the `Cookie` header of the request, which must be passed to [`provide_cookies`]
+//! before rendering, and written cookies are collected as `Set-Cookie` headers to be sent with the
+//! response. This means that components reading preferences (e.g. the theme) or session cookies
+//! render the same thing on the server and on the client.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::cookies::{create_cookie, use_cookies, CookieOptions};
+//!
+//! #[component]
+//! fn ThemeToggle<G: Html>(cx: Scope) -> View<G> {
+//!     let theme = create_cookie(cx, "theme");
+//!     let toggle = move |_| {
+//!         let next = match theme.get().as_deref() {
+//!             Some("dark") => "light",
+//!             _ => "dark",
+//!         };
+//!         use_cookies(cx).set("theme", next, &CookieOptions::default().max_age(60 * 60 * 24 * 365));
+//!     };
+//!     view! { cx,
+//!         button(on:click=toggle) { "Theme: " (theme.get().as_deref().unwrap_or("light").to_string()) }
+//!     }
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::prelude::*;
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// The cookie is only sent with requests from the same site.
+    Strict,
+    /// The cookie is also sent when navigating to the site from another site.
+    Lax,
+    /// The cookie is sent with all requests. Requires [`CookieOptions::secure`].
+    None,
+}
+
+impl SameSite {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+}
+
+/// Attributes of a cookie written with [`Cookies::set`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieOptions {
+    max_age: Option<i64>,
+    path: Option<String>,
+    domain: Option<String>,
+    same_site: Option<SameSite>,
+    secure: bool,
+    http_only: bool,
+}
+
+impl CookieOptions {
+    /// Sets the number of seconds until the cookie expires. Without a max age, the cookie is
+    /// deleted when the browser is closed.
+    pub fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Sets the path for which the cookie is sent. Defaults to the path of the current page.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the domain for which the cookie is sent. Defaults to the current host.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the `SameSite` attribute of the cookie.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Only send the cookie over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Prevent scripts from reading the cookie. Such cookies can only be written on the server.
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+}
+
+/// Percent-encodes the characters that are not allowed in cookie names and values.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'!' | b'#'..=b'+' | b'-'..=b':' | b'<'..=b'[' | b']'..=b'~' if byte != b'%' => {
+                encoded.push(byte as char);
+            }
+            _ => write!(encoded, "%{byte:02X}").unwrap(),
+        }
+    }
+    encoded
+}
+
+/// Decodes a percent-encoded string. Invalid escapes are kept as is.
+pub(super) fn decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Parses a `Cookie` header or the value of `document.cookie`.
+fn parse(header: &str) -> HashMap<String, String> {
+    header
+        .split(';')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+            Some((decode(name.trim()), decode(value)))
+        })
+        .collect()
+}
+
+/// Formats a cookie for a `Set-Cookie` header or for `document.cookie`.
+fn format_cookie(name: &str, value: &str, options: &CookieOptions) -> String {
+    // `=` is allowed in values but separates the name from the value.
+    let mut cookie = format!("{}={}", encode(name).replace('=', "%3D"), encode(value));
+    if let Some(max_age) = options.max_age {
+        write!(cookie, "; Max-Age={max_age}").unwrap();
+    }
+    if let Some(path) = &options.path {
+        write!(cookie, "; Path={path}").unwrap();
+    }
+    if let Some(domain) = &options.domain {
+        write!(cookie, "; Domain={domain}").unwrap();
+    }
+    if let Some(same_site) = options.same_site {
+        write!(cookie, "; SameSite={}", same_site.as_str()).unwrap();
+    }
+    if options.secure {
+        cookie.push_str("; Secure");
+    }
+    if options.http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    cookie
+}
+
+/// The cookies of the current page or request. Retrieved with [`use_cookies`].
+#[derive(Debug)]
+pub struct Cookies {
+    values: RcSignal<HashMap<String, String>>,
+    /// The `Set-Cookie` headers of the cookies written on the server.
+    set_cookie_headers: RefCell<Vec<String>>,
+}
+
+impl Cookies {
+    /// Reads the cookies of the current page. Empty if not on `wasm32` target.
+    fn from_document() -> Self {
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        if let Some(cookie) = document_cookie() {
+            return Self::new(parse(&cookie));
+        }
+        Self::new(HashMap::new())
+    }
+
+    fn new(values: HashMap<String, String>) -> Self {
+        Self {
+            values: create_rc_signal(values),
+            set_cookie_headers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the value of the cookie `name`. This value can be tracked.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.values.get().get(name).cloned()
+    }
+
+    /// Returns all the cookies. This value can be tracked.
+    pub fn all(&self) -> HashMap<String, String> {
+        self.values.get().as_ref().clone()
+    }
+
+    /// Writes the cookie `name`. On the client, the cookie is written to `document.cookie`. On
+    /// the server, a `Set-Cookie` header is added to [`Cookies::set_cookie_headers`].
+    pub fn set(&self, name: &str, value: &str, options: &CookieOptions) {
+        self.write(format_cookie(name, value, options));
+        let mut values = self.values.modify();
+        if matches!(options.max_age, Some(max_age) if max_age <= 0) {
+            values.remove(name);
+        } else {
+            values.insert(name.to_string(), value.to_string());
+        }
+    }
+
+    /// Deletes the cookie `name`. `options` must have the same path and domain as the cookie.
+    pub fn remove(&self, name: &str, options: &CookieOptions) {
+        self.set(name, "", &options.clone().max_age(0));
+    }
+
+    /// Returns the `Set-Cookie` headers of the cookies written on the server. These headers should
+    /// be added to the response once the page is rendered.
+    pub fn set_cookie_headers(&self) -> Vec<String> {
+        self.set_cookie_headers.borrow().clone()
+    }
+
+    fn write(&self, cookie: String) {
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+            let _ = js_sys::Reflect::set(&document, &"cookie".into(), &cookie.into());
+            return;
+        }
+        self.set_cookie_headers.borrow_mut().push(cookie);
+    }
+}
+
+/// Returns the value of `document.cookie`.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+fn document_cookie() -> Option<String> {
+    let document = web_sys::window()?.document()?;
+    js_sys::Reflect::get(&document, &"cookie".into())
+        .ok()?
+        .as_string()
+}
+
+/// Provides the cookies of the request to the component tree. Call this on the server before
+/// rendering, with the value of the `Cookie` header of the request.
+///
+/// On the client, the cookies are read from `document.cookie` by [`use_cookies`] and this
+/// function does not need to be called.
+pub fn provide_cookies<'a>(cx: Scope<'a>, cookie_header: Option<&str>) -> &'a Cookies {
+    provide_context(
+        cx,
+        Cookies::new(cookie_header.map(parse).unwrap_or_default()),
+    )
+}
+
+/// Returns the [`Cookies`] provided by [`provide_cookies`] in a parent scope. If there are none,
+/// the cookies of the current page are read and provided in the current scope.
+pub fn use_cookies(cx: Scope<'_>) -> &Cookies {
+    use_context_or_else(cx, Cookies::from_document)
+}
+
+/// Creates a signal holding the value of the cookie `name`, or `None` if it is not set. The signal
+/// is updated when the cookie is written with [`Cookies::set`].
+pub fn create_cookie<'a>(cx: Scope<'a>, name: &'a str) -> &'a ReadSignal<Option<String>> {
+    let cookies = use_cookies(cx);
+    create_selector(cx, move || cookies.values.get().get(name).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_encode() {
+        let cookies = parse(r#"theme=dark; name="a%20b"; broken; empty="#);
+        assert_eq!(cookies["theme"], "dark");
+        assert_eq!(cookies["name"], "a b");
+        assert_eq!(cookies["empty"], "");
+        assert_eq!(cookies.len(), 3);
+
+        let value = "a b;c=%\"é";
+        assert_eq!(encode(value), "a%20b%3Bc=%25%22%C3%A9");
+        assert_eq!(decode(&encode(value)), value);
+    }
+
+    #[test]
+    fn set_on_server() {
+        create_scope_immediate(|cx| {
+            let cookies = provide_cookies(cx, Some("theme=light; session=abc"));
+            let theme = create_cookie(cx, "theme");
+            assert_eq!(theme.get().as_deref(), Some("light"));
+
+            let options = CookieOptions::default()
+                .max_age(3600)
+                .path("/")
+                .same_site(SameSite::Lax)
+                .secure(true);
+            use_cookies(cx).set("theme", "dark", &options);
+            assert_eq!(theme.get().as_deref(), Some("dark"));
+            cookies.remove("session", &CookieOptions::default().path("/"));
+            assert_eq!(cookies.get("session"), None);
+            assert_eq!(
+                cookies.set_cookie_headers(),
+                [
+                    "theme=dark; Max-Age=3600; Path=/; SameSite=Lax; Secure",
+                    "session=; Max-Age=0; Path=/"
+                ]
+            );
+        });
+    }
+}
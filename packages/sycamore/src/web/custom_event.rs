@@ -0,0 +1,125 @@
+//! Dispatching and listening to
+//! [`CustomEvent`](https://developer.mozilla.org/en-US/docs/Web/API/CustomEvent)s with a typed
+//! `detail`, e.g. to communicate with JS code or web components that are not written with
+//! Sycamore.
+//!
+//! The `detail` is converted to and from a plain JS value through JSON using `serde`, so that JS
+//! listeners see an ordinary object.
+//!
+//! _This API requires the following crate features to be activated: `custom-events`_
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::node_ref_node;
+use crate::prelude::*;
+
+/// Dispatches a `CustomEvent` named `name` on the node referenced by `node_ref`, with `detail`
+/// serialized as its `detail`. The event bubbles, is cancelable and crosses shadow DOM
+/// boundaries, like the events of built-in elements.
+///
+/// Returns `false` if a listener called `preventDefault()`. Does nothing and returns `true` if
+/// `node_ref` is not set or if not rendering in a browser.
+///
+/// # Panics
+/// Panics if `detail` cannot be serialized to JSON, e.g. a map with keys that are not strings.
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use sycamore::prelude::*;
+/// use sycamore::web::custom_event::dispatch_custom_event;
+///
+/// #[derive(Serialize)]
+/// struct Selected {
+///     id: u32,
+/// }
+///
+/// #[component]
+/// fn Picker<G: Html>(cx: Scope) -> View<G> {
+///     let node_ref = create_node_ref(cx);
+///     view! { cx,
+///         button(ref=node_ref, on:click=|_| {
+///             dispatch_custom_event(node_ref, "item-selected", &Selected { id: 1 });
+///         }) { "Select" }
+///     }
+/// }
+/// ```
+pub fn dispatch_custom_event<G: Html, T: Serialize + ?Sized>(
+    node_ref: &NodeRef<G>,
+    name: &str,
+    detail: &T,
+) -> bool {
+    let Some(node) = node_ref_node(node_ref) else {
+        return true;
+    };
+    let json = serde_json::to_string(detail).expect("custom event detail should be serializable");
+    let init = web_sys::CustomEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_detail(&js_sys::JSON::parse(&json).unwrap_throw());
+    let event = web_sys::CustomEvent::new_with_event_init_dict(name, &init).unwrap_throw();
+    node.dispatch_event(&event).unwrap_throw()
+}
+
+/// Returns an event handler for `on:` that deserializes the `detail` of a `CustomEvent` into `T`
+/// and calls `handler` with it. This is the counterpart of [`dispatch_custom_event`], and also
+/// accepts the events dispatched by JS code.
+///
+/// Events whose `detail` does not deserialize into `T`, and events that are not `CustomEvent`s,
+/// are ignored. A warning is logged for them in debug builds.
+///
+/// # Example
+/// ```
+/// use serde::Deserialize;
+/// use sycamore::prelude::*;
+/// use sycamore::web::custom_event::custom_event_handler;
+///
+/// #[derive(Deserialize)]
+/// struct Selected {
+///     id: u32,
+/// }
+///
+/// #[component]
+/// fn List<G: Html>(cx: Scope) -> View<G> {
+///     let selected = create_signal(cx, None);
+///     view! { cx,
+///         div(on:item-selected=custom_event_handler(|detail: Selected| selected.set(Some(detail.id)))) {
+///             // Children that dispatch `item-selected` events.
+///         }
+///     }
+/// }
+/// ```
+pub fn custom_event_handler<'a, T: DeserializeOwned>(
+    mut handler: impl FnMut(T) + 'a,
+) -> impl FnMut(web_sys::Event) + 'a {
+    move |event: web_sys::Event| match event_detail(&event) {
+        Ok(detail) => handler(detail),
+        Err(_err) => {
+            #[cfg(debug_assertions)]
+            web_sys::console::warn_2(
+                &format!("ignored `{}` event: {_err}", event.type_()).into(),
+                &event,
+            );
+        }
+    }
+}
+
+/// Deserializes the `detail` of `event`.
+fn event_detail<T: DeserializeOwned>(event: &web_sys::Event) -> Result<T, String> {
+    let event = event
+        .dyn_ref::<web_sys::CustomEvent>()
+        .ok_or("the event is not a `CustomEvent`")?;
+    let detail = event.detail();
+    if detail.is_undefined() {
+        return serde_json::from_str("null").map_err(|err| err.to_string());
+    }
+    let json = js_sys::JSON::stringify(&detail)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or("the detail cannot be converted to JSON")?;
+    serde_json::from_str(&json).map_err(|err| err.to_string())
+}
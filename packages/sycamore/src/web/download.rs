@@ -0,0 +1,85 @@
+//! Helpers for downloading files and creating object URLs.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::download::download_blob;
+//!
+//! #[component]
+//! fn Export<G: Html>(cx: Scope) -> View<G> {
+//!     let export = |_| download_blob("data.csv", b"name,score\nAlice,42\n", "text/csv");
+//!     view! { cx, button(on:click=export) { "Export CSV" } }
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, HtmlAnchorElement, Url};
+
+use crate::prelude::*;
+
+/// Creates a [`Blob`] containing `bytes` with the MIME type `mime`.
+pub fn create_blob(bytes: &[u8], mime: &str) -> Blob {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"type".into(), &mime.into()).unwrap_throw();
+    Blob::new_with_u8_array_sequence_and_options(&parts, options.unchecked_ref()).unwrap_throw()
+}
+
+/// Makes the browser download a file named `name` containing `bytes` with the MIME type `mime`.
+///
+/// Does nothing if not on `wasm32` target.
+pub fn download_blob(name: &str, bytes: &[u8], mime: &str) {
+    if !cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
+        return;
+    }
+    let url = Url::create_object_url_with_blob(&create_blob(bytes, mime)).unwrap_throw();
+    download_url(name, &url);
+    // Revoke the URL once the download has started.
+    let revoke = Closure::once_into_js(move || Url::revoke_object_url(&url).unwrap_throw());
+    web_sys::window()
+        .unwrap_throw()
+        .set_timeout_with_callback(revoke.unchecked_ref())
+        .unwrap_throw();
+}
+
+/// Makes the browser download the file at `url`, saving it as `name`. `url` must have the same
+/// origin as the page, or be a `blob:` or `data:` URL.
+///
+/// Does nothing if not on `wasm32` target.
+pub fn download_url(name: &str, url: &str) {
+    if !cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
+        return;
+    }
+    let anchor: HtmlAnchorElement = web_sys::window()
+        .unwrap_throw()
+        .document()
+        .unwrap_throw()
+        .create_element("a")
+        .unwrap_throw()
+        .unchecked_into();
+    anchor.set_href(url);
+    anchor.set_download(name);
+    anchor.click();
+}
+
+/// Creates an object URL for `blob`, e.g. to display an image generated on the client in an
+/// `<img>` element. The URL is revoked when the scope is disposed.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::download::{create_blob, create_object_url};
+///
+/// #[component]
+/// fn Preview<G: Html>(cx: Scope) -> View<G> {
+///     let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><circle r="10"/></svg>"#;
+///     let url = create_object_url(cx, &create_blob(svg.as_bytes(), "image/svg+xml"));
+///     view! { cx, img(src=url) }
+/// }
+/// ```
+pub fn create_object_url<'a>(cx: Scope<'a>, blob: &Blob) -> &'a str {
+    let url = create_ref(cx, Url::create_object_url_with_blob(blob).unwrap_throw());
+    on_cleanup(cx, || Url::revoke_object_url(url).unwrap_throw());
+    url
+}
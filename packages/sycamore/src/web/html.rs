@@ -4,14 +4,23 @@
 
 pub use sycamore_web::on_mount;
 
+#[cfg(feature = "builder")]
 use crate::builder::ElementBuilder;
 use crate::generic_node::SycamoreElement;
+#[cfg(feature = "builder")]
 use crate::prelude::*;
 
-/// MBE for generating elements.
+/// The namespace of SVG elements.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// The namespace of MathML elements.
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// MBE for generating elements. `$docs` is the section of the MDN docs of the elements.
 macro_rules! define_elements {
     (
         $ns:expr,
+        $docs:literal,
         $(
             $(#[$attr:meta])*
             $el:ident {
@@ -24,7 +33,7 @@ macro_rules! define_elements {
     ) => {
         $(
             #[allow(non_camel_case_types)]
-            #[doc = concat!("Build a [`<", stringify!($el), ">`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/", stringify!($el), ") element.")]
+            #[doc = concat!("Build a [`<", stringify!($el), ">`](https://developer.mozilla.org/en-US/docs/Web/", $docs, "/Element/", stringify!($el), ") element.")]
             $(#[$attr])*
             #[derive(Debug)]
             pub struct $el {}
@@ -34,8 +43,9 @@ macro_rules! define_elements {
                 const NAME_SPACE: Option<&'static str> = $ns;
             }
 
+            #[cfg(feature = "builder")]
             #[allow(non_snake_case)]
-            #[doc = concat!("Create a [`<", stringify!($el), ">`](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/", stringify!($el), ") element builder.")]
+            #[doc = concat!("Create a [`<", stringify!($el), ">`](https://developer.mozilla.org/en-US/docs/Web/", $docs, "/Element/", stringify!($el), ") element builder.")]
             $(#[$attr])*
             pub fn $el<'a, G: GenericNode>() -> ElementBuilder<'a, G, impl FnOnce(Scope<'a>) -> G> {
                 ElementBuilder::new(move |_| G::element::<$el>())
@@ -47,6 +57,7 @@ macro_rules! define_elements {
 // A list of valid HTML5 elements (does not include removed or obsolete elements).
 define_elements! {
     None,
+    "HTML",
     /// The `<a>` HTML element (or anchor element), with its `href` attribute, creates a hyperlink to web pages, files, email addresses, locations in the same page, or anything else a URL can address.
     ///
     /// Content within each `<a>` should indicate the link's destination. If the `href` attribute is present, pressing the enter key while focused on the `<a>` element will activate it.
@@ -206,9 +217,10 @@ define_elements! {
 }
 
 // A list of valid SVG elements. Some elements are commented out because they conflict with the HTML
-// elements.
+// elements. Inside of an `<svg>` element, the `view!` macro creates them in the SVG namespace.
 define_elements! {
-    Some("http://www.w3.org/2000/svg"),
+    Some(SVG_NAMESPACE),
+    "SVG",
     svg {},
     // a,
     animate {},
@@ -276,3 +288,39 @@ define_elements! {
     r#use {},
     view {},
 }
+
+// A list of valid MathML elements.
+define_elements! {
+    Some(MATHML_NAMESPACE),
+    "MathML",
+    math {},
+    maction {},
+    annotation {},
+    menclose {},
+    merror {},
+    mfrac {},
+    mi {},
+    mmultiscripts {},
+    mn {},
+    mo {},
+    mover {},
+    mpadded {},
+    mphantom {},
+    mprescripts {},
+    mroot {},
+    mrow {},
+    ms {},
+    mspace {},
+    msqrt {},
+    mstyle {},
+    msub {},
+    msubsup {},
+    msup {},
+    mtable {},
+    mtd {},
+    mtext {},
+    mtr {},
+    munder {},
+    munderover {},
+    semantics {},
+}
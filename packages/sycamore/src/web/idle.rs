@@ -0,0 +1,224 @@
+//! Detection of user inactivity.
+//!
+//! Useful for logging out inactive users or for pausing expensive background work (polling,
+//! animations, ...) while the user is away.
+//!
+//! # Example
+//! ```
+//! use std::time::Duration;
+//!
+//! use sycamore::prelude::*;
+//! use sycamore::web::idle::create_idle_timeout;
+//!
+//! #[component]
+//! fn Presence<G: Html>(cx: Scope) -> View<G> {
+//!     let idle = create_idle_timeout(cx, Duration::from_secs(60));
+//!     view! { cx,
+//!         (if *idle.is_idle().get() { "Away" } else { "Online" })
+//!     }
+//! }
+//! ```
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// Tracks whether the user is idle. Created with [`create_idle_timeout`].
+pub struct IdleTimeout<'a> {
+    is_idle: &'a RcSignal<bool>,
+    activity: Rc<dyn Fn()>,
+}
+
+impl<'a> IdleTimeout<'a> {
+    /// A signal that is `true` once the user has been inactive for the duration of the timeout,
+    /// or while the page is hidden.
+    pub fn is_idle(&self) -> &'a ReadSignal<bool> {
+        self.is_idle
+    }
+
+    /// Restarts the timeout as if the user was active, e.g. after a "Are you still there?"
+    /// dialog.
+    pub fn reset(&self) {
+        (self.activity)();
+    }
+}
+
+impl<'a> std::fmt::Debug for IdleTimeout<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdleTimeout")
+            .field("is_idle", &*self.is_idle.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+mod tracker {
+    use std::cell::Cell;
+    use std::rc::{Rc, Weak};
+
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use crate::prelude::*;
+
+    /// Sets `is_idle` once no activity has been reported for `timeout_ms`.
+    ///
+    /// Activity only records a timestamp. The timer checks the timestamp when it fires and is
+    /// rescheduled if there was activity in the meantime, so that frequent events such as
+    /// `pointermove` do not reset the timer every time.
+    pub struct Tracker {
+        is_idle: RcSignal<bool>,
+        timeout_ms: f64,
+        last_activity: Cell<f64>,
+        timer: Cell<Option<i32>>,
+        on_timeout: Closure<dyn Fn()>,
+    }
+
+    impl Tracker {
+        pub fn new(is_idle: RcSignal<bool>, timeout_ms: f64) -> Rc<Self> {
+            Rc::new_cyclic(|tracker: &Weak<Self>| {
+                let tracker = Weak::clone(tracker);
+                Self {
+                    is_idle,
+                    timeout_ms,
+                    last_activity: Cell::new(js_sys::Date::now()),
+                    timer: Cell::new(None),
+                    on_timeout: Closure::wrap(Box::new(move || {
+                        if let Some(tracker) = tracker.upgrade() {
+                            tracker.check();
+                        }
+                    }) as Box<dyn Fn()>),
+                }
+            })
+        }
+
+        fn schedule(&self, ms: f64) {
+            self.clear();
+            let timer = web_sys::window()
+                .unwrap_throw()
+                .set_timeout_with_callback_and_timeout_and_arguments_0(
+                    self.on_timeout.as_ref().unchecked_ref(),
+                    ms.min(i32::MAX as f64) as i32,
+                )
+                .unwrap_throw();
+            self.timer.set(Some(timer));
+        }
+
+        pub fn clear(&self) {
+            if let Some(timer) = self.timer.take() {
+                web_sys::window()
+                    .unwrap_throw()
+                    .clear_timeout_with_handle(timer);
+            }
+        }
+
+        fn check(&self) {
+            self.timer.set(None);
+            let elapsed = js_sys::Date::now() - self.last_activity.get();
+            if elapsed >= self.timeout_ms {
+                self.is_idle.set(true);
+            } else {
+                self.schedule(self.timeout_ms - elapsed);
+            }
+        }
+
+        pub fn activity(&self) {
+            self.last_activity.set(js_sys::Date::now());
+            if *self.is_idle.get_untracked() || self.timer.get().is_none() {
+                self.is_idle.set(false);
+                self.schedule(self.timeout_ms);
+            }
+        }
+
+        pub fn hidden(&self) {
+            self.clear();
+            self.is_idle.set(true);
+        }
+    }
+}
+
+/// Events on `window` that count as user activity.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+const ACTIVITY_EVENTS: &[&str] = &[
+    "pointermove",
+    "pointerdown",
+    "keydown",
+    "wheel",
+    "scroll",
+    "touchstart",
+];
+
+/// Creates an [`IdleTimeout`] that becomes idle when there has been no pointer or keyboard
+/// activity for `duration`, or when the page is hidden. Any activity, or the page becoming
+/// visible again, makes it active again.
+///
+/// The event listeners are removed when the scope is disposed. If not on `wasm32` target, the
+/// user is never idle.
+pub fn create_idle_timeout(cx: Scope<'_>, duration: Duration) -> &IdleTimeout<'_> {
+    let is_idle = create_ref(cx, create_rc_signal(false));
+
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    let activity: Rc<dyn Fn()> = {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let tracker = tracker::Tracker::new(is_idle.clone(), duration.as_millis() as f64);
+        tracker.activity();
+
+        let window = web_sys::window().unwrap_throw();
+        let document = window.document().unwrap_throw();
+        let on_activity = Closure::wrap(Box::new({
+            let tracker = Rc::clone(&tracker);
+            move || tracker.activity()
+        }) as Box<dyn Fn()>);
+        let on_visibility_change = Closure::wrap(Box::new({
+            let tracker = Rc::clone(&tracker);
+            let document = document.clone();
+            move || {
+                if document.hidden() {
+                    tracker.hidden();
+                } else {
+                    tracker.activity();
+                }
+            }
+        }) as Box<dyn Fn()>);
+        for event in ACTIVITY_EVENTS {
+            window
+                .add_event_listener_with_callback(event, on_activity.as_ref().unchecked_ref())
+                .unwrap_throw();
+        }
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                on_visibility_change.as_ref().unchecked_ref(),
+            )
+            .unwrap_throw();
+
+        on_cleanup(cx, {
+            let tracker = Rc::clone(&tracker);
+            move || {
+                for event in ACTIVITY_EVENTS {
+                    let _ = window.remove_event_listener_with_callback(
+                        event,
+                        on_activity.as_ref().unchecked_ref(),
+                    );
+                }
+                let _ = document.remove_event_listener_with_callback(
+                    "visibilitychange",
+                    on_visibility_change.as_ref().unchecked_ref(),
+                );
+                tracker.clear();
+            }
+        });
+        Rc::new(move || tracker.activity())
+    };
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+    let activity: Rc<dyn Fn()> = {
+        let _ = duration;
+        let is_idle = is_idle.clone();
+        Rc::new(move || is_idle.set(false))
+    };
+
+    create_ref(cx, IdleTimeout { is_idle, activity })
+}
@@ -0,0 +1,270 @@
+//! Partial hydration with islands.
+//!
+//! Components annotated with `#[component(island)]` are rendered inside of a `<sycamore-island>`
+//! element that records their name and their props. Instead of hydrating the whole page, the
+//! client only hydrates these islands with [`Islands`], and the rest of the page is kept as the
+//! static HTML that was rendered on the server. Since the components that are not islands do not
+//! need to be in the client bundle, content-heavy sites can ship a much smaller WASM binary.
+//!
+//! The props of an island are serialized to JSON on the server and deserialized on the client, so
+//! they must implement [`Serialize`] and [`DeserializeOwned`].
+//!
+//! An island is hydrated when the page is loaded, unless it is annotated with
+//! `#[component(island(visible))]`, in which case it is hydrated once it is scrolled into view, or
+//! with `#[component(island(interaction))]`, in which case it is hydrated once the pointer moves
+//! over it or it is focused.
+//!
+//! # Example
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use sycamore::prelude::*;
+//! use sycamore::web::island::Islands;
+//!
+//! #[derive(Prop, Serialize, Deserialize)]
+//! struct CounterProps {
+//!     initial: i32,
+//! }
+//!
+//! #[component(island(visible))]
+//! fn Counter<G: Html>(cx: Scope, props: CounterProps) -> View<G> {
+//!     let count = create_signal(cx, props.initial);
+//!     view! { cx,
+//!         button(on:click=|_| count.set(*count.get() + 1)) { (count.get()) }
+//!     }
+//! }
+//!
+//! // On the server, the page is rendered as usual, e.g. with `render_to_string`.
+//! #[component]
+//! fn Page<G: Html>(cx: Scope) -> View<G> {
+//!     view! { cx,
+//!         article { "Lots of static content..." }
+//!         Counter { initial: 1 }
+//!     }
+//! }
+//!
+//! // On the client, only the islands are hydrated.
+//! fn main() {
+//! #   if false {
+//!     Islands::new().island_with_props("Counter", Counter).hydrate();
+//! #   }
+//! }
+//! ```
+//!
+//! _This API requires the following crate features to be activated: `islands`_
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::prelude::*;
+use crate::utils::hydrate;
+
+/// When an island is hydrated on the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HydrateOn {
+    /// When [`Islands::hydrate`] is called, usually when the page is loaded.
+    Load,
+    /// When the island is scrolled into view.
+    Visible,
+    /// When the pointer moves over the island, when it is touched or when it is focused.
+    Interaction,
+}
+
+impl HydrateOn {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Load => "load",
+            Self::Visible => "visible",
+            Self::Interaction => "interaction",
+        }
+    }
+}
+
+/// Renders the view of an island inside of a `<sycamore-island>` element. Called by the
+/// components that are annotated with `#[component(island)]`.
+#[doc(hidden)]
+pub fn island<'a, G: Html, P: Serialize>(
+    cx: Scope<'a>,
+    name: &'static str,
+    hydrate_on: HydrateOn,
+    props: P,
+    view: impl FnOnce(P) -> View<G>,
+) -> View<G> {
+    let props_json = serde_json::to_string(&props).expect("island props should be serializable");
+    // The component id is used to find the hydration keys of the island on the client.
+    let component_id = hydrate::get_current_id()
+        .map(|(component_id, _)| component_id.to_string())
+        .unwrap_or_default();
+    let content = view(props);
+    view! { cx,
+        sycamore-island(
+            data-island=name,
+            data-props=props_json,
+            data-hydrate=hydrate_on.as_str(),
+            data-component=component_id,
+        ) {
+            (content)
+        }
+    }
+}
+
+/// Hydrates an island from the JSON of its props.
+type HydrateIsland = Rc<dyn Fn(Scope<'_>, &str)>;
+
+/// The islands that are hydrated on the client. The islands are registered with the names of their
+/// components.
+///
+/// Islands that are nested inside of another island are hydrated with the outer island and do not
+/// need to be registered.
+#[derive(Default)]
+pub struct Islands {
+    islands: HashMap<&'static str, HydrateIsland>,
+}
+
+impl Islands {
+    /// Creates an empty list of islands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an island component that does not take props.
+    pub fn island(
+        mut self,
+        name: &'static str,
+        component: impl Fn(Scope<'_>) -> View<HydrateNode> + 'static,
+    ) -> Self {
+        self.islands.insert(
+            name,
+            Rc::new(move |cx, _| {
+                component(cx);
+            }),
+        );
+        self
+    }
+
+    /// Registers an island component that takes props.
+    pub fn island_with_props<P: DeserializeOwned>(
+        mut self,
+        name: &'static str,
+        component: impl Fn(Scope<'_>, P) -> View<HydrateNode> + 'static,
+    ) -> Self {
+        self.islands.insert(
+            name,
+            Rc::new(move |cx, props| {
+                let props = serde_json::from_str(props).expect("island props should be valid");
+                component(cx, props);
+            }),
+        );
+        self
+    }
+
+    /// Hydrates the islands of the page, or schedules them to be hydrated later, depending on their
+    /// [`HydrateOn`]. Islands whose component was not registered are left as static HTML and a
+    /// warning is logged to the console.
+    pub fn hydrate(self) {
+        let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+        let elements = document
+            .query_selector_all("sycamore-island")
+            .unwrap_throw();
+        for i in 0..elements.length() {
+            let element = elements
+                .get(i)
+                .unwrap()
+                .unchecked_into::<web_sys::Element>();
+            let nested = element
+                .parent_element()
+                .and_then(|parent| parent.closest("sycamore-island").unwrap_throw())
+                .is_some();
+            if nested {
+                continue;
+            }
+            let name = element.get_attribute("data-island").unwrap_or_default();
+            let Some(island) = self.islands.get(name.as_str()) else {
+                web_sys::console::warn_1(
+                    &format!("island `{name}` is not registered and is not hydrated").into(),
+                );
+                continue;
+            };
+            let hydrate_on = element.get_attribute("data-hydrate");
+            match hydrate_on.as_deref() {
+                Some("visible") => hydrate_when_visible(element, Rc::clone(island)),
+                Some("interaction") => hydrate_on_interaction(element, Rc::clone(island)),
+                _ => hydrate_island(&element, island),
+            }
+        }
+        // Now that the event handlers are attached, replay the events that happened before.
+        hydrate::web::replay_events();
+    }
+}
+
+impl std::fmt::Debug for Islands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.islands.keys()).finish()
+    }
+}
+
+/// Hydrates the island rendered in `element`.
+fn hydrate_island(element: &web_sys::Element, island: &HydrateIsland) {
+    let component_id = element
+        .get_attribute("data-component")
+        .and_then(|id| id.parse().ok())
+        .unwrap_or_default();
+    let props = element.get_attribute("data-props").unwrap_or_default();
+    // Do not call the destructor function, effectively leaking the scope like `hydrate_to`.
+    let _ = create_scope(|cx| {
+        hydrate::with_component_hydration_context(component_id, || island(cx, &props));
+    });
+}
+
+/// Hydrates the island rendered in `element` once it intersects with the viewport.
+fn hydrate_when_visible(element: web_sys::Element, island: HydrateIsland) {
+    let target = element.clone();
+    let callback = Closure::wrap(Box::new(
+        move |entries: js_sys::Array, observer: web_sys::IntersectionObserver| {
+            let visible = entries.iter().any(|entry| {
+                entry
+                    .unchecked_into::<web_sys::IntersectionObserverEntry>()
+                    .is_intersecting()
+            });
+            if visible {
+                observer.disconnect();
+                hydrate_island(&element, &island);
+            }
+        },
+    )
+        as Box<dyn Fn(js_sys::Array, web_sys::IntersectionObserver)>);
+    let observer =
+        web_sys::IntersectionObserver::new(callback.as_ref().unchecked_ref()).unwrap_throw();
+    observer.observe(&target);
+    callback.forget();
+}
+
+/// Hydrates the island rendered in `element` once the user interacts with it.
+fn hydrate_on_interaction(element: web_sys::Element, island: HydrateIsland) {
+    const EVENTS: [&str; 3] = ["pointerover", "touchstart", "focusin"];
+
+    let hydrated = Rc::new(std::cell::Cell::new(false));
+    let target = element.clone();
+    let callback = Closure::wrap(Box::new(move || {
+        if !hydrated.replace(true) {
+            hydrate_island(&target, &island);
+        }
+    }) as Box<dyn Fn()>);
+    let options = web_sys::AddEventListenerOptions::new();
+    options.set_once(true);
+    options.set_passive(true);
+    for event in EVENTS {
+        element
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                event,
+                callback.as_ref().unchecked_ref(),
+                &options,
+            )
+            .unwrap_throw();
+    }
+    callback.forget();
+}
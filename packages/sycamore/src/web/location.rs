@@ -0,0 +1,299 @@
+//! Reactive access to `window.location` and its search params, without the router.
+//!
+//! Useful for storing state such as filters or the current tab in the URL in apps that do not use
+//! `sycamore-router`. The signals are updated when the location is changed with the functions of
+//! this module and when navigating with the back and forward buttons of the browser, but not when
+//! the location is changed by other code, e.g. by the router.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::location::{create_search_params, HistoryMode};
+//!
+//! #[component]
+//! fn Pagination<G: Html>(cx: Scope) -> View<G> {
+//!     let params = create_search_params(cx);
+//!     let page = create_memo(cx, move || params.get_as::<u32>("page").unwrap_or(1));
+//!     view! { cx,
+//!         "Page " (page.get())
+//!         button(on:click=move |_| params.set("page", *page.get() + 1, HistoryMode::Push)) {
+//!             "Next"
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+/// The parts of the current URL.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Location {
+    /// The path, e.g. `/posts`.
+    pub pathname: String,
+    /// The search string, including the leading `?` if not empty, e.g. `?page=2`.
+    pub search: String,
+    /// The fragment, including the leading `#` if not empty, e.g. `#comments`.
+    pub hash: String,
+}
+
+impl Location {
+    /// Reads the location of the current page. Defaults to `/` if not on `wasm32` target.
+    fn current() -> Self {
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        if let Some(location) = web_sys::window().map(|window| window.location()) {
+            return Self {
+                pathname: location.pathname().unwrap_or_default(),
+                search: location.search().unwrap_or_default(),
+                hash: location.hash().unwrap_or_default(),
+            };
+        }
+        Self {
+            pathname: "/".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the decoded search params, in order.
+    pub fn search_params(&self) -> Vec<(String, String)> {
+        parse_query(&self.search)
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.pathname, self.search, self.hash)
+    }
+}
+
+/// How the location is changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryMode {
+    /// Push a new history entry. Going back restores the previous location.
+    #[default]
+    Push,
+    /// Replace the current history entry.
+    Replace,
+}
+
+/// Decodes a component of a query string.
+fn decode(s: &str) -> String {
+    super::cookies::decode(&s.replace('+', " "))
+}
+
+/// Encodes a component of a query string. Same as `encodeURIComponent`.
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => encoded.push(byte as char),
+            b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                encoded.push(byte as char);
+            }
+            _ => write!(encoded, "%{byte:02X}").unwrap(),
+        }
+    }
+    encoded
+}
+
+fn parse_query(search: &str) -> Vec<(String, String)> {
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn format_query(params: &[(String, String)]) -> String {
+    let mut search = String::new();
+    for (i, (key, value)) in params.iter().enumerate() {
+        search.push(if i == 0 { '?' } else { '&' });
+        write!(search, "{}={}", encode(key), encode(value)).unwrap();
+    }
+    search
+}
+
+thread_local! {
+    /// The current location, shared by all the signals.
+    static LOCATION: RcSignal<Location> = {
+        let location = create_rc_signal(Location::current());
+        #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+        {
+            use wasm_bindgen::prelude::*;
+            use wasm_bindgen::JsCast;
+
+            let on_popstate = Closure::wrap(Box::new({
+                let location = location.clone();
+                move || location.set(Location::current())
+            }) as Box<dyn Fn()>);
+            web_sys::window()
+                .unwrap_throw()
+                .add_event_listener_with_callback("popstate", on_popstate.as_ref().unchecked_ref())
+                .unwrap_throw();
+            // The listener is registered once for the whole page.
+            on_popstate.forget();
+        }
+        location
+    };
+}
+
+/// Changes the location to `location` without reloading the page. `location` must have the same
+/// origin as the current page.
+pub fn set_location(location: Location, mode: HistoryMode) {
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    {
+        use wasm_bindgen::prelude::*;
+
+        let history = web_sys::window().unwrap_throw().history().unwrap_throw();
+        let url = location.to_string();
+        match mode {
+            HistoryMode::Push => history.push_state_with_url(&JsValue::NULL, "", Some(&url)),
+            HistoryMode::Replace => history.replace_state_with_url(&JsValue::NULL, "", Some(&url)),
+        }
+        .unwrap_throw();
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+    let _ = mode;
+    LOCATION.with(|signal| signal.set(location));
+}
+
+/// Creates a signal holding the current [`Location`].
+///
+/// On the client, the signal is updated when the location is changed with [`set_location`] or
+/// [`SearchParams`], or when navigating through the history. If not on `wasm32` target, the
+/// location is `/`.
+pub fn create_location(cx: Scope<'_>) -> &ReadSignal<Location> {
+    let location: &RcSignal<Location> = create_ref(cx, LOCATION.with(Clone::clone));
+    location
+}
+
+/// Reactive access to the search params of the current location. Created with
+/// [`create_search_params`].
+#[derive(Debug, Clone, Copy)]
+pub struct SearchParams<'a> {
+    location: &'a ReadSignal<Location>,
+}
+
+impl<'a> SearchParams<'a> {
+    /// Returns all the search params, in order. This value can be tracked.
+    pub fn all(&self) -> Vec<(String, String)> {
+        self.location.get().search_params()
+    }
+
+    /// Returns the value of the first param named `key`. This value can be tracked.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.all()
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the value of the first param named `key` parsed as `T`, or `None` if the param is
+    /// missing or cannot be parsed. This value can be tracked.
+    pub fn get_as<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// Returns the values of all the params named `key`. This value can be tracked.
+    pub fn get_all(&self, key: &str) -> Vec<String> {
+        self.all()
+            .into_iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    /// Sets the param `key` to `value`, replacing all the params with the same name.
+    pub fn set(&self, key: &str, value: impl fmt::Display, mode: HistoryMode) {
+        let mut value = Some(value.to_string());
+        let mut params = self.location.get_untracked().search_params();
+        // Replace the first param in place to keep the order of the params.
+        params.retain_mut(|(k, v)| {
+            if k != key {
+                return true;
+            }
+            match value.take() {
+                Some(value) => {
+                    *v = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Some(value) = value {
+            params.push((key.to_string(), value));
+        }
+        self.set_all(&params, mode);
+    }
+
+    /// Removes all the params named `key`.
+    pub fn remove(&self, key: &str, mode: HistoryMode) {
+        let mut params = self.location.get_untracked().search_params();
+        params.retain(|(k, _)| k != key);
+        self.set_all(&params, mode);
+    }
+
+    /// Replaces all the search params.
+    pub fn set_all(&self, params: &[(String, String)], mode: HistoryMode) {
+        let location = Location {
+            search: format_query(params),
+            ..self.location.get_untracked().as_ref().clone()
+        };
+        set_location(location, mode);
+    }
+}
+
+/// Creates a [`SearchParams`] handle to read and write the search params of the current location.
+/// See [`create_location`].
+pub fn create_search_params(cx: Scope<'_>) -> SearchParams<'_> {
+    SearchParams {
+        location: create_location(cx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_encoding() {
+        let params = parse_query("?q=a+b%26c&tag=x&tag=y&empty");
+        assert_eq!(
+            params,
+            [
+                ("q".to_string(), "a b&c".to_string()),
+                ("tag".to_string(), "x".to_string()),
+                ("tag".to_string(), "y".to_string()),
+                ("empty".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(format_query(&params), "?q=a%20b%26c&tag=x&tag=y&empty=");
+        assert_eq!(parse_query(&format_query(&params)), params);
+    }
+
+    #[test]
+    fn search_params() {
+        create_scope_immediate(|cx| {
+            let location = create_location(cx);
+            let params = create_search_params(cx);
+            let page = create_memo(cx, move || params.get_as::<u32>("page"));
+            assert_eq!(*page.get(), None);
+
+            params.set("page", 2, HistoryMode::Push);
+            params.set("tag", "a", HistoryMode::Push);
+            assert_eq!(*page.get(), Some(2));
+            params.set("page", 3, HistoryMode::Replace);
+            assert_eq!(location.get().to_string(), "/?page=3&tag=a");
+
+            params.remove("page", HistoryMode::Push);
+            assert_eq!(*page.get(), None);
+            assert_eq!(params.get_all("tag"), ["a"]);
+        });
+    }
+}
@@ -1,7 +1,34 @@
 //! Web support for Sycamore.
 
+#[cfg(feature = "auth")]
+pub mod auth;
+pub mod bounding_rect;
+pub mod cookies;
+#[cfg(feature = "custom-events")]
+pub mod custom_event;
+pub mod download;
 pub mod html;
+pub mod idle;
+#[cfg(feature = "islands")]
+pub mod island;
+pub mod location;
+pub mod mutation_observer;
+#[cfg(feature = "suspense")]
+pub mod polling;
 pub mod portal;
+pub mod preload;
+pub mod render_phase;
+pub mod responsive;
+pub mod scheduler;
+pub mod seo;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod style;
+#[cfg(feature = "tauri")]
+pub mod tauri;
+pub mod unique_id;
+pub mod view_transition;
+pub mod virtual_list;
 
 /* Re-export sycamore-web */
 pub use sycamore_web::*;
@@ -9,6 +36,18 @@ pub use sycamore_web::*;
 #[allow(unused_imports)]
 use crate::prelude::*;
 
+/// Returns the `web_sys::Node` stored inside the [`NodeRef`], if any.
+pub(crate) fn node_ref_node<G: Html>(node_ref: &NodeRef<G>) -> Option<web_sys::Node> {
+    use std::any::Any;
+
+    let node = node_ref.try_get_raw()?;
+    let raw = <dyn Any>::downcast_ref::<DomNode>(&node).map(DomNode::inner_element);
+    #[cfg(feature = "hydrate")]
+    let raw = raw
+        .or_else(|| <dyn Any>::downcast_ref::<HydrateNode>(&node).map(HydrateNode::inner_element));
+    raw
+}
+
 /// Render a [`View`] into a static [`String`]. Useful
 /// for rendering to a string on the server side.
 ///
@@ -60,6 +99,107 @@ pub async fn render_to_string_await_suspense(
     ret
 }
 
+/// Render a [`View`] into a stream of HTML chunks. Useful for sending the response of the server
+/// before all the data is loaded.
+///
+/// The first chunk contains everything that can be rendered synchronously, with the fallbacks of
+/// the [`Suspense`](crate::suspense::Suspense) components that are not resolved yet. Every time
+/// one of these is resolved, a chunk containing its content and a small inline script that swaps
+/// the fallback with the content is sent. The stream ends once all of them are resolved.
+///
+/// The chunks are sent in the order in which the [`Suspense`](crate::suspense::Suspense)
+/// components are resolved, not in the order in which they appear in the page. A
+/// [`Suspense`](crate::suspense::Suspense) nested inside of another one does not delay the outer
+/// one: the outer one is sent with the fallback of the inner one, which is then sent in its own
+/// chunk. If the inner one is resolved first, it is sent along with the outer one.
+///
+/// The stream must be polled inside of a [`tokio::task::LocalSet`], like
+/// [`render_to_string_await_suspense`].
+///
+/// # Example
+/// ```
+/// # use futures::StreamExt;
+/// # use sycamore::prelude::*;
+/// # use sycamore::suspense::Suspense;
+/// # use sycamore::web::render_to_string_stream;
+/// #[component]
+/// async fn Data<G: Html>(cx: Scope<'_>) -> View<G> {
+///     view! { cx, "Loaded" }
+/// }
+///
+/// # async fn handler() {
+/// let mut chunks = render_to_string_stream(|cx| view! { cx,
+///     Suspense {
+///         fallback: view! { cx, "Loading..." },
+///         Data {}
+///     }
+/// });
+/// while let Some(chunk) = chunks.next().await {
+///     // Send the chunk to the client.
+/// }
+/// # }
+/// ```
+///
+/// _This API requires the following crate features to be activated: `suspense`, `ssr`_
+///
+/// [`tokio::task::LocalSet`]: https://docs.rs/tokio/latest/tokio/task/struct.LocalSet.html
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+pub fn render_to_string_stream(
+    view: impl FnOnce(Scope<'_>) -> View<SsrNode> + 'static,
+) -> impl futures::Stream<Item = String> {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures::channel::{mpsc, oneshot};
+    use sycamore_futures::spawn_local_scoped;
+
+    use crate::suspense::StreamState;
+    use crate::utils::hydrate::with_hydration_context_async;
+
+    /// Disposes the scope of the view once the stream is dropped.
+    struct RenderStream {
+        chunks: mpsc::UnboundedReceiver<String>,
+        disposer: Option<ScopeDisposer<'static>>,
+    }
+
+    impl futures::Stream for RenderStream {
+        type Item = String;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+            Pin::new(&mut self.chunks).poll_next(cx)
+        }
+    }
+
+    impl Drop for RenderStream {
+        fn drop(&mut self) {
+            // SAFETY: the scope is not accessible outside of the stream.
+            unsafe { self.disposer.take().unwrap().dispose() };
+        }
+    }
+
+    let (sender, chunks) = mpsc::unbounded();
+    let (done, done_receiver) = oneshot::channel();
+    let disposer = create_scope(move |cx| {
+        let state = provide_context(cx, StreamState::new(sender, done));
+        spawn_local_scoped(cx, async move {
+            with_hydration_context_async(async {
+                let mut shell = String::new();
+                for node in view(cx).flatten() {
+                    node.write_to_string(&mut shell);
+                }
+                state.send_shell(shell);
+                // Keep the hydration context until all the Suspense components are resolved.
+                let _ = done_receiver.await;
+            })
+            .await;
+        });
+    });
+    RenderStream {
+        chunks,
+        disposer: Some(disposer),
+    }
+}
+
 /// Props for [`NoHydrate`].
 #[cfg(feature = "hydrate")]
 #[derive(Prop, Debug)]
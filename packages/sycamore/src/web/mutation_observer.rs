@@ -0,0 +1,184 @@
+//! Observing DOM changes made outside of Sycamore using a
+//! [`MutationObserver`](https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver).
+//!
+//! Some third-party scripts (analytics, browser extensions, legacy widgets) mutate nodes that are
+//! rendered by Sycamore. [`create_mutation_observer`] lets a component react to these changes.
+//!
+//! In debug builds with the `diagnostics` feature, a warning is also logged to the console whenever
+//! a third-party script inserts nodes directly inside an element created by Sycamore. Sycamore does
+//! not know about these nodes and might remove or misplace them the next time it updates the
+//! element's children.
+
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::node_ref_node;
+use crate::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    pub(crate) type MutationObserver;
+    #[wasm_bindgen(constructor)]
+    pub(crate) fn new(cb: &js_sys::Function) -> MutationObserver;
+    #[wasm_bindgen(method)]
+    pub(crate) fn observe(this: &MutationObserver, target: &web_sys::Node, options: &JsValue);
+    #[wasm_bindgen(method)]
+    pub(crate) fn disconnect(this: &MutationObserver);
+}
+
+/// Options for [`create_mutation_observer`]. Mirrors the
+/// [`MutationObserverInit`](https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver/observe#options)
+/// dictionary.
+///
+/// At least one of `child_list`, `attributes` or `character_data` must be `true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MutationObserverOptions {
+    /// Observe additions and removals of child nodes.
+    pub child_list: bool,
+    /// Observe changes to attributes.
+    pub attributes: bool,
+    /// Observe changes to the data of text nodes.
+    pub character_data: bool,
+    /// Extend observation to the whole subtree instead of only the target node.
+    pub subtree: bool,
+    /// Record the previous value of changed attributes.
+    pub attribute_old_value: bool,
+    /// Record the previous data of changed text nodes.
+    pub character_data_old_value: bool,
+    /// Only observe changes to the attributes with these names.
+    pub attribute_filter: Option<Vec<String>>,
+}
+
+impl MutationObserverOptions {
+    pub(crate) fn to_js(&self) -> JsValue {
+        let options = js_sys::Object::new();
+        let set = |key: &str, value: &JsValue| {
+            js_sys::Reflect::set(&options, &key.into(), value).unwrap_throw();
+        };
+        set("childList", &self.child_list.into());
+        set("attributes", &self.attributes.into());
+        set("characterData", &self.character_data.into());
+        set("subtree", &self.subtree.into());
+        // Setting the `*OldValue` options to `false` explicitly is an error if the corresponding
+        // mutation type is not observed.
+        if self.attribute_old_value {
+            set("attributeOldValue", &true.into());
+        }
+        if self.character_data_old_value {
+            set("characterDataOldValue", &true.into());
+        }
+        if let Some(filter) = &self.attribute_filter {
+            set(
+                "attributeFilter",
+                &filter.iter().map(JsValue::from).collect::<js_sys::Array>(),
+            );
+        }
+        options.into()
+    }
+}
+
+/// Logs a warning for each node inserted by a third-party script inside an element created by
+/// Sycamore.
+#[cfg(all(debug_assertions, feature = "diagnostics"))]
+fn warn_external_mutations(records: &[web_sys::MutationRecord]) {
+    use super::is_managed_node;
+
+    for record in records {
+        if record.type_() != "childList" {
+            continue;
+        }
+        let target = match record.target() {
+            Some(target) if is_managed_node(&target) => target,
+            _ => continue,
+        };
+        let added = record.added_nodes();
+        for i in 0..added.length() {
+            let node = added.get(i).unwrap_throw();
+            if !is_managed_node(&node) {
+                web_sys::console::warn_3(
+                    &"A node that was not created by Sycamore was inserted inside an element \
+                      managed by Sycamore. It might be removed or misplaced the next time the \
+                      element is updated.\nnode: %o\nparent: %o"
+                        .into(),
+                    &node,
+                    &target,
+                );
+            }
+        }
+    }
+}
+
+/// Creates a `MutationObserver` that observes the node referenced by `node_ref` and calls
+/// `callback` with the list of mutations each time it changes.
+///
+/// The observer is attached once the component is mounted and is automatically disconnected when
+/// the scope is disposed. Does nothing if not rendering in a browser.
+///
+/// Note that mutations made by Sycamore itself are reported as well.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::mutation_observer::{create_mutation_observer, MutationObserverOptions};
+///
+/// #[component]
+/// fn Widget<G: Html>(cx: Scope) -> View<G> {
+///     let container = create_node_ref(cx);
+///     let changes = create_signal(cx, 0);
+///     create_mutation_observer(
+///         cx,
+///         container,
+///         MutationObserverOptions {
+///             child_list: true,
+///             subtree: true,
+///             ..Default::default()
+///         },
+///         |records| changes.set(*changes.get() + records.len()),
+///     );
+///     view! { cx,
+///         div(ref=container)
+///         p { "Changes: " (changes.get()) }
+///     }
+/// }
+/// ```
+pub fn create_mutation_observer<'a, G: Html>(
+    cx: Scope<'a>,
+    node_ref: &'a NodeRef<G>,
+    options: MutationObserverOptions,
+    callback: impl Fn(Vec<web_sys::MutationRecord>) + 'a,
+) {
+    if G::IS_BROWSER {
+        let callback: Box<dyn Fn(Vec<web_sys::MutationRecord>) + 'a> = Box::new(callback);
+        // SAFETY: The observer is disconnected and the closure is dropped when the scope is
+        // disposed, so `callback` is never called after its lifetime ends.
+        let callback: Box<dyn Fn(Vec<web_sys::MutationRecord>) + 'static> =
+            unsafe { std::mem::transmute(callback) };
+        let callback = Rc::new(callback);
+
+        on_mount(cx, move || {
+            let node = match node_ref_node(node_ref) {
+                Some(node) => node,
+                None => return,
+            };
+            let callback = Rc::clone(&callback);
+            let closure = Closure::wrap(Box::new(move |records: js_sys::Array| {
+                let records = records
+                    .iter()
+                    .map(JsCast::unchecked_into)
+                    .collect::<Vec<web_sys::MutationRecord>>();
+                #[cfg(all(debug_assertions, feature = "diagnostics"))]
+                warn_external_mutations(&records);
+                callback(records);
+            }) as Box<dyn Fn(js_sys::Array)>);
+            let observer = MutationObserver::new(closure.as_ref().unchecked_ref());
+            observer.observe(&node, &options.to_js());
+
+            on_cleanup(cx, move || {
+                observer.disconnect();
+                drop(closure);
+            });
+        });
+    }
+}
@@ -0,0 +1,291 @@
+//! Resources that are refetched periodically.
+//!
+//! _This API requires the following crate features to be activated: `suspense`_
+
+use std::cell::Cell;
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+use sycamore_futures::spawn_local_scoped;
+use sycamore_futures::{create_resource, Resource};
+
+use crate::prelude::*;
+
+/// The delay between fetches is at most `interval * 2^MAX_BACKOFF_EXPONENT` when fetches keep
+/// failing.
+const MAX_BACKOFF_EXPONENT: u32 = 5;
+
+/// A resource that is refetched periodically. Created with [`create_polling_resource`].
+pub struct PollingResource<'a, U, E> {
+    resource: &'a Resource<'a, U, E>,
+    interval: Duration,
+    last_updated: &'a Signal<Option<u64>>,
+    is_paused: &'a RcSignal<bool>,
+    /// Number of consecutive failed fetches.
+    failures: &'a Cell<u32>,
+}
+
+impl<'a, U, E> PollingResource<'a, U, E> {
+    /// The value of the latest successful fetch, or `None` until a fetch succeeds. The value is
+    /// kept when a later fetch fails.
+    pub fn data(&self) -> &'a ReadSignal<Option<U>> {
+        self.resource.value()
+    }
+
+    /// The error of the latest fetch, or `None` if it succeeded.
+    pub fn error(&self) -> &'a ReadSignal<Option<E>> {
+        self.resource.error()
+    }
+
+    /// When the data was last fetched successfully, in milliseconds since the Unix epoch.
+    pub fn last_updated(&self) -> &'a ReadSignal<Option<u64>> {
+        self.last_updated
+    }
+
+    /// A signal that is `true` while polling is paused because the page is hidden or the browser
+    /// is offline.
+    pub fn is_paused(&self) -> &'a ReadSignal<bool> {
+        self.is_paused
+    }
+
+    /// Returns the delay between the latest fetch and the next one. The interval is doubled for
+    /// every consecutive failed fetch.
+    pub fn next_delay(&self) -> Duration {
+        self.interval * 2u32.pow(self.failures.get().min(MAX_BACKOFF_EXPONENT))
+    }
+
+    /// Fetches the data immediately, without waiting for the interval.
+    pub fn refetch(&self) {
+        self.resource.refetch();
+    }
+}
+
+impl<'a, U, E> std::fmt::Debug for PollingResource<'a, U, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PollingResource")
+            .field("interval", &self.interval)
+            .field("failures", &self.failures.get())
+            .field("last_updated", &*self.last_updated.get_untracked())
+            .finish()
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    return js_sys::Date::now() as u64;
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// Creates a [`PollingResource`] that calls `fetcher` immediately and then every `interval`.
+///
+/// The first fetch is awaited by the closest `Suspense`. When a fetch fails, the delay until the
+/// next fetch is doubled, up to 32 times the interval, and reset once a fetch succeeds. Polling is
+/// paused while the page is hidden or the browser is offline and the data is fetched again as soon
+/// as the page is visible and online.
+///
+/// If not on `wasm32` target, `fetcher` is only called once.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+///
+/// use sycamore::prelude::*;
+/// use sycamore::web::polling::create_polling_resource;
+///
+/// async fn fetch_visitors() -> Result<u32, String> {
+///     // Fetch the number of visitors from the server...
+///     Ok(42)
+/// }
+///
+/// #[component]
+/// fn Visitors<G: Html>(cx: Scope) -> View<G> {
+///     let visitors = create_polling_resource(cx, Duration::from_secs(10), fetch_visitors);
+///     view! { cx,
+///         "Visitors: " (visitors.data().get().map(|n| n.to_string()).unwrap_or_default())
+///     }
+/// }
+/// ```
+pub fn create_polling_resource<'a, U, E, F, Fut>(
+    cx: Scope<'a>,
+    interval: Duration,
+    fetcher: F,
+) -> &'a PollingResource<'a, U, E>
+where
+    U: 'a,
+    E: 'a,
+    F: Fn() -> Fut + 'a,
+    Fut: Future<Output = Result<U, E>> + 'a,
+{
+    let resource = create_ref(
+        cx,
+        PollingResource {
+            resource: create_resource(cx, || (), move |()| fetcher()),
+            interval,
+            last_updated: create_signal(cx, None),
+            is_paused: create_ref(cx, create_rc_signal(false)),
+            failures: create_ref(cx, Cell::new(0)),
+        },
+    );
+    create_effect(cx, move || {
+        if resource.resource.value().get().is_some() {
+            resource.failures.set(0);
+            resource.last_updated.set(Some(now_ms()));
+        }
+    });
+    create_effect(cx, move || {
+        if resource.resource.error().get().is_some() {
+            resource.failures.set(resource.failures.get() + 1);
+        }
+    });
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    spawn_local_scoped(cx, poll(cx, resource));
+    resource
+}
+
+/// Refetches `resource` every time the delay elapses, while the page is visible and online.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+async fn poll<'a, U, E>(cx: Scope<'a>, resource: &'a PollingResource<'a, U, E>) {
+    use futures::channel::mpsc;
+    use futures::future::{self, Either};
+    use futures::StreamExt;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    let window = web_sys::window().unwrap_throw();
+    let document = window.document().unwrap_throw();
+    let is_paused = {
+        let window = window.clone();
+        let document = document.clone();
+        move || {
+            let on_line = js_sys::Reflect::get(&window, &"navigator".into())
+                .and_then(|navigator| js_sys::Reflect::get(&navigator, &"onLine".into()))
+                .map_or(true, |on_line| on_line.as_bool() != Some(false));
+            document.hidden() || !on_line
+        }
+    };
+    resource.is_paused.set(is_paused());
+
+    // Wakes the loop up when the page becomes visible and online again.
+    let (wake, mut woken) = mpsc::unbounded::<()>();
+    let on_change = Closure::wrap(Box::new({
+        let is_paused_signal = resource.is_paused.clone();
+        move || {
+            let paused = is_paused();
+            is_paused_signal.set(paused);
+            if !paused {
+                let _ = wake.unbounded_send(());
+            }
+        }
+    }) as Box<dyn Fn()>);
+    for event in ["online", "offline"] {
+        window
+            .add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref())
+            .unwrap_throw();
+    }
+    document
+        .add_event_listener_with_callback("visibilitychange", on_change.as_ref().unchecked_ref())
+        .unwrap_throw();
+    on_cleanup(cx, {
+        let window = window.clone();
+        let document = document.clone();
+        move || {
+            for event in ["online", "offline"] {
+                let _ = window
+                    .remove_event_listener_with_callback(event, on_change.as_ref().unchecked_ref());
+            }
+            let _ = document.remove_event_listener_with_callback(
+                "visibilitychange",
+                on_change.as_ref().unchecked_ref(),
+            );
+        }
+    });
+
+    // Wakes the loop up when a fetch is done.
+    let (fetched, mut on_fetched) = mpsc::unbounded::<()>();
+    create_effect(cx, move || {
+        if !*resource.resource.loading().get() {
+            let _ = fetched.unbounded_send(());
+        }
+    });
+
+    loop {
+        // Wait for the pending fetch so that the delay takes its result into account.
+        while *resource.resource.loading().get_untracked() {
+            if on_fetched.next().await.is_none() {
+                return;
+            }
+        }
+        let delay = resource.next_delay().as_millis().min(i32::MAX as u128) as u32;
+        let sleep = Box::pin(crate::suspense::sleep(delay));
+        if let Either::Right((None, _)) = future::select(sleep, woken.next()).await {
+            return;
+        }
+        while *resource.is_paused.get_untracked() {
+            if woken.next().await.is_none() {
+                return;
+            }
+        }
+        resource.refetch();
+    }
+}
+
+#[cfg(all(test, feature = "ssr", not(miri)))]
+mod tests {
+    use sycamore_futures::{provide_executor_scope, spawn_local_scoped};
+
+    use super::*;
+
+    async fn fetch<U, E>(resource: &PollingResource<'_, U, E>) {
+        resource.refetch();
+        while *resource.resource.loading().get() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_and_backoff() {
+        provide_executor_scope(async {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let disposer = create_scope(|cx| {
+                let fail = create_ref(cx, Cell::new(false));
+                let resource =
+                    create_polling_resource(cx, Duration::from_secs(1), move || async move {
+                        if fail.get() {
+                            Err("error")
+                        } else {
+                            Ok(42)
+                        }
+                    });
+                spawn_local_scoped(cx, async move {
+                    while resource.data().get().is_none() {
+                        tokio::task::yield_now().await;
+                    }
+                    assert!(resource.last_updated().get().is_some());
+                    assert_eq!(resource.next_delay(), Duration::from_secs(1));
+
+                    fail.set(true);
+                    for _ in 0..10 {
+                        fetch(resource).await;
+                    }
+                    assert_eq!(*resource.error().get(), Some("error"));
+                    assert_eq!(*resource.data().get(), Some(42));
+                    assert_eq!(resource.next_delay(), Duration::from_secs(32));
+
+                    fail.set(false);
+                    fetch(resource).await;
+                    assert_eq!(*resource.error().get(), None);
+                    assert_eq!(resource.next_delay(), Duration::from_secs(1));
+                    sender.send(()).unwrap();
+                });
+            });
+            receiver.await.unwrap();
+            unsafe { disposer.dispose() };
+        })
+        .await;
+    }
+}
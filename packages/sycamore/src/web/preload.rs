@@ -0,0 +1,221 @@
+//! Preloading the assets of the app while rendering on the server.
+//!
+//! The browser only starts downloading the wasm binary once it has loaded and run the JS glue,
+//! which delays hydration. Rendering [`PreloadLinks`] in the `<head>` of the page emits
+//! `<link rel="preload">` and `<link rel="modulepreload">` tags so that the browser starts
+//! downloading them right away, along with the assets registered by the rendered components (e.g.
+//! the lazily loaded chunks of the current route) with [`preload`].
+//!
+//! The preloads are configured on the server with [`provide_preloads`]. On the client, where no
+//! preloads are provided, [`preload`] does nothing and [`PreloadLinks`] renders nothing.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::preload::{preload, provide_preloads, PreloadConfig, PreloadKind, PreloadLinks};
+//!
+//! #[component]
+//! fn Chart<G: Html>(cx: Scope) -> View<G> {
+//!     preload(cx, "/chunks/chart.wasm", PreloadKind::Wasm);
+//!     view! { cx, div(id="chart") }
+//! }
+//!
+//! # #[cfg(feature = "ssr")]
+//! # {
+//! let html = sycamore::render_to_string(|cx| {
+//!     provide_preloads(cx, PreloadConfig::new().wasm("/app_bg.wasm").js("/app.js"));
+//!     view! { cx,
+//!         head { PreloadLinks {} }
+//!         body { Chart {} }
+//!     }
+//! });
+//! assert!(html.contains(r#"rel="preload" href="/chunks/chart.wasm""#));
+//! # }
+//! ```
+
+use crate::prelude::*;
+
+/// How a preloaded asset is used, which determines the `rel` and `as` attributes of its `<link>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreloadKind {
+    /// A wasm binary, fetched with `fetch` and compiled with `WebAssembly.instantiateStreaming`.
+    Wasm,
+    /// A JS module, e.g. the JS glue generated by `wasm-bindgen`. Uses `rel="modulepreload"`.
+    Module,
+    /// A classic script.
+    Script,
+    /// A stylesheet.
+    Style,
+    /// Any other resource loaded with `fetch`, e.g. JSON data.
+    Fetch,
+}
+
+/// An asset to preload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preload {
+    /// The URL of the asset.
+    pub href: String,
+    /// How the asset is used.
+    pub kind: PreloadKind,
+}
+
+/// Configuration for [`provide_preloads`].
+#[derive(Debug, Clone, Default)]
+pub struct PreloadConfig {
+    preloads: Vec<Preload>,
+}
+
+impl PreloadConfig {
+    /// Creates a new configuration without any preload.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Preloads the wasm binary of the app.
+    pub fn wasm(self, href: impl Into<String>) -> Self {
+        self.asset(href, PreloadKind::Wasm)
+    }
+
+    /// Preloads the JS glue of the app, which is an ES module.
+    pub fn js(self, href: impl Into<String>) -> Self {
+        self.asset(href, PreloadKind::Module)
+    }
+
+    /// Preloads any other asset that is needed on every page.
+    pub fn asset(mut self, href: impl Into<String>, kind: PreloadKind) -> Self {
+        self.preloads.push(Preload {
+            href: href.into(),
+            kind,
+        });
+        self
+    }
+}
+
+/// The assets to preload, provided as a context by [`provide_preloads`].
+#[derive(Debug, Clone)]
+pub struct Preloads {
+    preloads: RcSignal<Vec<Preload>>,
+}
+
+impl Preloads {
+    /// Adds an asset to preload. Does nothing if the asset is already preloaded.
+    pub fn add(&self, href: impl Into<String>, kind: PreloadKind) {
+        let preload = Preload {
+            href: href.into(),
+            kind,
+        };
+        if !self.preloads.get_untracked().contains(&preload) {
+            self.preloads.modify().push(preload);
+        }
+    }
+
+    /// Returns the assets to preload, in the order they were added.
+    pub fn get(&self) -> Vec<Preload> {
+        self.preloads.get_untracked().as_ref().clone()
+    }
+}
+
+/// Provides the assets to preload as a context. Call this on the server, at the root of the app,
+/// before rendering [`PreloadLinks`] and the components that call [`preload`].
+pub fn provide_preloads(cx: Scope<'_>, config: PreloadConfig) -> &Preloads {
+    provide_context(
+        cx,
+        Preloads {
+            preloads: create_rc_signal(config.preloads),
+        },
+    )
+}
+
+/// Returns the [`Preloads`] provided by [`provide_preloads`], if any.
+pub fn try_use_preloads(cx: Scope<'_>) -> Option<&Preloads> {
+    try_use_context::<Preloads>(cx)
+}
+
+/// Adds an asset needed by the current component to the preloads, e.g. a lazily loaded chunk of
+/// the current route. Does nothing if [`provide_preloads`] was not called, e.g. on the client.
+pub fn preload(cx: Scope<'_>, href: impl Into<String>, kind: PreloadKind) {
+    if let Some(preloads) = try_use_preloads(cx) {
+        preloads.add(href, kind);
+    }
+}
+
+/// Renders the `<link>` tag for a single preload.
+fn preload_link<G: Html>(cx: Scope<'_>, preload: Preload) -> View<G> {
+    let href = preload.href;
+    match preload.kind {
+        PreloadKind::Wasm => view! { cx,
+            link(rel="preload", href=href, as="fetch", type="application/wasm", crossorigin="")
+        },
+        PreloadKind::Module => view! { cx, link(rel="modulepreload", href=href) },
+        PreloadKind::Script => view! { cx, link(rel="preload", href=href, as="script") },
+        PreloadKind::Style => view! { cx, link(rel="preload", href=href, as="style") },
+        PreloadKind::Fetch => view! { cx,
+            link(rel="preload", href=href, as="fetch", crossorigin="")
+        },
+    }
+}
+
+/// Renders the `<link>` tags of the [`Preloads`], usually in the `<head>` of the page.
+///
+/// The assets added with [`preload`] by components that are rendered after this one are included
+/// as well, as long as the page is rendered to a string only once all of its components are
+/// created. Renders nothing if [`provide_preloads`] was not called.
+#[component]
+pub fn PreloadLinks<G: Html>(cx: Scope<'_>) -> View<G> {
+    match try_use_preloads(cx) {
+        Some(preloads) => View::new_dyn(cx, move || {
+            View::new_fragment(
+                preloads
+                    .preloads
+                    .get()
+                    .iter()
+                    .map(|preload| preload_link(cx, preload.clone()))
+                    .collect(),
+            )
+        }),
+        None => View::empty(),
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[component]
+    fn Route<G: Html>(cx: Scope<'_>) -> View<G> {
+        preload(cx, "/chunks/route.wasm", PreloadKind::Wasm);
+        preload(cx, "/chunks/route.wasm", PreloadKind::Wasm);
+        view! { cx, p { "Route" } }
+    }
+
+    #[test]
+    fn preload_links() {
+        let html = crate::web::render_to_string(|cx| {
+            provide_preloads(cx, PreloadConfig::new().wasm("/app_bg.wasm").js("/app.js"));
+            view! { cx,
+                PreloadLinks {}
+                Route {}
+            }
+        });
+        assert_eq!(
+            html,
+            "<link data-hk=\"2.0\" rel=\"preload\" href=\"/app_bg.wasm\" as=\"fetch\" \
+             type=\"application/wasm\" crossorigin=\"\"/>\
+             <link data-hk=\"2.1\" rel=\"modulepreload\" href=\"/app.js\"/>\
+             <link data-hk=\"2.2\" rel=\"preload\" href=\"/chunks/route.wasm\" as=\"fetch\" \
+             type=\"application/wasm\" crossorigin=\"\"/>\
+             <p data-hk=\"2.3\">Route</p>"
+        );
+    }
+
+    #[test]
+    fn no_preloads() {
+        let html = crate::web::render_to_string(|cx| {
+            view! { cx,
+                PreloadLinks {}
+                Route {}
+            }
+        });
+        assert_eq!(html, "<!----><p data-hk=\"2.0\">Route</p>");
+    }
+}
@@ -0,0 +1,89 @@
+//! Reactive flags for the current rendering phase: on the server, hydrating on the client or fully
+//! rendered on the client.
+//!
+//! Components sometimes need to render differently depending on the phase, e.g. to only use
+//! browser APIs once on the client or to delay rendering content that would not match the HTML
+//! rendered on the server (random values, the current time, ...). Rendering different content
+//! while hydrating causes a hydration mismatch, so such content should only be rendered once
+//! [`use_is_client`] is `true`.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::render_phase::use_is_client;
+//!
+//! #[component]
+//! fn LocalTime<G: Html>(cx: Scope) -> View<G> {
+//!     let is_client = use_is_client::<G>(cx);
+//!     view! { cx,
+//!         (if *is_client.get() {
+//!             view! { cx, "Rendered on the client" }
+//!         } else {
+//!             view! { cx, "Loading..." }
+//!         })
+//!     }
+//! }
+//! ```
+
+use crate::prelude::*;
+
+/// The phase in which a component is rendered. See [`use_render_phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderPhase {
+    /// Rendering on the server, e.g. to a string with `render_to_string`.
+    Server,
+    /// Hydrating HTML that was rendered on the server. The phase changes to
+    /// [`RenderPhase::Client`] once the component is mounted.
+    Hydrating,
+    /// Rendered on the client, either after hydration or without hydration at all.
+    Client,
+}
+
+/// Returns `true` if currently inside a hydration context.
+fn in_hydration_context() -> bool {
+    #[cfg(feature = "hydrate")]
+    {
+        !sycamore_core::hydrate::hydration_completed()
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        false
+    }
+}
+
+/// Creates a signal holding the [`RenderPhase`] of the current component.
+///
+/// On the server, the phase is always [`RenderPhase::Server`]. On the client, the phase is
+/// [`RenderPhase::Hydrating`] if the component is created while hydrating and changes to
+/// [`RenderPhase::Client`] once the component is mounted. Components that are rendered on the
+/// client without hydration start directly in [`RenderPhase::Client`].
+///
+/// The phase is determined by the rendering backend `G`, not by the compilation target.
+pub fn use_render_phase<'a, G: Html>(cx: Scope<'a>) -> &'a ReadSignal<RenderPhase> {
+    let phase = if !G::IS_BROWSER {
+        RenderPhase::Server
+    } else if in_hydration_context() {
+        RenderPhase::Hydrating
+    } else {
+        RenderPhase::Client
+    };
+    let phase = create_signal(cx, phase);
+    if *phase.get_untracked() == RenderPhase::Hydrating {
+        on_mount(cx, || phase.set(RenderPhase::Client));
+    }
+    phase
+}
+
+/// Creates a signal that is `true` while the current component is being hydrated. See
+/// [`use_render_phase`].
+pub fn use_is_hydrating<'a, G: Html>(cx: Scope<'a>) -> &'a ReadSignal<bool> {
+    let phase = use_render_phase::<G>(cx);
+    create_selector(cx, || *phase.get() == RenderPhase::Hydrating)
+}
+
+/// Creates a signal that is `true` once the current component is rendered on the client and
+/// hydration, if any, is done. This is always `false` on the server. See [`use_render_phase`].
+pub fn use_is_client<'a, G: Html>(cx: Scope<'a>) -> &'a ReadSignal<bool> {
+    let phase = use_render_phase::<G>(cx);
+    create_selector(cx, || *phase.get() == RenderPhase::Client)
+}
@@ -0,0 +1,317 @@
+//! Reactive utilities for responsive layouts.
+//!
+//! All the signals created by [`create_window_size`] and [`create_breakpoint`] share a single
+//! `resize` event listener on the `window`. The listener is throttled to at most one update per
+//! animation frame and is removed once the last scope using it is disposed.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::responsive::{create_breakpoint, BreakpointConfig};
+//!
+//! #[component]
+//! fn Layout<G: Html>(cx: Scope) -> View<G> {
+//!     let breakpoint = create_breakpoint(cx, BreakpointConfig::default());
+//!     view! { cx,
+//!         (if *breakpoint.get() == "xs" {
+//!             view! { cx, "Mobile layout" }
+//!         } else {
+//!             view! { cx, "Desktop layout" }
+//!         })
+//!     }
+//! }
+//! ```
+
+use std::cell::RefCell;
+
+use crate::reactive::*;
+
+/// The inner size of the browser window, in CSS pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WindowSize {
+    /// The value of `window.innerWidth`.
+    pub width: f64,
+    /// The value of `window.innerHeight`.
+    pub height: f64,
+}
+
+/// State shared between all the signals created with [`create_window_size`].
+struct SharedWindowSize {
+    size: RcSignal<WindowSize>,
+    /// The number of scopes currently using the shared state.
+    subscribers: usize,
+    /// Removes the event listener when dropped.
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    _listener: ResizeListener,
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+struct ResizeListener {
+    on_resize: wasm_bindgen::closure::Closure<dyn Fn()>,
+    /// Handle of the pending animation frame, if any.
+    frame: std::rc::Rc<std::cell::Cell<Option<i32>>>,
+}
+
+thread_local! {
+    static WINDOW_SIZE: RefCell<Option<SharedWindowSize>> = RefCell::new(None);
+}
+
+impl SharedWindowSize {
+    fn new() -> Self {
+        let size = create_rc_signal(current_window_size());
+        Self {
+            #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+            _listener: ResizeListener::new(size.clone()),
+            size,
+            subscribers: 0,
+        }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+impl ResizeListener {
+    fn new(size: RcSignal<WindowSize>) -> Self {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let frame = Rc::new(Cell::new(None));
+        let on_frame = Closure::wrap(Box::new({
+            let frame = Rc::clone(&frame);
+            move || {
+                frame.set(None);
+                let new = current_window_size();
+                if *size.get_untracked() != new {
+                    size.set(new);
+                }
+            }
+        }) as Box<dyn Fn()>);
+        let on_resize = Closure::wrap(Box::new({
+            let frame = Rc::clone(&frame);
+            move || {
+                // Only schedule an update if there isn't one already pending.
+                if frame.get().is_none() {
+                    let handle = web_sys::window()
+                        .unwrap_throw()
+                        .request_animation_frame(on_frame.as_ref().unchecked_ref())
+                        .unwrap_throw();
+                    frame.set(Some(handle));
+                }
+            }
+        }) as Box<dyn Fn()>);
+        web_sys::window()
+            .unwrap_throw()
+            .add_event_listener_with_callback("resize", on_resize.as_ref().unchecked_ref())
+            .unwrap_throw();
+
+        Self { on_resize, frame }
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+impl Drop for ResizeListener {
+    fn drop(&mut self) {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        let window = web_sys::window().unwrap_throw();
+        // Cancel the pending frame, if any, because its callback is dropped together with
+        // `on_resize`.
+        if let Some(handle) = self.frame.take() {
+            window.cancel_animation_frame(handle).unwrap_throw();
+        }
+        window
+            .remove_event_listener_with_callback("resize", self.on_resize.as_ref().unchecked_ref())
+            .unwrap_throw();
+    }
+}
+
+/// Returns the current size of the window. Always returns [`WindowSize::default()`] if not on
+/// `wasm32` target.
+fn current_window_size() -> WindowSize {
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    {
+        use wasm_bindgen::UnwrapThrowExt;
+
+        let window = web_sys::window().unwrap_throw();
+        WindowSize {
+            width: window
+                .inner_width()
+                .unwrap_throw()
+                .as_f64()
+                .unwrap_or_default(),
+            height: window
+                .inner_height()
+                .unwrap_throw()
+                .as_f64()
+                .unwrap_or_default(),
+        }
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+    {
+        WindowSize::default()
+    }
+}
+
+/// Creates a signal that tracks the inner size of the browser window.
+///
+/// Updates are throttled to at most once per animation frame. All the signals created with this
+/// function share a single `resize` event listener.
+///
+/// If not on `wasm32` target, the signal always holds [`WindowSize::default()`].
+pub fn create_window_size<'a>(cx: Scope<'a>) -> &'a ReadSignal<WindowSize> {
+    let size = WINDOW_SIZE.with(|shared| {
+        let mut shared = shared.borrow_mut();
+        let shared = shared.get_or_insert_with(SharedWindowSize::new);
+        shared.subscribers += 1;
+        shared.size.clone()
+    });
+    on_cleanup(cx, || {
+        let removed = WINDOW_SIZE.with(|shared| {
+            let mut shared = shared.borrow_mut();
+            let state = shared.as_mut().expect("shared window size should exist");
+            state.subscribers -= 1;
+            if state.subscribers == 0 {
+                shared.take()
+            } else {
+                None
+            }
+        });
+        // Drop the listener outside of the borrow.
+        drop(removed);
+    });
+    let size: &RcSignal<WindowSize> = create_ref(cx, size);
+    size
+}
+
+/// Configuration for [`create_breakpoint`]. Maps window widths to named breakpoints.
+///
+/// A width resolves to the breakpoint with the largest minimum width that is less than or equal
+/// to it. If the width is smaller than all the breakpoints, it resolves to the base name.
+///
+/// The [`Default`] configuration uses the same breakpoints as
+/// [Tailwind CSS](https://tailwindcss.com/docs/responsive-design): `xs` (base), `sm` (640px),
+/// `md` (768px), `lg` (1024px), `xl` (1280px) and `2xl` (1536px).
+///
+/// # Example
+/// ```
+/// # use sycamore::web::responsive::BreakpointConfig;
+/// let config = BreakpointConfig::new("mobile")
+///     .breakpoint("tablet", 600.0)
+///     .breakpoint("desktop", 1200.0);
+/// assert_eq!(config.resolve(320.0), "mobile");
+/// assert_eq!(config.resolve(800.0), "tablet");
+/// assert_eq!(config.resolve(1200.0), "desktop");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakpointConfig {
+    base: &'static str,
+    /// Breakpoints sorted by minimum width.
+    breakpoints: Vec<(&'static str, f64)>,
+}
+
+impl BreakpointConfig {
+    /// Creates a new configuration without any breakpoints. All widths resolve to `base`.
+    pub fn new(base: &'static str) -> Self {
+        Self {
+            base,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// Adds a breakpoint that applies to all widths greater than or equal to `min_width`.
+    pub fn breakpoint(mut self, name: &'static str, min_width: f64) -> Self {
+        let index = self
+            .breakpoints
+            .partition_point(|(_, width)| *width <= min_width);
+        self.breakpoints.insert(index, (name, min_width));
+        self
+    }
+
+    /// Returns the name of the breakpoint that applies to `width`.
+    pub fn resolve(&self, width: f64) -> &'static str {
+        self.breakpoints
+            .iter()
+            .rev()
+            .find(|(_, min_width)| width >= *min_width)
+            .map_or(self.base, |(name, _)| name)
+    }
+}
+
+impl Default for BreakpointConfig {
+    fn default() -> Self {
+        Self::new("xs")
+            .breakpoint("sm", 640.0)
+            .breakpoint("md", 768.0)
+            .breakpoint("lg", 1024.0)
+            .breakpoint("xl", 1280.0)
+            .breakpoint("2xl", 1536.0)
+    }
+}
+
+/// Creates a signal holding the name of the breakpoint that applies to the current window width.
+///
+/// The signal is only updated when the breakpoint changes, not on every resize. See
+/// [`BreakpointConfig`] for how widths are mapped to breakpoints.
+pub fn create_breakpoint<'a>(
+    cx: Scope<'a>,
+    config: BreakpointConfig,
+) -> &'a ReadSignal<&'static str> {
+    let size = create_window_size(cx);
+    create_selector(cx, move || config.resolve(size.get().width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_default_breakpoints() {
+        let config = BreakpointConfig::default();
+        assert_eq!(config.resolve(0.0), "xs");
+        assert_eq!(config.resolve(639.0), "xs");
+        assert_eq!(config.resolve(640.0), "sm");
+        assert_eq!(config.resolve(1000.0), "md");
+        assert_eq!(config.resolve(4000.0), "2xl");
+    }
+
+    #[test]
+    fn breakpoints_are_sorted() {
+        let config = BreakpointConfig::new("base")
+            .breakpoint("large", 1000.0)
+            .breakpoint("small", 100.0);
+        assert_eq!(config.resolve(50.0), "base");
+        assert_eq!(config.resolve(500.0), "small");
+        assert_eq!(config.resolve(1500.0), "large");
+    }
+
+    #[test]
+    fn window_size_is_shared() {
+        create_scope_immediate(|cx| {
+            let disposer = create_child_scope(cx, |cx| {
+                let _ = create_window_size(cx);
+            });
+            let _ = create_window_size(cx);
+            WINDOW_SIZE.with(|shared| {
+                assert_eq!(shared.borrow().as_ref().unwrap().subscribers, 2);
+            });
+            unsafe {
+                disposer.dispose();
+            }
+            WINDOW_SIZE.with(|shared| {
+                assert_eq!(shared.borrow().as_ref().unwrap().subscribers, 1);
+            });
+        });
+        WINDOW_SIZE.with(|shared| assert!(shared.borrow().is_none()));
+    }
+
+    #[test]
+    fn breakpoint_without_window() {
+        create_scope_immediate(|cx| {
+            let breakpoint = create_breakpoint(cx, BreakpointConfig::default());
+            assert_eq!(*breakpoint.get(), "xs");
+        });
+    }
+}
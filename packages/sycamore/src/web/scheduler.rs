@@ -0,0 +1,298 @@
+//! Batching of DOM reads and writes to avoid layout thrashing.
+//!
+//! Reading layout information (e.g. `getBoundingClientRect()` or `offsetHeight`) after mutating
+//! the DOM forces the browser to synchronously recalculate the layout. When many components
+//! measure and then mutate in the same tick, the layout is recalculated once per component.
+//!
+//! [`read_layout`] and [`write_dom`] queue callbacks that are run in two phases: first all the
+//! reads, then all the writes. This means that the layout is only recalculated once per flush, no
+//! matter how many components are measuring.
+//!
+//! The callbacks queued while a signal update is propagating, e.g. by the effects of the
+//! components that are updated, are flushed as soon as the update has finished propagating. The
+//! reads therefore see the DOM once it has been updated by all the effects, and run before the
+//! browser renders the page. The callbacks queued outside of an update, e.g. in an event handler,
+//! are flushed on the next animation frame.
+//!
+//! Signal updates made inside [`write_dom`] are propagated synchronously, as usual, so the DOM
+//! updates caused by these signals are also part of the write phase.
+//!
+//! With the `suspense` feature, the flushes that are not run after an update are run by
+//! [`DeterministicScheduler::tick`](crate::futures::deterministic::DeterministicScheduler::tick)
+//! instead of on the next animation frame while the deterministic scheduler is enabled.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::scheduler::{read_layout, write_dom};
+//! use wasm_bindgen::JsCast;
+//!
+//! #[component]
+//! fn Square<G: Html>(cx: Scope) -> View<G> {
+//!     let node_ref = create_node_ref(cx);
+//!     on_mount(cx, move || {
+//!         read_layout(cx, move || {
+//!             let element = node_ref.get::<DomNode>().unchecked_into::<web_sys::HtmlElement>();
+//!             let width = element.offset_width();
+//!             write_dom(cx, move || {
+//!                 element.set_attribute("style", &format!("height: {width}px")).unwrap();
+//!             });
+//!         });
+//!     });
+//!     view! { cx, div(ref=node_ref) }
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use crate::prelude::*;
+
+type Task = Box<dyn FnOnce()>;
+
+thread_local! {
+    static READS: RefCell<VecDeque<Task>> = Default::default();
+    static WRITES: RefCell<VecDeque<Task>> = Default::default();
+    /// `true` while the queues are being flushed.
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+    /// `true` if a flush is scheduled on the next animation frame.
+    static SCHEDULED: Cell<bool> = const { Cell::new(false) };
+    /// `true` if a flush is due once the current signal update has finished propagating.
+    static AFTER_UPDATE: Cell<bool> = const { Cell::new(false) };
+    /// `true` once [`flush_after_update`] is added as a hook of the reactive runtime.
+    static HOOK_ADDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Queues `f` to be run in the read phase of the next flush. Use this to read layout information
+/// from the DOM without mutating it.
+///
+/// Reads queued during the read phase are run in the same phase. Reads queued during the write
+/// phase are run on the next flush, so that they see the updated layout.
+///
+/// The callback is not called if the scope is disposed before the flush. If not on `wasm32`
+/// target, the queues are flushed immediately outside of signal updates, unless the deterministic
+/// scheduler is enabled.
+pub fn read_layout<'a>(cx: Scope<'a>, f: impl FnOnce() + 'a) {
+    enqueue(cx, &READS, f);
+}
+
+/// Queues `f` to be run in the write phase of the next flush, after all the reads. Use this to
+/// mutate the DOM.
+///
+/// Writes queued during the read or the write phase are run in the same flush.
+///
+/// The callback is not called if the scope is disposed before the flush. If not on `wasm32`
+/// target, the queues are flushed immediately outside of signal updates, unless the deterministic
+/// scheduler is enabled.
+pub fn write_dom<'a>(cx: Scope<'a>, f: impl FnOnce() + 'a) {
+    enqueue(cx, &WRITES, f);
+}
+
+fn enqueue<'a>(
+    cx: Scope<'a>,
+    queue: &'static std::thread::LocalKey<RefCell<VecDeque<Task>>>,
+    f: impl FnOnce() + 'a,
+) {
+    let status = use_scope_status(cx);
+    let f: Box<dyn FnOnce() + 'a> = Box::new(f);
+    // SAFETY: `f` is only called if the scope is still alive.
+    let f: Box<dyn FnOnce() + 'static> = unsafe { std::mem::transmute(f) };
+    queue.with(|queue| {
+        queue.borrow_mut().push_back(Box::new(move || {
+            if *status.get_untracked() {
+                f();
+            }
+        }));
+    });
+    schedule_flush();
+}
+
+/// Runs the tasks in the queue, including the tasks that are queued while running.
+fn run_all(queue: &'static std::thread::LocalKey<RefCell<VecDeque<Task>>>) {
+    while let Some(task) = queue.with(|queue| queue.borrow_mut().pop_front()) {
+        task();
+    }
+}
+
+fn is_empty(queue: &'static std::thread::LocalKey<RefCell<VecDeque<Task>>>) -> bool {
+    queue.with(|queue| queue.borrow().is_empty())
+}
+
+fn flush() {
+    /// Resets [`FLUSHING`] when dropped, so that the next flushes are still scheduled if a task
+    /// panics.
+    struct Flushing;
+    impl Drop for Flushing {
+        fn drop(&mut self) {
+            FLUSHING.with(|flushing| flushing.set(false));
+        }
+    }
+
+    SCHEDULED.with(|scheduled| scheduled.set(false));
+    AFTER_UPDATE.with(|after_update| after_update.set(false));
+    FLUSHING.with(|flushing| flushing.set(true));
+    let flushing = Flushing;
+    run_all(&READS);
+    run_all(&WRITES);
+    drop(flushing);
+    // Reads that were queued during the write phase.
+    if !is_empty(&READS) || !is_empty(&WRITES) {
+        schedule_flush();
+    }
+}
+
+/// Flushes the queues once a signal update has finished propagating, if callbacks were queued
+/// during the update.
+fn flush_after_update() {
+    if AFTER_UPDATE.with(Cell::get) && !FLUSHING.with(Cell::get) {
+        flush();
+    }
+}
+
+fn schedule_flush() {
+    if FLUSHING.with(Cell::get) {
+        return;
+    }
+    if is_updating() {
+        if !HOOK_ADDED.with(|added| added.replace(true)) {
+            add_after_update_hook(flush_after_update);
+        }
+        AFTER_UPDATE.with(|after_update| after_update.set(true));
+        return;
+    }
+    if SCHEDULED.with(Cell::get) {
+        return;
+    }
+    #[cfg(feature = "suspense")]
+    if sycamore_futures::deterministic::is_deterministic() {
+        /// Unschedules the flush if the deterministic scheduler is dropped before running it.
+        struct Unschedule;
+        impl Drop for Unschedule {
+            fn drop(&mut self) {
+                SCHEDULED.with(|scheduled| scheduled.set(false));
+            }
+        }
+
+        SCHEDULED.with(|scheduled| scheduled.set(true));
+        let unschedule = Unschedule;
+        sycamore_futures::deterministic::queue_task(move || {
+            drop(unschedule);
+            flush();
+        });
+        return;
+    }
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web"))]
+    {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        thread_local! {
+            static ON_FRAME: Closure<dyn Fn()> = Closure::wrap(Box::new(flush) as Box<dyn Fn()>);
+        }
+
+        SCHEDULED.with(|scheduled| scheduled.set(true));
+        ON_FRAME.with(|on_frame| {
+            web_sys::window()
+                .unwrap_throw()
+                .request_animation_frame(on_frame.as_ref().unchecked_ref())
+                .unwrap_throw();
+        });
+    }
+    #[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"), feature = "web")))]
+    flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_before_writes() {
+        create_scope_immediate(|cx| {
+            let log = create_ref(cx, RefCell::new(Vec::new()));
+            // Reads queued from inside a write are batched in the next flush, and writes are only
+            // run after all the reads.
+            write_dom(cx, move || {
+                read_layout(cx, || log.borrow_mut().push("read 1"));
+                write_dom(cx, move || {
+                    log.borrow_mut().push("write 1");
+                    read_layout(cx, || log.borrow_mut().push("read 3"));
+                });
+                read_layout(cx, move || {
+                    log.borrow_mut().push("read 2");
+                    write_dom(cx, || log.borrow_mut().push("write 2"));
+                });
+            });
+            assert_eq!(
+                *log.borrow(),
+                ["write 1", "read 1", "read 2", "read 3", "write 2"]
+            );
+        });
+    }
+
+    #[test]
+    fn skip_disposed_scope() {
+        create_scope_immediate(|cx| {
+            let called = create_ref(cx, Cell::new(false));
+            write_dom(cx, move || {
+                let disposer = create_child_scope(cx, move |cx| {
+                    read_layout(cx, move || called.set(true));
+                });
+                unsafe { disposer.dispose() };
+            });
+            assert!(!called.get());
+        });
+    }
+
+    #[test]
+    fn flush_after_panic() {
+        create_scope_immediate(|cx| {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                write_dom(cx, || panic!("panic in write"));
+            }));
+            assert!(result.is_err());
+            let called = create_ref(cx, Cell::new(false));
+            write_dom(cx, || called.set(true));
+            assert!(called.get());
+        });
+    }
+
+    #[test]
+    fn flush_after_update() {
+        create_scope_immediate(|cx| {
+            let log = create_ref(cx, RefCell::new(Vec::new()));
+            let width = create_signal(cx, 0);
+            // Every effect measures the layout after its update, which would thrash the layout if
+            // the reads were run immediately.
+            for name in ["a", "b"] {
+                create_effect(cx, move || {
+                    let width = *width.get();
+                    log.borrow_mut().push(format!("write {name} {width}"));
+                    read_layout(cx, move || log.borrow_mut().push(format!("read {name}")));
+                });
+            }
+            log.borrow_mut().clear();
+            width.set(1);
+            assert_eq!(
+                *log.borrow(),
+                ["write b 1", "write a 1", "read b", "read a"]
+            );
+        });
+    }
+
+    #[cfg(feature = "suspense")]
+    #[test]
+    fn deterministic_flush() {
+        use sycamore_futures::deterministic::enable_deterministic_scheduler;
+
+        let scheduler = enable_deterministic_scheduler();
+        create_scope_immediate(|cx| {
+            let log = create_ref(cx, RefCell::new(Vec::new()));
+            write_dom(cx, || log.borrow_mut().push("write"));
+            read_layout(cx, || log.borrow_mut().push("read"));
+            assert!(log.borrow().is_empty());
+            scheduler.tick();
+            assert_eq!(*log.borrow(), ["read", "write"]);
+        });
+    }
+}
@@ -0,0 +1,536 @@
+//! Components for the tags in the `<head>` of the page, e.g. for search engines and social
+//! networks: the [`Title`] of the page, [`Meta`] tags (e.g. Open Graph), [`Link`] tags,
+//! [`Canonical`] links and [`JsonLd`] structured data.
+//!
+//! These components can be rendered anywhere in the view, e.g. by the component of the current
+//! route, and render nothing where they are. Instead:
+//! - on the server, their tags are collected by [`provide_seo_tags`] and rendered by [`SeoHead`],
+//!   usually in the `<head>` of the page,
+//! - in the browser, their tags are added to the `<head>` of the document when they are created and
+//!   removed when they are destroyed, which keeps the `<head>` in sync when navigating between
+//!   routes. The tags rendered on the server are replaced. The [`Title`] sets the title of the
+//!   document instead, and the previous title is restored once there is no [`Title`] left.
+//!
+//! If several tags have the same key (e.g. two [`Title`]s, or two [`Meta`] tags with the same
+//! `property`), the one that was created last wins. This lets a page override a default set
+//! by the app.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::seo::{provide_seo_tags, Canonical, JsonLd, Meta, SeoHead, Title};
+//!
+//! #[component]
+//! fn Article<G: Html>(cx: Scope) -> View<G> {
+//!     view! { cx,
+//!         Title { title: "My article" }
+//!         Canonical { href: "https://example.com/article" }
+//!         Meta { property: "og:title", content: "My article" }
+//!         JsonLd { data: r#"{"@context":"https://schema.org","@type":"Article"}"# }
+//!         h1 { "My article" }
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "ssr")]
+//! # {
+//! let html = sycamore::render_to_string(|cx| {
+//!     provide_seo_tags(cx);
+//!     view! { cx,
+//!         head { SeoHead {} }
+//!         body { Article {} }
+//!     }
+//! });
+//! assert!(html.contains("My article</title>"));
+//! assert!(html.contains(r#"rel="canonical" href="https://example.com/article""#));
+//! # }
+//! ```
+
+use std::cell::{Cell, RefCell};
+
+use wasm_bindgen::prelude::*;
+
+use crate::prelude::*;
+
+/// A tag in the `<head>` of the page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeoTag {
+    /// A `<title>` tag.
+    Title {
+        /// The title of the page.
+        title: String,
+    },
+    /// A `<link rel="canonical">` tag.
+    Canonical {
+        /// The canonical URL of the page.
+        href: String,
+    },
+    /// A `<meta>` tag.
+    Meta {
+        /// The `name` attribute, e.g. `description`. Empty if not set.
+        name: String,
+        /// The `property` attribute, e.g. `og:title`. Empty if not set.
+        property: String,
+        /// The `content` attribute.
+        content: String,
+    },
+    /// A `<link>` tag.
+    Link {
+        /// Distinguishes links with the same `rel` and `href`. Links with the same non-empty `id`
+        /// replace each other.
+        id: String,
+        /// The `rel` attribute, e.g. `icon`.
+        rel: String,
+        /// The `href` attribute.
+        href: String,
+        /// The `hreflang` attribute. Empty if not set.
+        hreflang: String,
+        /// The `media` attribute. Empty if not set.
+        media: String,
+    },
+    /// A `<script type="application/ld+json">` tag.
+    JsonLd {
+        /// Distinguishes several blocks of structured data on the same page.
+        id: String,
+        /// The JSON data.
+        data: String,
+    },
+}
+
+impl SeoTag {
+    /// Returns the key of the tag. Only the last created tag with a given key is rendered.
+    pub fn key(&self) -> String {
+        match self {
+            Self::Title { .. } => "title".to_string(),
+            Self::Canonical { .. } => "canonical".to_string(),
+            Self::Meta { name, property, .. } if property.is_empty() => format!("name:{name}"),
+            Self::Meta { property, .. } => format!("property:{property}"),
+            Self::Link { id, .. } if !id.is_empty() => format!("link:{id}"),
+            Self::Link { rel, href, .. } => format!("link:{rel}:{href}"),
+            Self::JsonLd { id, .. } => format!("json-ld:{id}"),
+        }
+    }
+}
+
+/// The tags of the components that are alive, provided as a context by [`provide_seo_tags`].
+#[derive(Debug)]
+pub struct SeoTags {
+    next_id: Cell<u32>,
+    tags: RcSignal<Vec<(u32, SeoTag)>>,
+}
+
+impl SeoTags {
+    fn new() -> Self {
+        Self {
+            next_id: Cell::new(0),
+            tags: create_rc_signal(Vec::new()),
+        }
+    }
+
+    /// Adds a tag and returns its id.
+    fn add(&self, tag: SeoTag) -> u32 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.tags.modify().push((id, tag));
+        id
+    }
+
+    /// Removes a tag. This does not re-render [`SeoHead`], because tags are removed while the
+    /// scopes are disposed, e.g. at the end of the rendering. The removal is taken into account
+    /// the next time a tag is added.
+    fn remove(&self, id: u32) {
+        let mut tags = self.tags.get_untracked().as_ref().clone();
+        tags.retain(|(tag_id, _)| *tag_id != id);
+        self.tags.set_silent(tags);
+    }
+
+    /// Returns the tags to render: for every key, the tag that was added last, in the order in
+    /// which the keys were first added.
+    pub fn get(&self) -> Vec<SeoTag> {
+        let mut tags: Vec<SeoTag> = Vec::new();
+        for (_, tag) in self.tags.get().iter() {
+            match tags.iter_mut().find(|t| t.key() == tag.key()) {
+                Some(existing) => *existing = tag.clone(),
+                None => tags.push(tag.clone()),
+            }
+        }
+        tags
+    }
+}
+
+thread_local! {
+    /// The tags of the components that are alive in the browser.
+    static BROWSER_TAGS: SeoTags = SeoTags::new();
+    /// The title of the document before the first [`Title`] was added.
+    static DEFAULT_TITLE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Provides the [`SeoTags`] as a context. Call this on the server, at the root of the app, before
+/// rendering [`SeoHead`] and the SEO components.
+pub fn provide_seo_tags(cx: Scope<'_>) -> &SeoTags {
+    provide_context(cx, SeoTags::new())
+}
+
+/// Adds `tag` while `cx` is alive.
+fn use_seo_tag<G: Html>(cx: Scope<'_>, tag: SeoTag) {
+    if G::IS_BROWSER {
+        let id = BROWSER_TAGS.with(|tags| tags.add(tag));
+        sync_head();
+        on_cleanup(cx, move || {
+            BROWSER_TAGS.with(|tags| tags.remove(id));
+            sync_head();
+        });
+    } else if let Some(tags) = try_use_context::<SeoTags>(cx) {
+        let id = tags.add(tag);
+        on_cleanup(cx, move || tags.remove(id));
+    }
+}
+
+/// Replaces the tags in the `<head>` of the document with the tags of the components that are
+/// alive.
+fn sync_head() {
+    let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+    let Some(head) = document.query_selector("head").unwrap_throw() else {
+        return;
+    };
+    // The title is kept, since it is updated with the title of the document.
+    while let Some(element) = head.query_selector("[data-seo]:not(title)").unwrap_throw() {
+        element.remove();
+    }
+    // Untracked, since this is called while creating the components.
+    let tags = untrack(|| BROWSER_TAGS.with(SeoTags::get));
+    let title = tags.iter().find_map(|tag| match tag {
+        SeoTag::Title { title } => Some(title.clone()),
+        _ => None,
+    });
+    DEFAULT_TITLE.with(|default| {
+        let mut default = default.borrow_mut();
+        match title {
+            Some(title) => {
+                default.get_or_insert_with(|| document.title());
+                document.set_title(&title);
+            }
+            None => {
+                if let Some(title) = default.take() {
+                    document.set_title(&title);
+                }
+            }
+        }
+    });
+    for tag in tags {
+        let element = match &tag {
+            SeoTag::Title { .. } => continue,
+            SeoTag::Canonical { href } => {
+                let element = document.create_element("link").unwrap_throw();
+                element.set_attribute("rel", "canonical").unwrap_throw();
+                element.set_attribute("href", href).unwrap_throw();
+                element
+            }
+            SeoTag::Meta {
+                name,
+                property,
+                content,
+            } => {
+                let element = document.create_element("meta").unwrap_throw();
+                if !name.is_empty() {
+                    element.set_attribute("name", name).unwrap_throw();
+                }
+                if !property.is_empty() {
+                    element.set_attribute("property", property).unwrap_throw();
+                }
+                element.set_attribute("content", content).unwrap_throw();
+                element
+            }
+            SeoTag::Link {
+                rel,
+                href,
+                hreflang,
+                media,
+                ..
+            } => {
+                let element = document.create_element("link").unwrap_throw();
+                element.set_attribute("rel", rel).unwrap_throw();
+                element.set_attribute("href", href).unwrap_throw();
+                if !hreflang.is_empty() {
+                    element.set_attribute("hreflang", hreflang).unwrap_throw();
+                }
+                if !media.is_empty() {
+                    element.set_attribute("media", media).unwrap_throw();
+                }
+                element
+            }
+            SeoTag::JsonLd { data, .. } => {
+                let element = document.create_element("script").unwrap_throw();
+                element
+                    .set_attribute("type", "application/ld+json")
+                    .unwrap_throw();
+                element.set_text_content(Some(data));
+                element
+            }
+        };
+        element.set_attribute("data-seo", &tag.key()).unwrap_throw();
+        head.append_child(&element).unwrap_throw();
+    }
+}
+
+/// Renders a single tag on the server.
+fn seo_tag<G: Html>(cx: Scope<'_>, tag: SeoTag) -> View<G> {
+    let key = tag.key();
+    match tag {
+        SeoTag::Title { title } => view! { cx,
+            title(data-seo=key) { (title) }
+        },
+        SeoTag::Canonical { href } => view! { cx,
+            link(rel="canonical", href=href, data-seo=key)
+        },
+        SeoTag::Meta {
+            name,
+            property,
+            content,
+        } if property.is_empty() => view! { cx,
+            meta(name=name, content=content, data-seo=key)
+        },
+        SeoTag::Meta {
+            property, content, ..
+        } => view! { cx,
+            meta(property=property, content=content, data-seo=key)
+        },
+        SeoTag::Link {
+            rel,
+            href,
+            hreflang,
+            media,
+            ..
+        } => {
+            let element = G::element_from_tag("link");
+            element.set_attribute("rel", &rel);
+            element.set_attribute("href", &href);
+            if !hreflang.is_empty() {
+                element.set_attribute("hreflang", &hreflang);
+            }
+            if !media.is_empty() {
+                element.set_attribute("media", &media);
+            }
+            element.set_attribute("data-seo", &key);
+            View::new_node(element)
+        }
+        SeoTag::JsonLd { data, .. } => {
+            // Prevent the data from closing the script tag.
+            let data = data.replace("</", "<\\/");
+            view! { cx,
+                script(type="application/ld+json", data-seo=key, dangerously_set_inner_html=&data)
+            }
+        }
+    }
+}
+
+/// Renders the tags of the SEO components on the server, usually in the `<head>` of the page.
+///
+/// The tags of the components that are rendered after this one are included as well, as long as
+/// the page is rendered to a string only once all of its components are created. Renders nothing
+/// if [`provide_seo_tags`] was not called, e.g. in the browser.
+#[component]
+pub fn SeoHead<G: Html>(cx: Scope<'_>) -> View<G> {
+    match try_use_context::<SeoTags>(cx).filter(|_| !G::IS_BROWSER) {
+        Some(tags) => View::new_dyn(cx, move || {
+            View::new_fragment(tags.get().into_iter().map(|tag| seo_tag(cx, tag)).collect())
+        }),
+        None => View::empty(),
+    }
+}
+
+/// Props for [`Title`].
+#[derive(Prop, Debug)]
+pub struct TitleProps {
+    /// The title of the page.
+    #[builder(setter(into))]
+    title: String,
+}
+
+/// Sets the title of the page. On the server, [`SeoHead`] renders it as a `<title>` tag, so the
+/// `<head>` of the page should not contain another one. See the
+/// [module-level documentation](self).
+#[component]
+pub fn Title<G: Html>(cx: Scope<'_>, props: TitleProps) -> View<G> {
+    use_seo_tag::<G>(cx, SeoTag::Title { title: props.title });
+    View::empty()
+}
+
+/// Props for [`Canonical`].
+#[derive(Prop, Debug)]
+pub struct CanonicalProps {
+    /// The canonical URL of the page.
+    #[builder(setter(into))]
+    href: String,
+}
+
+/// Adds a `<link rel="canonical">` tag to the `<head>` of the page. See the
+/// [module-level documentation](self).
+#[component]
+pub fn Canonical<G: Html>(cx: Scope<'_>, props: CanonicalProps) -> View<G> {
+    use_seo_tag::<G>(cx, SeoTag::Canonical { href: props.href });
+    View::empty()
+}
+
+/// Props for [`Meta`].
+#[derive(Prop, Debug)]
+pub struct MetaProps {
+    /// The `name` attribute, e.g. `description`.
+    #[builder(default, setter(into))]
+    name: String,
+    /// The `property` attribute, e.g. `og:title`.
+    #[builder(default, setter(into))]
+    property: String,
+    /// The `content` attribute.
+    #[builder(setter(into))]
+    content: String,
+}
+
+/// Adds a `<meta>` tag to the `<head>` of the page, e.g. an Open Graph tag. Either `name` or
+/// `property` should be set. See the [module-level documentation](self).
+#[component]
+pub fn Meta<G: Html>(cx: Scope<'_>, props: MetaProps) -> View<G> {
+    let MetaProps {
+        name,
+        property,
+        content,
+    } = props;
+    use_seo_tag::<G>(
+        cx,
+        SeoTag::Meta {
+            name,
+            property,
+            content,
+        },
+    );
+    View::empty()
+}
+
+/// Props for [`Link`].
+#[derive(Prop, Debug)]
+pub struct LinkProps {
+    /// Distinguishes links with the same `rel` and `href`. Links with the same `id` replace each
+    /// other, e.g. to let a page override the `icon` of the app.
+    #[builder(default, setter(into))]
+    id: String,
+    /// The `rel` attribute, e.g. `icon` or `alternate`.
+    #[builder(setter(into))]
+    rel: String,
+    /// The `href` attribute.
+    #[builder(setter(into))]
+    href: String,
+    /// The `hreflang` attribute, e.g. for the translations of the page.
+    #[builder(default, setter(into))]
+    hreflang: String,
+    /// The `media` attribute.
+    #[builder(default, setter(into))]
+    media: String,
+}
+
+/// Adds a `<link>` tag to the `<head>` of the page, e.g. an icon or the translations of the page.
+/// See the [module-level documentation](self).
+#[component]
+pub fn Link<G: Html>(cx: Scope<'_>, props: LinkProps) -> View<G> {
+    let LinkProps {
+        id,
+        rel,
+        href,
+        hreflang,
+        media,
+    } = props;
+    use_seo_tag::<G>(
+        cx,
+        SeoTag::Link {
+            id,
+            rel,
+            href,
+            hreflang,
+            media,
+        },
+    );
+    View::empty()
+}
+
+/// Props for [`JsonLd`].
+#[derive(Prop, Debug)]
+pub struct JsonLdProps {
+    /// Distinguishes several blocks of structured data on the same page. Blocks with the same `id`
+    /// replace each other.
+    #[builder(default, setter(into))]
+    id: String,
+    /// The structured data, serialized as JSON.
+    #[builder(setter(into))]
+    data: String,
+}
+
+/// Adds a `<script type="application/ld+json">` tag with structured data to the `<head>` of the
+/// page. See the [module-level documentation](self).
+#[component]
+pub fn JsonLd<G: Html>(cx: Scope<'_>, props: JsonLdProps) -> View<G> {
+    let JsonLdProps { id, data } = props;
+    use_seo_tag::<G>(cx, SeoTag::JsonLd { id, data });
+    View::empty()
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[component]
+    fn Page<G: Html>(cx: Scope<'_>) -> View<G> {
+        view! { cx,
+            Title { title: "Page <1>" }
+            Canonical { href: "https://example.com/page" }
+            Meta { property: "og:title", content: "Page" }
+            Link { id: "icon", rel: "icon", href: "/page.png" }
+            JsonLd { data: r#"{"name":"</script>"}"# }
+            p { "Page" }
+        }
+    }
+
+    #[test]
+    fn seo_head() {
+        let html = crate::web::render_to_string(|cx| {
+            provide_seo_tags(cx);
+            view! { cx,
+                SeoHead {}
+                Title { title: "App" }
+                Link { id: "icon", rel: "icon", href: "/app.png" }
+                Link { rel: "alternate", href: "/fr", hreflang: "fr" }
+                Canonical { href: "https://example.com" }
+                Meta { name: "description", content: "An app" }
+                Meta { property: "og:title", content: "App" }
+                Page {}
+            }
+        });
+        assert!(html.contains(
+            "rel=\"canonical\" href=\"https://example.com/page\" data-seo=\"canonical\"/>"
+        ));
+        assert!(!html.contains("href=\"https://example.com\""));
+        assert!(html
+            .contains("name=\"description\" content=\"An app\" data-seo=\"name:description\"/>"));
+        assert!(html
+            .contains("property=\"og:title\" content=\"Page\" data-seo=\"property:og:title\"/>"));
+        assert!(!html.contains("content=\"App\""));
+        assert!(html.contains("data-seo=\"title\">Page &lt;1></title>"));
+        assert!(!html.contains(">App</title>"));
+        assert!(html.contains("rel=\"icon\" href=\"/page.png\" data-seo=\"link:icon\"/>"));
+        assert!(!html.contains("/app.png"));
+        assert!(html.contains(
+            "rel=\"alternate\" href=\"/fr\" hreflang=\"fr\" data-seo=\"link:alternate:/fr\"/>"
+        ));
+        assert!(html.contains(
+            "type=\"application/ld+json\" data-seo=\"json-ld:\">{\"name\":\"<\\/script>\"}</script>"
+        ));
+    }
+
+    #[test]
+    fn no_seo_tags() {
+        let html = crate::web::render_to_string(|cx| {
+            view! { cx,
+                SeoHead {}
+                Page {}
+            }
+        });
+        assert!(!html.contains("canonical"));
+    }
+}
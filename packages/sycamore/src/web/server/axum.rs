@@ -0,0 +1,227 @@
+//! Integration with [`axum`](https://docs.rs/axum).
+//!
+//! [`RequestContext`] can be extracted from the request in a handler, and [`RenderedPage`] can be
+//! returned as the response. Since rendering a page is not [`Send`], the pages that await
+//! `Suspense` or are streamed are rendered on a blocking thread with a local executor by
+//! [`render_await_suspense`] and [`render_stream`].
+//!
+//! # Example
+//! ```no_run
+//! use axum::response::Response;
+//! use sycamore::prelude::*;
+//! use sycamore::web::server::{axum::render_stream, RequestContext, Template};
+//!
+//! #[component]
+//! fn App<G: Html>(cx: Scope) -> View<G> {
+//!     view! { cx, p { "Hello World!" } }
+//! }
+//!
+//! async fn handler(request: RequestContext) -> Response {
+//!     let template = Template::new("<html><head></head><body></body></html>")
+//!         .unwrap()
+//!         .hydration_script("/app.js", "/app_bg.wasm");
+//!     render_stream(&template, request, |cx| view! { cx, App {} }).await
+//! }
+//!
+//! let app: axum::Router = axum::Router::new().fallback(handler);
+//! ```
+//!
+//! _This API requires the following crate features to be activated: `axum`_
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use ::axum::body::Body;
+use ::axum::extract::FromRequestParts;
+use ::axum::http::header::{CONTENT_TYPE, COOKIE, SET_COOKIE};
+use ::axum::http::request::Parts;
+use ::axum::http::HeaderValue;
+use ::axum::response::{IntoResponse, Response};
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
+
+use super::*;
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let cookie_header = parts
+            .headers
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Ok(Self {
+            path: parts.uri.path().to_string(),
+            query: parts.uri.query().unwrap_or_default().to_string(),
+            cookie_header: (!cookie_header.is_empty()).then_some(cookie_header),
+        })
+    }
+}
+
+impl IntoResponse for RenderedPage {
+    fn into_response(self) -> Response {
+        with_cookies(
+            ::axum::response::Html(self.html).into_response(),
+            self.set_cookie_headers,
+        )
+    }
+}
+
+/// Adds the `Set-Cookie` headers to `response`. Invalid headers are skipped.
+fn with_cookies(mut response: Response, set_cookie_headers: Vec<String>) -> Response {
+    for header in set_cookie_headers {
+        if let Ok(value) = HeaderValue::from_str(&header) {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+/// Runs the future returned by `f` on a blocking thread, inside of a
+/// [`LocalSet`](tokio::task::LocalSet).
+fn spawn_local_thread<F, Fut>(f: F) -> tokio::task::JoinHandle<Fut::Output>
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future + 'static,
+    Fut::Output: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the runtime for rendering");
+        tokio::task::LocalSet::new().block_on(&runtime, f())
+    })
+}
+
+/// Renders the page of `app` with [`render_page_await_suspense`].
+///
+/// # Panics
+/// Panics if rendering the page panics.
+pub async fn render_await_suspense<F>(
+    template: &Template,
+    request: RequestContext,
+    app: F,
+) -> RenderedPage
+where
+    F: FnOnce(Scope<'_>) -> View<SsrNode> + Send + 'static,
+{
+    let template = template.clone();
+    spawn_local_thread(
+        move || async move { render_page_await_suspense(&template, request, app).await },
+    )
+    .await
+    .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+}
+
+/// Renders the page of `app` with [`render_page_stream`] and returns a streaming response.
+/// Resolves once the first chunk is rendered.
+pub async fn render_stream<F>(template: &Template, request: RequestContext, app: F) -> Response
+where
+    F: FnOnce(Scope<'_>) -> View<SsrNode> + Send + 'static,
+{
+    let template = template.clone();
+    let (headers, headers_receiver) = oneshot::channel();
+    let (chunks, chunks_receiver) = mpsc::unbounded();
+    spawn_local_thread(move || async move {
+        let page = render_page_stream(&template, request, app).await;
+        let _ = headers.send(page.set_cookie_headers);
+        let mut body = page.body;
+        while let Some(chunk) = body.next().await {
+            if chunks.unbounded_send(chunk).is_err() {
+                // The response was dropped.
+                break;
+            }
+        }
+    });
+    // If rendering panicked, the response is empty.
+    let set_cookie_headers = headers_receiver.await.unwrap_or_default();
+    let mut response = Response::new(Body::from_stream(chunks_receiver.map(Ok::<_, Infallible>)));
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    with_cookies(response, set_cookie_headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use ::axum::http::Request;
+
+    use super::*;
+    use crate::suspense::Suspense;
+    use crate::web::cookies::{use_cookies, CookieOptions};
+
+    #[component]
+    async fn Data<G: Html>(cx: Scope<'_>) -> View<G> {
+        tokio::task::yield_now().await;
+        use_cookies(cx).set("late", "1", &CookieOptions::default());
+        view! { cx, "Loaded" }
+    }
+
+    fn app<G: Html>(cx: Scope<'_>) -> View<G> {
+        use_cookies(cx).set("early", "1", &CookieOptions::default());
+        view! { cx,
+            Suspense {
+                fallback: view! { cx, "Loading" },
+                Data {}
+            }
+        }
+    }
+
+    fn template() -> Template {
+        Template::new("<html><head></head><body></body></html>").unwrap()
+    }
+
+    #[tokio::test]
+    async fn extract_request_context() {
+        let (mut parts, _) = Request::builder()
+            .uri("/posts/1?page=2")
+            .header(COOKIE, "a=1")
+            .header(COOKIE, "b=2")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let request = RequestContext::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert_eq!(
+            request,
+            RequestContext::new("/posts/1?page=2").cookie_header("a=1; b=2")
+        );
+    }
+
+    #[tokio::test]
+    async fn await_suspense_response() {
+        let page = render_await_suspense(&template(), RequestContext::new("/"), app).await;
+        assert!(page.html.contains("Loaded"));
+        assert_eq!(page.set_cookie_headers, ["early=1", "late=1"]);
+
+        let response = page.into_response();
+        assert_eq!(response.headers().get_all(SET_COOKIE).iter().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn stream_response() {
+        let response = render_stream(&template(), RequestContext::new("/"), app).await;
+        assert_eq!(
+            response
+                .headers()
+                .get_all(SET_COOKIE)
+                .iter()
+                .collect::<Vec<_>>(),
+            ["early=1"]
+        );
+        let body = ::axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.starts_with("<html><head>"));
+        assert!(body.contains("Loading"));
+        assert!(body.contains("Loaded"));
+        assert!(body.ends_with("</body></html>"));
+    }
+}
@@ -0,0 +1,671 @@
+//! Rendering pages on the server and handing them over to the client.
+//!
+//! Every server app needs the same glue around [`render_to_string`](crate::render_to_string):
+//! providing the request to the components, rendering the tags collected by
+//! [`SeoHead`](crate::web::seo::SeoHead), [`StyleTags`](crate::web::style::StyleTags) and
+//! [`PreloadLinks`](crate::web::preload::PreloadLinks) in the `<head>`, collecting the cookies
+//! written while rendering, and injecting everything into the HTML template of the app along with
+//! the script that loads the app for hydration. This module does that in one call:
+//! - a [`Template`] is parsed once from the `index.html` of the app,
+//! - [`render_page`], [`render_page_await_suspense`] and [`render_page_stream`] render a page for a
+//!   [`RequestContext`], which the components can access with [`use_request_context`],
+//! - [`provide_server_state`] serializes state on the server into the page, and
+//!   [`use_server_state`] reads it back on the client, e.g. to hydrate with the data that was
+//!   fetched on the server instead of fetching it again,
+//! - [`load_server_data`] fetches data once per page, even if several components need it, and hands
+//!   it over to the client.
+//!
+//! The [`axum`] module implements the extractors and responses for `axum`. With other frameworks,
+//! build the [`RequestContext`] from the request and send the [`RenderedPage`] or the
+//! [`PageStream`] as the response.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::server::{render_page, use_request_context, RequestContext, Template};
+//! use sycamore::web::seo::Title;
+//!
+//! #[component]
+//! fn App<G: Html>(cx: Scope) -> View<G> {
+//!     let path = use_request_context(cx)
+//!         .map(|request| request.path.clone())
+//!         .unwrap_or_default();
+//!     view! { cx,
+//!         Title { title: "Home" }
+//!         p { "Requested " (path) }
+//!     }
+//! }
+//!
+//! let template = Template::new("<html><head></head><body></body></html>")
+//!     .unwrap()
+//!     .hydration_script("/app.js", "/app_bg.wasm");
+//! let page = render_page(&template, RequestContext::new("/home"), |cx| view! { cx, App {} });
+//! assert!(page.html.contains(">Home</title>"));
+//! assert!(page.html.contains("Requested /home"));
+//! ```
+//!
+//! _This API requires the following crate features to be activated: `server`_
+
+#[cfg(feature = "axum")]
+pub mod axum;
+
+#[cfg(feature = "suspense")]
+use std::any::Any;
+use std::cell::RefCell;
+#[cfg(feature = "suspense")]
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "suspense")]
+use std::future::Future;
+use std::rc::Rc;
+
+#[cfg(feature = "suspense")]
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::prelude::*;
+use crate::web::preload::PreloadConfig;
+
+/// The id of the `<script>` tag holding the state provided with [`provide_server_state`].
+pub const STATE_SCRIPT_ID: &str = "sycamore-state";
+
+/// The placeholder in a [`Template`] where the app is rendered. If there is none, the app is
+/// rendered at the start of the `<body>`.
+pub const APP_PLACEHOLDER: &str = "<!--sycamore-app-->";
+
+/// The parts of the request that the components can access while rendering on the server, with
+/// [`use_request_context`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    /// The path of the request, e.g. `/posts/1`.
+    pub path: String,
+    /// The query string of the request without the leading `?`, e.g. `page=2`. Empty if there is
+    /// none.
+    pub query: String,
+    /// The value of the `Cookie` header of the request, which is provided to the components with
+    /// [`provide_cookies`](crate::web::cookies::provide_cookies).
+    pub cookie_header: Option<String>,
+}
+
+impl RequestContext {
+    /// Creates a context from the path and the query of the request, e.g. `/posts/1?page=2`.
+    pub fn new(path_and_query: &str) -> Self {
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+        Self {
+            path: path.to_string(),
+            query: query.to_string(),
+            cookie_header: None,
+        }
+    }
+
+    /// Sets the value of the `Cookie` header of the request.
+    pub fn cookie_header(mut self, cookie_header: impl Into<String>) -> Self {
+        self.cookie_header = Some(cookie_header.into());
+        self
+    }
+
+    /// Returns the path and the query of the request, e.g. `/posts/1?page=2`. This is the `url`
+    /// to pass to the `StaticRouter` of `sycamore-router`.
+    pub fn url(&self) -> String {
+        if self.query.is_empty() {
+            self.path.clone()
+        } else {
+            format!("{}?{}", self.path, self.query)
+        }
+    }
+}
+
+/// Returns the [`RequestContext`] of the page that is being rendered on the server, or `None` on
+/// the client.
+pub fn use_request_context(cx: Scope<'_>) -> Option<&RequestContext> {
+    try_use_context(cx)
+}
+
+/// The state provided with [`provide_server_state`] while rendering a page.
+#[derive(Debug, Clone, Default)]
+struct ServerState(Rc<RefCell<Map<String, Value>>>);
+
+impl ServerState {
+    /// Returns the `<script>` tag holding the state, or an empty string if there is none.
+    #[cfg(feature = "ssr")]
+    fn to_script(&self) -> String {
+        let state = self.0.borrow();
+        if state.is_empty() {
+            return String::new();
+        }
+        let json = serde_json::to_string(&*state).expect("server state should be serializable");
+        // `<\/` is a valid escape in JSON strings and cannot close the `<script>` tag.
+        let json = json.replace("</", "<\\/");
+        format!(r#"<script id="{STATE_SCRIPT_ID}" type="application/json">{json}</script>"#)
+    }
+}
+
+/// Hands `value` over to the client under `key`, where it can be read with [`use_server_state`].
+/// The value is serialized into the page rendered by [`render_page`],
+/// [`render_page_await_suspense`] or [`render_page_stream`]. Does nothing on the client.
+///
+/// When streaming, the state is sent at the end of the page, so it can be provided by the
+/// components that are rendered inside of a `Suspense` as well.
+///
+/// # Panics
+/// Panics if `value` cannot be serialized.
+pub fn provide_server_state<T: Serialize>(cx: Scope<'_>, key: &str, value: &T) {
+    if let Some(state) = try_use_context::<ServerState>(cx) {
+        let value = serde_json::to_value(value).expect("server state should be serializable");
+        state.0.borrow_mut().insert(key.to_string(), value);
+    }
+}
+
+/// Returns the value provided under `key` with [`provide_server_state`], or `None` if there is
+/// none or if it cannot be deserialized into a `T`.
+///
+/// On the client, the value is read from the page rendered on the server. On the server, the
+/// value provided so far while rendering the page is returned.
+pub fn use_server_state<T: DeserializeOwned>(cx: Scope<'_>, key: &str) -> Option<T> {
+    let value = match try_use_context::<ServerState>(cx) {
+        Some(state) => state.0.borrow().get(key).cloned(),
+        None => page_state(key),
+    }?;
+    serde_json::from_value(value).ok()
+}
+
+/// Reads the state under `key` from the `<script>` tag rendered on the server.
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+fn page_state(key: &str) -> Option<Value> {
+    let json = web_sys::window()?
+        .document()?
+        .get_element_by_id(STATE_SCRIPT_ID)?
+        .text_content()?;
+    let mut state: Map<String, Value> = serde_json::from_str(&json).ok()?;
+    state.remove(key)
+}
+
+#[cfg(not(all(target_arch = "wasm32", not(target_os = "wasi"))))]
+fn page_state(_key: &str) -> Option<Value> {
+    None
+}
+
+/// A value being loaded with [`load_server_data`], which can be awaited by several components.
+#[cfg(feature = "suspense")]
+type Load = Shared<LocalBoxFuture<'static, Rc<dyn Any>>>;
+
+/// The values loaded with [`load_server_data`] while rendering a page, by key.
+#[cfg(feature = "suspense")]
+#[derive(Clone, Default)]
+struct Loaders(Rc<RefCell<HashMap<String, Load>>>);
+
+/// Loads the value of `key` with `fetcher`, at most once per page.
+///
+/// On the server, the first component that loads `key` while a page is rendered with
+/// [`render_page`], [`render_page_await_suspense`] or [`render_page_stream`] calls `fetcher`, and
+/// the other ones wait for its value instead of fetching it again. The value is then handed over
+/// to the client with [`provide_server_state`], only once no matter how many components loaded it.
+///
+/// On the client, the value handed over by the server is returned if there is one, so that the
+/// page is hydrated with the same data. Otherwise, `fetcher` is called.
+///
+/// # Panics
+/// Panics if `key` is loaded with different types on the same page, or if the value cannot be
+/// serialized.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::server::load_server_data;
+///
+/// async fn fetch_user(id: u32) -> String {
+///     // Fetch the user from the database...
+///     format!("User {id}")
+/// }
+///
+/// #[component]
+/// async fn UserName<G: Html>(cx: Scope<'_>, id: u32) -> View<G> {
+///     let name = load_server_data(cx, &format!("user/{id}"), move || fetch_user(id)).await;
+///     view! { cx, (name) }
+/// }
+/// ```
+///
+/// _This API requires the following crate features to be activated: `server`, `suspense`_
+#[cfg(feature = "suspense")]
+pub async fn load_server_data<T, Fut>(cx: Scope<'_>, key: &str, fetcher: impl FnOnce() -> Fut) -> T
+where
+    T: Serialize + DeserializeOwned + Clone + 'static,
+    Fut: Future<Output = T> + 'static,
+{
+    let Some(loaders) = try_use_context::<Loaders>(cx) else {
+        return match use_server_state(cx, key) {
+            Some(value) => value,
+            None => fetcher().await,
+        };
+    };
+    let load = loaders
+        .0
+        .borrow_mut()
+        .entry(key.to_string())
+        .or_insert_with(|| {
+            let state = use_context::<ServerState>(cx).clone();
+            let key = key.to_string();
+            let fetch = fetcher();
+            async move {
+                let value = fetch.await;
+                let json =
+                    serde_json::to_value(&value).expect("server state should be serializable");
+                state.0.borrow_mut().insert(key, json);
+                Rc::new(value) as Rc<dyn Any>
+            }
+            .boxed_local()
+            .shared()
+        })
+        .clone();
+    load.await
+        .downcast_ref::<T>()
+        .expect("key should always be loaded with the same type")
+        .clone()
+}
+
+/// The error returned by [`Template::new`] when the template is not a valid HTML page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    missing: &'static str,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "template is missing `{}`", self.missing)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// The HTML page that the pages of the app are rendered into, usually the `index.html` of the app.
+///
+/// The tags of the `<head>` are inserted before `</head>`, and the app is rendered at the
+/// [`APP_PLACEHOLDER`] or, if there is none, at the start of the `<body>`. The state provided with
+/// [`provide_server_state`] and the [hydration script](Self::hydration_script) are inserted before
+/// `</body>`.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "ssr"), allow(dead_code))]
+pub struct Template {
+    /// Up to `</head>`.
+    head: String,
+    /// From `</head>` up to where the app is rendered.
+    body_start: String,
+    /// From where the app is rendered up to `</body>`.
+    body_end: String,
+    /// From `</body>`.
+    tail: String,
+    script: Option<String>,
+    preloads: Option<PreloadConfig>,
+}
+
+impl Template {
+    /// Parses the template from `html`.
+    pub fn new(html: &str) -> Result<Self, TemplateError> {
+        let missing = |missing| TemplateError { missing };
+        let head_end = html.find("</head>").ok_or_else(|| missing("</head>"))?;
+        let body_end = html.rfind("</body>").ok_or_else(|| missing("</body>"))?;
+        let (app_start, app_end) = match html.find(APP_PLACEHOLDER) {
+            Some(start) => (start, start + APP_PLACEHOLDER.len()),
+            None => {
+                let body = html[head_end..]
+                    .find("<body")
+                    .ok_or_else(|| missing("<body>"))?
+                    + head_end;
+                let start = html[body..].find('>').ok_or_else(|| missing("<body>"))? + body + 1;
+                (start, start)
+            }
+        };
+        if app_start < head_end || app_end > body_end {
+            return Err(missing("<body>"));
+        }
+        Ok(Self {
+            head: html[..head_end].to_string(),
+            body_start: html[head_end..app_start].to_string(),
+            body_end: html[app_end..body_end].to_string(),
+            tail: html[body_end..].to_string(),
+            script: None,
+            preloads: None,
+        })
+    }
+
+    /// Adds the script that loads the app in the browser, with the paths of the JS glue and of the
+    /// wasm binary generated by `wasm-bindgen --target web`. The app is expected to hydrate the
+    /// page when it starts.
+    pub fn hydration_script(mut self, js: &str, wasm: &str) -> Self {
+        let js = serde_json::to_string(js).unwrap();
+        let wasm = serde_json::to_string(wasm).unwrap();
+        self.script = Some(format!(
+            r#"<script type="module">import init from {js};init({{module_or_path:{wasm}}});</script>"#
+        ));
+        self
+    }
+
+    /// Provides `config` to the pages with
+    /// [`provide_preloads`](crate::web::preload::provide_preloads), so that the assets of the app
+    /// are preloaded from the `<head>`.
+    pub fn preloads(mut self, config: PreloadConfig) -> Self {
+        self.preloads = Some(config);
+        self
+    }
+
+    /// Returns the start of the page, up to where the app is rendered.
+    #[cfg(feature = "ssr")]
+    fn prefix(&self, head: &str) -> String {
+        [self.head.as_str(), head, &self.body_start].concat()
+    }
+
+    /// Returns the end of the page, from where the app is rendered.
+    #[cfg(feature = "ssr")]
+    fn suffix(&self, state: &ServerState) -> String {
+        [
+            self.body_end.as_str(),
+            &state.to_script(),
+            self.script.as_deref().unwrap_or_default(),
+            &self.tail,
+        ]
+        .concat()
+    }
+}
+
+/// A page rendered by [`render_page`] or [`render_page_await_suspense`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedPage {
+    /// The HTML of the page.
+    pub html: String,
+    /// The `Set-Cookie` headers of the cookies written while rendering, to be sent with the
+    /// response.
+    pub set_cookie_headers: Vec<String>,
+}
+
+/// What is collected while rendering a page, which outlives the scope of the page.
+#[cfg(feature = "ssr")]
+#[derive(Default)]
+struct Collected {
+    head: RefCell<Option<View<SsrNode>>>,
+    set_cookie_headers: RefCell<Vec<String>>,
+    state: ServerState,
+}
+
+#[cfg(feature = "ssr")]
+impl Collected {
+    /// Provides the contexts of the page and creates the view of the app and of the tags in the
+    /// `<head>`.
+    fn create<'a>(
+        self: &Rc<Self>,
+        cx: Scope<'a>,
+        template: &Template,
+        request: RequestContext,
+        app: impl FnOnce(Scope<'a>) -> View<SsrNode>,
+    ) -> View<SsrNode> {
+        use crate::utils::hydrate::with_no_hydration_context;
+        use crate::web::cookies::provide_cookies;
+        use crate::web::preload::{provide_preloads, PreloadLinks};
+        use crate::web::seo::{provide_seo_tags, SeoHead};
+        use crate::web::style::{provide_styles, StyleTags};
+
+        let cookies = provide_cookies(cx, request.cookie_header.as_deref());
+        provide_context(cx, request);
+        provide_context(cx, self.state.clone());
+        #[cfg(feature = "suspense")]
+        provide_context(cx, Loaders::default());
+        provide_seo_tags(cx);
+        provide_styles(cx);
+        if let Some(preloads) = &template.preloads {
+            provide_preloads(cx, preloads.clone());
+        }
+        let view = app(cx);
+        // The `<head>` is not part of the app, so it is not hydrated. The tags are updated until
+        // the page is rendered to a string.
+        let head = with_no_hydration_context(|| {
+            view! { cx, SeoHead {} StyleTags {} PreloadLinks {} }
+        });
+        *self.head.borrow_mut() = Some(head);
+        // The cookies written so far are needed to send the headers of a stream, and the ones
+        // written by the components that are resolved later are needed for the other pages.
+        *self.set_cookie_headers.borrow_mut() = cookies.set_cookie_headers();
+        let collected = Rc::clone(self);
+        on_cleanup(cx, move || {
+            *collected.set_cookie_headers.borrow_mut() = cookies.set_cookie_headers();
+        });
+        view
+    }
+
+    /// Renders the tags in the `<head>`.
+    fn head(&self) -> String {
+        let mut html = String::new();
+        if let Some(head) = self.head.borrow().clone() {
+            for node in head.flatten() {
+                sycamore_web::WriteToString::write_to_string(&node, &mut html);
+            }
+        }
+        html
+    }
+
+    fn into_page(self: Rc<Self>, template: &Template, body: &str) -> RenderedPage {
+        let mut html = template.prefix(&self.head());
+        html.push_str(body);
+        html.push_str(&template.suffix(&self.state));
+        RenderedPage {
+            html,
+            set_cookie_headers: self.set_cookie_headers.take(),
+        }
+    }
+}
+
+/// Renders the page of `app` for `request` into `template`.
+///
+/// _This API requires the following crate features to be activated: `server`, `ssr`_
+#[cfg(feature = "ssr")]
+pub fn render_page(
+    template: &Template,
+    request: RequestContext,
+    app: impl FnOnce(Scope<'_>) -> View<SsrNode>,
+) -> RenderedPage {
+    let collected = Rc::new(Collected::default());
+    let body = crate::render_to_string(|cx| collected.create(cx, template, request, app));
+    collected.into_page(template, &body)
+}
+
+/// Renders the page of `app` for `request` into `template`, once the
+/// [`Suspense`](crate::suspense::Suspense) components are resolved. See
+/// [`render_to_string_await_suspense`](crate::render_to_string_await_suspense).
+///
+/// _This API requires the following crate features to be activated: `server`, `ssr`,
+/// `suspense`_
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+pub async fn render_page_await_suspense(
+    template: &Template,
+    request: RequestContext,
+    app: impl FnOnce(Scope<'_>) -> View<SsrNode> + 'static,
+) -> RenderedPage {
+    let collected = Rc::new(Collected::default());
+    let body = crate::render_to_string_await_suspense({
+        let collected = Rc::clone(&collected);
+        let template = template.clone();
+        move |cx| collected.create(cx, &template, request, app)
+    })
+    .await;
+    collected.into_page(template, &body)
+}
+
+/// A page rendered by [`render_page_stream`].
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+pub struct PageStream {
+    /// The `Set-Cookie` headers of the cookies written before the first chunk was rendered, to be
+    /// sent with the response.
+    pub set_cookie_headers: Vec<String>,
+    /// The chunks of the page.
+    pub body: std::pin::Pin<Box<dyn futures::Stream<Item = String>>>,
+}
+
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+impl fmt::Debug for PageStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PageStream")
+            .field("set_cookie_headers", &self.set_cookie_headers)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Renders the page of `app` for `request` into `template` as a stream of HTML chunks. Resolves
+/// once the first chunk is rendered. See
+/// [`render_to_string_stream`](crate::render_to_string_stream).
+///
+/// The `<head>` only contains the tags that were collected when the first chunk was rendered,
+/// since it cannot be changed after it is sent.
+///
+/// _This API requires the following crate features to be activated: `server`, `ssr`,
+/// `suspense`_
+#[cfg(all(feature = "ssr", feature = "suspense"))]
+pub async fn render_page_stream(
+    template: &Template,
+    request: RequestContext,
+    app: impl FnOnce(Scope<'_>) -> View<SsrNode> + 'static,
+) -> PageStream {
+    use futures::{stream, StreamExt};
+
+    let collected = Rc::new(Collected::default());
+    let mut chunks = crate::render_to_string_stream({
+        let collected = Rc::clone(&collected);
+        let template = template.clone();
+        move |cx| collected.create(cx, &template, request, app)
+    });
+    let shell = chunks.next().await.unwrap_or_default();
+    let first = template.prefix(&collected.head()) + &shell;
+    // The state is only sent once all the Suspense components are resolved.
+    let last = {
+        let template = template.clone();
+        let collected = Rc::clone(&collected);
+        async move { template.suffix(&collected.state) }
+    };
+    PageStream {
+        set_cookie_headers: collected.set_cookie_headers.take(),
+        body: Box::pin(
+            stream::once(async { first })
+                .chain(chunks)
+                .chain(stream::once(last)),
+        ),
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::web::cookies::{use_cookies, CookieOptions};
+
+    const INDEX: &str = r#"<!DOCTYPE html><html><head><meta charset="utf-8"></head><body class="app"><noscript>JS</noscript></body></html>"#;
+
+    #[test]
+    fn parse_template() {
+        let template = Template::new(INDEX).unwrap();
+        assert_eq!(
+            template.prefix("<title>"),
+            r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title></head><body class="app">"#
+        );
+        assert_eq!(
+            template.suffix(&ServerState::default()),
+            "<noscript>JS</noscript></body></html>"
+        );
+
+        let template =
+            Template::new("<head></head><body><main><!--sycamore-app--></main></body>").unwrap();
+        assert_eq!(template.prefix(""), "<head></head><body><main>");
+        assert_eq!(template.suffix(&ServerState::default()), "</main></body>");
+
+        assert_eq!(
+            Template::new("<body></body>").unwrap_err().to_string(),
+            "template is missing `</head>`"
+        );
+    }
+
+    #[test]
+    fn request_context() {
+        let request = RequestContext::new("/posts/1?page=2").cookie_header("theme=dark");
+        assert_eq!(request.path, "/posts/1");
+        assert_eq!(request.query, "page=2");
+        assert_eq!(request.url(), "/posts/1?page=2");
+        assert_eq!(RequestContext::new("/").url(), "/");
+    }
+
+    #[test]
+    fn render_page_with_state_and_cookies() {
+        #[component]
+        fn App<G: Html>(cx: Scope<'_>) -> View<G> {
+            let request = use_request_context(cx).unwrap();
+            let theme = use_cookies(cx).get("theme").unwrap_or_default();
+            use_cookies(cx).set("visited", "1", &CookieOptions::default());
+            provide_server_state(cx, "user", &("</script>", 1));
+            assert_eq!(
+                use_server_state::<(String, u32)>(cx, "user"),
+                Some(("</script>".to_string(), 1))
+            );
+            let text = format!("{} {theme}", request.path);
+            view! { cx,
+                crate::web::seo::Title { title: "Page" }
+                p { (text) }
+            }
+        }
+
+        let template = Template::new(INDEX)
+            .unwrap()
+            .hydration_script("/app.js", "/app_bg.wasm");
+        let page = render_page(
+            &template,
+            RequestContext::new("/page").cookie_header("theme=dark"),
+            |cx| view! { cx, App {} },
+        );
+        expect_test::expect![[r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title data-seo="title">Page</title><!----></head><body class="app"><!----><p data-hk="1.0">/page dark</p><noscript>JS</noscript><script id="sycamore-state" type="application/json">{"user":["<\/script>",1]}</script><script type="module">import init from "/app.js";init({module_or_path:"/app_bg.wasm"});</script></body></html>"#]].assert_eq(&page.html);
+        assert_eq!(page.set_cookie_headers, ["visited=1"]);
+    }
+
+    #[cfg(feature = "suspense")]
+    #[tokio::test]
+    async fn load_server_data_once() {
+        use std::cell::Cell;
+
+        use crate::suspense::Suspense;
+
+        thread_local! {
+            static FETCHES: Cell<u32> = Cell::new(0);
+        }
+
+        async fn fetch_user() -> String {
+            FETCHES.with(|fetches| fetches.set(fetches.get() + 1));
+            tokio::task::yield_now().await;
+            "Alice".to_string()
+        }
+
+        #[component]
+        async fn UserName<G: Html>(cx: Scope<'_>) -> View<G> {
+            let name = load_server_data(cx, "user", fetch_user).await;
+            view! { cx, p { (name) } }
+        }
+
+        let page = sycamore_futures::provide_executor_scope(async {
+            render_page_await_suspense(
+                &Template::new(INDEX).unwrap(),
+                RequestContext::new("/"),
+                |cx| {
+                    view! { cx,
+                        Suspense {
+                            fallback: view! { cx, "Loading" },
+                            div {
+                                UserName {}
+                                UserName {}
+                            }
+                        }
+                    }
+                },
+            )
+            .await
+        })
+        .await;
+        assert_eq!(FETCHES.with(Cell::get), 1);
+        assert_eq!(page.html.matches("Alice").count(), 3);
+        assert!(page.html.contains(r#">{"user":"Alice"}</script>"#));
+    }
+}
@@ -0,0 +1,221 @@
+//! Component-scoped styles, declared with the [`css!`](crate::css) macro.
+//!
+//! A [`ScopedStyle`] is a stylesheet whose selectors are scoped to a generated class name. It is
+//! applied to the root elements of a view with [`ScopedStyle::scope`], which also adds the
+//! stylesheet to the page the first time it is used:
+//! - in the browser, a `<style>` tag is added to the `<head>` of the document,
+//! - on the server, the stylesheet is collected by [`provide_styles`] and rendered by
+//!   [`StyleTags`], usually in the `<head>` of the page. The browser then reuses these tags.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::style::{provide_styles, ScopedStyle, StyleTags};
+//!
+//! const STYLE: ScopedStyle = css!("& { padding: 8px; } p { color: gray; }");
+//!
+//! #[component]
+//! fn Card<G: Html>(cx: Scope) -> View<G> {
+//!     STYLE.scope(cx, view! { cx,
+//!         div { p { "Hello" } }
+//!     })
+//! }
+//!
+//! # #[cfg(feature = "ssr")]
+//! # {
+//! let html = sycamore::render_to_string(|cx| {
+//!     provide_styles(cx);
+//!     view! { cx,
+//!         head { StyleTags {} }
+//!         body { Card {} }
+//!     }
+//! });
+//! assert!(html.contains(STYLE.css()));
+//! assert!(html.contains(&format!("class=\"{}\"><p", STYLE.class())));
+//! # }
+//! ```
+
+use std::cell::RefCell;
+
+use wasm_bindgen::prelude::*;
+
+use crate::prelude::*;
+
+/// A stylesheet scoped to a class name. Created with the [`css!`](crate::css) macro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopedStyle {
+    class: &'static str,
+    css: &'static str,
+}
+
+impl ScopedStyle {
+    /// Creates a new [`ScopedStyle`]. Use the [`css!`](crate::css) macro instead, which scopes
+    /// the selectors of the stylesheet to the class.
+    #[doc(hidden)]
+    pub const fn new(class: &'static str, css: &'static str) -> Self {
+        Self { class, css }
+    }
+
+    /// Returns the generated class name.
+    pub fn class(&self) -> &'static str {
+        self.class
+    }
+
+    /// Returns the scoped stylesheet.
+    pub fn css(&self) -> &'static str {
+        self.css
+    }
+
+    /// Adds the stylesheet to the page if it was not added yet. Use this along with
+    /// [`class`](Self::class) to apply the style to other elements than the root elements of a
+    /// view.
+    pub fn inject<G: Html>(&self, cx: Scope<'_>) {
+        if G::IS_BROWSER {
+            inject_in_head(*self);
+        } else if let Some(styles) = try_use_context::<Styles>(cx) {
+            styles.add(*self);
+        }
+    }
+
+    /// Adds the class to the root elements of `view` and adds the stylesheet to the page. Only
+    /// the elements that are at the root of `view` when it is created get the class, not the root
+    /// elements of dynamic views.
+    pub fn scope<G: Html>(&self, cx: Scope<'_>, view: View<G>) -> View<G> {
+        self.inject::<G>(cx);
+        for node in view.clone().flatten() {
+            if node.is_element() {
+                node.add_class(self.class);
+            }
+        }
+        view
+    }
+}
+
+thread_local! {
+    /// The classes of the styles added to the `<head>` of the document.
+    static INJECTED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds a `<style>` tag for `style` to the `<head>` of the document, unless there is already one,
+/// e.g. rendered by [`StyleTags`] on the server.
+fn inject_in_head(style: ScopedStyle) {
+    if INJECTED.with(|injected| injected.borrow().contains(&style.class)) {
+        return;
+    }
+    INJECTED.with(|injected| injected.borrow_mut().push(style.class));
+    let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+    let Some(head) = document.query_selector("head").unwrap_throw() else {
+        return;
+    };
+    let selector = format!("style[data-style=\"{}\"]", style.class);
+    if head.query_selector(&selector).unwrap_throw().is_some() {
+        return;
+    }
+    let element = document.create_element("style").unwrap_throw();
+    element
+        .set_attribute("data-style", style.class)
+        .unwrap_throw();
+    element.set_text_content(Some(style.css));
+    head.append_child(&element).unwrap_throw();
+}
+
+/// The styles used by the rendered components, provided as a context by [`provide_styles`].
+#[derive(Debug, Clone)]
+pub struct Styles {
+    styles: RcSignal<Vec<ScopedStyle>>,
+}
+
+impl Styles {
+    /// Adds a style. Does nothing if the style was already added.
+    pub fn add(&self, style: ScopedStyle) {
+        if !self.styles.get_untracked().contains(&style) {
+            self.styles.modify().push(style);
+        }
+    }
+
+    /// Returns the styles, in the order they were added.
+    pub fn get(&self) -> Vec<ScopedStyle> {
+        self.styles.get_untracked().as_ref().clone()
+    }
+}
+
+/// Provides the [`Styles`] as a context. Call this on the server, at the root of the app, before
+/// rendering [`StyleTags`] and the components that use a [`ScopedStyle`].
+pub fn provide_styles(cx: Scope<'_>) -> &Styles {
+    provide_context(
+        cx,
+        Styles {
+            styles: create_rc_signal(Vec::new()),
+        },
+    )
+}
+
+/// Renders a `<style>` tag for every [`ScopedStyle`] used by the rendered components, usually in
+/// the `<head>` of the page.
+///
+/// The styles used by the components that are rendered after this one are included as well, as
+/// long as the page is rendered to a string only once all of its components are created. Renders
+/// nothing if [`provide_styles`] was not called, e.g. in the browser.
+#[component]
+pub fn StyleTags<G: Html>(cx: Scope<'_>) -> View<G> {
+    match try_use_context::<Styles>(cx).filter(|_| !G::IS_BROWSER) {
+        Some(styles) => View::new_dyn(cx, move || {
+            View::new_fragment(
+                styles
+                    .styles
+                    .get()
+                    .iter()
+                    .map(|style| {
+                        let class = style.class;
+                        let css = style.css;
+                        view! { cx, style(data-style=class, dangerously_set_inner_html=css) }
+                    })
+                    .collect(),
+            )
+        }),
+        None => View::empty(),
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    const CARD: ScopedStyle = crate::css!("& { padding: 8px; } p { color: gray; }");
+
+    #[component]
+    fn Card<G: Html>(cx: Scope<'_>) -> View<G> {
+        CARD.scope(
+            cx,
+            view! { cx,
+                div(class="card") { p { "Hello" } }
+                "text"
+                span {}
+            },
+        )
+    }
+
+    #[test]
+    fn style_tags() {
+        let html = crate::web::render_to_string(|cx| {
+            provide_styles(cx);
+            view! { cx,
+                StyleTags {}
+                Card {}
+                Card {}
+            }
+        });
+        let class = CARD.class();
+        assert!(class.starts_with("css-"));
+        assert_eq!(
+            CARD.css(),
+            format!(".{class}{{padding: 8px;}}.{class} p{{color: gray;}}")
+        );
+        assert_eq!(html.matches("<style").count(), 1);
+        assert!(html.contains(&format!("data-style=\"{class}\">{}</style>", CARD.css())));
+        assert!(html.contains(&format!("class=\"{class}\"></span>")));
+        // Once in the `data-style` attribute, twice in the stylesheet, and on the `div` and the
+        // `span` of both cards.
+        assert_eq!(html.matches(class).count(), 7);
+    }
+}
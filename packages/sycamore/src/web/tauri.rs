@@ -0,0 +1,258 @@
+//! Integration with [Tauri](https://tauri.app) commands and events.
+//!
+//! This module uses the global Tauri API, which requires `build.withGlobalTauri` to be enabled in
+//! `tauri.conf.json`. Values are sent to and received from the Rust backend as JSON using `serde`.
+//!
+//! All the primitives do nothing if not on `wasm32` target, e.g. when rendering on the server.
+//!
+//! _This API requires the following crate features to be activated: `tauri`_
+
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sycamore_futures::create_resource;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"], js_name = invoke)]
+    fn tauri_invoke(cmd: &str, args: &JsValue) -> js_sys::Promise;
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen)]
+    fn tauri_listen(event: &str, handler: &js_sys::Function) -> js_sys::Promise;
+}
+
+/// An error returned by a Tauri command, or an error while converting values to or from JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TauriError {
+    message: String,
+}
+
+impl TauriError {
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for TauriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TauriError {}
+
+impl From<JsValue> for TauriError {
+    fn from(value: JsValue) -> Self {
+        let message = value.as_string().unwrap_or_else(|| {
+            js_sys::JSON::stringify(&value)
+                .ok()
+                .and_then(|s| s.as_string())
+                .unwrap_or_else(|| "unknown error".to_string())
+        });
+        Self { message }
+    }
+}
+
+impl From<serde_json::Error> for TauriError {
+    fn from(err: serde_json::Error) -> Self {
+        Self {
+            message: err.to_string(),
+        }
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, TauriError> {
+    Ok(js_sys::JSON::parse(&serde_json::to_string(value)?)?)
+}
+
+fn from_js<T: DeserializeOwned>(value: &JsValue) -> Result<T, TauriError> {
+    if value.is_undefined() {
+        return Ok(serde_json::from_str("null")?);
+    }
+    let json = js_sys::JSON::stringify(value)?
+        .as_string()
+        .unwrap_or_default();
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Invokes the Tauri command `cmd` with the arguments `args` and returns its result.
+///
+/// `args` must serialize to a JSON object whose keys are the names of the arguments of the
+/// command.
+pub async fn invoke<A: Serialize, R: DeserializeOwned>(
+    cmd: &str,
+    args: &A,
+) -> Result<R, TauriError> {
+    let args = to_js(args)?;
+    let res = JsFuture::from(tauri_invoke(cmd, &args)).await?;
+    from_js(&res)
+}
+
+/// Creates a resource that invokes the Tauri command `cmd` with the arguments returned by `args`.
+///
+/// `args` is tracked: the command is invoked again every time a signal accessed in `args` changes.
+/// Results of outdated invocations are ignored. The first invocation is awaited by the closest
+/// `Suspense`.
+///
+/// The returned signal is `None` until the first invocation resolves.
+///
+/// # Example
+/// ```
+/// use serde::Serialize;
+/// use sycamore::prelude::*;
+/// use sycamore::web::tauri::create_tauri_command_resource;
+///
+/// #[derive(Serialize)]
+/// struct GreetArgs {
+///     name: String,
+/// }
+///
+/// #[component]
+/// fn Greet<G: Html>(cx: Scope) -> View<G> {
+///     let name = create_signal(cx, String::from("World"));
+///     let greeting = create_tauri_command_resource::<_, String>(cx, "greet", || GreetArgs {
+///         name: name.get().to_string(),
+///     });
+///     view! { cx,
+///         (match greeting.get().as_ref() {
+///             Some(Ok(greeting)) => greeting.clone(),
+///             Some(Err(err)) => err.to_string(),
+///             None => "Loading...".to_string(),
+///         })
+///     }
+/// }
+/// ```
+pub fn create_tauri_command_resource<'a, A, R>(
+    cx: Scope<'a>,
+    cmd: &'static str,
+    args: impl Fn() -> A + 'a,
+) -> &'a ReadSignal<Option<Result<R, TauriError>>>
+where
+    A: Serialize + 'a,
+    R: DeserializeOwned + 'a,
+{
+    if !cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
+        return create_signal(cx, None);
+    }
+    let res = create_resource(
+        cx,
+        move || to_js(&args()),
+        move |args| async move {
+            let result = match args {
+                Ok(args) => match JsFuture::from(tauri_invoke(cmd, &args)).await {
+                    Ok(value) => from_js(&value),
+                    Err(err) => Err(err.into()),
+                },
+                Err(err) => Err(err),
+            };
+            Ok::<_, Infallible>(result)
+        },
+    );
+    res.value()
+}
+
+/// Creates a signal that is updated with the payload of the Tauri event `event` every time it is
+/// emitted. The signal holds `initial` until the first event is received. Payloads that cannot be
+/// deserialized into `T` are ignored.
+///
+/// The event listener is removed when the scope is disposed.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::tauri::create_tauri_event_signal;
+///
+/// #[component]
+/// fn Progress<G: Html>(cx: Scope) -> View<G> {
+///     let progress = create_tauri_event_signal(cx, "download-progress", 0u32);
+///     view! { cx, "Downloaded: " (progress.get()) "%" }
+/// }
+/// ```
+pub fn create_tauri_event_signal<'a, T>(cx: Scope<'a>, event: &str, initial: T) -> &'a ReadSignal<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    let signal = create_rc_signal(initial);
+    if cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
+        let handler = Closure::wrap(Box::new({
+            let signal = signal.clone();
+            move |event: JsValue| {
+                let payload = js_sys::Reflect::get(&event, &"payload".into()).unwrap_throw();
+                if let Ok(payload) = from_js(&payload) {
+                    signal.set(payload);
+                }
+            }
+        }) as Box<dyn Fn(JsValue)>);
+        let listener = Rc::new(EventListener {
+            _handler: handler,
+            unlisten: RefCell::new(None),
+            disposed: Cell::new(false),
+        });
+        let promise = tauri_listen(event, listener._handler.as_ref().unchecked_ref());
+        // Not scoped to `cx` because the listener should be removed even if it is registered after
+        // the scope is disposed.
+        wasm_bindgen_futures::spawn_local({
+            let listener = Rc::clone(&listener);
+            async move {
+                if let Ok(unlisten) = JsFuture::from(promise).await {
+                    *listener.unlisten.borrow_mut() = unlisten.dyn_into().ok();
+                    if listener.disposed.get() {
+                        listener.unlisten();
+                    }
+                }
+            }
+        });
+        on_cleanup(cx, move || {
+            listener.disposed.set(true);
+            listener.unlisten();
+        });
+    }
+    let signal: &RcSignal<T> = create_ref(cx, signal);
+    signal
+}
+
+/// A Tauri event listener. The handler is kept alive until the listener is removed.
+struct EventListener {
+    _handler: Closure<dyn Fn(JsValue)>,
+    /// The function returned by `listen`, once the listener is registered.
+    unlisten: RefCell<Option<js_sys::Function>>,
+    disposed: Cell<bool>,
+}
+
+impl EventListener {
+    fn unlisten(&self) {
+        if let Some(unlisten) = self.unlisten.borrow_mut().take() {
+            let _ = unlisten.call0(&JsValue::UNDEFINED);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noop_when_not_wasm() {
+        create_scope_immediate(|cx| {
+            let res = create_tauri_command_resource::<_, String>(cx, "greet", || ());
+            assert!(res.get().is_none());
+            let progress = create_tauri_event_signal(cx, "progress", 0u32);
+            assert_eq!(*progress.get(), 0);
+        });
+    }
+
+    #[test]
+    fn json_error() {
+        let err: TauriError = serde_json::from_str::<u32>("\"a\"").unwrap_err().into();
+        assert!(err.message().contains("invalid type"));
+    }
+}
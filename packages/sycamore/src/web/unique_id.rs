@@ -0,0 +1,64 @@
+//! Generation of unique ids that are stable between server side rendering and hydration.
+//!
+//! Ids are often needed to associate elements with each other, e.g. with the `for` attribute of a
+//! `<label>` or with `aria-labelledby`. Random ids do not work when hydrating because the ids
+//! generated on the client would not match the ids in the HTML rendered on the server.
+
+use std::cell::Cell;
+
+use crate::prelude::*;
+
+thread_local! {
+    /// Counter for the ids generated outside of a hydration context.
+    static CLIENT_ID_COUNTER: Cell<usize> = Cell::new(0);
+}
+
+/// Generates an id that is unique within the page.
+///
+/// When rendering on the server or hydrating, the id is derived from the hydration keys of the
+/// current component. This means that the same component generates the same ids on the server
+/// and on the client, as long as the ids are generated in the same order. Outside of hydration,
+/// the id comes from a global counter and has a different format, so that it never collides with
+/// the ids generated while hydrating.
+///
+/// # Example
+/// ```
+/// use sycamore::prelude::*;
+/// use sycamore::web::unique_id::create_unique_id;
+///
+/// #[component]
+/// fn Checkbox<G: Html>(cx: Scope) -> View<G> {
+///     let id = create_unique_id(cx);
+///     view! { cx,
+///         input(type="checkbox", id=id)
+///         label(for=id) { "Check me" }
+///     }
+/// }
+/// ```
+pub fn create_unique_id<'a>(cx: Scope<'a>) -> &'a str {
+    #[cfg(feature = "hydrate")]
+    if let Some((component, id)) = sycamore_core::hydrate::get_next_unique_id() {
+        return create_ref(cx, format!("sycamore-{component}-{id}"));
+    }
+    let id = CLIENT_ID_COUNTER.with(|counter| {
+        let id = counter.get();
+        counter.set(id + 1);
+        id
+    });
+    create_ref(cx, format!("sycamore-c{id}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_ids_are_unique() {
+        create_scope_immediate(|cx| {
+            let a = create_unique_id(cx);
+            let b = create_unique_id(cx);
+            assert_ne!(a, b);
+            assert!(a.starts_with("sycamore-c"));
+        });
+    }
+}
@@ -0,0 +1,105 @@
+//! Integration with the
+//! [View Transitions API](https://developer.mozilla.org/en-US/docs/Web/API/View_Transitions_API).
+//!
+//! Wrapping a state update inside [`start_view_transition`] lets the browser snapshot the old DOM,
+//! run the update, and then animate between the two states (cross-fade by default, or shared
+//! element transitions using the `view-transition-name` CSS property).
+//!
+//! If the browser does not support view transitions (or if not on `wasm32`), the update is simply
+//! executed immediately.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::view_transition::start_view_transition_scoped;
+//!
+//! #[component]
+//! fn List<G: Html>(cx: Scope) -> View<G> {
+//!     let items = create_signal(cx, vec![1, 2, 3]);
+//!     let reverse = move |_| {
+//!         start_view_transition_scoped(cx, move || {
+//!             items.modify().reverse();
+//!         });
+//!     };
+//!     view! { cx,
+//!         button(on:click=reverse) { "Reverse" }
+//!         ul {
+//!             Keyed {
+//!                 iterable: items,
+//!                 view: |cx, x| view! { cx,
+//!                     li(style=format!("view-transition-name: item-{x}")) { (x) }
+//!                 },
+//!                 key: |x| *x,
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::reactive::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(extends = web_sys::Document)]
+    type DocumentStartViewTransition;
+    #[wasm_bindgen(method, js_name = "startViewTransition")]
+    fn start_view_transition(this: &DocumentStartViewTransition, cb: &js_sys::Function) -> JsValue;
+}
+
+/// Returns `true` if the browser supports `document.startViewTransition`.
+///
+/// Always returns `false` if not on `wasm32` target.
+pub fn supports_view_transitions() -> bool {
+    if cfg!(all(target_arch = "wasm32", not(target_os = "wasi"))) {
+        let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+        js_sys::Reflect::has(&document, &"startViewTransition".into()).unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Runs `f` inside `document.startViewTransition` if the browser supports it. Otherwise, `f` is
+/// called immediately.
+///
+/// Note that when view transitions are supported, `f` is called asynchronously once the browser
+/// has captured a snapshot of the current page.
+///
+/// For a version that accepts a closure borrowing from a [`Scope`], see
+/// [`start_view_transition_scoped`].
+pub fn start_view_transition(f: impl FnOnce() + 'static) {
+    if supports_view_transitions() {
+        let document = web_sys::window().unwrap_throw().document().unwrap_throw();
+        let cb = Closure::once_into_js(f);
+        document
+            .unchecked_into::<DocumentStartViewTransition>()
+            .start_view_transition(cb.unchecked_ref());
+    } else {
+        f();
+    }
+}
+
+/// Same as [`start_view_transition`] but allows `f` to reference data allocated on the [`Scope`].
+///
+/// If the scope is disposed before the browser calls `f`, `f` is never called.
+pub fn start_view_transition_scoped<'a>(cx: Scope<'a>, f: impl FnOnce() + 'a) {
+    if supports_view_transitions() {
+        let scope_status = use_scope_status(cx);
+
+        let f: Box<dyn FnOnce() + 'a> = Box::new(f);
+        // SAFETY: We do not call `f_extended` until we verify that the scope is still valid using
+        // `use_scope_status`.
+        let f_extended: Box<dyn FnOnce() + 'static> = unsafe { std::mem::transmute(f) };
+
+        start_view_transition(move || {
+            if *scope_status.get() {
+                // Scope is still valid. We can safely execute the callback.
+                f_extended();
+            }
+        });
+    } else {
+        f();
+    }
+}
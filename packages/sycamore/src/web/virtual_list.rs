@@ -0,0 +1,495 @@
+//! Virtualized lists, which only render the rows that are visible in a scroll container.
+//!
+//! Rows can have different heights: the height of every rendered row is measured and remembered by
+//! its key, and the rows that have not been rendered yet are assumed to have the average height of
+//! the measured rows. When rows above the visible area change height, the scroll position is
+//! adjusted so that the visible rows do not move.
+//!
+//! # Example
+//! ```
+//! use sycamore::prelude::*;
+//! use sycamore::web::virtual_list::VirtualList;
+//!
+//! #[component]
+//! fn Rows<G: Html>(cx: Scope) -> View<G> {
+//!     let rows = create_signal(cx, (0..50_000).collect::<Vec<u32>>());
+//!     let container = create_node_ref(cx);
+//!     view! { cx,
+//!         div(ref=container, style="height: 400px; overflow-y: auto") {
+//!             VirtualList {
+//!                 iterable: rows,
+//!                 container: container,
+//!                 item_height: 24.0,
+//!                 view: |cx, row| view! { cx, p(style="height: 24px") { (row) } },
+//!                 key: |row| *row,
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use super::node_ref_node;
+use crate::prelude::*;
+
+/// Props for [`VirtualList`].
+#[derive(Prop)]
+pub struct VirtualListProps<'a, T, F, G: GenericNode, K, Key>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    K: Fn(&T) -> Key + 'a,
+    Key: Clone + Hash + Eq,
+    T: Clone + PartialEq,
+{
+    iterable: &'a ReadSignal<Vec<T>>,
+    /// The map function that renders a [`View`] for each visible element in `iterable`.
+    view: F,
+    /// The key function that assigns each element in `iterable` an unique key.
+    key: K,
+    /// The element that scrolls, usually an ancestor of the list with a fixed height and
+    /// `overflow-y: auto`.
+    container: &'a NodeRef<G>,
+    /// The estimated height of a row, in pixels, used until rows are measured.
+    item_height: f64,
+    /// The number of rows that are rendered above and below the visible rows, which avoids
+    /// flashing empty space when scrolling quickly.
+    #[builder(default = 5)]
+    overscan: usize,
+    /// The number of rows that are rendered before the size of the container is known, e.g. when
+    /// rendering on the server.
+    #[builder(default = 20)]
+    initial_rows: usize,
+    /// A reference for scrolling the list, see [`create_virtual_list_ref`].
+    #[builder(default, setter(strip_option))]
+    list_ref: Option<&'a VirtualListRef>,
+}
+
+impl<'a, T, F, G: GenericNode, K, Key> fmt::Debug for VirtualListProps<'a, T, F, G, K, Key>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    K: Fn(&T) -> Key + 'a,
+    Key: Clone + Hash + Eq,
+    T: Clone + PartialEq,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualListProps")
+            .field("item_height", &self.item_height)
+            .field("overscan", &self.overscan)
+            .field("initial_rows", &self.initial_rows)
+            .finish()
+    }
+}
+
+type ScrollToIndex = Rc<dyn Fn(usize)>;
+
+/// A reference to a [`VirtualList`] for scrolling it to a row. Created with
+/// [`create_virtual_list_ref`] and passed to the `list_ref` prop.
+#[derive(Clone, Default)]
+pub struct VirtualListRef(Rc<RefCell<Option<ScrollToIndex>>>);
+
+impl VirtualListRef {
+    /// Scrolls the container so that the row at `index` is at the top of the visible area. The
+    /// index is clamped to the length of the list. Does nothing if the list is not mounted.
+    pub fn scroll_to_index(&self, index: usize) {
+        let scroll_to = self.0.borrow().clone();
+        if let Some(scroll_to) = scroll_to {
+            scroll_to(index);
+        }
+    }
+}
+
+impl fmt::Debug for VirtualListRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualListRef")
+            .field("is_mounted", &self.0.borrow().is_some())
+            .finish()
+    }
+}
+
+/// Creates a new [`VirtualListRef`].
+pub fn create_virtual_list_ref(cx: Scope<'_>) -> &VirtualListRef {
+    create_ref(cx, VirtualListRef::default())
+}
+
+/// The part of the list that is visible in the container, in pixels from the top of the list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Viewport {
+    top: f64,
+    height: f64,
+}
+
+/// Returns the offset of every row from the top of the list, followed by the height of the list.
+fn row_offsets(heights: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut offsets = vec![0.0];
+    let mut offset = 0.0;
+    for height in heights {
+        offset += height;
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Returns the range of rows to render, given the offsets returned by [`row_offsets`].
+fn visible_range(
+    viewport: Option<Viewport>,
+    offsets: &[f64],
+    overscan: usize,
+    initial_rows: usize,
+) -> (usize, usize) {
+    let len = offsets.len() - 1;
+    let Some(viewport) = viewport.filter(|_| offsets[len] > 0.0) else {
+        return (0, initial_rows.min(len));
+    };
+    let top = viewport.top.max(0.0);
+    // The first row that ends below the top of the viewport, and the rows that start above its
+    // bottom.
+    let first = offsets[1..].partition_point(|end| *end <= top);
+    let last = offsets[..len].partition_point(|start| *start < top + viewport.height);
+    let start = first.saturating_sub(overscan).min(len);
+    let end = last.saturating_add(overscan).min(len);
+    (start, end)
+}
+
+/// Returns the element referenced by `node_ref`, if any.
+fn node_ref_element<G: Html>(node_ref: &NodeRef<G>) -> Option<web_sys::Element> {
+    node_ref_node(node_ref).and_then(|node| node.dyn_into().ok())
+}
+
+/// Virtualized keyed iteration. Only the rows that are visible in the `container` (plus a few
+/// rows above and below) are rendered, so that very long lists stay fast. The rendered rows are
+/// reconciled with [`Keyed`], and the space taken by the other rows is filled with an empty `div`
+/// before and after them.
+///
+/// The rendered rows are measured whenever the container is scrolled or resized, and their heights
+/// are remembered by key. The other rows are assumed to have the average height of the measured
+/// rows, or `item_height` until rows are measured. Each row should render a single element,
+/// otherwise the rendered rows are assumed to have the same height. The container can contain
+/// other content before the list.
+///
+/// To scroll to a row, pass a [`VirtualListRef`] to `list_ref`.
+///
+/// When not rendering in a browser, the first `initial_rows` rows are rendered.
+#[component]
+pub fn VirtualList<'a, G: Html, T, F, K, Key>(
+    cx: Scope<'a>,
+    props: VirtualListProps<'a, T, F, G, K, Key>,
+) -> View<G>
+where
+    F: Fn(BoundedScope<'_, 'a>, T) -> View<G> + 'a,
+    K: Fn(&T) -> Key + 'a,
+    Key: Clone + Hash + Eq + 'a,
+    T: Clone + Eq + 'a,
+{
+    let VirtualListProps {
+        iterable,
+        view,
+        key,
+        container,
+        item_height,
+        overscan,
+        initial_rows,
+        list_ref,
+    } = props;
+    let key = create_ref(cx, key);
+
+    // `RcSignal`s so that they can be updated from the event listeners.
+    let viewport = create_ref(cx, create_rc_signal(None::<Viewport>));
+    // The index of the first rendered row and the measured heights of the rendered rows.
+    let measured = create_ref(cx, create_rc_signal((0, Vec::<f64>::new())));
+
+    let heights = create_signal(cx, HashMap::<Key, f64>::new());
+    create_effect(cx, || {
+        let measured = measured.get();
+        let (start, measured) = measured.as_ref();
+        untrack(|| {
+            let iterable = iterable.get();
+            let rows = iterable.iter().skip(*start).zip(measured);
+            let changed = rows
+                .clone()
+                .any(|(row, height)| match heights.get().get(&key(row)) {
+                    Some(old) => (old - height).abs() > 0.5,
+                    None => true,
+                });
+            if changed {
+                let mut heights = heights.modify();
+                for (row, height) in rows {
+                    heights.insert(key(row), *height);
+                }
+            }
+        });
+    });
+    let offsets = create_memo(cx, move || {
+        let heights = heights.get();
+        let estimate = if heights.is_empty() {
+            item_height
+        } else {
+            heights.values().sum::<f64>() / heights.len() as f64
+        };
+        row_offsets(
+            iterable
+                .get()
+                .iter()
+                .map(|row| heights.get(&key(row)).copied().unwrap_or(estimate)),
+        )
+    });
+    let range = create_selector(cx, move || {
+        visible_range(*viewport.get(), &offsets.get(), overscan, initial_rows)
+    });
+    let visible = create_memo(cx, || {
+        let (start, end) = *range.get();
+        iterable.get()[start..end].to_vec()
+    });
+    let before = create_memo(cx, || format!("height: {}px", offsets.get()[range.get().0]));
+    let after = create_memo(cx, || {
+        let offsets = offsets.get();
+        let end = range.get().1;
+        format!("height: {}px", offsets[offsets.len() - 1] - offsets[end])
+    });
+
+    // The rendered range and the offsets, for the event listeners.
+    let rendered = Rc::new(Cell::new((0, 0)));
+    create_effect(cx, {
+        let rendered = Rc::clone(&rendered);
+        move || rendered.set(*range.get())
+    });
+    let current_offsets = Rc::new(RefCell::new(Rc::new(Vec::new())));
+    create_effect(cx, {
+        let current_offsets = Rc::clone(&current_offsets);
+        move || *current_offsets.borrow_mut() = offsets.get()
+    });
+
+    let before_ref = create_node_ref(cx);
+    let after_ref = create_node_ref(cx);
+
+    if G::IS_BROWSER {
+        on_mount(cx, move || {
+            let Some(element) = node_ref_element(container) else {
+                return;
+            };
+            // The scroll position is adjusted below when rows change height.
+            if let Some(element) = element.dyn_ref::<web_sys::HtmlElement>() {
+                element
+                    .style()
+                    .set_property("overflow-anchor", "none")
+                    .unwrap_throw();
+            }
+            let container_top = {
+                let element = element.clone();
+                move || element.get_bounding_client_rect().top() + f64::from(element.client_top())
+            };
+            let measure = Rc::new({
+                let element = element.clone();
+                let container_top = container_top.clone();
+                let viewport = viewport.clone();
+                let measured = measured.clone();
+                let rendered = Rc::clone(&rendered);
+                let before_ref = before_ref.clone();
+                let after_ref = after_ref.clone();
+                move || {
+                    let (Some(before), Some(after)) =
+                        (node_ref_element(&before_ref), node_ref_element(&after_ref))
+                    else {
+                        return;
+                    };
+                    let mut rows = Vec::new();
+                    let mut next = before.next_element_sibling();
+                    while let Some(row) = next.filter(|row| *row != after) {
+                        next = row.next_element_sibling();
+                        rows.push(row);
+                    }
+                    let tops = rows
+                        .iter()
+                        .map(|row| row.get_bounding_client_rect().top())
+                        .chain([after.get_bounding_client_rect().top()])
+                        .collect::<Vec<_>>();
+                    // The first visible row, which should not move when the rows above it change
+                    // height.
+                    let visible_top = container_top();
+                    let anchor = rows
+                        .iter()
+                        .zip(&tops[1..])
+                        .find(|(_, bottom)| **bottom > visible_top)
+                        .map(|(row, _)| (row.clone(), row.get_bounding_client_rect().top()));
+
+                    let (start, end) = rendered.get();
+                    if end > start {
+                        let row_heights = if rows.len() == end - start {
+                            tops.windows(2).map(|tops| tops[1] - tops[0]).collect()
+                        } else {
+                            let height =
+                                tops[tops.len() - 1] - before.get_bounding_client_rect().bottom();
+                            vec![height / (end - start) as f64; end - start]
+                        };
+                        if *measured.get_untracked() != (start, row_heights.clone()) {
+                            measured.set((start, row_heights));
+                        }
+                    }
+
+                    if let Some((anchor, top)) = anchor {
+                        let delta = anchor.get_bounding_client_rect().top() - top;
+                        if delta.abs() >= 1.0 {
+                            element.set_scroll_top(element.scroll_top() + delta.round() as i32);
+                        }
+                    }
+                    // The distance between the top of the list and the top of the visible area
+                    // of the container.
+                    let new = Viewport {
+                        top: container_top() - before.get_bounding_client_rect().top(),
+                        height: f64::from(element.client_height()),
+                    };
+                    if *viewport.get_untracked() != Some(new) {
+                        viewport.set(Some(new));
+                    }
+                }
+            });
+
+            if let Some(list_ref) = list_ref {
+                let element = element.clone();
+                let measure = Rc::clone(&measure);
+                let before_ref = before_ref.clone();
+                let current_offsets = Rc::clone(&current_offsets);
+                *list_ref.0.borrow_mut() = Some(Rc::new(move |index: usize| {
+                    // Rows are measured as they are rendered, which moves the row. Scroll again
+                    // until it stays in place.
+                    for _ in 0..3 {
+                        let Some(before) = node_ref_element(&before_ref) else {
+                            return;
+                        };
+                        let offsets = Rc::clone(&current_offsets.borrow());
+                        let offset = offsets[index.min(offsets.len() - 1)];
+                        let list_top = before.get_bounding_client_rect().top() - container_top()
+                            + f64::from(element.scroll_top());
+                        let scroll_top = (list_top + offset).round() as i32;
+                        if scroll_top == element.scroll_top() {
+                            break;
+                        }
+                        element.set_scroll_top(scroll_top);
+                        measure();
+                    }
+                }));
+                on_cleanup(cx, move || *list_ref.0.borrow_mut() = None);
+            }
+
+            let listener = Closure::wrap(Box::new({
+                let measure = Rc::clone(&measure);
+                move || measure()
+            }) as Box<dyn Fn()>);
+            measure();
+            let cb: &js_sys::Function = listener.as_ref().unchecked_ref();
+            element
+                .add_event_listener_with_callback("scroll", cb)
+                .unwrap_throw();
+            let window = web_sys::window().unwrap_throw();
+            window
+                .add_event_listener_with_callback("resize", cb)
+                .unwrap_throw();
+            on_cleanup(cx, move || {
+                let cb: &js_sys::Function = listener.as_ref().unchecked_ref();
+                element
+                    .remove_event_listener_with_callback("scroll", cb)
+                    .unwrap_throw();
+                window
+                    .remove_event_listener_with_callback("resize", cb)
+                    .unwrap_throw();
+            });
+        });
+    }
+
+    view! { cx,
+        div(ref=before_ref, style=before.get(), aria-hidden="true")
+        Keyed {
+            iterable: visible,
+            view: view,
+            key: |row: &T| key(row),
+        }
+        div(ref=after_ref, style=after.get(), aria-hidden="true")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range() {
+        let viewport = |top, height| Some(Viewport { top, height });
+        let uniform = |len| row_offsets(vec![10.0; len].into_iter());
+        assert_eq!(visible_range(None, &uniform(100), 2, 20), (0, 20));
+        assert_eq!(visible_range(None, &uniform(5), 2, 20), (0, 5));
+        assert_eq!(
+            visible_range(viewport(0.0, 50.0), &uniform(100), 2, 20),
+            (0, 7)
+        );
+        assert_eq!(
+            visible_range(viewport(105.0, 50.0), &uniform(100), 2, 20),
+            (8, 18)
+        );
+        assert_eq!(
+            visible_range(viewport(990.0, 50.0), &uniform(100), 2, 20),
+            (97, 100)
+        );
+        assert_eq!(
+            visible_range(viewport(-20.0, 50.0), &uniform(100), 0, 20),
+            (0, 5)
+        );
+        assert_eq!(
+            visible_range(
+                viewport(0.0, 50.0),
+                &row_offsets([0.0; 100].into_iter()),
+                2,
+                20
+            ),
+            (0, 20)
+        );
+        assert_eq!(visible_range(viewport(0.0, 50.0), &[0.0], 2, 20), (0, 0));
+    }
+
+    #[test]
+    fn variable_heights() {
+        let offsets = row_offsets([10.0, 100.0, 10.0, 10.0, 50.0].into_iter());
+        assert_eq!(offsets, [0.0, 10.0, 110.0, 120.0, 130.0, 180.0]);
+        let viewport = |top, height| Some(Viewport { top, height });
+        assert_eq!(visible_range(viewport(0.0, 20.0), &offsets, 0, 20), (0, 2));
+        assert_eq!(visible_range(viewport(50.0, 20.0), &offsets, 0, 20), (1, 2));
+        assert_eq!(
+            visible_range(viewport(115.0, 10.0), &offsets, 0, 20),
+            (2, 4)
+        );
+        assert_eq!(
+            visible_range(viewport(115.0, 10.0), &offsets, 1, 20),
+            (1, 5)
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn initial_rows() {
+        let html = crate::web::render_to_string(|cx| {
+            let rows = create_signal(cx, (0..1000).collect::<Vec<u32>>());
+            let container = create_node_ref(cx);
+            view! { cx,
+                div(ref=container) {
+                    VirtualList {
+                        iterable: rows,
+                        container: container,
+                        item_height: 10.0,
+                        initial_rows: 3,
+                        view: |cx, row| view! { cx, p { (row) } },
+                        key: |row| *row,
+                    }
+                }
+            }
+        });
+        assert_eq!(html.matches("<p").count(), 3);
+        assert!(html.contains("style=\"height: 0px\""));
+        assert!(html.contains("style=\"height: 9970px\""));
+    }
+}
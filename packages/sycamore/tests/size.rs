@@ -0,0 +1,87 @@
+//! Binary size budget for a minimal app.
+//!
+//! Builds the `counter` example for `wasm32-unknown-unknown` in release mode and checks that the
+//! size of its code and data stays under [`BUDGET`]. Custom sections (debug info, names) and the
+//! other sections that are rewritten by `wasm-bindgen` are not counted.
+//!
+//! This test is ignored by default because it needs the `wasm32-unknown-unknown` target and takes
+//! a while. Run it with `cargo test -p sycamore --test size -- --ignored`, as the `size` job of the
+//! CI does. If [`twiggy`](https://github.com/rustwasm/twiggy) is installed, the largest functions
+//! of the binary are printed when the budget is exceeded.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The maximum size of the code and data of the `counter` example, in bytes.
+const BUDGET: usize = 320 * 1024;
+
+/// Section ids, see <https://webassembly.github.io/spec/core/binary/modules.html#sections>.
+const CODE_SECTION: u8 = 10;
+const DATA_SECTION: u8 = 11;
+
+fn read_leb128(bytes: &[u8], pos: &mut usize) -> usize {
+    let mut result = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*pos];
+        *pos += 1;
+        result |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return result;
+        }
+        shift += 7;
+    }
+}
+
+/// Returns the total size of the code and data sections of a wasm module.
+fn code_and_data_size(wasm: &[u8]) -> usize {
+    assert_eq!(&wasm[..4], b"\0asm", "not a wasm module");
+    // Skip the magic number and the version.
+    let mut pos = 8;
+    let mut size = 0;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        pos += 1;
+        let len = read_leb128(wasm, &mut pos);
+        if id == CODE_SECTION || id == DATA_SECTION {
+            size += len;
+        }
+        pos += len;
+    }
+    size
+}
+
+fn build_counter(target_dir: &Path) -> PathBuf {
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "-p", "counter", "--release"])
+        .args(["--target", "wasm32-unknown-unknown", "--target-dir"])
+        .arg(target_dir)
+        .status()
+        .expect("could not run cargo");
+    assert!(status.success(), "could not build the counter example");
+    target_dir.join("wasm32-unknown-unknown/release/counter.wasm")
+}
+
+/// Returns the output of `twiggy top`, or `None` if twiggy is not installed.
+fn twiggy_top(wasm: &Path) -> Option<String> {
+    let output = Command::new("twiggy")
+        .args(["top", "-n", "30"])
+        .arg(wasm)
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+#[ignore = "needs the wasm32-unknown-unknown target"]
+fn counter_size_budget() {
+    let path = build_counter(&Path::new(env!("CARGO_TARGET_TMPDIR")).join("size"));
+    let wasm = std::fs::read(&path).unwrap();
+    let size = code_and_data_size(&wasm);
+    println!("code and data size of the counter example: {size} bytes (budget: {BUDGET} bytes)");
+    if size > BUDGET {
+        let report = twiggy_top(&path)
+            .unwrap_or_else(|| "install twiggy to see the largest functions".to_string());
+        panic!("the counter example is {size} bytes, over the budget of {BUDGET} bytes\n{report}");
+    }
+}
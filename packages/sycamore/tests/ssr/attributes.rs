@@ -0,0 +1,92 @@
+use sycamore::prelude::*;
+
+#[derive(Attributes)]
+struct Disclosure<'a> {
+    aria_expanded: MaybeDyn<'a, bool>,
+    aria_controls: &'static str,
+    #[attribute(name = "data-count")]
+    count: &'a ReadSignal<u32>,
+    title: Option<String>,
+}
+
+#[derive(Attributes)]
+struct Role {
+    role: String,
+}
+
+#[test]
+fn spread_attributes() {
+    create_scope_immediate(|cx| {
+        let open = create_signal(cx, false);
+        let count = create_signal(cx, 0);
+        let disclosure = Disclosure {
+            aria_expanded: open.into(),
+            aria_controls: "panel",
+            count,
+            title: None,
+        };
+        let node = view! { cx,
+            button(..disclosure, ..Role { role: "switch".to_string() }, class="toggle")
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button aria-controls=\"panel\" data-count=\"0\" role=\"switch\" class=\"toggle\"></button>"
+        );
+
+        open.set(true);
+        count.set(1);
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button aria-controls=\"panel\" data-count=\"1\" role=\"switch\" class=\"toggle\" aria-expanded=\"\"></button>"
+        );
+    });
+}
+
+#[derive(Prop)]
+struct ButtonProps<'a, G: Html> {
+    #[builder(default)]
+    attributes: AttributeMap<'a, G>,
+    children: Children<'a, G>,
+}
+
+#[component]
+fn Button<'a, G: Html>(cx: Scope<'a>, props: ButtonProps<'a, G>) -> View<G> {
+    let children = props.children.call(cx);
+    view! { cx,
+        button(class="btn", ..props.attributes, type="button") { (children) }
+    }
+}
+
+#[test]
+fn forward_attribute_map() {
+    create_scope_immediate(|cx| {
+        let disabled = create_signal(cx, false);
+        let attributes = AttributeMap::new()
+            .attr("type", "submit")
+            .attr("disabled", disabled)
+            .attr("aria-label", "Send")
+            .class("btn-primary")
+            .on("click", |_| {});
+        assert!(!attributes.is_empty());
+        let node: View<SsrNode> = view! { cx,
+            Button { attributes: attributes, "Send" }
+        };
+        // The attributes after the spread take precedence.
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button class=\"btn btn-primary\" type=\"button\" aria-label=\"Send\">Send</button>"
+        );
+
+        disabled.set(true);
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button class=\"btn btn-primary\" type=\"button\" aria-label=\"Send\" disabled=\"\">Send</button>"
+        );
+
+        let node: View<SsrNode> = view! { cx, Button { "Plain" } };
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button class=\"btn\" type=\"button\">Plain</button>"
+        );
+    });
+}
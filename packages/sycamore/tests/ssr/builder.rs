@@ -0,0 +1,25 @@
+use sycamore::builder::prelude::*;
+use sycamore::prelude::*;
+
+#[test]
+fn reactive_text_and_attributes() {
+    create_scope_immediate(|cx| {
+        let count = create_signal(cx, 1);
+        let disabled = create_signal(cx, false);
+        let node: View<SsrNode> = button()
+            .attr_value("data-count", count)
+            .attr_value("disabled", disabled)
+            .dyn_t(t_fmt!("Count: {}", count.get()))
+            .view(cx);
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button data-count=\"1\"><!--#-->Count: 1<!--/--></button>"
+        );
+        count.set(2);
+        disabled.set(true);
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<button data-count=\"2\" disabled=\"\"><!--#-->Count: 2<!--/--></button>"
+        );
+    });
+}
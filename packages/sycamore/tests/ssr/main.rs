@@ -1,8 +1,12 @@
+mod attributes;
+#[cfg(feature = "builder")]
+mod builder;
 mod noderef;
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 use sycamore::prelude::*;
+use sycamore::template::Template;
 
 #[test]
 fn hello_world() {
@@ -62,6 +66,69 @@ fn self_closing_tag() {
     });
 }
 
+#[test]
+fn text_modifiers() {
+    create_scope_immediate(|cx| {
+        let node = view! { cx,
+            p { "  Hello,
+                world!  "trim }
+            pre {
+                r#"
+                fn main() {
+                    println!("Hello");
+                }
+                "#dedent
+            }
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<p>Hello, world!</p><pre>fn main() {\n    println!(\"Hello\");\n}</pre>"
+        );
+    });
+}
+
+#[test]
+fn comments() {
+    create_scope_immediate(|cx| {
+        let count = 1;
+        let node = view! { cx,
+            comment("root")
+            div {
+                comment("static")
+                comment(count)
+            }
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<!--root--><div><!--static--><!--1--></div>"
+        );
+    });
+}
+
+#[test]
+fn html_syntax() {
+    create_scope_immediate(|cx| {
+        let count = create_signal(cx, 1);
+        let node = html! { cx,
+            <div class="counter" data-count={count.get()}>
+                <p>"Count: " {count.get()}</p>
+                <input type="number" disabled>
+            </div>
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<div class=\"counter\" data-count=\"1\"><p>Count: <!--#-->1<!--/--></p>\
+             <input type=\"number\" disabled=\"\"/></div>"
+        );
+        count.set(2);
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<div class=\"counter\" data-count=\"2\"><p>Count: <!--#-->2<!--/--></p>\
+             <input type=\"number\" disabled=\"\"/></div>"
+        );
+    });
+}
+
 #[test]
 fn fragments() {
     create_scope_immediate(|cx| {
@@ -77,6 +144,25 @@ fn fragments() {
     });
 }
 
+#[test]
+fn interpolate_iterators() {
+    create_scope_immediate(|cx| {
+        let items: &[&str] = &["a", "b"];
+        let views: Vec<View<SsrNode>> = items
+            .iter()
+            .map(|item| view! { cx, li { (item.to_string()) } })
+            .collect();
+        let node = view! { cx,
+            ul { (items.iter().map(|item| view! { cx, li { (item.to_string()) } })) }
+            ul { (views) }
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<ul><li>a</li><li>b</li></ul><ul><li>a</li><li>b</li></ul>"
+        );
+    });
+}
+
 #[test]
 fn indexed() {
     create_scope_immediate(|cx| {
@@ -86,7 +172,7 @@ fn indexed() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -105,6 +191,449 @@ fn indexed() {
     });
 }
 
+#[test]
+fn keyed_enter_exit_hooks() {
+    create_scope_immediate(|cx| {
+        let items = create_signal(cx, vec![1, 2, 3]);
+        let entered = create_ref(cx, Cell::new(0));
+        let exits = create_ref(cx, RefCell::new(Vec::new()));
+        let node = view! { cx,
+            ul {
+                Keyed {
+                    iterable: items,
+                    view: |cx, item| view! { cx, li { (item) } },
+                    key: |item| *item,
+                    on_enter: Box::new(|nodes| entered.set(entered.get() + nodes.len())),
+                    on_exit: Box::new(|_, done| exits.borrow_mut().push(done)),
+                }
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<ul><li>1</li><li>2</li><li>3</li></ul>");
+        assert_eq!(entered.get(), 0);
+
+        // Removed rows are kept at their position until the exit transition is done.
+        items.set(vec![1, 3, 4]);
+        assert_eq!(entered.get(), 1);
+        assert_eq!(
+            render(),
+            "<ul><li>1</li><li>2</li><li>3</li><li>4</li></ul>"
+        );
+        items.set(vec![4]);
+        assert_eq!(
+            render(),
+            "<ul><li>1</li><li>2</li><li>3</li><li>4</li></ul>"
+        );
+
+        let mut exits = exits.borrow_mut();
+        assert_eq!(exits.len(), 3);
+        (exits.remove(0))();
+        assert_eq!(render(), "<ul><li>1</li><li>3</li><li>4</li></ul>");
+        for done in exits.drain(..) {
+            done();
+        }
+        assert_eq!(render(), "<ul><li>4</li></ul>");
+    });
+}
+
+#[test]
+fn keyed_vec() {
+    create_scope_immediate(|cx| {
+        let items = create_signal_vec(cx, vec![1, 2]);
+        let node = view! { cx,
+            ul {
+                KeyedVec {
+                    iterable: items,
+                    view: |cx, item| view! { cx, li { (item) } },
+                }
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<ul><li>1</li><li>2</li></ul>");
+
+        items.push(3);
+        items.swap(0, 2);
+        assert_eq!(render(), "<ul><li>3</li><li>2</li><li>1</li></ul>");
+        items.remove(1);
+        assert_eq!(render(), "<ul><li>3</li><li>1</li></ul>");
+    });
+}
+
+#[test]
+fn keyed_fragments() {
+    create_scope_immediate(|cx| {
+        let order = create_signal(cx, vec![1, 2]);
+        let created = create_ref(cx, Cell::new(0));
+        let disposed = create_ref(cx, RefCell::new(Vec::new()));
+        let node = view! { cx,
+            div {
+                KeyedFragments {
+                    view: move |_, fragments| View::new_fragment(
+                        order
+                            .get()
+                            .iter()
+                            .map(|&key| {
+                                fragments.get(key, move |cx| {
+                                    created.set(created.get() + 1);
+                                    on_cleanup(cx, move || disposed.borrow_mut().push(key));
+                                    view! { cx, p { (key) } }
+                                })
+                            })
+                            .collect(),
+                    ),
+                }
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<div><p>1</p><p>2</p></div>");
+        assert_eq!(created.get(), 2);
+
+        // Fragments that are kept are not rendered again.
+        order.set(vec![1, 3, 2]);
+        assert_eq!(render(), "<div><p>1</p><p>3</p><p>2</p></div>");
+        assert_eq!(created.get(), 3);
+
+        order.set(vec![3, 2]);
+        assert_eq!(render(), "<div><p>3</p><p>2</p></div>");
+        assert_eq!(created.get(), 3);
+        assert_eq!(*disposed.borrow(), [1]);
+    });
+}
+
+#[derive(Prop)]
+struct DraftProps<'a> {
+    name: String,
+    edit: &'a RefCell<Option<RcSignal<String>>>,
+}
+
+#[component]
+fn Draft<'a, G: Html>(cx: Scope<'a>, props: DraftProps<'a>) -> View<G> {
+    let text = create_ref(cx, create_rc_signal(props.name));
+    *props.edit.borrow_mut() = Some(text.clone());
+    view! { cx, input(value=text.get()) }
+}
+
+#[test]
+fn key_resets_state() {
+    create_scope_immediate(|cx| {
+        let id = create_signal(cx, 1);
+        let count = create_signal(cx, 0);
+        let edit = create_ref(cx, RefCell::new(None));
+        let node = view! { cx,
+            div {
+                Draft { key=*id.get(), name: format!("user {}", id.get()), edit: edit }
+                p(key=*id.get(), data-count=count.get())
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(
+            render(),
+            "<div><!--#--><input value=\"user 1\"/><!--/--><!----><!--#--><p data-count=\"0\"></p><!--/--></div>"
+        );
+
+        edit.borrow().as_ref().unwrap().set("edited".to_string());
+        count.set(1);
+        assert_eq!(
+            render(),
+            "<div><!--#--><input value=\"edited\"/><!--/--><!----><!--#--><p data-count=\"1\"></p><!--/--></div>"
+        );
+
+        // Changing the key creates the nodes again, with fresh state.
+        id.set(2);
+        assert_eq!(
+            render(),
+            "<div><!--#--><input value=\"user 2\"/><!--/--><!----><!--#--><p data-count=\"1\"></p><!--/--></div>"
+        );
+    });
+}
+
+#[derive(Prop)]
+struct DataTableProps<'a, G: GenericNode> {
+    rows: Vec<(u32, &'static str)>,
+    children: ChildrenWith<'a, (usize, u32, &'static str), G>,
+}
+
+#[component]
+fn DataTable<'a, G: Html>(cx: Scope<'a>, props: DataTableProps<'a, G>) -> View<G> {
+    let rows = View::new_fragment(
+        props
+            .rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, (id, name))| {
+                let row = props.children.call(cx, (i, id, name));
+                view! { cx, tr { (row) } }
+            })
+            .collect(),
+    );
+    view! { cx, table { (rows) } }
+}
+
+#[derive(Prop)]
+struct RepeatProps<'a, G: GenericNode> {
+    times: usize,
+    children: ChildrenWith<'a, usize, G>,
+}
+
+#[component]
+fn Repeat<'a, G: Html>(cx: Scope<'a>, props: RepeatProps<'a, G>) -> View<G> {
+    View::new_fragment(
+        (0..props.times)
+            .map(|i| props.children.call(cx, i))
+            .collect(),
+    )
+}
+
+#[test]
+fn children_with_params() {
+    create_scope_immediate(|cx| {
+        let table = view! { cx,
+            DataTable {
+                rows: vec![(7, "a"), (9, "b")],
+                |i, id: u32, name| {
+                    td { (i) }
+                    td(data-id=id) { (name) }
+                }
+            }
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| table),
+            "<table><tr><td>0</td><td data-id=\"7\">a</td></tr><tr><td>1</td><td data-id=\"9\">b</td></tr></table>"
+        );
+
+        let repeat = view! { cx, Repeat { times: 3, |i| span { (i) } } };
+        assert_eq!(
+            sycamore::render_to_string(|_| repeat),
+            "<span>0</span><span>1</span><span>2</span>"
+        );
+    });
+}
+
+#[test]
+fn template_instantiate() {
+    create_scope_immediate(|cx| {
+        let row = Template::new(|cx, holes| {
+            view! { cx,
+                li(class="row") {
+                    span { (holes.hole("name")) }
+                    " = "
+                    (holes.hole("value"))
+                }
+                (holes.hole("after"))
+            }
+        });
+        assert_eq!(row.holes().collect::<Vec<_>>(), ["name", "value", "after"]);
+
+        let value = create_signal(cx, 1);
+        let rows = View::new_fragment(
+            ["a", "b"]
+                .into_iter()
+                .map(|name| {
+                    row.instantiate(cx, |hole| match hole {
+                        "name" => view! { cx, (name) },
+                        "value" => view! { cx, (value.get()) },
+                        _ => view! { cx, hr },
+                    })
+                })
+                .collect(),
+        );
+        let list = view! { cx, ul { (rows) } };
+        assert_eq!(
+            sycamore::render_to_string(|_| list.clone()),
+            "<ul><li class=\"row\"><span>a<!----></span> = <!--#-->1<!----><!--/--></li><hr/><li class=\"row\"><span>b<!----></span> = <!--#-->1<!----><!--/--></li><hr/></ul>"
+        );
+
+        value.set(2);
+        assert_eq!(
+            sycamore::render_to_string(|_| list),
+            "<ul><li class=\"row\"><span>a<!----></span> = <!--#-->2<!----><!--/--></li><hr/><li class=\"row\"><span>b<!----></span> = <!--#-->2<!----><!--/--></li><hr/></ul>"
+        );
+    });
+}
+
+#[component]
+fn Panics<G: Html>(_cx: Scope) -> View<G> {
+    panic!("render failed")
+}
+
+#[test]
+fn error_boundary_catches_panic() {
+    create_scope_immediate(|cx| {
+        let node: View<SsrNode> = view! { cx,
+            ErrorBoundary {
+                view: |cx| view! { cx,
+                    p { "before" }
+                    Panics {}
+                },
+                fallback: |cx, err, _| view! { cx, p { (err.to_string()) } },
+            }
+        };
+        assert_eq!(sycamore::render_to_string(|_| node), "<p>render failed</p>");
+    });
+}
+
+#[component]
+fn Parses<G: Html>(cx: Scope) -> View<G> {
+    let result = "not a number".parse::<i32>().map(|n| view! { cx, (n) });
+    view_or_throw(cx, result)
+}
+
+#[test]
+fn error_boundary_catches_err() {
+    create_scope_immediate(|cx| {
+        let node: View<SsrNode> = view! { cx,
+            ErrorBoundary {
+                view: |cx| view! { cx, p { "before" } Parses {} },
+                fallback: |cx, err, _| view! { cx, p { (err.to_string()) } },
+            }
+        };
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<p>invalid digit found in string</p>"
+        );
+
+        let node: View<SsrNode> = sycamore::error_boundary::try_error_boundary(
+            cx,
+            |cx| "not a number".parse::<i32>().map(|n| view! { cx, (n) }),
+            |cx, err, _| view! { cx, p { (err.to_string()) } },
+        );
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<p>invalid digit found in string</p>"
+        );
+    });
+}
+
+#[test]
+fn error_boundary_throw_error_and_reset() {
+    create_scope_immediate(|cx| {
+        let fail = create_signal(cx, false);
+        let reset = create_ref(cx, RefCell::new(None));
+        let node = view! { cx,
+            ErrorBoundary {
+                view: move |cx| {
+                    create_effect(cx, move || {
+                        if *fail.get() {
+                            throw_error(cx, "failed");
+                        }
+                    });
+                    view! { cx, p { "ok" } }
+                },
+                fallback: move |cx, err, r| {
+                    *reset.borrow_mut() = Some(r);
+                    view! { cx, p { "error: " (err.to_string()) } }
+                },
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<p>ok</p>");
+
+        fail.set(true);
+        assert_eq!(render(), "<p>error: <!--#-->failed<!--/--></p>");
+
+        fail.set(false);
+        reset.borrow().as_ref().unwrap().reset();
+        assert_eq!(render(), "<p>ok</p>");
+    });
+}
+
+#[cfg(all(debug_assertions, feature = "diagnostics"))]
+#[test]
+fn strict_mode() {
+    create_scope_immediate(|cx| {
+        let renders = create_ref(cx, Cell::new(0));
+        let effects = create_ref(cx, Cell::new(0));
+        let node: View<SsrNode> = view! { cx,
+            p { "before" }
+            StrictMode {
+                view: |cx| {
+                    renders.set(renders.get() + 1);
+                    create_effect(cx, || effects.set(effects.get() + 1));
+                    view! { cx, p { "inside" } }
+                },
+            }
+        };
+        // The children are only rendered once on the server so that the hydration keys match.
+        assert_eq!(renders.get(), 1);
+        assert_eq!(effects.get(), 2);
+        assert_eq!(
+            sycamore::render_to_string(|_| node),
+            "<p>before</p><p>inside</p>"
+        );
+    });
+}
+
+#[test]
+fn render_hooks() {
+    use std::borrow::Cow;
+
+    use sycamore::web::render_hooks::{
+        add_render_hook, clear_render_hooks, HookElement, RenderHook,
+    };
+
+    struct Hook;
+    impl RenderHook for Hook {
+        fn element(&self, element: &HookElement) {
+            if element.tag() == "button" {
+                element.set_attribute("type", "button");
+            }
+        }
+
+        fn attribute<'v>(
+            &self,
+            tag: &str,
+            name: &str,
+            value: Cow<'v, str>,
+        ) -> Option<Cow<'v, str>> {
+            match name {
+                "class" => Some(format!("{tag}-{value}").into()),
+                "style" => None,
+                _ => Some(value),
+            }
+        }
+    }
+
+    add_render_hook(Hook);
+    let html = sycamore::render_to_string(|cx| {
+        view! { cx,
+            button(class="primary", style="color: red", title="Save")
+        }
+    });
+    clear_render_hooks();
+    assert_eq!(
+        html,
+        "<button data-hk=\"0.0\" type=\"button\" class=\"button-primary\" title=\"Save\"></button>"
+    );
+}
+
+#[cfg(feature = "test-ids")]
+#[test]
+fn test_ids() {
+    #[component]
+    fn Counter<G: Html>(cx: Scope) -> View<G> {
+        view! { cx,
+            div {
+                p { "0" }
+                button { "+" }
+            }
+        }
+    }
+
+    sycamore::component::set_test_ids(true);
+    let html = sycamore::render_to_string(|cx| {
+        view! { cx,
+            main { Counter {} }
+        }
+    });
+    sycamore::component::set_test_ids(false);
+    assert_eq!(
+        html,
+        "<main data-hk=\"0.0\"><div data-hk=\"1.0\" data-testid=\"Counter-1.0\">\
+         <p data-hk=\"1.1\" data-testid=\"Counter-1.1\">0</p>\
+         <button data-hk=\"1.2\" data-testid=\"Counter-1.2\">+</button></div></main>"
+    );
+}
+
 #[test]
 fn bind() {
     create_scope_immediate(|cx| {
@@ -117,6 +646,39 @@ fn bind() {
     });
 }
 
+#[test]
+fn if_else() {
+    create_scope_immediate(|cx| {
+        let count = create_signal(cx, 0);
+        let renders = create_ref(cx, Cell::new(0));
+        let node = view! { cx,
+            p {
+                if *count.get() == 0 {
+                    "zero"
+                } else if *count.get() < 10 {
+                    ({
+                        renders.set(renders.get() + 1);
+                        view! { cx, span { "small" } }
+                    })
+                } else {
+                    "large"
+                }
+            }
+        };
+        assert_eq!(sycamore::render_to_string(|_| node.clone()), "<p>zero</p>");
+        count.set(1);
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<p><span>small</span></p>"
+        );
+        // The branch is not re-rendered if the condition changes but the branch does not.
+        count.set(2);
+        assert_eq!(renders.get(), 1);
+        count.set(10);
+        assert_eq!(sycamore::render_to_string(|_| node.clone()), "<p>large</p>");
+    });
+}
+
 #[test]
 fn using_cx_in_dyn_node_creates_nested_scope() {
     let _ = sycamore::render_to_string(|cx| {
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use sycamore::prelude::*;
 use sycamore::web::html;
 
@@ -34,3 +36,210 @@ fn noderef_with_ssrnode() {
         assert!(noderef.try_get::<SsrNode>().is_some());
     });
 }
+
+#[test]
+fn on_set_noderef() {
+    create_scope_immediate(|cx| {
+        let noderef = create_node_ref(cx);
+        let calls = create_signal(cx, 0);
+        noderef.on_set(cx, |_: SsrNode| calls.set(*calls.get() + 1));
+        assert_eq!(*calls.get(), 0);
+        let _: View<SsrNode> = view! { cx, div(ref=noderef) };
+        assert_eq!(*calls.get(), 1);
+
+        // Called right away if the NodeRef is already set.
+        let late = create_signal(cx, None);
+        noderef.on_set(cx, |node| late.set(Some(node)));
+        assert_eq!(*late.get(), noderef.try_get_raw());
+    });
+}
+
+#[test]
+fn on_set_noderef_removed_on_dispose() {
+    let noderef = NodeRef::<SsrNode>::new();
+    let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+    let disposer = create_scope({
+        let noderef = noderef.clone();
+        let calls = calls.clone();
+        move |cx| noderef.on_set(cx, move |_| calls.set(calls.get() + 1))
+    });
+    noderef.set(SsrNode::element::<html::div>());
+    assert_eq!(calls.get(), 1);
+    unsafe { disposer.dispose() };
+    noderef.set(SsrNode::element::<html::div>());
+    assert_eq!(calls.get(), 1);
+}
+
+#[derive(Prop)]
+struct ItemProps<'a, G: GenericNode> {
+    text: &'static str,
+    removals: &'a std::cell::RefCell<Vec<sycamore::noderef::Removal<G>>>,
+    defer: &'a std::cell::Cell<bool>,
+}
+
+#[component]
+fn Item<'a, G: Html>(cx: Scope<'a>, props: ItemProps<'a, G>) -> View<G> {
+    let noderef = create_node_ref(cx);
+    noderef.on_destroy(cx, move |node: G, removal| {
+        // The node is still attached when the callback is called.
+        assert!(node.parent_node().is_some());
+        if props.defer.get() {
+            props.removals.borrow_mut().push(removal);
+        }
+    });
+    view! { cx, li(ref=noderef) { (props.text) } }
+}
+
+#[test]
+fn on_destroy_noderef() {
+    create_scope_immediate(|cx| {
+        let items = create_signal(cx, vec!["a", "b", "c"]);
+        let defer = create_ref(cx, std::cell::Cell::new(true));
+        let removals = create_ref(cx, std::cell::RefCell::new(Vec::new()));
+        let node = view! { cx,
+            ul {
+                Keyed {
+                    iterable: items,
+                    view: move |cx, text| view! { cx,
+                        Item { text: text, removals: removals, defer: defer }
+                    },
+                    key: |text| *text,
+                }
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<ul><li>a</li><li>b</li><li>c</li></ul>");
+
+        // The removal is deferred while the handle is kept.
+        items.set(vec!["a", "c"]);
+        assert_eq!(removals.borrow().len(), 1);
+        assert_eq!(render(), "<ul><li>a</li><li>b</li><li>c</li></ul>");
+        removals.borrow_mut().clear();
+        assert_eq!(render(), "<ul><li>a</li><li>c</li></ul>");
+
+        // The node is removed right away when the handle is dropped in the callback.
+        defer.set(false);
+        items.set(vec!["a", "c", "d"]);
+        items.set(vec!["a", "c"]);
+        assert!(removals.borrow().is_empty());
+        assert_eq!(render(), "<ul><li>a</li><li>c</li></ul>");
+
+        // Also when all the nodes are removed at once.
+        items.set(vec!["e", "f"]);
+        defer.set(true);
+        items.set(Vec::new());
+        assert_eq!(removals.borrow().len(), 2);
+        assert_eq!(render(), "<ul><li>e</li><li>f</li></ul>");
+        removals.borrow_mut().clear();
+        assert_eq!(render(), "<ul></ul>");
+    });
+}
+
+#[test]
+fn exit_handler_defers_removal() {
+    create_scope_immediate(|cx| {
+        let show = create_signal(cx, true);
+        let removals = create_ref(cx, std::cell::RefCell::new(Vec::new()));
+        provide_exit_handler(cx, |node: SsrNode, removal| {
+            assert!(node.parent_node().is_some());
+            removals.borrow_mut().push(removal);
+        });
+        let node = view! { cx,
+            div {
+                (if *show.get() {
+                    view! { cx, p { "Shown" } }
+                } else {
+                    view! { cx, span { "Hidden" } }
+                })
+            }
+        };
+        let render = || sycamore::render_to_string(|_| node.clone());
+        assert_eq!(render(), "<div><p>Shown</p></div>");
+
+        // The outgoing node is kept until its removal is dropped.
+        show.set(false);
+        assert_eq!(removals.borrow().len(), 1);
+        assert_eq!(render(), "<div><p>Shown</p><span>Hidden</span></div>");
+        removals.borrow_mut().clear();
+        assert_eq!(render(), "<div><span>Hidden</span></div>");
+
+        // Dropping the removal right away removes the node right away.
+        let _ = create_child_scope(cx, |cx| {
+            provide_exit_handler(cx, |_: SsrNode, _| {});
+            let node = view! { cx, div { (if *show.get() { "a" } else { "b" }) } };
+            show.set(true);
+            assert_eq!(sycamore::render_to_string(|_| node), "<div>a</div>");
+        });
+    });
+}
+
+#[derive(Prop)]
+struct MyButtonProps<G: GenericNode> {
+    #[builder(default)]
+    node_ref: NodeRef<G>,
+    #[builder(default)]
+    label: &'static str,
+}
+
+#[component]
+fn MyButton<G: Html>(cx: Scope, props: MyButtonProps<G>) -> View<G> {
+    let own = create_node_ref(cx);
+    let node_ref = merge_node_refs(cx, [props.node_ref, own.clone()]);
+    view! { cx,
+        button(ref=node_ref) { (props.label) }
+    }
+}
+
+#[test]
+fn forward_noderef_to_component() {
+    create_scope_immediate(|cx| {
+        let first = create_node_ref(cx);
+        let node = view! { cx, MyButton(ref=first) };
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<button></button>"
+        );
+        assert_eq!(node.as_node(), Some(&first.get_raw()));
+
+        let second = create_node_ref(cx);
+        let node = view! { cx, div { MyButton { ref=second, label: "Ok" } } };
+        assert_eq!(
+            sycamore::render_to_string(|_| node.clone()),
+            "<div><button>Ok</button></div>"
+        );
+        assert_eq!(
+            second.get::<SsrNode>().parent_node().as_ref(),
+            node.as_node()
+        );
+    });
+}
+
+#[test]
+fn multiple_refs_and_callbacks() {
+    create_scope_immediate(|cx| {
+        let first = create_node_ref(cx);
+        let last = create_node_ref(cx);
+        let calls = create_ref(cx, RefCell::new(Vec::new()));
+        let callback = |node: SsrNode| calls.borrow_mut().push(("named", node));
+        let node: View<SsrNode> = view! { cx,
+            div(
+                ref=first,
+                ref=|node| {
+                    assert!(first.try_get_raw().is_some());
+                    calls.borrow_mut().push(("closure", node));
+                },
+                ref=callback,
+                ref=last,
+            ) {
+                p
+            }
+        };
+        let div = node.as_node().unwrap().clone();
+        assert_eq!(first.get_raw(), div);
+        assert_eq!(last.get_raw(), div);
+        assert_eq!(
+            *calls.borrow(),
+            [("closure", div.clone()), ("named", div.clone())]
+        );
+    });
+}
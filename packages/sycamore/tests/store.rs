@@ -0,0 +1,129 @@
+//! Tests for the `Store` derive macro.
+
+use std::cell::Cell;
+
+use sycamore::prelude::*;
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct Todo {
+    title: String,
+    done: bool,
+}
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct Settings {
+    theme: String,
+}
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct App {
+    name: String,
+    #[store(nested)]
+    settings: Settings,
+    #[store(nested)]
+    todos: Vec<Todo>,
+    tags: Vec<String>,
+}
+
+#[derive(Store, Clone, Debug, PartialEq)]
+struct Pair<T: Clone> {
+    first: T,
+    second: T,
+}
+
+fn app() -> App {
+    App {
+        name: "app".to_string(),
+        settings: Settings {
+            theme: "light".to_string(),
+        },
+        todos: vec![Todo {
+            title: "Write tests".to_string(),
+            done: false,
+        }],
+        tags: vec!["a".to_string()],
+    }
+}
+
+#[test]
+fn fields_are_tracked_individually() {
+    create_scope_immediate(|cx| {
+        let store = create_store(cx, app());
+        let theme_runs = create_ref(cx, Cell::new(0));
+        create_effect(cx, || {
+            store.settings().theme().track();
+            theme_runs.set(theme_runs.get() + 1);
+        });
+        assert_eq!(theme_runs.get(), 1);
+
+        store.name().set("renamed".to_string());
+        store.tags().modify().push("b".to_string());
+        assert_eq!(theme_runs.get(), 1);
+
+        store.settings().theme().set("dark".to_string());
+        assert_eq!(theme_runs.get(), 2);
+        assert_eq!(*store.settings().theme().get(), "dark");
+    });
+}
+
+#[test]
+fn nested_vec() {
+    create_scope_immediate(|cx| {
+        let store = create_store(cx, app());
+        let done = create_memo(cx, || {
+            store
+                .todos()
+                .get()
+                .iter()
+                .filter(|todo| *todo.done().get())
+                .count()
+        });
+        assert_eq!(*done.get(), 0);
+        store.todos().get()[0].done().set(true);
+        assert_eq!(*done.get(), 1);
+        store.todos().modify().push(
+            Todo {
+                title: "Ship".to_string(),
+                done: true,
+            }
+            .into_store(),
+        );
+        assert_eq!(*done.get(), 2);
+    });
+}
+
+#[test]
+fn get_and_set() {
+    create_scope_immediate(|cx| {
+        let store = create_store(cx, app());
+        assert_eq!(store.get(), app());
+
+        let mut new = app();
+        new.settings.theme = "dark".to_string();
+        new.todos.clear();
+        store.set(new.clone());
+        assert_eq!(store.get(), new);
+        assert_eq!(*store.settings().theme().get(), "dark");
+    });
+}
+
+#[test]
+fn generic_store() {
+    create_scope_immediate(|cx| {
+        let pair = create_store(
+            cx,
+            Pair {
+                first: 1,
+                second: 2,
+            },
+        );
+        pair.second().set(3);
+        assert_eq!(
+            pair.get(),
+            Pair {
+                first: 1,
+                second: 3
+            }
+        );
+    });
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use sycamore::web::custom_event::{custom_event_handler, dispatch_custom_event};
+
+use super::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Selected {
+    id: u32,
+    label: String,
+}
+
+#[wasm_bindgen_test]
+fn dispatch_and_handle_custom_event() {
+    create_scope_immediate(|cx| {
+        let button = create_node_ref(cx);
+        let received = create_signal(cx, Vec::new());
+        let node = view! { cx,
+            div(on:item-selected=custom_event_handler(|detail: Selected| received.modify().push(detail))) {
+                button(ref=button)
+            }
+        };
+        sycamore::render_to(|_| node, &test_container());
+
+        let selected = Selected {
+            id: 1,
+            label: "First".to_string(),
+        };
+        assert!(dispatch_custom_event(button, "item-selected", &selected));
+        assert_eq!(*received.get(), [selected]);
+
+        // Events dispatched by JS code are handled too, and ignored if the detail does not match.
+        let init = web_sys::CustomEventInit::new();
+        init.set_bubbles(true);
+        init.set_detail(&"not an object".into());
+        let event = web_sys::CustomEvent::new_with_event_init_dict("item-selected", &init).unwrap();
+        button
+            .get::<DomNode>()
+            .inner_element()
+            .dispatch_event(&event)
+            .unwrap();
+        assert_eq!(received.get().len(), 1);
+    });
+}
@@ -0,0 +1,15 @@
+use sycamore::web::download::{create_blob, create_object_url};
+
+use super::*;
+
+#[wasm_bindgen_test]
+fn object_url() {
+    let blob = create_blob(b"Hello", "text/plain");
+    assert_eq!(blob.size(), 5.0);
+    assert_eq!(blob.type_(), "text/plain");
+
+    create_scope_immediate(|cx| {
+        let url = create_object_url(cx, &blob);
+        assert!(url.starts_with("blob:"));
+    });
+}
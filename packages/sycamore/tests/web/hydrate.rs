@@ -287,3 +287,155 @@ mod top_level_dynamic_with_siblings {
         });
     }
 }
+
+mod render_phase {
+    use std::cell::Cell;
+
+    use sycamore::web::render_phase::{use_render_phase, RenderPhase};
+
+    use super::*;
+    fn v<G: Html>(cx: Scope) -> View<G> {
+        let phase = use_render_phase::<G>(cx);
+        view! { cx, p { (format!("{:?}", *phase.get_untracked())) } }
+    }
+    #[test]
+    fn ssr() {
+        check(
+            &sycamore::render_to_string(v),
+            expect![[r#"<p data-hk="0.0">Server</p>"#]],
+        );
+    }
+    #[wasm_bindgen_test]
+    fn test() {
+        let html = sycamore::render_to_string(v);
+        let c = test_container();
+        c.set_inner_html(&html);
+
+        let phase = Cell::new(None);
+        sycamore::hydrate_to(
+            |cx| {
+                phase.set(Some(*use_render_phase::<HydrateNode>(cx).get()));
+                v(cx)
+            },
+            &c,
+        );
+        assert_eq!(phase.get(), Some(RenderPhase::Hydrating));
+
+        create_scope_immediate(|cx| {
+            let _: View<DomNode> = v(cx);
+            assert_eq!(*use_render_phase::<DomNode>(cx).get(), RenderPhase::Client);
+        });
+    }
+}
+
+mod unique_id {
+    use sycamore::web::unique_id::create_unique_id;
+
+    use super::*;
+    #[component]
+    fn Field<G: Html>(cx: Scope) -> View<G> {
+        let id = create_unique_id(cx);
+        view! { cx, label(for=id) { "Name" } input(id=id) }
+    }
+    fn v<G: Html>(cx: Scope) -> View<G> {
+        let id = create_unique_id(cx);
+        view! { cx, div(id=id) { Field {} Field {} } }
+    }
+    #[test]
+    fn ssr() {
+        check(
+            &sycamore::render_to_string(v),
+            expect![[
+                r#"<div data-hk="0.0" id="sycamore-0-0"><!--#--><label data-hk="1.0" for="sycamore-1-0">Name</label><input data-hk="1.1" id="sycamore-1-0"/><!--/--><!----><!--#--><label data-hk="2.0" for="sycamore-2-0">Name</label><input data-hk="2.1" id="sycamore-2-0"/><!--/--></div>"#
+            ]],
+        );
+    }
+    #[wasm_bindgen_test]
+    fn test() {
+        let html = sycamore::render_to_string(v);
+        let c = test_container();
+        c.set_inner_html(&html);
+
+        sycamore::hydrate_to(v, &c);
+
+        // The ids generated while hydrating should match the ids rendered on the server.
+        assert_eq!(c.inner_html(), html);
+    }
+}
+
+mod event_replay {
+    use super::*;
+    fn v<'a, G: Html>(cx: Scope<'a>, count: &'a Signal<i32>) -> View<G> {
+        view! { cx,
+            div(on:click=|_| {}) {
+                button(on:click=|_| count.set(*count.get() + 1), on:click=|_| {}) { (count.get()) }
+                p { "Not annotated" }
+            }
+        }
+    }
+    #[test]
+    fn ssr() {
+        check(
+            &sycamore::render_to_string(|cx| v(cx, create_signal(cx, 0))),
+            expect![[
+                r#"<div data-hk="0.0" data-on="click"><button data-hk="0.1" data-on="click">0</button><p data-hk="0.2">Not annotated</p></div>"#
+            ]],
+        );
+    }
+    #[wasm_bindgen_test]
+    fn test() {
+        let html = sycamore::render_to_string(|cx| v(cx, create_signal(cx, 0)));
+        let c = test_container();
+        c.set_inner_html(&html);
+
+        js_sys::eval(sycamore::web::hydrate::EVENT_REPLAY_SCRIPT).unwrap();
+        let button = c.query_selector("button").unwrap().unwrap();
+        // Clicks that happen before hydration are recorded.
+        button.unchecked_ref::<HtmlElement>().click();
+        button.unchecked_ref::<HtmlElement>().click();
+
+        create_scope_immediate(|cx| {
+            let count = create_signal(cx, 0);
+            sycamore::hydrate_to(|_| v(cx, count), &c);
+            assert_eq!(*count.get(), 2);
+
+            // Clicks after hydration are not recorded anymore.
+            button.unchecked_ref::<HtmlElement>().click();
+            assert_eq!(*count.get(), 3);
+        });
+    }
+}
+
+mod mismatch {
+    use sycamore::web::hydrate::{take_hydration_mismatches, HydrationMismatch};
+
+    use super::*;
+    fn v<G: Html>(cx: Scope) -> View<G> {
+        view! { cx, div { p { "Client" } span { "Other" } } }
+    }
+    #[wasm_bindgen_test]
+    fn test() {
+        let c = test_container();
+        c.set_inner_html(
+            r#"<div data-hk="0.0"><p data-hk="0.1">Server</p><em data-hk="0.2">Other</em></div>"#,
+        );
+        take_hydration_mismatches();
+
+        sycamore::hydrate_to(v, &c);
+
+        let mismatches = take_hydration_mismatches();
+        assert_eq!(mismatches.len(), 2);
+        assert!(matches!(
+            &mismatches[0],
+            HydrationMismatch::Text { path, expected, actual }
+                if path.ends_with(r#"p[data-hk="0.1"]"#) && expected == "Client" && actual == "Server"
+        ));
+        assert!(matches!(
+            &mismatches[1],
+            HydrationMismatch::Tag { path, expected, actual }
+                if path.ends_with(r#"div[data-hk="0.0"] > em[data-hk="0.2"]"#)
+                    && expected == "span"
+                    && actual == "em"
+        ));
+    }
+}
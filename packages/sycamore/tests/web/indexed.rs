@@ -12,7 +12,7 @@ fn append() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -46,7 +46,7 @@ fn swap_rows() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -83,7 +83,7 @@ fn update_row() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -99,6 +99,38 @@ fn update_row() {
     });
 }
 
+#[wasm_bindgen_test]
+fn update_row_reuses_nodes() {
+    create_scope_immediate(|cx| {
+        let count = create_signal(cx, vec![1, 2, 3]);
+
+        let node = view! { cx,
+            ul {
+                Indexed {
+                    iterable: count,
+                    view: |cx, item| view! { cx,
+                        li { (item.get()) }
+                    },
+                }
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let p = document().query_selector("ul").unwrap().unwrap();
+        let last = p.last_element_child().unwrap();
+        assert_eq!(p.text_content().unwrap(), "123");
+
+        count.set(vec![1, 4, 3]);
+        assert_eq!(p.text_content().unwrap(), "143");
+        assert_eq!(p.last_element_child(), Some(last.clone()));
+
+        count.set(vec![1, 4, 3, 5]);
+        assert_eq!(p.text_content().unwrap(), "1435");
+        assert_eq!(p.children().item(2), Some(last));
+    });
+}
+
 #[wasm_bindgen_test]
 fn trigger_with_same_data() {
     create_scope_immediate(|cx| {
@@ -109,7 +141,7 @@ fn trigger_with_same_data() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -135,7 +167,7 @@ fn delete_row() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -165,7 +197,7 @@ fn delete_row_from_start() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -191,7 +223,7 @@ fn delete_row_from_end() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -217,7 +249,7 @@ fn clear() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -243,7 +275,7 @@ fn insert_front() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
             }
@@ -279,7 +311,7 @@ fn nested_reactivity() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        li { (item.get()) }
+                        li { (item.get().get()) }
                     },
                 }
             }
@@ -313,7 +345,7 @@ fn fragment_template() {
                     iterable: count,
                     view: |cx, item| view! { cx,
                         span { "The value is: " }
-                        strong { (item) }
+                        strong { (item.get()) }
                     },
                 }
             }
@@ -362,7 +394,7 @@ fn template_top_level() {
             Indexed {
                 iterable: count,
                 view: |cx, item| view! { cx,
-                    li { (item) }
+                    li { (item.get()) }
                 },
             }
         };
@@ -398,7 +430,7 @@ fn template_dyn_top_level() {
                 Indexed {
                     iterable: count,
                     view: |cx, item| view! { cx,
-                        (item)
+                        (item.get())
                     },
                 }
             }
@@ -437,13 +469,13 @@ fn template_with_other_nodes_at_same_level() {
                 Indexed {
                     iterable: vec1,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
                 Indexed {
                     iterable: vec2,
                     view: |cx, item| view! { cx,
-                        li { (item) }
+                        li { (item.get()) }
                     },
                 }
                 li { "after" }
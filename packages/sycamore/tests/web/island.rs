@@ -0,0 +1,59 @@
+use expect_test::expect;
+use serde::{Deserialize, Serialize};
+use sycamore::web::island::Islands;
+
+use super::*;
+
+#[derive(Prop, Serialize, Deserialize)]
+struct CounterProps {
+    initial: i32,
+}
+
+#[component(island)]
+fn Counter<G: Html>(cx: Scope, CounterProps { initial }: CounterProps) -> View<G> {
+    let count = create_signal(cx, initial);
+    view! { cx,
+        button(on:click=|_| count.set(*count.get() + 1)) { (count.get()) }
+    }
+}
+
+#[component(island(visible))]
+fn Lazy<G: Html>(cx: Scope) -> View<G> {
+    view! { cx, "Lazy" }
+}
+
+fn v<G: Html>(cx: Scope) -> View<G> {
+    view! { cx,
+        p { "Static" }
+        Counter { initial: 1 }
+        Lazy {}
+    }
+}
+
+#[test]
+fn ssr() {
+    expect![[r#"<p data-hk="0.0">Static</p><sycamore-island data-hk="1.1" data-island="Counter" data-props="{&quot;initial&quot;:1}" data-hydrate="load" data-component="1"><button data-hk="1.0" data-on="click">1</button></sycamore-island><sycamore-island data-hk="2.0" data-island="Lazy" data-props="null" data-hydrate="visible" data-component="2">Lazy</sycamore-island>"#]]
+        .assert_eq(&sycamore::render_to_string(v));
+}
+
+#[wasm_bindgen_test]
+fn hydrate_islands() {
+    let html = sycamore::render_to_string(v);
+    let c = test_container();
+    c.set_inner_html(&html);
+
+    Islands::new()
+        .island_with_props("Counter", Counter)
+        .hydrate();
+
+    // Hydration should not change inner html.
+    assert_eq!(c.inner_html(), html);
+
+    let button = c
+        .query_selector("button")
+        .unwrap()
+        .unwrap()
+        .unchecked_into::<HtmlElement>();
+    button.click();
+    assert_eq!(button.text_content().unwrap(), "2");
+}
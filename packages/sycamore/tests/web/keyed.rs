@@ -481,3 +481,37 @@ fn template_with_other_nodes_at_same_level() {
         assert_eq!(elem.text_content().unwrap(), "before145after");
     });
 }
+
+#[wasm_bindgen_test]
+fn keyed_fragments_swap() {
+    create_scope_immediate(|cx| {
+        let swapped = create_signal(cx, false);
+
+        let node = view! { cx,
+            ul {
+                KeyedFragments {
+                    view: |cx, fragments| {
+                        let first = fragments.get(1, |cx| view! { cx, li { "1" } });
+                        let second = fragments.get(2, |cx| view! { cx, li { "2" } });
+                        if *swapped.get() {
+                            view! { cx, (second) (first) }
+                        } else {
+                            view! { cx, (first) (second) }
+                        }
+                    },
+                }
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let elem = document().query_selector("ul").unwrap().unwrap();
+        let first = elem.first_child().unwrap();
+        assert_eq!(elem.text_content().unwrap(), "12");
+
+        swapped.set(true);
+        assert_eq!(elem.text_content().unwrap(), "21");
+        // The nodes are moved instead of being rendered again.
+        assert!(elem.last_child().unwrap().is_same_node(Some(&first)));
+    });
+}
@@ -1,14 +1,22 @@
-#[cfg(all(feature = "hydrate"))]
+#[cfg(all(feature = "builder", feature = "hydrate"))]
 pub mod builder_hydrate;
 pub mod cleanup;
+#[cfg(feature = "custom-events")]
+pub mod custom_event;
+pub mod download;
 #[cfg(feature = "hydrate")]
 pub mod hydrate;
 pub mod indexed;
+#[cfg(all(feature = "islands", feature = "ssr"))]
+pub mod island;
 pub mod keyed;
 pub mod portal;
 pub mod reconcile;
 pub mod render;
+pub mod seo;
+pub mod style;
 pub mod svg;
+pub mod virtual_list;
 
 use sycamore::prelude::*;
 use sycamore::web::html;
@@ -368,6 +376,32 @@ fn static_property() {
     });
 }
 
+#[wasm_bindgen_test]
+fn two_way_bind_to_value_as_number() {
+    create_scope_immediate(|cx| {
+        let value = create_signal(cx, 1.0);
+
+        let node = view! { cx,
+            input(type="number", bind:valueAsNumber=value)
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+        let input = document()
+            .query_selector("input")
+            .unwrap()
+            .unwrap()
+            .unchecked_into::<HtmlInputElement>();
+
+        assert_eq!(input.value_as_number(), 1.0);
+        value.set(2.5);
+        assert_eq!(input.value_as_number(), 2.5);
+
+        input.set_value_as_number(3.0);
+        input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+        assert_eq!(*value.get(), 3.0);
+    });
+}
+
 #[wasm_bindgen_test]
 #[ignore]
 fn two_way_bind_to_props() {
@@ -398,6 +432,144 @@ fn two_way_bind_to_props() {
     });
 }
 
+#[wasm_bindgen_test]
+fn event_modifiers() {
+    create_scope_immediate(|cx| {
+        let prevented = create_signal(cx, false);
+        let clicks = create_signal(cx, 0);
+        let outer_clicks = create_signal(cx, 0);
+
+        let node = view! { cx,
+            div(on:click=|_| outer_clicks.set(*outer_clicks.get() + 1)) {
+                button(
+                    on:click.prevent.stop.once=|event: Event| {
+                        prevented.set(event.default_prevented());
+                        clicks.set(*clicks.get() + 1);
+                    }
+                )
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+        let button = document()
+            .query_selector("button")
+            .unwrap()
+            .unwrap()
+            .unchecked_into::<HtmlElement>();
+
+        button.click();
+        assert!(*prevented.get());
+        assert_eq!(*clicks.get(), 1);
+        assert_eq!(*outer_clicks.get(), 0);
+
+        // The listener is removed after the first event.
+        button.click();
+        assert_eq!(*clicks.get(), 1);
+        assert_eq!(*outer_clicks.get(), 1);
+    });
+}
+
+#[wasm_bindgen_test]
+fn event_listeners() {
+    create_scope_immediate(|cx| {
+        let clicks = create_signal(cx, Vec::new());
+        let node: View<DomNode> = view! { cx,
+            button(
+                on:click=|_| clicks.modify().push("a"),
+                on:click.once=|_| clicks.modify().push("b"),
+                on:focus=|_| {},
+            )
+        };
+        sycamore::render_to(|_| node.clone(), &test_container());
+        let node = node.as_node().unwrap();
+        let button = node.clone().unchecked_into::<HtmlElement>();
+
+        let names = |node: &DomNode| {
+            node.event_listeners()
+                .into_iter()
+                .map(|listener| listener.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(node), ["click", "click", "focus"]);
+        assert!(node.event_listeners()[1].options.once);
+
+        // The `once` handler is no longer listed once it is called.
+        button.click();
+        assert_eq!(*clicks.get(), ["a", "b"]);
+        assert_eq!(names(node), ["click", "focus"]);
+
+        let listener = node.event_listeners().remove(0);
+        assert!(node.remove_event_listener(&listener));
+        assert!(!node.remove_event_listener(&listener));
+        button.click();
+        assert_eq!(*clicks.get(), ["a", "b"]);
+
+        // Swap the behavior of the button.
+        node.event(cx, "click", |_| clicks.modify().push("c"));
+        button.click();
+        assert_eq!(*clicks.get(), ["a", "b", "c"]);
+
+        node.remove_event_listeners("click");
+        assert_eq!(names(node), ["focus"]);
+        button.click();
+        assert_eq!(*clicks.get(), ["a", "b", "c"]);
+    });
+}
+
+#[cfg(all(debug_assertions, feature = "diagnostics"))]
+#[wasm_bindgen_test]
+fn strict_mode_mounts_twice() {
+    create_scope_immediate(|cx| {
+        let mounted = create_signal(cx, 0);
+        let effects = create_signal(cx, 0);
+
+        let node = view! { cx,
+            StrictMode {
+                view: |cx| {
+                    mounted.set(*mounted.get_untracked() + 1);
+                    on_cleanup(cx, || mounted.set(*mounted.get_untracked() - 1));
+                    create_effect(cx, || effects.set(*effects.get_untracked() + 1));
+                    view! { cx, p { "inside" } }
+                },
+            }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+        assert_eq!(*mounted.get(), 1);
+        // The effect is run twice each time the children are rendered.
+        assert_eq!(*effects.get(), 4);
+        assert_eq!(document().query_selector_all("p").unwrap().length(), 1);
+    });
+}
+
+#[cfg(all(debug_assertions, feature = "diagnostics"))]
+#[wasm_bindgen_test]
+fn update_overlay() {
+    use sycamore::web::update_overlay::{
+        disable_update_overlay, enable_update_overlay, update_counts,
+    };
+
+    #[component]
+    fn Counter<'a, G: Html>(cx: Scope<'a>, count: &'a ReadSignal<i32>) -> View<G> {
+        view! { cx, p { (count.get()) } }
+    }
+
+    enable_update_overlay();
+    create_scope_immediate(|cx| {
+        let count = create_signal(cx, 0);
+        let node = view! { cx, Counter(count) };
+        sycamore::render_to(|_| node, &test_container());
+        // The initial render is not an update.
+        assert!(update_counts().is_empty());
+
+        count.set(1);
+        count.set(2);
+        assert_eq!(update_counts(), vec![("Counter", 2)]);
+    });
+    disable_update_overlay();
+    assert!(update_counts().is_empty());
+}
+
 #[wasm_bindgen_test]
 fn noderefs() {
     create_scope_immediate(|cx| {
@@ -418,6 +590,25 @@ fn noderefs() {
     });
 }
 
+#[wasm_bindgen_test]
+fn noderef_get_as() {
+    create_scope_immediate(|cx| {
+        let noderef = create_node_ref(cx);
+        let node = view! { cx,
+            input(ref=noderef, value="hello")
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        assert_eq!(noderef.get_as::<HtmlInputElement>().value(), "hello");
+        assert!(noderef.try_get_as::<HtmlElement>().is_some());
+        assert!(noderef.try_get_as::<web_sys::HtmlAnchorElement>().is_none());
+        assert!(create_node_ref::<DomNode>(cx)
+            .try_get_as::<Node>()
+            .is_none());
+    });
+}
+
 #[wasm_bindgen_test]
 fn fragments() {
     create_scope_immediate(|cx| {
@@ -512,3 +703,58 @@ fn dom_node_add_class_splits_at_whitespace() {
         "multiple classes"
     );
 }
+
+#[cfg(feature = "test-ids")]
+#[wasm_bindgen_test]
+fn test_ids_without_hydration() {
+    #[component]
+    fn Counter<G: Html>(cx: Scope) -> View<G> {
+        view! { cx, p { "0" } button { "+" } }
+    }
+
+    sycamore::component::set_test_ids(true);
+    sycamore::render_to(|cx| view! { cx, Counter {} Counter {} }, &test_container());
+    sycamore::component::set_test_ids(false);
+
+    let ids: Vec<_> = ["p", "button"]
+        .iter()
+        .flat_map(|tag| {
+            let nodes = document()
+                .query_selector_all(&format!("#test-container {tag}"))
+                .unwrap();
+            (0..nodes.length())
+                .map(|i| {
+                    let element = nodes.get(i).unwrap().unchecked_into::<Element>();
+                    element.get_attribute("data-testid").unwrap()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    // Every element has a different id.
+    assert_eq!(ids.len(), 4);
+    for (i, id) in ids.iter().enumerate() {
+        assert!(id.starts_with("Counter-"));
+        assert!(!ids[i + 1..].contains(id));
+    }
+}
+
+#[wasm_bindgen_test]
+fn component_comments() {
+    #[component]
+    fn Counter<G: Html>(cx: Scope) -> View<G> {
+        view! { cx, p { "0" } }
+    }
+
+    sycamore::component::set_component_comments(true);
+    sycamore::render_to(|cx| view! { cx, Counter {} }, &test_container());
+    sycamore::component::set_component_comments(false);
+
+    assert_eq!(
+        document()
+            .query_selector("#test-container")
+            .unwrap()
+            .unwrap()
+            .inner_html(),
+        "<!--<Counter>--><p>0</p><!--</Counter>-->"
+    );
+}
@@ -74,3 +74,36 @@ fn lazy_in_fragment() {
         assert_eq!(test_container.text_content().unwrap(), "before1after");
     });
 }
+
+#[wasm_bindgen_test]
+fn render_detached_then_attach() {
+    create_scope_immediate(|cx| {
+        let step = create_signal(cx, 1);
+        let node = view! { cx,
+            p { "Step " (step.get()) }
+            (if *step.get() > 1 { view! { cx, "done" } } else { view! { cx, } })
+        };
+        let mut detached = sycamore::render_detached(|_| node);
+        assert_eq!(detached.fragment().text_content().unwrap(), "Step 1");
+        step.set(2);
+        assert_eq!(detached.fragment().text_content().unwrap(), "Step 2done");
+
+        let p = detached.nodes()[0].clone();
+        let test_container = test_container();
+        detached.attach(&test_container, None);
+        assert_eq!(detached.fragment().child_nodes().length(), 0);
+        assert_eq!(test_container.text_content().unwrap(), "Step 2done");
+        // The nodes are moved, not created again.
+        assert_eq!(test_container.first_child(), Some(p));
+
+        step.set(1);
+        assert_eq!(test_container.text_content().unwrap(), "Step 1");
+        assert_eq!(detached.fragment().child_nodes().length(), 0);
+
+        detached.detach();
+        assert_eq!(test_container.text_content().unwrap(), "");
+        step.set(3);
+        assert_eq!(detached.fragment().text_content().unwrap(), "Step 3done");
+        unsafe { detached.dispose() };
+    });
+}
@@ -0,0 +1,76 @@
+use sycamore::web::seo::{Canonical, Meta, Title};
+
+use super::*;
+
+fn canonical() -> Option<String> {
+    document()
+        .query_selector("head link[rel=canonical]")
+        .unwrap()
+        .and_then(|link| link.get_attribute("href"))
+}
+
+#[wasm_bindgen_test]
+fn seo_tags_follow_components() {
+    create_scope_immediate(|cx| {
+        let page = create_signal(cx, 0);
+        sycamore::render_to(
+            |_| {
+                view! { cx,
+                    Canonical { href: "https://example.com" }
+                    Meta { property: "og:title", content: "App" }
+                    (if *page.get() == 1 {
+                        view! { cx, Canonical { href: "https://example.com/page" } }
+                    } else {
+                        view! { cx, }
+                    })
+                }
+            },
+            &test_container(),
+        );
+        assert_eq!(canonical().as_deref(), Some("https://example.com"));
+        assert!(document()
+            .query_selector("head meta[property='og:title'][content=App]")
+            .unwrap()
+            .is_some());
+
+        page.set(1);
+        assert_eq!(canonical().as_deref(), Some("https://example.com/page"));
+        assert_eq!(
+            document()
+                .query_selector_all("head link[rel=canonical]")
+                .unwrap()
+                .length(),
+            1
+        );
+
+        page.set(0);
+        assert_eq!(canonical().as_deref(), Some("https://example.com"));
+    });
+}
+
+#[wasm_bindgen_test]
+fn title_is_restored() {
+    document().set_title("Default");
+    create_scope_immediate(|cx| {
+        let page = create_signal(cx, 0);
+        sycamore::render_to(
+            |_| {
+                view! { cx,
+                    (if *page.get() == 1 {
+                        view! { cx, Title { title: "Page" } }
+                    } else {
+                        view! { cx, }
+                    })
+                }
+            },
+            &test_container(),
+        );
+        assert_eq!(document().title(), "Default");
+
+        page.set(1);
+        assert_eq!(document().title(), "Page");
+
+        page.set(0);
+        assert_eq!(document().title(), "Default");
+    });
+}
@@ -0,0 +1,32 @@
+use sycamore::web::style::ScopedStyle;
+
+use super::*;
+
+const STYLE: ScopedStyle = css!("& { color: red; }");
+
+#[wasm_bindgen_test]
+fn scoped_style_is_injected_once() {
+    create_scope_immediate(|cx| {
+        sycamore::render_to(
+            |_| {
+                view! { cx,
+                    (STYLE.scope(cx, view! { cx, p { "a" } }))
+                    (STYLE.scope(cx, view! { cx, p { "b" } }))
+                }
+            },
+            &test_container(),
+        );
+        let selector = format!("head style[data-style=\"{}\"]", STYLE.class());
+        assert_eq!(
+            document().query_selector_all(&selector).unwrap().length(),
+            1
+        );
+        assert_eq!(
+            document()
+                .query_selector_all(&format!("p.{}", STYLE.class()))
+                .unwrap()
+                .length(),
+            2
+        );
+    });
+}
@@ -11,3 +11,40 @@ fn issue_391_svg_with_class_should_not_use_classname() {
         &test_container(),
     );
 }
+
+#[wasm_bindgen_test]
+fn svg_elements_are_created_in_the_svg_namespace() {
+    let container = test_container();
+    sycamore::render_to(
+        |cx| {
+            view! { cx,
+                svg {
+                    a(href="#") { title { "Link" } }
+                    custom-shape
+                    foreignObject { div { a(href="#") } }
+                }
+                math { mi { "x" } }
+                a(href="#")
+            }
+        },
+        &container,
+    );
+    let namespace_of = |selector: &str| {
+        container
+            .query_selector(selector)
+            .unwrap()
+            .unwrap()
+            .namespace_uri()
+            .unwrap()
+    };
+    let svg = "http://www.w3.org/2000/svg";
+    let html = "http://www.w3.org/1999/xhtml";
+    assert_eq!(namespace_of("svg"), svg);
+    assert_eq!(namespace_of("svg > a"), svg);
+    assert_eq!(namespace_of("svg > a > title"), svg);
+    assert_eq!(namespace_of("custom-shape"), svg);
+    assert_eq!(namespace_of("foreignObject > div"), html);
+    assert_eq!(namespace_of("foreignObject a"), html);
+    assert_eq!(namespace_of("mi"), "http://www.w3.org/1998/Math/MathML");
+    assert_eq!(namespace_of("#test-container > a"), html);
+}
@@ -0,0 +1,77 @@
+use sycamore::web::virtual_list::{create_virtual_list_ref, VirtualList};
+
+use super::*;
+
+#[wasm_bindgen_test]
+fn renders_visible_rows() {
+    create_scope_immediate(|cx| {
+        let rows = create_signal(cx, (0..10_000).collect::<Vec<u32>>());
+        let container = create_node_ref(cx);
+        sycamore::render_to(
+            |_| {
+                view! { cx,
+                    div(ref=container, style="height: 100px; overflow-y: auto") {
+                        VirtualList {
+                            iterable: rows,
+                            container: container,
+                            item_height: 10.0,
+                            overscan: 0,
+                            view: |cx, row| view! { cx, p(style="height: 10px; margin: 0") { (row) } },
+                            key: |row| *row,
+                        }
+                    }
+                }
+            },
+            &test_container(),
+        );
+        let count = || document().query_selector_all("p").unwrap().length();
+        assert_eq!(count(), 10);
+
+        let element = container.get::<DomNode>().unchecked_into::<Element>();
+        element.set_scroll_top(5000);
+        element
+            .dispatch_event(&Event::new("scroll").unwrap())
+            .unwrap();
+        assert_eq!(count(), 10);
+        let first = document().query_selector("p").unwrap().unwrap();
+        assert_eq!(first.text_content().unwrap(), "500");
+    });
+}
+
+#[wasm_bindgen_test]
+fn scroll_to_index_with_variable_heights() {
+    create_scope_immediate(|cx| {
+        let rows = create_signal(cx, (0..10_000).collect::<Vec<u32>>());
+        let container = create_node_ref(cx);
+        let list_ref = create_virtual_list_ref(cx);
+        sycamore::render_to(
+            |_| {
+                view! { cx,
+                    div(ref=container, style="height: 100px; overflow-y: auto") {
+                        VirtualList {
+                            iterable: rows,
+                            container: container,
+                            item_height: 20.0,
+                            overscan: 0,
+                            list_ref: list_ref,
+                            view: |cx, row| {
+                                let style = format!("height: {}px; margin: 0", if row % 2 == 0 { 10 } else { 30 });
+                                view! { cx, p(style=style) { (row) } }
+                            },
+                            key: |row| *row,
+                        }
+                    }
+                }
+            },
+            &test_container(),
+        );
+        let element = container.get::<DomNode>().unchecked_into::<Element>();
+
+        list_ref.scroll_to_index(500);
+        let first = document().query_selector("p").unwrap().unwrap();
+        assert_eq!(first.text_content().unwrap(), "500");
+        let distance =
+            first.get_bounding_client_rect().top() - element.get_bounding_client_rect().top();
+        assert!(distance.abs() < 1.0);
+    });
+}
@@ -30,7 +30,7 @@ pub fn OutlineView<G: Html>(cx: Scope, outline: Vec<Outline>) -> View<G> {
             Indexed {
                 iterable: create_signal(cx, outline),
                 view: |cx, item| {
-                    let Outline { name, children } = item;
+                    let Outline { name, children } = item.get().as_ref().clone();
                     let nested = children.iter().map(|x| {
                         let name = x.name.clone();
                         let href = format!("#{}", x.name.trim().to_lowercase().replace(' ', "-"));